@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use log::{debug, trace};
 use std::sync::{Arc, Mutex};
 use tx_rs::Tx;
@@ -23,12 +24,14 @@ pub trait AddEmployee: HaveEmployeeDao {
     fn get_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>>;
     fn get_method(&self) -> Arc<Mutex<dyn PaymentMethod>>;
     fn get_affiliation(&self) -> Arc<Mutex<dyn Affiliation>>;
+    // None なら入社日を決めず、どの支払い期間も全額を支払う
+    fn get_hire_date(&self) -> Option<NaiveDate>;
 
-    fn execute<'a>(&self) -> Result<(), UsecaseError> {
+    fn execute(&self) -> Result<(), UsecaseError> {
         trace!("execute called");
         self.run_tx(|mut ctx| {
             trace!("run_tx called");
-            let emp = Employee::new(
+            let mut emp = Employee::new(
                 self.get_id(),
                 self.get_name(),
                 self.get_address(),
@@ -37,6 +40,9 @@ pub trait AddEmployee: HaveEmployeeDao {
                 self.get_method(),
                 self.get_affiliation(),
             );
+            if let Some(hire_date) = self.get_hire_date() {
+                emp.hire_on(hire_date);
+            }
             debug!("execute: emp={:?}", emp);
             self.dao().add(emp).run(&mut ctx).map(|_| ())
         })
@@ -17,16 +17,23 @@ pub trait ChangeAffiliation: HaveEmployeeDao {
     fn get_member_id(&self) -> MemberId;
     fn change(&self, aff: Arc<Mutex<dyn Affiliation>>) -> Result<(), DaoError>;
 
-    fn execute<'a>(&self) -> Result<(), UsecaseError> {
+    fn execute(&self) -> Result<(), UsecaseError> {
         trace!("execute called");
         self.run_tx(|mut ctx| {
             trace!("run_tx called");
-            let emp_id = self
-                .dao()
-                .find_union_member(self.get_member_id())
-                .run(&mut ctx)?;
+            let member_id = self.get_member_id();
+            let emp_id = self.dao().find_union_member(member_id).run(&mut ctx)?;
             debug!("found emp_id={}", emp_id);
-            let emp = self.dao().fetch(emp_id).run(&mut ctx)?;
+            let emp = self
+                .dao()
+                .fetch(emp_id)
+                .run(&mut ctx)
+                .map_err(|e| match e {
+                    DaoError::EmployeeNotFound(emp_id) => {
+                        DaoError::DanglingUnionMember(member_id, emp_id)
+                    }
+                    e => e,
+                })?;
             debug!("changing emp={:?}", emp);
             self.change(emp.affiliation())?;
             debug!("changed emp={:?}", emp);
@@ -0,0 +1,190 @@
+use chrono::{Days, NaiveDate};
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::UsecaseError;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{
+    BusinessCalendar, EarningLine, Employee, EmployeeId, Money, OvertimePolicy, Paycheck,
+};
+
+// ユースケース: ChangeClassification トランザクション(抽象レベルのビジネスロジック)
+// 給与の種類が変わらなければ入れ替えずに効力発生日からレートを変えるので、タイムカードや売上は残る
+// 支払い済みの期間にさかのぼる変更は、その期間の支給額の差を次の支払い期間で精算する
+// 種類が変わるときは、効力発生日の前日までのまだ支払っていない分を変更前の種類で計算して、変更後の種類に持ち越す
+pub trait ChangeClassification: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    // None なら最後の支払日の翌日 (まだ支払っていない期間すべて) から
+    // 種類が変わるときは、まだ支払っていない勤務の記録の翌日以降にもなる
+    fn get_effective(&self) -> Option<NaiveDate>;
+    // 差額を計算するときの会社全体の残業の規則
+    fn get_overtime(&self) -> &OvertimePolicy;
+    // 変更前の種類で支払う期間を決める会社の暦
+    fn get_calendar(&self) -> &BusinessCalendar;
+    // 給与の種類が同じならレートを変えて true を返す
+    // effective が None なのは一度も支払っていないときで、履歴ごと置き換える
+    fn change_rate(&self, emp: &Employee, effective: Option<NaiveDate>) -> Result<bool, DaoError>;
+    // 給与の種類ごと入れ替えて、effective から始める
+    // final_pay は変更前の種類での最後の支払いで、effective を含む支払い期間で支給する
+    // effective が None なのは一度も支払っておらず勤務の記録もないときで、そのまま入れ替える
+    fn change(
+        &self,
+        emp: &mut Employee,
+        effective: Option<NaiveDate>,
+        final_pay: Vec<EarningLine>,
+    ) -> Result<(), DaoError>;
+    fn add_retro_pay(
+        &self,
+        emp: &Employee,
+        carried_to: NaiveDate,
+        amount: Money,
+    ) -> Result<(), DaoError>;
+
+    fn execute(&self) -> Result<(), UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let id = self.get_id();
+            let mut emp = self.dao().fetch(id).run(&mut ctx)?;
+            let last_paid = self.dao().fetch_last_pay_date(id).run(&mut ctx)?;
+            // 入社日より前は支払わないので、そこからの変更にする
+            let effective = self
+                .get_effective()
+                .or(last_paid.map(|d| d + Days::new(1)))
+                .max(emp.hire_date());
+            // 効力発生日より後に支払日がある Paycheck は変更前のレートで支払っている
+            let paid = match (effective, last_paid) {
+                (Some(effective), Some(last_paid)) if effective <= last_paid => self
+                    .dao()
+                    .fetch_paychecks(id, effective..=last_paid)
+                    .run(&mut ctx)?,
+                _ => vec![],
+            };
+            let overtime = emp.overtime_policy().unwrap_or(self.get_overtime()).clone();
+            let calendar = self.get_calendar();
+            let before = paid_total(&emp, &paid, &overtime, calendar);
+
+            debug!("changing emp={:?}", emp);
+            if !self.change_rate(&emp, effective)? {
+                let effective = kind_change_effective(&emp, self.get_effective(), last_paid)?;
+                let final_pay = match effective {
+                    Some(effective) => final_pay(&emp, effective, last_paid, &overtime, calendar),
+                    None => vec![],
+                };
+                debug!("final pay {:?} carried to {:?}", final_pay, effective);
+                self.change(&mut emp, effective, final_pay)?;
+            } else if let Some(last_paid) = last_paid {
+                let retro = paid_total(&emp, &paid, &overtime, calendar) - before;
+                if !retro.is_zero() {
+                    let carried_to = last_paid + Days::new(1);
+                    debug!("retro pay {} carried to {}", retro, carried_to);
+                    self.add_retro_pay(&emp, carried_to, retro)?;
+                }
+            }
+            // 入れ替えた Classification や退職日より後の変更にも退職をかけ直す
+            if let Some(termination_date) = emp.termination_date() {
+                emp.terminate_on(termination_date);
+            }
+            debug!("changed emp={:?}", emp);
+            self.dao().update(emp).run(&mut ctx)
+        })
+    }
+}
+
+// 次の支払日を探す日数
+// 支払い期間は長くても 1 か月で、休日で後ろにずれる分も見込む
+pub(crate) const PAY_DATE_SEARCH_DAYS: usize = 62;
+
+// 支払い済みの Paycheck の期間を今のレートで計算し直した総支給額の合計
+fn paid_total(
+    emp: &Employee,
+    paid: &[Paycheck],
+    overtime: &OvertimePolicy,
+    calendar: &BusinessCalendar,
+) -> Money {
+    let classification = emp.classification();
+    let classification = classification.lock().unwrap();
+    paid.iter()
+        .flat_map(|pc| classification.calculate_pay(pc, overtime, calendar))
+        .map(|line| line.amount)
+        .sum()
+}
+
+// 種類が変わるときの効力発生日
+// 支払い済みの期間や勤務の記録のある日にはさかのぼれない
+fn kind_change_effective(
+    emp: &Employee,
+    effective: Option<NaiveDate>,
+    last_paid: Option<NaiveDate>,
+) -> Result<Option<NaiveDate>, DaoError> {
+    let last_record = emp.classification().lock().unwrap().last_record_date();
+    let last_day = last_paid.max(last_record);
+    if let Some(effective) = effective {
+        if last_day.is_some_and(|d| effective <= d) {
+            return Err(DaoError::InvalidEffectiveDate(emp.id(), effective));
+        }
+    }
+    // 入社日より前から始めても支払わないので入社日にそろえる
+    let effective = effective.or(last_day.map(|d| d + Days::new(1)));
+    Ok(effective.max(emp.hire_date()))
+}
+
+// 一度も支払っていない従業員の支払うべき最初の日
+// 入社日と最初の勤務の記録のうち早い方で、どちらもなければ None
+pub(crate) fn first_unpaid_record(emp: &Employee) -> Option<NaiveDate> {
+    let first_record = emp.classification().lock().unwrap().first_record_date();
+    match (emp.hire_date(), first_record) {
+        (Some(hire_date), Some(first_record)) => Some(hire_date.min(first_record)),
+        (hire_date, first_record) => hire_date.or(first_record),
+    }
+}
+
+// 変更前の種類で、最後の支払日の翌日から効力発生日の前日までを含む支払い期間の支給額
+// 一度も支払っていなければ入社日か最初の勤務の記録の早い方からで、どちらもなければ効力発生日の前日を含む期間だけ
+// 効力発生日以降の分は支払わないように閉じてから計算するので、期間の途中で変えれば按分になる
+// 差し戻しや差額のような持ち越しの分も、その期間に入っていれば含まれる
+// 退職したときの最後の Paycheck にも使う
+pub(crate) fn final_pay(
+    emp: &Employee,
+    effective: NaiveDate,
+    last_paid: Option<NaiveDate>,
+    overtime: &OvertimePolicy,
+    calendar: &BusinessCalendar,
+) -> Vec<EarningLine> {
+    let classification = emp.classification().lock().unwrap().clone_classification();
+    let mut classification = classification.lock().unwrap();
+    classification.close(effective);
+
+    let last_day = effective - Days::new(1);
+    let mut day = match last_paid {
+        Some(last_paid) => last_paid + Days::new(1),
+        None => first_unpaid_record(emp).map_or(last_day, |d| d.min(last_day)),
+    };
+    let mut lines: Vec<EarningLine> = vec![];
+    while let Some(pay_date) = day
+        .iter_days()
+        .take(PAY_DATE_SEARCH_DAYS)
+        .find(|d| emp.is_pay_date(*d, calendar))
+    {
+        let pc = Paycheck::new(emp.get_pay_period(pay_date, calendar));
+        debug!("final pay period: {:?}", pc.get_pay_period());
+        for line in classification.calculate_pay(&pc, overtime, calendar) {
+            match lines.iter_mut().find(|l| l.kind == line.kind) {
+                Some(l) => l.amount += line.amount,
+                None => lines.push(line),
+            }
+        }
+        if pay_date >= last_day {
+            break;
+        }
+        day = pay_date + Days::new(1);
+    }
+    lines.retain(|line| !line.amount.is_zero());
+    lines
+}
@@ -16,7 +16,7 @@ pub trait ChangeEmployee: HaveEmployeeDao {
     fn get_id(&self) -> EmployeeId;
     fn change(&self, emp: &mut Employee) -> Result<(), DaoError>;
 
-    fn execute<'a>(&self) -> Result<(), UsecaseError> {
+    fn execute(&self) -> Result<(), UsecaseError> {
         trace!("execute called");
         self.run_tx(|mut ctx| {
             trace!("run_tx called");
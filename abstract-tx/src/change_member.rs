@@ -18,13 +18,20 @@ pub trait ChangeMember: HaveEmployeeDao {
     fn get_affiliation(&self) -> Arc<Mutex<dyn Affiliation>>;
     fn record_membership<'a>(&self, ctx: &mut Self::Ctx<'a>) -> Result<(), DaoError>;
 
-    fn execute<'a>(&self) -> Result<(), UsecaseError> {
+    fn execute(&self) -> Result<(), UsecaseError> {
         trace!("execute called");
         self.run_tx(|mut ctx| {
             trace!("run_tx called");
+            let emp_id = self.get_emp_id();
+            let mut emp = self.dao().fetch(emp_id).run(&mut ctx)?;
+
+            // 古い member_id が残らないように、今の組合員登録を外してから新しい所属を記録する
+            for member_id in self.dao().find_member_ids(emp_id).run(&mut ctx)? {
+                debug!("release union member: {}", member_id);
+                self.dao().delete_union_member(member_id).run(&mut ctx)?;
+            }
             self.record_membership(&mut ctx)?;
 
-            let mut emp = self.dao().fetch(self.get_emp_id()).run(&mut ctx)?;
             debug!(
                 "changing emp member: {:?} -> {:?}",
                 emp.affiliation(),
@@ -0,0 +1,50 @@
+use chrono::Days;
+use log::{debug, trace};
+use std::sync::{Arc, Mutex};
+use tx_rs::Tx;
+
+use crate::{
+    change_classification::first_unpaid_record, terminate_employee::first_unpaid_day, UsecaseError,
+};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{BusinessCalendar, EmployeeId, PaymentSchedule};
+
+// ユースケース: ChangeSchedule トランザクション(抽象レベルのビジネスロジック)
+// 給与の種類とは別に支払いスケジュールだけを変える
+// 新しいスケジュールの最初の支払い期間は最後の支払日の翌日から始まるので、同じ日を二度支払うことはない
+// 一度も支払っていなければ、前のスケジュールでまだ支払っていない最初の支払い期間の初日から始まる
+pub trait ChangeSchedule: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    fn mk_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>>;
+    // 前のスケジュールの支払い期間を決めるのに使う (Payday と同じもの)
+    fn get_calendar(&self) -> &BusinessCalendar;
+
+    fn execute(&self) -> Result<(), UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let id = self.get_id();
+            let mut emp = self.dao().fetch(id).run(&mut ctx)?;
+            // 退職したら最後の Paycheck を支払い済みなので、もう支払日はない
+            if emp.is_terminated() {
+                return Err(DaoError::EmployeeTerminated(id));
+            }
+            let last_paid = self.dao().fetch_last_pay_date(id).run(&mut ctx)?;
+            let start = match last_paid {
+                Some(last_paid) => Some(last_paid + Days::new(1)),
+                None => first_unpaid_record(&emp)
+                    .map(|d| first_unpaid_day(&emp, d, self.get_calendar())),
+            };
+            debug!("changing schedule of emp={:?} from {:?}", emp, start);
+            emp.change_schedule(self.mk_schedule(), start);
+            debug!("schedule changed: {:?}", emp.schedule());
+            self.dao().update(emp).run(&mut ctx)
+        })
+    }
+}
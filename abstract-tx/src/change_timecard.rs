@@ -0,0 +1,63 @@
+use chrono::{Days, NaiveDate};
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::{change_classification::PAY_DATE_SEARCH_DAYS, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Employee, EmployeeId, Paycheck, TimeCardAudit};
+
+// 日付 date を含む期間の支払い済みの Paycheck
+// 休日で支払日が後ろにずれることもあるので、次の支払日を探すのと同じ日数だけ先まで見る
+pub(crate) fn paid_paycheck<'a, D>(
+    dao: &D,
+    ctx: &mut D::Ctx<'a>,
+    emp_id: EmployeeId,
+    date: NaiveDate,
+) -> Result<Option<Paycheck>, DaoError>
+where
+    D: EmployeeDao,
+{
+    trace!("paid_paycheck called");
+    let until = date + Days::new(PAY_DATE_SEARCH_DAYS as u64);
+    Ok(dao
+        .fetch_paychecks(emp_id, date..=until)
+        .run(ctx)?
+        .into_iter()
+        .find(|pc| pc.get_pay_period().contains(&date)))
+}
+
+// ユースケース: ChangeTimeCard トランザクション(抽象レベルのビジネスロジック)
+// タイムカードの訂正と取り消しで共通で、支払い済みの期間の変更は監査記録を残す
+pub trait ChangeTimeCard: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    fn get_date(&self) -> NaiveDate;
+    // 変更後の時間 (None なら取り消し)
+    fn get_hours(&self) -> Option<f32>;
+    // 変更前の時間を返す
+    fn change(&self, emp: &mut Employee) -> Result<f32, DaoError>;
+
+    fn execute(&self) -> Result<(), UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let (id, date) = (self.get_id(), self.get_date());
+            let mut emp = self.dao().fetch(id).run(&mut ctx)?;
+            debug!("changing emp={:?}", emp);
+            let old_hours = self.change(&mut emp)?;
+            debug!("changed emp={:?}", emp);
+
+            if let Some(pc) = paid_paycheck(self.dao(), &mut ctx, id, date)? {
+                let audit = TimeCardAudit::new(date, old_hours, self.get_hours(), pc.pay_date());
+                debug!("paid timecard changed: {:?}", audit);
+                self.dao().record_timecard_audit(id, audit).run(&mut ctx)?;
+            }
+            self.dao().update(emp).run(&mut ctx)
+        })
+    }
+}
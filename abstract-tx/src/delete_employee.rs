@@ -15,12 +15,17 @@ pub trait DeleteEmployee: HaveEmployeeDao {
 
     fn get_id(&self) -> EmployeeId;
 
-    fn execute<'a>(&self) -> Result<(), UsecaseError> {
+    fn execute(&self) -> Result<(), UsecaseError> {
         trace!("execute called");
         self.run_tx(|mut ctx| {
             trace!("run_tx called");
             let emp_id = self.get_id();
             debug!("execute: emp_id={}", emp_id);
+            // 従業員を指したままの組合員登録を残さない
+            for member_id in self.dao().find_member_ids(emp_id).run(&mut ctx)? {
+                debug!("release union member: {}", member_id);
+                self.dao().delete_union_member(member_id).run(&mut ctx)?;
+            }
             self.dao().delete(emp_id).run(&mut ctx)
         })
     }
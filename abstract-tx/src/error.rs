@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 use dao::DaoError;
+use payroll_domain::{DisbursementError, InvalidInput};
 
 #[derive(Debug, Clone, Error)]
 pub enum UsecaseError {
@@ -10,12 +11,50 @@ pub enum UsecaseError {
     ChangeEmployeeFailed(DaoError),
     #[error("delete employee failed: {0}")]
     DeleteEmployeeFailed(DaoError),
+    #[error("terminate employee failed: {0}")]
+    TerminateEmployeeFailed(DaoError),
     #[error("change affiliation failed: {0}")]
     ChangeAffiliationFailed(DaoError),
     #[error("change member failed: {0}")]
     ChangeMemberFailed(DaoError),
     #[error("payday failed: {0}")]
     PaydayFailed(DaoError),
+    #[error("preview payday failed: {0}")]
+    PreviewPaydayFailed(DaoError),
+    #[error("pay off-cycle failed: {0}")]
+    PayOffCycleFailed(DaoError),
     #[error("fetch paycheck failed: {0}")]
     FetchPaycheckFailed(DaoError),
+    #[error("fetch employee failed: {0}")]
+    FetchEmployeeFailed(DaoError),
+    #[error("fetch union member failed: {0}")]
+    FetchUnionMemberFailed(DaoError),
+    #[error("fetch year to date failed: {0}")]
+    FetchYearToDateFailed(DaoError),
+    #[error("disbursement failed: {0}")]
+    DisbursementFailed(DisbursementError),
+    #[error("{0}")]
+    InvalidInput(InvalidInput),
+}
+impl UsecaseError {
+    // どのユースケースで失敗したかに関わらず、原因の DaoError を取り出す
+    // 支払い記録の送出失敗や入力値の誤りは DB とは関係ないので None
+    pub fn dao_error(&self) -> Option<&DaoError> {
+        match self {
+            Self::AddEmployeeFailed(e)
+            | Self::ChangeEmployeeFailed(e)
+            | Self::DeleteEmployeeFailed(e)
+            | Self::TerminateEmployeeFailed(e)
+            | Self::ChangeAffiliationFailed(e)
+            | Self::ChangeMemberFailed(e)
+            | Self::PaydayFailed(e)
+            | Self::PreviewPaydayFailed(e)
+            | Self::PayOffCycleFailed(e)
+            | Self::FetchPaycheckFailed(e)
+            | Self::FetchEmployeeFailed(e)
+            | Self::FetchUnionMemberFailed(e)
+            | Self::FetchYearToDateFailed(e) => Some(e),
+            Self::DisbursementFailed(_) | Self::InvalidInput(_) => None,
+        }
+    }
 }
@@ -0,0 +1,28 @@
+use log::trace;
+use tx_rs::Tx;
+
+use crate::UsecaseError;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{EmployeeId, EmployeeInfo};
+
+// ユースケース: GetEmployee トランザクション(抽象レベルのビジネスロジック)
+pub trait GetEmployee: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+
+    fn execute(&self) -> Result<EmployeeInfo, UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            self.dao()
+                .fetch(self.get_id())
+                .run(&mut ctx)
+                .map(|emp| emp.info())
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use chrono::NaiveDate;
+use log::trace;
+use tx_rs::Tx;
+
+use crate::UsecaseError;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{EmployeeId, Paycheck, RunType};
+
+// ユースケース: GetPaycheck トランザクション(抽象レベルのビジネスロジック)
+pub trait GetPaycheck: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    fn get_pay_date(&self) -> NaiveDate;
+    fn get_run_type(&self) -> RunType;
+
+    fn execute(&self) -> Result<Paycheck, UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            self.dao()
+                .fetch_paycheck(self.get_id(), self.get_pay_date(), self.get_run_type())
+                .run(&mut ctx)
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::UsecaseError;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{EmployeeInfo, MemberId};
+
+// ユースケース: GetUnionMember トランザクション(抽象レベルのビジネスロジック)
+pub trait GetUnionMember: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_member_id(&self) -> MemberId;
+
+    fn execute(&self) -> Result<EmployeeInfo, UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let member_id = self.get_member_id();
+            let emp_id = self.dao().find_union_member(member_id).run(&mut ctx)?;
+            debug!("found emp_id={}", emp_id);
+            self.dao()
+                .fetch(emp_id)
+                .run(&mut ctx)
+                .map(|emp| emp.info())
+                .map_err(|e| match e {
+                    DaoError::EmployeeNotFound(emp_id) => {
+                        DaoError::DanglingUnionMember(member_id, emp_id)
+                    }
+                    e => e,
+                })
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use log::trace;
+use tx_rs::Tx;
+
+use crate::UsecaseError;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{EmployeeId, YearToDate};
+
+// ユースケース: GetYearToDate トランザクション(抽象レベルのビジネスロジック)
+pub trait GetYearToDate: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    fn get_year(&self) -> i32;
+
+    fn execute(&self) -> Result<YearToDate, UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            // 存在しない従業員は 0 の累計ではなくエラーにする
+            self.dao().fetch(self.get_id()).run(&mut ctx)?;
+            self.dao()
+                .fetch_year_to_date(self.get_id(), self.get_year())
+                .run(&mut ctx)
+        })
+    }
+}
@@ -1,15 +1,42 @@
 mod add_employee;
 mod change_affiliation;
+mod change_classification;
 mod change_employee;
 mod change_member;
+mod change_schedule;
+mod change_timecard;
 mod delete_employee;
 mod error;
+mod get_employee;
+mod get_paycheck;
+mod get_union_member;
+mod get_year_to_date;
+mod list_employees;
+mod pay_off_cycle;
 mod payday;
+mod preview_payday;
+mod reversal;
+mod terminate_employee;
+mod void_sales_receipt;
+mod void_service_charge;
 
 pub use add_employee::*;
 pub use change_affiliation::*;
+pub use change_classification::*;
 pub use change_employee::*;
 pub use change_member::*;
+pub use change_schedule::*;
+pub use change_timecard::*;
 pub use delete_employee::*;
 pub use error::*;
+pub use get_employee::*;
+pub use get_paycheck::*;
+pub use get_union_member::*;
+pub use get_year_to_date::*;
+pub use list_employees::*;
+pub use pay_off_cycle::*;
 pub use payday::*;
+pub use preview_payday::*;
+pub use terminate_employee::*;
+pub use void_sales_receipt::*;
+pub use void_service_charge::*;
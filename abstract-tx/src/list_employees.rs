@@ -0,0 +1,37 @@
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::UsecaseError;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{EmployeeFilter, EmployeeInfo};
+
+// ユースケース: ListEmployees トランザクション(抽象レベルのビジネスロジック)
+pub trait ListEmployees: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_filter(&self) -> EmployeeFilter;
+
+    fn execute(&self) -> Result<Vec<EmployeeInfo>, UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let filter = self.get_filter();
+            let mut emps = self
+                .dao()
+                .fetch_all()
+                .run(&mut ctx)?
+                .into_iter()
+                .map(|(_, emp)| emp.info())
+                .filter(|emp| filter.matches(emp))
+                .collect::<Vec<_>>();
+            // DB によって返る順序が違うので id 順にそろえる
+            emps.sort_by_key(|emp| emp.id);
+            debug!("found {} employees for filter={}", emps.len(), filter);
+            Ok(emps)
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::UsecaseError;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{
+    EarningKind, EarningLine, EmployeeId, InvalidInput, Money, PayRecord, Paycheck, RunType,
+    Withholding, YearToDate,
+};
+
+// ユースケース: PayOffCycle トランザクション(抽象レベルのビジネスロジック)
+// 定期の Payday とは別に、指定した従業員に指定した額の賞与・精算を支払う
+// 勤務の期間に対する支払いではないので、組合費やサービス料は差し引かない
+// Paycheck は (従業員, 支払日, 種類) で記録するので、同じ日の 2 回目の賞与・精算は PaycheckAlreadyExists になる
+pub trait PayOffCycle: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    // Bonus か Correction (定期の支払いと最後の支払いは Payday と TerminateEmployee で行う)
+    fn get_run_type(&self) -> RunType;
+    // 支払日で、支払いの対象の期間の最終日にもなる
+    fn get_date(&self) -> NaiveDate;
+    fn get_amount(&self) -> Money;
+    // 支払いの対象の期間の初日 (省略したら支払日の 1 日だけ)
+    fn get_period_start(&self) -> Option<NaiveDate>;
+    fn get_withholding(&self) -> &dyn Withholding;
+    // 支払い記録を送り出す (コミット後に呼ばれる)
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), UsecaseError>;
+
+    fn execute(&self) -> Result<Paycheck, UsecaseError> {
+        trace!("execute called");
+        let run_type = self.get_run_type();
+        let kind = earning_kind(run_type).ok_or_else(|| {
+            UsecaseError::InvalidInput(InvalidInput::new(format!(
+                "not an off-cycle run type: {}",
+                run_type
+            )))
+        })?;
+        let (pc, record) = self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let id = self.get_id();
+            let date = self.get_date();
+            let period = self.get_period_start().unwrap_or(date)..=date;
+            let emp = self.dao().fetch(id).run(&mut ctx)?;
+            // 入社前の期間や、期間の初日が支払日より後のものは支払えない
+            if period.is_empty() || emp.hire_date().is_some_and(|d| *period.start() < d) {
+                return Err(DaoError::InvalidEffectiveDate(id, *period.start()));
+            }
+
+            let mut pc = Paycheck::new(period).with_run_type(run_type);
+            debug!("off-cycle paycheck: {:?}", pc);
+            let mut ytd = self
+                .dao()
+                .fetch_year_to_date(id, YearToDate::year_of(pc.pay_date()))
+                .run(&mut ctx)?;
+            let earning_lines = vec![EarningLine::new(kind, self.get_amount())];
+            let record =
+                emp.off_cycle_payday(&mut pc, earning_lines, self.get_withholding(), &mut ytd);
+            self.dao().record_paycheck(id, pc.clone()).run(&mut ctx)?;
+            self.dao().update_year_to_date(id, ytd).run(&mut ctx)?;
+            Ok((pc, record))
+        })?;
+
+        // 送り出しに失敗しても Paycheck は記録済みなので GetPaycheck で確かめられる
+        self.disburse(&[record])?;
+        Ok(pc)
+    }
+}
+
+fn earning_kind(run_type: RunType) -> Option<EarningKind> {
+    match run_type {
+        RunType::Bonus => Some(EarningKind::Bonus),
+        RunType::Correction => Some(EarningKind::Adjustment),
+        RunType::Regular | RunType::Final => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_earning_kind() {
+        assert_eq!(earning_kind(RunType::Bonus), Some(EarningKind::Bonus));
+        assert_eq!(
+            earning_kind(RunType::Correction),
+            Some(EarningKind::Adjustment)
+        );
+        // 定期の支払いと最後の支払いは PayOffCycle では扱わない
+        assert_eq!(earning_kind(RunType::Regular), None);
+        assert_eq!(earning_kind(RunType::Final), None);
+    }
+}
@@ -1,10 +1,24 @@
 use chrono::NaiveDate;
 use log::{debug, trace};
+use std::collections::HashMap;
 use tx_rs::Tx;
 
 use crate::UsecaseError;
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::Paycheck;
+use payroll_domain::{
+    BusinessCalendar, EmployeeId, OvertimePolicy, PayRecord, Paycheck, RerunPolicy, Withholding,
+    YearToDate,
+};
+
+// Payday の実行結果: 従業員ごとにどう扱われたか
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaydayOutcome {
+    pub paid: Vec<EmployeeId>,
+    pub skipped: Vec<EmployeeId>,
+    pub replayed: Vec<EmployeeId>,
+    // 支払った (再送した) 分の支払い記録
+    pub records: Vec<PayRecord>,
+}
 
 // ユースケース: Payday トランザクション(抽象レベルのビジネスロジック)
 pub trait Payday: HaveEmployeeDao {
@@ -15,24 +29,100 @@ pub trait Payday: HaveEmployeeDao {
         F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
 
     fn get_pay_date(&self) -> NaiveDate;
+    fn get_rerun_policy(&self) -> RerunPolicy;
+    fn get_withholding(&self) -> &dyn Withholding;
+    // 会社全体の残業の規則 (従業員ごとの規則がない従業員に使う)
+    fn get_overtime_policy(&self) -> &OvertimePolicy;
+    // 休日に当たる支払日をずらす会社の暦
+    fn get_calendar(&self) -> &BusinessCalendar;
+    // 支払い記録を送り出す (コミット後に呼ばれる)
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), UsecaseError>;
 
-    fn execute<'a>(&self) -> Result<(), UsecaseError> {
+    fn execute(&self) -> Result<PaydayOutcome, UsecaseError> {
         trace!("execute called");
-        self.run_tx(|mut ctx| {
+        let outcome = self.run_tx(|mut ctx| {
             trace!("run_tx called");
-            let mut emps = self.dao().fetch_all().run(&mut ctx)?;
-            let paydate = self.get_pay_date();
-
-            for (emp_id, emp) in emps.iter_mut() {
-                if emp.is_pay_date(paydate) {
-                    debug!("execute: payday for emp_id={}", emp_id);
-                    let period = emp.get_pay_period(paydate);
-                    let mut pc = Paycheck::new(period);
-                    emp.payday(&mut pc);
-                    self.dao().record_paycheck(*emp_id, pc).run(&mut ctx)?;
-                }
+            pay_all(
+                self,
+                &mut ctx,
+                self.get_pay_date(),
+                self.get_rerun_policy(),
+                self.get_withholding(),
+                self.get_overtime_policy(),
+                self.get_calendar(),
+            )
+        })?;
+
+        // 送り出しに失敗しても Paycheck は記録済みなので、Replay で送り直せる
+        debug!("execute: disburse {} records", outcome.records.len());
+        self.disburse(&outcome.records)?;
+        Ok(outcome)
+    }
+}
+
+// 支払日の従業員全員に支払って Paycheck と累計を記録する (送り出しは呼び出し側が行う)
+// Payday と、巻き戻すトランザクションで同じ計算をする PreviewPayday で共有する
+pub(crate) fn pay_all<'a, T>(
+    tx: &T,
+    ctx: &mut T::Ctx<'a>,
+    paydate: NaiveDate,
+    policy: RerunPolicy,
+    withholding: &dyn Withholding,
+    overtime: &OvertimePolicy,
+    calendar: &BusinessCalendar,
+) -> Result<PaydayOutcome, DaoError>
+where
+    T: HaveEmployeeDao + ?Sized,
+{
+    let mut emps = tx.dao().fetch_all().run(ctx)?;
+
+    // 何かを支払う前に、この支払日の Paycheck が既に記録されているかを確認する
+    let mut recorded = tx
+        .dao()
+        .fetch_paychecks_for_run(paydate)
+        .run(ctx)?
+        .into_iter()
+        .collect::<HashMap<EmployeeId, Paycheck>>();
+    if policy == RerunPolicy::Reject {
+        if let Some((emp_id, _)) = emps.iter().find(|(emp_id, emp)| {
+            emp.is_pay_date(paydate, calendar) && recorded.contains_key(emp_id)
+        }) {
+            debug!("execute: already paid emp_id={}", emp_id);
+            return Err(DaoError::PaycheckAlreadyExists(*emp_id, paydate));
+        }
+    }
+
+    let mut outcome = PaydayOutcome::default();
+    for (emp_id, emp) in emps.iter_mut() {
+        if !emp.is_pay_date(paydate, calendar) {
+            continue;
+        }
+        match recorded.remove(emp_id) {
+            Some(pc) if policy == RerunPolicy::Replay => {
+                debug!("execute: replay paycheck for emp_id={}", emp_id);
+                outcome.records.push(emp.repay(&pc));
+                outcome.replayed.push(*emp_id);
+            }
+            Some(_) => {
+                debug!("execute: skip already paid emp_id={}", emp_id);
+                outcome.skipped.push(*emp_id);
+            }
+            None => {
+                debug!("execute: payday for emp_id={}", emp_id);
+                let period = emp.get_pay_period(paydate, calendar);
+                let mut pc = Paycheck::new(period);
+                // 累計は Paycheck と同じトランザクションで更新する
+                let mut ytd = tx
+                    .dao()
+                    .fetch_year_to_date(*emp_id, YearToDate::year_of(paydate))
+                    .run(ctx)?;
+                let record = emp.payday(&mut pc, withholding, overtime, calendar, &mut ytd);
+                tx.dao().record_paycheck(*emp_id, pc).run(ctx)?;
+                tx.dao().update_year_to_date(*emp_id, ytd).run(ctx)?;
+                outcome.paid.push(*emp_id);
+                outcome.records.push(record);
             }
-            Ok(())
-        })
+        }
     }
+    Ok(outcome)
 }
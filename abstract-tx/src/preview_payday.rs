@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+use std::collections::HashMap;
+use tx_rs::Tx;
+
+use crate::{pay_all, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{
+    BusinessCalendar, LastPay, OvertimePolicy, PayPreview, PaydayPreview, RerunPolicy, Withholding,
+};
+
+// ユースケース: PreviewPayday トランザクション(抽象レベルのビジネスロジック)
+// Payday と同じ計算をして、記録した Paycheck と累計は巻き戻す (送り出しもしない)
+pub trait PreviewPayday: HaveEmployeeDao {
+    // 必ず巻き戻すトランザクション (EmployeeDao::run_tx_rollback) で f を実行する
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_pay_date(&self) -> NaiveDate;
+    fn get_withholding(&self) -> &dyn Withholding;
+    // 会社全体の残業の規則 (従業員ごとの規則がない従業員に使う)
+    fn get_overtime_policy(&self) -> &OvertimePolicy;
+    // 休日に当たる支払日をずらす会社の暦
+    fn get_calendar(&self) -> &BusinessCalendar;
+
+    fn execute(&self) -> Result<PaydayPreview, UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let paydate = self.get_pay_date();
+
+            // 比べる相手は試算の支払日より前の直近の支払い (試算で記録する前に取っておく)
+            let mut names = HashMap::new();
+            let mut lasts = HashMap::new();
+            for (emp_id, emp) in self.dao().fetch_all().run(&mut ctx)? {
+                if !emp.is_pay_date(paydate, self.get_calendar()) {
+                    continue;
+                }
+                names.insert(emp_id, emp.name().to_string());
+                let Some(last_date) = self.dao().fetch_last_pay_date(emp_id).run(&mut ctx)? else {
+                    continue;
+                };
+                if last_date < paydate {
+                    let last = self
+                        .dao()
+                        .fetch_paychecks(emp_id, last_date..=last_date)
+                        .run(&mut ctx)?
+                        .pop();
+                    if let Some(pc) = last {
+                        lasts.insert(
+                            emp_id,
+                            LastPay {
+                                pay_date: last_date,
+                                amounts: (&pc).into(),
+                            },
+                        );
+                    }
+                }
+            }
+
+            // 支払い済みの従業員は、実際に実行したときと同じく支払わずに飛ばす
+            let outcome = pay_all(
+                self,
+                &mut ctx,
+                paydate,
+                RerunPolicy::Skip,
+                self.get_withholding(),
+                self.get_overtime_policy(),
+                self.get_calendar(),
+            )?;
+            debug!("execute: preview {} records", outcome.records.len());
+            let mut previews = outcome
+                .records
+                .iter()
+                .map(|record| PayPreview {
+                    emp_id: record.emp_id,
+                    name: names.remove(&record.emp_id).unwrap_or_default(),
+                    amounts: record.into(),
+                    last: lasts.remove(&record.emp_id),
+                })
+                .collect::<Vec<_>>();
+            previews.sort_by_key(|p| p.emp_id);
+            Ok(PaydayPreview {
+                pay_date: paydate,
+                previews,
+                skipped: outcome.skipped,
+            })
+        })
+    }
+}
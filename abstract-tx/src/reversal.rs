@@ -0,0 +1,27 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::change_timecard::paid_paycheck;
+use dao::{DaoError, EmployeeDao};
+use payroll_domain::EmployeeId;
+
+// 売上・サービス料の取り消しで共通: 日付 date の分が支払い済みなら最後の支払日を返す
+// 支払い済みのものは最後の支払日の次の支払い期間で差し戻すことになる
+pub(crate) fn last_paid<'a, D>(
+    dao: &D,
+    ctx: &mut D::Ctx<'a>,
+    emp_id: EmployeeId,
+    date: NaiveDate,
+) -> Result<Option<NaiveDate>, DaoError>
+where
+    D: EmployeeDao,
+{
+    trace!("last_paid called");
+    if paid_paycheck(dao, ctx, emp_id, date)?.is_none() {
+        return Ok(None);
+    }
+    let last_paid = dao.fetch_last_pay_date(emp_id).run(ctx)?;
+    debug!("{} already paid: last pay date={:?}", date, last_paid);
+    Ok(last_paid)
+}
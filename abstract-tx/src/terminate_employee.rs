@@ -0,0 +1,126 @@
+use chrono::{Days, NaiveDate};
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::{
+    change_classification::{final_pay, first_unpaid_record, PAY_DATE_SEARCH_DAYS},
+    UsecaseError,
+};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{
+    BusinessCalendar, Employee, EmployeeId, OvertimePolicy, PayRecord, Paycheck, RunType,
+    Withholding, YearToDate,
+};
+
+// ユースケース: TerminateEmployee トランザクション(抽象レベルのビジネスロジック)
+// 退職日までのまだ支払っていない分を、定期の Payday を待たずに最後の Paycheck で支払う
+// 記録は残したまま、以降の Payday の対象から外す
+pub trait TerminateEmployee: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    // 退職日 (最後に勤務した日で、最後の Paycheck の支払日になる)
+    fn get_date(&self) -> NaiveDate;
+    // 今日 (これより後の退職日は受け付けない)
+    fn get_today(&self) -> NaiveDate;
+    fn get_withholding(&self) -> &dyn Withholding;
+    // 会社全体の残業の規則 (従業員ごとの規則がない従業員に使う)
+    fn get_overtime_policy(&self) -> &OvertimePolicy;
+    // 休日に当たる支払日をずらす会社の暦
+    fn get_calendar(&self) -> &BusinessCalendar;
+    // 支払い記録を送り出す (コミット後に呼ばれる)
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), UsecaseError>;
+
+    // 退職日までを支払い済みなら最後の Paycheck はなく None を返す
+    fn execute(&self) -> Result<Option<Paycheck>, UsecaseError> {
+        trace!("execute called");
+        let paid = self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let id = self.get_id();
+            let date = self.get_date();
+            let mut emp = self.dao().fetch(id).run(&mut ctx)?;
+            if emp.is_terminated() {
+                return Err(DaoError::EmployeeTerminated(id));
+            }
+            // 入社前や、支払い済みの期間・勤務の記録のある日より前には退職日を置けない
+            // 最後の Paycheck をすぐに支払うので、まだ来ていない日も退職日にできない
+            let last_paid = self.dao().fetch_last_pay_date(id).run(&mut ctx)?;
+            let last_record = emp.classification().lock().unwrap().last_record_date();
+            if last_paid.max(last_record).is_some_and(|d| date < d)
+                || emp.hire_date().is_some_and(|d| date < d)
+                || self.get_today() < date
+            {
+                return Err(DaoError::InvalidEffectiveDate(id, date));
+            }
+
+            let next_day = date + Days::new(1);
+            let paid = match last_paid {
+                Some(last_paid) if date <= last_paid => None,
+                _ => {
+                    let overtime = emp
+                        .overtime_policy()
+                        .unwrap_or(self.get_overtime_policy())
+                        .clone();
+                    let earning_lines =
+                        final_pay(&emp, next_day, last_paid, &overtime, self.get_calendar());
+                    let start = match last_paid {
+                        Some(last_paid) => last_paid + Days::new(1),
+                        None => first_unpaid_day(&emp, date, self.get_calendar()),
+                    };
+                    let mut pc = Paycheck::new(start..=date).with_run_type(RunType::Final);
+                    debug!("final paycheck period: {:?}", pc.get_pay_period());
+                    let mut ytd = self
+                        .dao()
+                        .fetch_year_to_date(id, YearToDate::year_of(date))
+                        .run(&mut ctx)?;
+                    let record = emp.off_cycle_payday(
+                        &mut pc,
+                        earning_lines,
+                        self.get_withholding(),
+                        &mut ytd,
+                    );
+                    self.dao().record_paycheck(id, pc.clone()).run(&mut ctx)?;
+                    self.dao().update_year_to_date(id, ytd).run(&mut ctx)?;
+                    Some((pc, record))
+                }
+            };
+            debug!("execute: terminate emp_id={} on {}", id, date);
+            emp.terminate_on(date);
+            self.dao().update(emp).run(&mut ctx)?;
+            Ok(paid)
+        })?;
+
+        let Some((pc, record)) = paid else {
+            return Ok(None);
+        };
+        // 送り出しに失敗しても Paycheck は記録済みなので GetPaycheck で確かめられる
+        self.disburse(&[record])?;
+        Ok(Some(pc))
+    }
+}
+
+// 一度も支払っていない従業員の最後の Paycheck の期間の初日
+// 入社日か最初の勤務の記録の早い方を含む定期の支払い期間の初日から (入社日があればその早い方の日から)
+// どちらもなければ退職日を含む定期の支払い期間の初日から
+pub(crate) fn first_unpaid_day(
+    emp: &Employee,
+    date: NaiveDate,
+    calendar: &BusinessCalendar,
+) -> NaiveDate {
+    let first_day = first_unpaid_record(emp).unwrap_or(date);
+    let start = first_day
+        .iter_days()
+        .take(PAY_DATE_SEARCH_DAYS)
+        .find(|d| emp.is_pay_date(*d, calendar))
+        .map_or(first_day, |pay_date| {
+            *emp.get_pay_period(pay_date, calendar).start()
+        });
+    match emp.hire_date() {
+        Some(_) => start.max(first_day),
+        None => start,
+    }
+}
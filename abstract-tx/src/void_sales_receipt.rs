@@ -0,0 +1,37 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+use tx_rs::Tx;
+
+use crate::{reversal::last_paid, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Employee, EmployeeId};
+
+// ユースケース: VoidSalesReceipt トランザクション(抽象レベルのビジネスロジック)
+pub trait VoidSalesReceipt: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_id(&self) -> EmployeeId;
+    // 取り消す売上の日付
+    fn receipt_date(&self, emp: &Employee) -> Result<NaiveDate, DaoError>;
+    // last_paid は売上が支払い済みなら最後の支払日
+    fn void(&self, emp: &mut Employee, last_paid: Option<NaiveDate>) -> Result<(), DaoError>;
+
+    fn execute(&self) -> Result<(), UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let id = self.get_id();
+            let mut emp = self.dao().fetch(id).run(&mut ctx)?;
+            let date = self.receipt_date(&emp)?;
+            let last_paid = last_paid(self.dao(), &mut ctx, id, date)?;
+            debug!("voiding emp={:?}", emp);
+            self.void(&mut emp, last_paid)?;
+            debug!("voided emp={:?}", emp);
+            self.dao().update(emp).run(&mut ctx)
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+use std::sync::{Arc, Mutex};
+use tx_rs::Tx;
+
+use crate::{reversal::last_paid, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Affiliation, MemberId};
+
+// ユースケース: VoidServiceCharge トランザクション(抽象レベルのビジネスロジック)
+pub trait VoidServiceCharge: HaveEmployeeDao {
+    // TODO: このレイヤはユースケースで、本来 run_tx はサービスレベルにあるべき
+    // そしてサービスレベルの実装は EmployeeDao トレイトではなく具体的な Db 構造体を相手に run_tx を実装するべき
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, UsecaseError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+
+    fn get_member_id(&self) -> MemberId;
+    // 取り消すサービス料の日付
+    fn charge_date(&self, aff: Arc<Mutex<dyn Affiliation>>) -> Result<NaiveDate, DaoError>;
+    // last_paid はサービス料が支払い済みなら最後の支払日
+    fn void(
+        &self,
+        aff: Arc<Mutex<dyn Affiliation>>,
+        last_paid: Option<NaiveDate>,
+    ) -> Result<(), DaoError>;
+
+    fn execute(&self) -> Result<(), UsecaseError> {
+        trace!("execute called");
+        self.run_tx(|mut ctx| {
+            trace!("run_tx called");
+            let member_id = self.get_member_id();
+            let emp_id = self.dao().find_union_member(member_id).run(&mut ctx)?;
+            debug!("found emp_id={}", emp_id);
+            let emp = self
+                .dao()
+                .fetch(emp_id)
+                .run(&mut ctx)
+                .map_err(|e| match e {
+                    DaoError::EmployeeNotFound(emp_id) => {
+                        DaoError::DanglingUnionMember(member_id, emp_id)
+                    }
+                    e => e,
+                })?;
+            let date = self.charge_date(emp.affiliation())?;
+            let last_paid = last_paid(self.dao(), &mut ctx, emp_id, date)?;
+            debug!("voiding emp={:?}", emp);
+            self.void(emp.affiliation(), last_paid)?;
+            debug!("voided emp={:?}", emp);
+            self.dao().update(emp).run(&mut ctx)
+        })
+    }
+}
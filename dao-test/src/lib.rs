@@ -0,0 +1,642 @@
+// EmployeeDao の実装が共通に満たすべき振る舞いのテスト
+// 各 DB の実装は dao_conformance! でここのテストを自分のテストとして実行する
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use dao::{DaoError, EmployeeDao};
+use payroll_domain::{
+    DeductionKind, DeductionLine, EarningKind, EarningLine, FilingStatus, Money, NoWithholding,
+    OvertimePolicy, Paycheck, RerunPolicy, RunType, TaxProfile, TimeCardAudit, Withholding,
+    YearToDate,
+};
+use payroll_impl::{MemorySink, PayrollFactoryImpl, TableWithholding, WithholdingRule};
+use tx_app::{Response, Transaction};
+use tx_impl::{
+    AddHourlyEmployeeTx, AddServiceChargeTx, AddTimeCardTx, AmendTimeCardTx, ChangeEmployeeTaxTx,
+    ChangeMemberTx, DeleteEmployeeTx, PayOffCycleTx, PaydayTx, PreviewPaydayTx,
+    VoidServiceChargeTx,
+};
+use tx_rs::Tx;
+
+// $open はテストの名前を受け取って空の DB を返す
+// $cleanup を渡せば、テストが終わったあとにテストの名前で呼ぶ
+#[macro_export]
+macro_rules! dao_conformance {
+    ($open:expr) => {
+        $crate::dao_conformance!($open, |_: &str| ());
+    };
+    ($open:expr, $cleanup:expr) => {
+        mod dao_conformance {
+            use super::*;
+
+            $crate::dao_conformance!(
+                @tests $open, $cleanup;
+                test_add_and_delete_employee,
+                test_union_members,
+                test_find_member_ids,
+                test_fetch_paycheck,
+                test_record_paycheck_twice,
+                test_fetch_paychecks,
+                test_fetch_paychecks_for_run,
+                test_rollback_partial_run,
+                test_year_to_date,
+                test_tax_profile,
+                test_payday,
+                test_payday_rerun_rejected,
+                test_preview_payday_rolls_back,
+                test_off_cycle_paycheck,
+                test_amend_paid_timecard,
+                test_void_paid_service_charge,
+                test_delete_employee_keeps_pay_history
+            );
+        }
+    };
+    (@tests $open:expr, $cleanup:expr; $($name:ident),*) => {
+        $(
+            #[test]
+            fn $name() {
+                let name = stringify!($name);
+                $crate::$name(($open)(name));
+                ($cleanup)(name);
+            }
+        )*
+    };
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+fn paycheck(start: NaiveDate, end: NaiveDate, gross_cents: i64) -> Paycheck {
+    let mut pc = Paycheck::new(start..=end);
+    pc.set_earning_lines(vec![EarningLine::new(
+        EarningKind::Salary,
+        Money::from_cents(gross_cents),
+    )]);
+    pc
+}
+// 以下の 2 つは DB ごとのテストでも使う
+pub fn add_hourly<D>(db: &D, id: u32)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    AddHourlyEmployeeTx::new(
+        id.into(),
+        "Bob",
+        "Home",
+        Money::from_cents(1575),
+        db.clone(),
+        PayrollFactoryImpl,
+    )
+    .execute()
+    .unwrap();
+}
+// 時給 15.75 で 1/30 に 8h, 1/31 に 10h 働き、組合費 9.25 とサービス料 19.95 のある組合員
+pub fn add_member<D>(db: &D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(db, 1);
+    let txs: Vec<Box<dyn Transaction>> = vec![
+        Box::new(AddTimeCardTx::new(
+            1.into(),
+            date(2025, 1, 30),
+            8.0,
+            db.clone(),
+        )),
+        Box::new(AddTimeCardTx::new(
+            1.into(),
+            date(2025, 1, 31),
+            10.0,
+            db.clone(),
+        )),
+        Box::new(ChangeMemberTx::new(
+            7734.into(),
+            1.into(),
+            Money::from_cents(925),
+            db.clone(),
+            PayrollFactoryImpl,
+        )),
+        Box::new(AddServiceChargeTx::new(
+            7734.into(),
+            date(2025, 1, 31),
+            Money::from_cents(1995),
+            db.clone(),
+        )),
+    ];
+    for tx in txs {
+        tx.execute().unwrap();
+    }
+}
+fn pension() -> TableWithholding {
+    TableWithholding::new(vec![WithholdingRule::Flat {
+        name: "Pension".to_string(),
+        rate: 0.1,
+    }])
+    .unwrap()
+}
+fn payday<D>(db: &D, pay_date: NaiveDate, withholding: Arc<dyn Withholding>) -> Response
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    PaydayTx::new(
+        pay_date,
+        RerunPolicy::Reject,
+        db.clone(),
+        Arc::new(MemorySink::new()),
+        withholding,
+        Arc::new(OvertimePolicy::default()),
+    )
+    .execute()
+    .unwrap()
+}
+fn fetch_paycheck<D>(db: &D, pay_date: NaiveDate, run_type: RunType) -> Paycheck
+where
+    D: EmployeeDao,
+{
+    db.run_tx(|mut ctx| {
+        db.fetch_paycheck(1.into(), pay_date, run_type)
+            .run(&mut ctx)
+    })
+    .unwrap()
+}
+
+pub fn test_add_and_delete_employee<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    add_hourly(&db, 2);
+    let result = db.run_tx(|mut ctx| db.fetch_all().run(&mut ctx));
+    let mut ids: Vec<_> = result.unwrap().into_iter().map(|(id, _)| id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![1.into(), 2.into()]);
+
+    let result = db.run_tx(|mut ctx| {
+        let mut emp = db.fetch(1.into()).run(&mut ctx)?;
+        emp.set_name("Robert");
+        db.update(emp).run(&mut ctx)?;
+        db.fetch(1.into()).run(&mut ctx)
+    });
+    assert_eq!(result.unwrap().name(), "Robert");
+
+    db.run_tx(|mut ctx| db.delete(1.into()).run(&mut ctx))
+        .unwrap();
+    let result = db.run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx));
+    assert!(matches!(result, Err(DaoError::EmployeeNotFound(id)) if id == 1.into()));
+    let result = db.run_tx(|mut ctx| db.delete(1.into()).run(&mut ctx));
+    assert!(matches!(result, Err(DaoError::EmployeeNotFound(_))));
+}
+
+pub fn test_union_members<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    let result = db.run_tx(|mut ctx| {
+        db.add_union_member(7734.into(), 1.into()).run(&mut ctx)?;
+        let emp_id = db.find_union_member(7734.into()).run(&mut ctx)?;
+        let member_ids = db.find_member_ids(1.into()).run(&mut ctx)?;
+        Ok((emp_id, member_ids))
+    });
+    assert_eq!(result.unwrap(), (1.into(), vec![7734.into()]));
+
+    let result = db.run_tx(|mut ctx| db.add_union_member(7734.into(), 1.into()).run(&mut ctx));
+    assert!(matches!(result, Err(DaoError::MemberAlreadyExists(..))));
+
+    db.run_tx(|mut ctx| db.delete_union_member(7734.into()).run(&mut ctx))
+        .unwrap();
+    let result = db.run_tx(|mut ctx| db.find_union_member(7734.into()).run(&mut ctx));
+    assert!(matches!(result, Err(DaoError::MemberNotFound(_))));
+    let result = db.run_tx(|mut ctx| db.find_member_ids(1.into()).run(&mut ctx));
+    assert_eq!(result.unwrap(), vec![]);
+}
+
+pub fn test_find_member_ids<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    add_hourly(&db, 2);
+    db.run_tx(|mut ctx| {
+        db.add_union_member(8000.into(), 1.into()).run(&mut ctx)?;
+        db.add_union_member(7234.into(), 1.into()).run(&mut ctx)?;
+        db.add_union_member(7777.into(), 2.into()).run(&mut ctx)
+    })
+    .unwrap();
+    let member_ids = |emp_id: u32| {
+        db.run_tx(|mut ctx| db.find_member_ids(emp_id.into()).run(&mut ctx))
+            .unwrap()
+    };
+    // member_id の順に返す
+    assert_eq!(member_ids(1), vec![7234.into(), 8000.into()]);
+    assert_eq!(member_ids(2), vec![7777.into()]);
+    assert_eq!(member_ids(3), vec![]);
+}
+
+pub fn test_fetch_paycheck<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    let jan = paycheck(date(2025, 1, 1), date(2025, 1, 31), 100000);
+    let feb = paycheck(date(2025, 2, 1), date(2025, 2, 28), 110000);
+    let result = db.run_tx(|mut ctx| {
+        db.record_paycheck(1.into(), jan.clone()).run(&mut ctx)?;
+        db.record_paycheck(1.into(), feb.clone()).run(&mut ctx)?;
+        db.fetch_paycheck(1.into(), date(2025, 2, 28), RunType::Regular)
+            .run(&mut ctx)
+    });
+    assert_eq!(result.unwrap(), feb);
+
+    let result = db.run_tx(|mut ctx| {
+        db.fetch_paycheck(1.into(), date(2025, 2, 27), RunType::Regular)
+            .run(&mut ctx)
+    });
+    assert!(matches!(
+        result,
+        Err(DaoError::PaycheckNotFound(emp_id, pay_date))
+            if emp_id == 1.into() && pay_date == date(2025, 2, 27)
+    ));
+    let result = db.run_tx(|mut ctx| {
+        db.fetch_paycheck(1.into(), date(2025, 2, 28), RunType::Bonus)
+            .run(&mut ctx)
+    });
+    assert!(matches!(result, Err(DaoError::PaycheckNotFound(..))));
+    let result = db.run_tx(|mut ctx| {
+        db.fetch_paycheck(2.into(), date(2025, 2, 28), RunType::Regular)
+            .run(&mut ctx)
+    });
+    assert!(matches!(result, Err(DaoError::PaycheckNotFound(..))));
+}
+
+pub fn test_record_paycheck_twice<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    let first = paycheck(date(2025, 1, 1), date(2025, 1, 31), 100000);
+    let second = paycheck(date(2025, 1, 1), date(2025, 1, 31), 200000);
+    db.run_tx(|mut ctx| db.record_paycheck(1.into(), first.clone()).run(&mut ctx))
+        .unwrap();
+    let result = db.run_tx(|mut ctx| db.record_paycheck(1.into(), second).run(&mut ctx));
+    assert!(matches!(
+        result,
+        Err(DaoError::PaycheckAlreadyExists(emp_id, pay_date))
+            if emp_id == 1.into() && pay_date == date(2025, 1, 31)
+    ));
+    assert_eq!(
+        fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular),
+        first
+    );
+}
+
+pub fn test_fetch_paychecks<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    let jan = paycheck(date(2025, 1, 1), date(2025, 1, 31), 100000);
+    let feb = paycheck(date(2025, 2, 1), date(2025, 2, 28), 110000);
+    let mar = paycheck(date(2025, 3, 1), date(2025, 3, 31), 120000);
+    let result = db.run_tx(|mut ctx| {
+        db.record_paycheck(1.into(), jan.clone()).run(&mut ctx)?;
+        db.record_paycheck(1.into(), feb.clone()).run(&mut ctx)?;
+        db.record_paycheck(1.into(), mar.clone()).run(&mut ctx)?;
+        db.fetch_paychecks(1.into(), date(2025, 2, 1)..=date(2025, 3, 31))
+            .run(&mut ctx)
+    });
+    assert_eq!(result.unwrap(), vec![feb, mar]);
+    let result = db.run_tx(|mut ctx| db.fetch_last_pay_date(1.into()).run(&mut ctx));
+    assert_eq!(result.unwrap(), Some(date(2025, 3, 31)));
+
+    let result = db.run_tx(|mut ctx| {
+        db.fetch_paychecks(2.into(), date(2025, 1, 1)..=date(2025, 12, 31))
+            .run(&mut ctx)
+    });
+    assert_eq!(result.unwrap(), vec![]);
+    let result = db.run_tx(|mut ctx| db.fetch_last_pay_date(2.into()).run(&mut ctx));
+    assert_eq!(result.unwrap(), None);
+}
+
+pub fn test_fetch_paychecks_for_run<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    add_hourly(&db, 2);
+    let monthly = paycheck(date(2025, 1, 1), date(2025, 1, 31), 100000);
+    let weekly = paycheck(date(2025, 1, 25), date(2025, 1, 31), 20000);
+    let previous = paycheck(date(2025, 1, 18), date(2025, 1, 24), 15000);
+    let result = db.run_tx(|mut ctx| {
+        db.record_paycheck(2.into(), previous.clone())
+            .run(&mut ctx)?;
+        db.record_paycheck(2.into(), weekly.clone()).run(&mut ctx)?;
+        db.record_paycheck(1.into(), monthly.clone())
+            .run(&mut ctx)?;
+        db.fetch_paychecks_for_run(date(2025, 1, 31)).run(&mut ctx)
+    });
+    assert_eq!(
+        result.unwrap(),
+        vec![(1.into(), monthly), (2.into(), weekly)]
+    );
+}
+
+pub fn test_rollback_partial_run<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    for id in [1, 2, 3] {
+        add_hourly(&db, id);
+    }
+    let recorded = paycheck(date(2025, 1, 25), date(2025, 1, 31), 100);
+    db.run_tx(|mut ctx| db.record_paycheck(2.into(), recorded.clone()).run(&mut ctx))
+        .unwrap();
+
+    // 途中の従業員で失敗したら、それまでに記録した Paycheck も残らない
+    let result = db.run_tx(|mut ctx| {
+        for emp_id in [1, 2, 3] {
+            db.record_paycheck(
+                emp_id.into(),
+                paycheck(date(2025, 1, 25), date(2025, 1, 31), 200),
+            )
+            .run(&mut ctx)?;
+        }
+        Ok(())
+    });
+    assert!(matches!(result, Err(DaoError::PaycheckAlreadyExists(..))));
+
+    let result = db.run_tx(|mut ctx| db.fetch_paychecks_for_run(date(2025, 1, 31)).run(&mut ctx));
+    assert_eq!(result.unwrap(), vec![(2.into(), recorded)]);
+}
+
+pub fn test_year_to_date<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    // まだ支払いのない年は 0 の累計
+    let result = db.run_tx(|mut ctx| db.fetch_year_to_date(1.into(), 2025).run(&mut ctx));
+    assert_eq!(result.unwrap(), YearToDate::new(2025));
+
+    let ytd = YearToDate {
+        year: 2025,
+        gross_pay: Money::from_cents(100000),
+        deductions: vec![DeductionLine::new(
+            DeductionKind::Dues,
+            Money::from_cents(925),
+        )],
+        net_pay: Money::from_cents(99075),
+    };
+    let result = db.run_tx(|mut ctx| {
+        db.update_year_to_date(1.into(), ytd.clone())
+            .run(&mut ctx)?;
+        db.fetch_year_to_date(1.into(), 2025).run(&mut ctx)
+    });
+    assert_eq!(result.unwrap(), ytd);
+    let result = db.run_tx(|mut ctx| db.fetch_year_to_date(1.into(), 2026).run(&mut ctx));
+    assert_eq!(result.unwrap(), YearToDate::new(2026));
+}
+
+pub fn test_tax_profile<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_hourly(&db, 1);
+    ChangeEmployeeTaxTx::new(
+        1.into(),
+        TaxProfile::new(FilingStatus::Married, 1),
+        db.clone(),
+    )
+    .execute()
+    .unwrap();
+    let emp = db
+        .run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx))
+        .unwrap();
+    assert_eq!(emp.tax_profile(), TaxProfile::new(FilingStatus::Married, 1));
+}
+
+pub fn test_payday<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_member(&db);
+    let response = payday(&db, date(2025, 1, 31), Arc::new(pension()));
+    assert!(matches!(response, Response::Payday { .. }));
+
+    let pc = fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular);
+    // 8h + (8h + 2h * 1.5) = 19h
+    assert_eq!(
+        pc.earning_lines(),
+        &[
+            EarningLine::new(EarningKind::Regular, Money::from_cents(1575 * 16)),
+            EarningLine::new(EarningKind::Overtime, Money::from_cents(1575 * 3)),
+        ]
+    );
+    assert_eq!(pc.gross_pay(), Money::from_cents(1575 * 19));
+    assert_eq!(
+        pc.deduction_lines(),
+        &[
+            DeductionLine::new(DeductionKind::Dues, Money::from_cents(925)),
+            DeductionLine::new(DeductionKind::ServiceCharge, Money::from_cents(1995)),
+            // 299.25 * 10% = 29.925 は銀行丸めで 29.92
+            DeductionLine::new(
+                DeductionKind::Withholding("Pension".to_string()),
+                Money::from_cents(2992)
+            ),
+        ]
+    );
+    assert_eq!(pc.deductions(), Money::from_cents(925 + 1995 + 2992));
+    assert_eq!(pc.net_pay(), pc.gross_pay() - pc.deductions());
+
+    // 最初の Payday なので累計は Paycheck と同じ
+    let ytd = pc.year_to_date().unwrap();
+    assert_eq!(ytd.gross_pay, pc.gross_pay());
+    assert_eq!(ytd.deductions, pc.deduction_lines());
+    assert_eq!(ytd.net_pay, pc.net_pay());
+    let stored = db
+        .run_tx(|mut ctx| db.fetch_year_to_date(1.into(), 2025).run(&mut ctx))
+        .unwrap();
+    assert_eq!(&stored, ytd);
+}
+
+pub fn test_payday_rerun_rejected<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_member(&db);
+    payday(&db, date(2025, 1, 31), Arc::new(NoWithholding));
+    let pc = fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular);
+
+    // 同じ支払日の Payday は Reject され、記録済みの Paycheck は変わらない
+    let rerun = PaydayTx::new(
+        date(2025, 1, 31),
+        RerunPolicy::Reject,
+        db.clone(),
+        Arc::new(MemorySink::new()),
+        Arc::new(NoWithholding),
+        Arc::new(OvertimePolicy::default()),
+    );
+    assert!(rerun.execute().is_err());
+    let found = db
+        .run_tx(|mut ctx| db.fetch_paychecks_for_run(date(2025, 1, 31)).run(&mut ctx))
+        .unwrap();
+    assert_eq!(found, vec![(1.into(), pc)]);
+}
+
+pub fn test_preview_payday_rolls_back<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_member(&db);
+    let preview = PreviewPaydayTx::new(
+        date(2025, 1, 31),
+        db.clone(),
+        Arc::new(pension()),
+        Arc::new(OvertimePolicy::default()),
+    );
+    let Response::PaydayPreview(preview) = preview.execute().unwrap() else {
+        panic!("unexpected response");
+    };
+    assert_eq!(preview.previews.len(), 1);
+
+    // 試算はロールバックするので Paycheck も累計も残らない
+    let result = db.run_tx(|mut ctx| {
+        db.fetch_paycheck(1.into(), date(2025, 1, 31), RunType::Regular)
+            .run(&mut ctx)
+    });
+    assert!(matches!(result, Err(DaoError::PaycheckNotFound(..))));
+    let last_pay_date = db
+        .run_tx(|mut ctx| db.fetch_last_pay_date(1.into()).run(&mut ctx))
+        .unwrap();
+    assert_eq!(last_pay_date, None);
+    let ytd = db
+        .run_tx(|mut ctx| db.fetch_year_to_date(1.into(), 2025).run(&mut ctx))
+        .unwrap();
+    assert_eq!(ytd.gross_pay, Money::ZERO);
+
+    // そのあとの Payday は試算と同じ額を支払う
+    payday(&db, date(2025, 1, 31), Arc::new(pension()));
+    let pc = fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular);
+    assert_eq!(preview.previews[0].amounts.gross_pay, pc.gross_pay());
+    assert_eq!(preview.previews[0].amounts.deductions, pc.deductions());
+    assert_eq!(preview.previews[0].amounts.net_pay, pc.net_pay());
+}
+
+pub fn test_off_cycle_paycheck<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_member(&db);
+    payday(&db, date(2025, 1, 31), Arc::new(NoWithholding));
+    let pc = fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular);
+
+    // 同じ支払日の賞与は別の Paycheck として記録し、源泉徴収だけを差し引く
+    let bonus = PayOffCycleTx::new(
+        1.into(),
+        RunType::Bonus,
+        date(2025, 1, 31),
+        Money::from_cents(10000),
+        db.clone(),
+        Arc::new(MemorySink::new()),
+        Arc::new(pension()),
+    );
+    let Response::Paycheck(bonus) = bonus.execute().unwrap() else {
+        panic!("unexpected response");
+    };
+    assert_eq!(
+        fetch_paycheck(&db, date(2025, 1, 31), RunType::Bonus),
+        bonus
+    );
+    assert_eq!(
+        bonus.deduction_lines(),
+        &[DeductionLine::new(
+            DeductionKind::Withholding("Pension".to_string()),
+            Money::from_cents(1000)
+        )]
+    );
+
+    // 定期の Paycheck は変わらず、支払い済みの期間にも数えない
+    assert_eq!(fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular), pc);
+    let found = db
+        .run_tx(|mut ctx| db.fetch_paychecks_for_run(date(2025, 1, 31)).run(&mut ctx))
+        .unwrap();
+    assert_eq!(found, vec![(1.into(), pc.clone())]);
+    let found = db
+        .run_tx(|mut ctx| {
+            db.fetch_paychecks(1.into(), date(2025, 1, 1)..=date(2025, 1, 31))
+                .run(&mut ctx)
+        })
+        .unwrap();
+    assert_eq!(found, vec![pc]);
+}
+
+pub fn test_amend_paid_timecard<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_member(&db);
+    payday(&db, date(2025, 1, 31), Arc::new(NoWithholding));
+
+    // 支払い済みのタイムカードの訂正は監査記録に残る
+    AmendTimeCardTx::new(1.into(), date(2025, 1, 30), 7.5, db.clone())
+        .execute()
+        .unwrap();
+    let audits = db
+        .run_tx(|mut ctx| db.fetch_timecard_audits(1.into()).run(&mut ctx))
+        .unwrap();
+    assert_eq!(
+        audits,
+        vec![TimeCardAudit::new(
+            date(2025, 1, 30),
+            8.0,
+            Some(7.5),
+            date(2025, 1, 31)
+        )]
+    );
+}
+
+pub fn test_void_paid_service_charge<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_member(&db);
+    payday(&db, date(2025, 1, 31), Arc::new(NoWithholding));
+
+    // 支払い済みのサービス料の取り消しは次の Payday で払い戻す
+    VoidServiceChargeTx::new(7734.into(), 1.into(), db.clone())
+        .execute()
+        .unwrap();
+    payday(&db, date(2025, 2, 7), Arc::new(NoWithholding));
+    let pc = fetch_paycheck(&db, date(2025, 2, 7), RunType::Regular);
+    assert_eq!(
+        pc.deduction_lines(),
+        &[
+            DeductionLine::new(DeductionKind::Dues, Money::from_cents(925)),
+            DeductionLine::new(DeductionKind::ServiceChargeRefund, Money::from_cents(-1995)),
+        ]
+    );
+}
+
+pub fn test_delete_employee_keeps_pay_history<D>(db: D)
+where
+    D: EmployeeDao + Clone + 'static,
+{
+    add_member(&db);
+    payday(&db, date(2025, 1, 31), Arc::new(NoWithholding));
+    AmendTimeCardTx::new(1.into(), date(2025, 1, 30), 7.5, db.clone())
+        .execute()
+        .unwrap();
+    let pc = fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular);
+
+    // 従業員を削除しても支払いと訂正の履歴は残る
+    DeleteEmployeeTx::new(1.into(), db.clone())
+        .execute()
+        .unwrap();
+    let result = db.run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx));
+    assert!(matches!(result, Err(DaoError::EmployeeNotFound(_))));
+    assert_eq!(fetch_paycheck(&db, date(2025, 1, 31), RunType::Regular), pc);
+    let audits = db
+        .run_tx(|mut ctx| db.fetch_timecard_audits(1.into()).run(&mut ctx))
+        .unwrap();
+    assert_eq!(audits.len(), 1);
+}
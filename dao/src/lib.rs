@@ -1,7 +1,11 @@
 use chrono::NaiveDate;
+use std::ops::RangeInclusive;
 use thiserror::Error;
 
-use payroll_domain::{Employee, EmployeeId, MemberId, Paycheck};
+use payroll_domain::{
+    ChargeId, Employee, EmployeeId, MemberId, Paycheck, ReceiptId, RunType, TimeCardAudit,
+    YearToDate,
+};
 
 #[derive(Debug, Clone, Error)]
 pub enum DaoError {
@@ -17,12 +21,32 @@ pub enum DaoError {
     UnexpectedError(String),
     #[error("paycheck not found: emp_id={0}, pay_date={1}")]
     PaycheckNotFound(EmployeeId, NaiveDate),
+    #[error("paycheck already exists: emp_id={0}, pay_date={1}")]
+    PaycheckAlreadyExists(EmployeeId, NaiveDate),
+    #[error("dangling union member_id={0}: emp_id={1} not found")]
+    DanglingUnionMember(MemberId, EmployeeId),
+    #[error("timecard not found: emp_id={0}, date={1}")]
+    TimeCardNotFound(EmployeeId, NaiveDate),
+    #[error("timecard already exists: emp_id={0}, date={1}")]
+    TimeCardAlreadyExists(EmployeeId, NaiveDate),
+    #[error("sales receipt not found: emp_id={0}, receipt_id={1}")]
+    SalesReceiptNotFound(EmployeeId, ReceiptId),
+    #[error("service charge not found: member_id={0}, charge_id={1}")]
+    ServiceChargeNotFound(MemberId, ChargeId),
+    #[error("invalid effective date: emp_id={0}, effective={1}")]
+    InvalidEffectiveDate(EmployeeId, NaiveDate),
+    #[error("emp_id={0} already terminated")]
+    EmployeeTerminated(EmployeeId),
 }
 
 pub trait EmployeeDao {
     type Ctx<'a>;
 
     fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
+    // f の結果だけを返して、f が行った変更は成功しても必ず取り消す (Payday の試算に使う)
+    fn run_tx_rollback<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
     where
         F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>;
 
@@ -56,11 +80,59 @@ pub trait EmployeeDao {
         &self,
         member_id: MemberId,
     ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError>;
+    fn find_member_ids<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<MemberId>, Err = DaoError>;
+    // 同じ支払日でも種類 (RunType) が違えば別の Paycheck になる
     fn record_paycheck<'a>(
         &self,
         emp_id: EmployeeId,
         paycheck: Paycheck,
     ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError>;
+    fn fetch_paycheck<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_date: NaiveDate,
+        run_type: RunType,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Paycheck, Err = DaoError>;
+    // 以下の 2 つは勤務の期間に対する Paycheck (RunType::pays_work) だけを対象にする
+    fn fetch_paychecks<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_dates: RangeInclusive<NaiveDate>,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<Paycheck>, Err = DaoError>;
+    // まだ一度も支払っていなければ None
+    fn fetch_last_pay_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Option<NaiveDate>, Err = DaoError>;
+    // Payday の再実行を調べるための定期の Paycheck
+    fn fetch_paychecks_for_run<'a>(
+        &self,
+        pay_date: NaiveDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Paycheck)>, Err = DaoError>;
+    // 年初来の累計: まだ支払いのない年は 0 の累計を返す
+    fn fetch_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        year: i32,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = YearToDate, Err = DaoError>;
+    fn update_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        ytd: YearToDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError>;
+    fn record_timecard_audit<'a>(
+        &self,
+        emp_id: EmployeeId,
+        audit: TimeCardAudit,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError>;
+    // 記録した順に返す
+    fn fetch_timecard_audits<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<TimeCardAudit>, Err = DaoError>;
 }
 
 pub trait HaveEmployeeDao {
@@ -0,0 +1,106 @@
+// 追記専用のジャーナルファイル
+// 1 行が 1 トランザクション分の Op の列 (JSON) になっている
+use log::{debug, trace, warn};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::record::Op;
+use dao::DaoError;
+
+fn io_error(path: &Path, e: impl std::fmt::Display) -> DaoError {
+    DaoError::UnexpectedError(format!("journal {}: {}", path.display(), e))
+}
+
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: File,
+}
+impl Journal {
+    // ジャーナルを開いて、コミット済みのトランザクションを先頭から順に返す
+    pub fn open(path: &Path) -> Result<(Self, Vec<Vec<Op>>), DaoError> {
+        trace!("Journal::open called: path={}", path.display());
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| io_error(path, e))?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|e| io_error(path, e))?;
+
+        let mut txs = vec![];
+        let mut committed_len = 0;
+        let mut rest = content.as_str();
+        while !rest.is_empty() {
+            let (line, complete) = match rest.find('\n') {
+                Some(i) => (&rest[..i], true),
+                None => (rest, false),
+            };
+            match serde_json::from_str::<Vec<Op>>(line) {
+                Ok(ops) if complete => {
+                    txs.push(ops);
+                    committed_len += line.len() + 1;
+                    rest = &rest[line.len() + 1..];
+                }
+                result => {
+                    // 書き込み途中で落ちた末尾の行だけは捨てて続行する
+                    let is_last = !complete || rest[line.len() + 1..].is_empty();
+                    if !is_last {
+                        return Err(io_error(
+                            path,
+                            format!("corrupted entry at byte {}", committed_len),
+                        ));
+                    }
+                    warn!(
+                        "discard incomplete journal entry at byte {}: {:?}",
+                        committed_len,
+                        result.err()
+                    );
+                    file.set_len(committed_len as u64)
+                        .map_err(|e| io_error(path, e))?;
+                    break;
+                }
+            }
+        }
+        debug!(
+            "Journal::open: {} transactions replayed from {}",
+            txs.len(),
+            path.display()
+        );
+
+        Ok((
+            Self {
+                path: path.to_path_buf(),
+                file,
+            },
+            txs,
+        ))
+    }
+
+    pub fn append(&mut self, ops: &[Op]) -> Result<(), DaoError> {
+        trace!("Journal::append called: ops={:?}", ops);
+        let mut line = serde_json::to_string(ops).map_err(|e| io_error(&self.path, e))?;
+        line.push('\n');
+
+        let len = self
+            .file
+            .metadata()
+            .map_err(|e| io_error(&self.path, e))?
+            .len();
+        let result = self
+            .file
+            .write_all(line.as_bytes())
+            .and_then(|_| self.file.sync_data());
+        if let Err(e) = result {
+            // 中途半端に書かれた行が残らないように元の長さに戻しておく
+            let _ = self.file.set_len(len);
+            return Err(io_error(&self.path, e));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,826 @@
+// dao の具体的な実装
+// メモリ上のテーブルをジャーナルファイルに追記することで永続化する
+use chrono::NaiveDate;
+use log::{debug, trace, warn};
+use std::{
+    collections::HashMap,
+    ops::RangeInclusive,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use dao::{DaoError, EmployeeDao};
+use payroll_domain::{
+    Employee, EmployeeId, MemberId, Paycheck, RunType, TimeCardAudit, YearToDate,
+};
+
+mod journal;
+mod record;
+
+use journal::Journal;
+use record::{EmployeeRecord, Op, PaycheckRecord};
+
+#[derive(Debug, Clone)]
+pub struct FileDB {
+    // ジャーナルの lock はトランザクション全体で保持して、トランザクションを直列化する
+    journal: Arc<Mutex<Journal>>,
+    payroll_db: Arc<Mutex<PayrollDb>>,
+}
+impl FileDB {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DaoError> {
+        trace!("FileDB::open called");
+        let (journal, txs) = Journal::open(path.as_ref())?;
+        let mut db = PayrollDb::default();
+        for ops in txs {
+            for op in ops {
+                db.apply(op);
+            }
+        }
+        debug!(
+            "FileDB::open: {} employees, {} union members loaded",
+            db.employees.len(),
+            db.union_members.len()
+        );
+        Ok(Self {
+            journal: Arc::new(Mutex::new(journal)),
+            payroll_db: Arc::new(Mutex::new(db)),
+        })
+    }
+}
+#[derive(Debug, Clone, Default)]
+pub struct PayrollDb {
+    employees: HashMap<EmployeeId, EmployeeRecord>,
+    union_members: HashMap<MemberId, EmployeeId>,
+    paychecks: HashMap<EmployeeId, Vec<PaycheckRecord>>,
+    year_to_dates: HashMap<(EmployeeId, i32), YearToDate>,
+    timecard_audits: HashMap<EmployeeId, Vec<TimeCardAudit>>,
+
+    // 実行中のトランザクションでまだジャーナルに書いていない操作
+    pending: Vec<Op>,
+}
+impl PayrollDb {
+    fn apply(&mut self, op: Op) {
+        match op {
+            Op::AddEmployee(emp) | Op::UpdateEmployee(emp) => {
+                self.employees.insert(emp.id(), emp);
+            }
+            Op::DeleteEmployee(id) => {
+                self.employees.remove(&id);
+            }
+            Op::AddUnionMember(member_id, emp_id) => {
+                self.union_members.insert(member_id, emp_id);
+            }
+            Op::DeleteUnionMember(member_id) => {
+                self.union_members.remove(&member_id);
+            }
+            Op::RecordPaycheck(emp_id, pc) => {
+                // 累計を取る前に記録された Paycheck は、読み込むときに累計へ足しておく
+                // (累計を持つ Paycheck は後に続く UpdateYearToDate で累計が更新される)
+                if !pc.has_year_to_date() {
+                    let year = YearToDate::year_of(pc.pay_date());
+                    self.year_to_dates
+                        .entry((emp_id, year))
+                        .or_insert_with(|| YearToDate::new(year))
+                        .add(&pc.to_paycheck());
+                }
+                self.paychecks.entry(emp_id).or_default().push(pc);
+            }
+            Op::UpdateYearToDate(emp_id, ytd) => {
+                self.year_to_dates.insert((emp_id, ytd.year), ytd);
+            }
+            Op::RecordTimeCardAudit(emp_id, audit) => {
+                self.timecard_audits.entry(emp_id).or_default().push(audit);
+            }
+        }
+    }
+    fn write(&mut self, op: Op) {
+        self.apply(op.clone());
+        self.pending.push(op);
+    }
+}
+// DB の実装ごとに EmployeeDao トレイトを実装する
+impl EmployeeDao for FileDB {
+    type Ctx<'a> = MutexGuard<'a, PayrollDb>;
+
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
+    {
+        trace!("run_tx called");
+        let mut journal = self.journal.lock().unwrap();
+        // ロールバック用にトランザクション開始時点の状態を取っておく
+        let snapshot = self.payroll_db.lock().unwrap().clone();
+
+        let result = f(self.payroll_db.lock().unwrap());
+
+        let mut db = self.payroll_db.lock().unwrap();
+        let ops = std::mem::take(&mut db.pending);
+        let result = result.and_then(|v| {
+            if !ops.is_empty() {
+                journal.append(&ops)?;
+            }
+            Ok(v)
+        });
+        if let Err(e) = &result {
+            warn!("run_tx: rollback: {}", e);
+            *db = snapshot;
+        }
+        result
+    }
+    fn run_tx_rollback<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
+    {
+        trace!("run_tx_rollback called");
+        let _journal = self.journal.lock().unwrap();
+        let snapshot = self.payroll_db.lock().unwrap().clone();
+
+        let result = f(self.payroll_db.lock().unwrap());
+        // ジャーナルには何も書かず、書くはずだった操作ごと開始時点の状態に戻す
+        *self.payroll_db.lock().unwrap() = snapshot;
+        result
+    }
+
+    fn add<'a>(
+        &self,
+        emp: Employee,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
+        trace!("add called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            let emp_id = emp.id();
+            trace!("add::with_tx called: emp_id={},emp={:?}", emp_id, emp);
+            if tx.employees.contains_key(&emp_id) {
+                return Err(DaoError::EmployeeAlreadyExists(emp_id));
+            }
+            tx.write(Op::AddEmployee(EmployeeRecord::from_employee(&emp)?));
+            Ok(emp_id)
+        })
+    }
+    fn delete<'a>(
+        &self,
+        id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("delete called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("delete::with_tx called: id={}", id);
+            if tx.employees.contains_key(&id) {
+                tx.write(Op::DeleteEmployee(id));
+                return Ok(());
+            }
+            Err(DaoError::EmployeeNotFound(id))
+        })
+    }
+    fn fetch<'a>(
+        &self,
+        id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Employee, Err = DaoError> {
+        trace!("fetch called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch::with_tx called: id={}", id);
+            tx.employees
+                .get(&id)
+                .map(EmployeeRecord::to_employee)
+                .ok_or(DaoError::EmployeeNotFound(id))
+        })
+    }
+    fn fetch_all<'a>(
+        &self,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Employee)>, Err = DaoError> {
+        trace!("fetch_all called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_all::with_tx called");
+            Ok(tx
+                .employees
+                .iter()
+                .map(|(k, v)| (*k, v.to_employee()))
+                .collect())
+        })
+    }
+    fn update<'a>(
+        &self,
+        emp: Employee,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("save called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            let emp_id = emp.id();
+            trace!("save::with_tx called: emp_id={},emp={:?}", emp_id, emp);
+            if tx.employees.contains_key(&emp_id) {
+                tx.write(Op::UpdateEmployee(EmployeeRecord::from_employee(&emp)?));
+                return Ok(());
+            }
+            Err(DaoError::EmployeeNotFound(emp_id))
+        })
+    }
+    fn add_union_member<'a>(
+        &self,
+        member_id: MemberId,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("add_union_member called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "add_union_member::with_tx called: member_id={},emp_id={}",
+                member_id,
+                emp_id
+            );
+            if tx.union_members.contains_key(&member_id) {
+                return Err(DaoError::MemberAlreadyExists(member_id, emp_id));
+            }
+            tx.write(Op::AddUnionMember(member_id, emp_id));
+            Ok(())
+        })
+    }
+    fn delete_union_member<'a>(
+        &self,
+        member_id: MemberId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("delete_union_member called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "delete_union_member::with_tx called: member_id={}",
+                member_id
+            );
+            if !tx.union_members.contains_key(&member_id) {
+                return Err(DaoError::MemberNotFound(member_id));
+            }
+            tx.write(Op::DeleteUnionMember(member_id));
+            Ok(())
+        })
+    }
+    fn find_union_member<'a>(
+        &self,
+        member_id: MemberId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
+        trace!("find_union_members called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "find_union_members::with_tx called: member_id={}",
+                member_id
+            );
+            tx.union_members
+                .get(&member_id)
+                .cloned()
+                .ok_or(DaoError::MemberNotFound(member_id))
+        })
+    }
+    fn find_member_ids<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<MemberId>, Err = DaoError> {
+        trace!("find_member_ids called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("find_member_ids::with_tx called: emp_id={}", emp_id);
+            let mut found = tx
+                .union_members
+                .iter()
+                .filter(|(_, e)| **e == emp_id)
+                .map(|(m, _)| *m)
+                .collect::<Vec<_>>();
+            found.sort();
+            Ok(found)
+        })
+    }
+    fn record_paycheck<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pc: Paycheck,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("record_paycheck called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "record_paycheck::with_tx called: emp_id={},paycheck={:?}",
+                emp_id,
+                pc
+            );
+            let (pay_date, run_type) = (pc.pay_date(), pc.run_type());
+            let recorded = tx.paychecks.get(&emp_id).is_some_and(|pcs| {
+                pcs.iter()
+                    .any(|r| r.pay_date() == pay_date && r.run_type() == run_type)
+            });
+            if recorded {
+                return Err(DaoError::PaycheckAlreadyExists(emp_id, pay_date));
+            }
+            tx.write(Op::RecordPaycheck(emp_id, PaycheckRecord::from(&pc)));
+            Ok(())
+        })
+    }
+    fn fetch_paycheck<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_date: NaiveDate,
+        run_type: RunType,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Paycheck, Err = DaoError> {
+        trace!("fetch_paycheck called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paycheck::with_tx called: emp_id={},pay_date={},run_type={}",
+                emp_id,
+                pay_date,
+                run_type
+            );
+            tx.paychecks
+                .get(&emp_id)
+                .and_then(|pcs| {
+                    pcs.iter()
+                        .find(|pc| pc.pay_date() == pay_date && pc.run_type() == run_type)
+                })
+                .map(PaycheckRecord::to_paycheck)
+                .ok_or(DaoError::PaycheckNotFound(emp_id, pay_date))
+        })
+    }
+    fn fetch_paychecks<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_dates: RangeInclusive<NaiveDate>,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<Paycheck>, Err = DaoError> {
+        trace!("fetch_paychecks called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paychecks::with_tx called: emp_id={},pay_dates={:?}",
+                emp_id,
+                pay_dates
+            );
+            Ok(tx
+                .paychecks
+                .get(&emp_id)
+                .map(|pcs| {
+                    pcs.iter()
+                        .filter(|pc| pc.run_type().pays_work())
+                        .filter(|pc| pay_dates.contains(&pc.pay_date()))
+                        .map(PaycheckRecord::to_paycheck)
+                        .collect()
+                })
+                .unwrap_or_default())
+        })
+    }
+    fn fetch_last_pay_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Option<NaiveDate>, Err = DaoError> {
+        trace!("fetch_last_pay_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_last_pay_date::with_tx called: emp_id={}", emp_id);
+            Ok(tx.paychecks.get(&emp_id).and_then(|pcs| {
+                pcs.iter()
+                    .filter(|pc| pc.run_type().pays_work())
+                    .map(PaycheckRecord::pay_date)
+                    .max()
+            }))
+        })
+    }
+    fn fetch_paychecks_for_run<'a>(
+        &self,
+        pay_date: NaiveDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Paycheck)>, Err = DaoError> {
+        trace!("fetch_paychecks_for_run called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paychecks_for_run::with_tx called: pay_date={}",
+                pay_date
+            );
+            let mut found = tx
+                .paychecks
+                .iter()
+                .flat_map(|(emp_id, pcs)| {
+                    pcs.iter()
+                        .filter(|pc| pc.run_type() == RunType::Regular && pc.pay_date() == pay_date)
+                        .map(|pc| (*emp_id, pc.to_paycheck()))
+                })
+                .collect::<Vec<_>>();
+            found.sort_by_key(|(emp_id, _)| *emp_id);
+            Ok(found)
+        })
+    }
+    fn fetch_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        year: i32,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = YearToDate, Err = DaoError> {
+        trace!("fetch_year_to_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_year_to_date::with_tx called: emp_id={},year={}",
+                emp_id,
+                year
+            );
+            Ok(tx
+                .year_to_dates
+                .get(&(emp_id, year))
+                .cloned()
+                .unwrap_or_else(|| YearToDate::new(year)))
+        })
+    }
+    fn update_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        ytd: YearToDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("update_year_to_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "update_year_to_date::with_tx called: emp_id={},ytd={:?}",
+                emp_id,
+                ytd
+            );
+            tx.write(Op::UpdateYearToDate(emp_id, ytd));
+            Ok(())
+        })
+    }
+    fn record_timecard_audit<'a>(
+        &self,
+        emp_id: EmployeeId,
+        audit: TimeCardAudit,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("record_timecard_audit called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "record_timecard_audit::with_tx called: emp_id={},audit={:?}",
+                emp_id,
+                audit
+            );
+            tx.write(Op::RecordTimeCardAudit(emp_id, audit));
+            Ok(())
+        })
+    }
+    fn fetch_timecard_audits<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<TimeCardAudit>, Err = DaoError> {
+        trace!("fetch_timecard_audits called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_timecard_audits::with_tx called: emp_id={}", emp_id);
+            Ok(tx.timecard_audits.get(&emp_id).cloned().unwrap_or_default())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use payroll_domain::{
+        DeductionKind, DeductionLine, EarningKind, EarningLine, FilingStatus, Money, NoAffiliation,
+        OvertimePolicy, ScheduleInfo, TaxProfile,
+    };
+    use payroll_impl::{
+        BiweeklyFromSchedule, HoldMethod, HourlyClassification, MonthlyOnSchedule,
+        UnionAffiliation, WeeklySchedule,
+    };
+    use std::{
+        fs,
+        io::Write,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+    use tx_rs::Tx;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    fn journal_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("file-db-{}-{}.journal", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    dao_test::dao_conformance!(
+        |name: &str| FileDB::open(journal_path(name)).unwrap(),
+        // journal_path が残ったジャーナルを消す
+        |name: &str| {
+            journal_path(name);
+        }
+    );
+
+    fn hourly(id: u32, name: &str) -> Employee {
+        Employee::new(
+            id.into(),
+            name,
+            "Home",
+            Arc::new(Mutex::new(HourlyClassification::new(Money::from_cents(
+                1575,
+            )))),
+            Arc::new(Mutex::new(WeeklySchedule)),
+            Arc::new(Mutex::new(HoldMethod)),
+            Arc::new(Mutex::new(NoAffiliation)),
+        )
+    }
+    fn add_timecard(emp: &Employee, date: NaiveDate, hours: f32) {
+        emp.classification()
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<HourlyClassification>()
+            .unwrap()
+            .add_timecard(date, hours);
+    }
+
+    #[test]
+    fn test_reopen() {
+        let path = journal_path("reopen");
+        let mut pc = Paycheck::new(date(2025, 1, 25)..=date(2025, 1, 31));
+        pc.set_earning_lines(vec![
+            EarningLine::new(EarningKind::Regular, Money::from_cents(12000)),
+            EarningLine::new(EarningKind::Overtime, Money::from_cents(600)),
+        ]);
+        pc.set_deduction_lines(vec![DeductionLine::new(
+            DeductionKind::Withholding("Income Tax".to_string()),
+            Money::from_cents(1260),
+        )]);
+
+        let expected = {
+            let db = FileDB::open(&path).unwrap();
+            db.run_tx(|mut ctx| {
+                let mut emp = hourly(1, "Bob");
+                add_timecard(&emp, date(2025, 1, 27), 8.0);
+                let mut union = UnionAffiliation::new(7734.into(), Money::from_cents(925));
+                let charge = union.add_service_charge(date(2025, 1, 24), Money::from_cents(1995));
+                union.void_service_charge(charge, Some(date(2025, 1, 31)));
+                emp.set_affiliation(Arc::new(Mutex::new(union)));
+                emp.set_tax_profile(TaxProfile::new(FilingStatus::Married, 2));
+                emp.set_overtime_policy(Some(OvertimePolicy {
+                    weekly_after: Some(40.0),
+                    ..OvertimePolicy::none()
+                }));
+                emp.hire_on(date(2025, 1, 6));
+                emp.change_schedule(
+                    Arc::new(Mutex::new(MonthlyOnSchedule::new(25))),
+                    Some(date(2025, 1, 11)),
+                );
+                db.add(emp).run(&mut ctx)?;
+                db.add_union_member(7734.into(), 1.into()).run(&mut ctx)
+            })
+            .unwrap();
+            db.run_tx(|mut ctx| {
+                db.add(hourly(2, "Alice")).run(&mut ctx)?;
+                db.delete(2.into()).run(&mut ctx)?;
+                let emp = db.fetch(1.into()).run(&mut ctx)?;
+                add_timecard(&emp, date(2025, 1, 28), 4.0);
+                db.update(emp).run(&mut ctx)?;
+                db.record_paycheck(1.into(), pc.clone()).run(&mut ctx)
+            })
+            .unwrap();
+            db.run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx))
+                .unwrap()
+        };
+
+        let db = FileDB::open(&path).unwrap();
+        let result = db.run_tx(|mut ctx| {
+            let emp = db.fetch(1.into()).run(&mut ctx)?;
+            let member = db.find_union_member(7734.into()).run(&mut ctx)?;
+            let recorded = db
+                .fetch_paycheck(1.into(), date(2025, 1, 31), RunType::Regular)
+                .run(&mut ctx)?;
+            let all = db.fetch_all().run(&mut ctx)?;
+            let last_pay_date = db.fetch_last_pay_date(1.into()).run(&mut ctx)?;
+            Ok((emp, member, recorded, all.len(), last_pay_date))
+        });
+        let (emp, member, recorded, count, last_pay_date) = result.unwrap();
+        assert_eq!(
+            EmployeeRecord::from_employee(&emp).unwrap(),
+            EmployeeRecord::from_employee(&expected).unwrap()
+        );
+        assert_eq!(emp.tax_profile(), TaxProfile::new(FilingStatus::Married, 2));
+        assert_eq!(
+            emp.overtime_policy().and_then(|p| p.weekly_after),
+            Some(40.0)
+        );
+        assert_eq!(emp.hire_date(), Some(date(2025, 1, 6)));
+        assert_eq!(emp.schedule_start(), Some(date(2025, 1, 11)));
+        assert_eq!(
+            emp.schedule().lock().unwrap().info(),
+            ScheduleInfo::MonthlyOn { day: 25 }
+        );
+        assert_eq!(member, 1.into());
+        assert_eq!(recorded, pc);
+        assert_eq!(count, 1);
+        assert_eq!(last_pay_date, Some(date(2025, 1, 31)));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rollback() {
+        let path = journal_path("rollback");
+        let db = FileDB::open(&path).unwrap();
+        db.run_tx(|mut ctx| db.add(hourly(1, "Bob")).run(&mut ctx))
+            .unwrap();
+
+        let result = db.run_tx(|mut ctx| {
+            db.add(hourly(2, "Alice")).run(&mut ctx)?;
+            let mut emp = db.fetch(1.into()).run(&mut ctx)?;
+            emp.set_name("Robert");
+            db.update(emp).run(&mut ctx)?;
+            db.add(hourly(1, "Bob")).run(&mut ctx)
+        });
+        assert!(matches!(result, Err(DaoError::EmployeeAlreadyExists(id)) if id == 1.into()));
+
+        let check = |db: &FileDB| {
+            db.run_tx(|mut ctx| {
+                let emp = db.fetch(1.into()).run(&mut ctx)?;
+                assert_eq!(emp.name(), "Bob");
+                assert!(matches!(
+                    db.fetch(2.into()).run(&mut ctx),
+                    Err(DaoError::EmployeeNotFound(_))
+                ));
+                Ok(())
+            })
+            .unwrap();
+        };
+        check(&db);
+        check(&FileDB::open(&path).unwrap());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_discard_incomplete_entry() {
+        let path = journal_path("incomplete");
+        {
+            let db = FileDB::open(&path).unwrap();
+            db.run_tx(|mut ctx| db.add(hourly(1, "Bob")).run(&mut ctx))
+                .unwrap();
+        }
+        // 書き込み途中で落ちたことにする
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"[{"AddEmployee":{"id":2,"na"#).unwrap();
+        drop(file);
+
+        let db = FileDB::open(&path).unwrap();
+        db.run_tx(|mut ctx| db.add(hourly(3, "Carol")).run(&mut ctx))
+            .unwrap();
+        drop(db);
+
+        let db = FileDB::open(&path).unwrap();
+        let ids = db
+            .run_tx(|mut ctx| db.fetch_all().run(&mut ctx))
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&1.into()) && ids.contains(&3.into()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_legacy_biweekly_schedule() {
+        // 起算日を持つ前のジャーナルの隔週払いは、それまでと同じ日に支払う
+        let mut record =
+            serde_json::to_value(EmployeeRecord::from_employee(&hourly(1, "Bob")).unwrap())
+                .unwrap();
+        record["schedule"] = serde_json::json!("Biweekly");
+        let emp = serde_json::from_value::<EmployeeRecord>(record)
+            .unwrap()
+            .to_employee();
+        let schedule = emp.schedule();
+        let schedule = schedule.lock().unwrap();
+        assert_eq!(
+            schedule.as_any().downcast_ref::<BiweeklyFromSchedule>(),
+            Some(&BiweeklyFromSchedule::default())
+        );
+        assert!(schedule.is_pay_date(date(2025, 1, 24)));
+
+        // 書き出すときは起算日指定の隔週払いとして書く
+        let emp = Employee::new(
+            1.into(),
+            "Bob",
+            "Home",
+            emp.classification(),
+            Arc::new(Mutex::new(BiweeklyFromSchedule::default())),
+            emp.method(),
+            emp.affiliation(),
+        );
+        let record = serde_json::to_value(EmployeeRecord::from_employee(&emp).unwrap()).unwrap();
+        assert_eq!(
+            record["schedule"],
+            serde_json::json!({"BiweeklyFrom": {"anchor": "2024-12-28"}})
+        );
+    }
+
+    #[test]
+    fn test_unitemized_paycheck() {
+        let path = journal_path("unitemized");
+        // 明細を記録する前のジャーナル
+        fs::write(
+            &path,
+            concat!(
+                r#"[{"RecordPaycheck":[1,{"period_start":"2025-01-25","period_end":"2025-01-31","#,
+                r#""gross_pay":"300.00","deductions":"20.00","net_pay":"280.00"}]}]"#,
+                "\n"
+            ),
+        )
+        .unwrap();
+
+        let db = FileDB::open(&path).unwrap();
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 1, 31), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(
+            pc.earning_lines(),
+            &[EarningLine::new(
+                EarningKind::Unitemized,
+                Money::from_cents(30000)
+            )]
+        );
+        assert_eq!(
+            pc.deduction_lines(),
+            &[DeductionLine::new(
+                DeductionKind::Unitemized,
+                Money::from_cents(2000)
+            )]
+        );
+        assert_eq!(pc.net_pay(), Money::from_cents(28000));
+        // 累計を記録する前の Paycheck は読み込むときに累計に積み上げる
+        let ytd = db
+            .run_tx(|mut ctx| db.fetch_year_to_date(1.into(), 2025).run(&mut ctx))
+            .unwrap();
+        assert_eq!(ytd.gross_pay, Money::from_cents(30000));
+        assert_eq!(
+            ytd.deduction(&DeductionKind::Unitemized),
+            Money::from_cents(2000)
+        );
+        assert_eq!(ytd.net_pay, Money::from_cents(28000));
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_year_to_date() {
+        let path = journal_path("year-to-date");
+        let mut pc = Paycheck::new(date(2025, 12, 1)..=date(2025, 12, 31));
+        pc.set_earning_lines(vec![EarningLine::new(
+            EarningKind::Salary,
+            Money::from_cents(100000),
+        )]);
+        let mut ytd = YearToDate::new(2025);
+        ytd.gross_pay = Money::from_cents(1200000);
+        ytd.net_pay = Money::from_cents(1200000);
+        pc.set_year_to_date(ytd.clone());
+        {
+            let db = FileDB::open(&path).unwrap();
+            db.run_tx(|mut ctx| {
+                db.record_paycheck(1.into(), pc.clone()).run(&mut ctx)?;
+                db.update_year_to_date(1.into(), ytd.clone()).run(&mut ctx)
+            })
+            .unwrap();
+        }
+
+        let db = FileDB::open(&path).unwrap();
+        let (fetched_pc, fetched_ytd) = db
+            .run_tx(|mut ctx| {
+                let pc = db
+                    .fetch_paycheck(1.into(), date(2025, 12, 31), RunType::Regular)
+                    .run(&mut ctx)?;
+                let ytd = db.fetch_year_to_date(1.into(), 2025).run(&mut ctx)?;
+                Ok((pc, ytd))
+            })
+            .unwrap();
+        assert_eq!(fetched_pc, pc);
+        // 累計を持つ Paycheck は再生時に二重に積み上げない
+        assert_eq!(fetched_ytd, ytd);
+        let next = db
+            .run_tx(|mut ctx| db.fetch_year_to_date(1.into(), 2026).run(&mut ctx))
+            .unwrap();
+        assert_eq!(next, YearToDate::new(2026));
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_timecard_audits() {
+        let path = journal_path("timecard-audits");
+        let audits = vec![
+            TimeCardAudit::new(date(2025, 1, 6), 8.0, Some(9.5), date(2025, 1, 10)),
+            TimeCardAudit::new(date(2025, 1, 7), 8.0, None, date(2025, 1, 10)),
+        ];
+        {
+            let db = FileDB::open(&path).unwrap();
+            for audit in &audits {
+                db.run_tx(|mut ctx| {
+                    db.record_timecard_audit(1.into(), audit.clone())
+                        .run(&mut ctx)
+                })
+                .unwrap();
+            }
+        }
+
+        let db = FileDB::open(&path).unwrap();
+        let fetched = db
+            .run_tx(|mut ctx| db.fetch_timecard_audits(1.into()).run(&mut ctx))
+            .unwrap();
+        assert_eq!(fetched, audits);
+        let fetched = db
+            .run_tx(|mut ctx| db.fetch_timecard_audits(2.into()).run(&mut ctx))
+            .unwrap();
+        assert_eq!(fetched, vec![]);
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
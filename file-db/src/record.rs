@@ -0,0 +1,298 @@
+// ファイルに書き出すためのレコード表現
+// Employee は trait object を Arc<Mutex<..>> で持っているので、そのままでは serialize できない
+// ここで具体的な型に落としてから永続化する
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use dao::DaoError;
+use payroll_domain::{
+    DeductionKind, DeductionLine, EarningKind, EarningLine, Employee, EmployeeId, EmploymentStatus,
+    MemberId, Money, NoAffiliation, OvertimePolicy, Paycheck, RunType, StatusChange, TaxProfile,
+    TimeCardAudit, YearToDate,
+};
+use payroll_impl::{
+    BiweeklyFromSchedule, CommissionedClassification, DirectMethod, HoldMethod,
+    HourlyClassification, MailMethod, MonthlyOnSchedule, MonthlySchedule, SalariedClassification,
+    SemimonthlySchedule, UnionAffiliation, WeeklyOnSchedule, WeeklySchedule,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClassificationRecord {
+    Salaried(SalariedClassification),
+    Hourly(HourlyClassification),
+    Commissioned(CommissionedClassification),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScheduleRecord {
+    Monthly,
+    Weekly,
+    // 起算日を持つ前のジャーナルの隔週払い (読むだけで書かない)
+    Biweekly,
+    Semimonthly,
+    MonthlyOn(MonthlyOnSchedule),
+    WeeklyOn(WeeklyOnSchedule),
+    BiweeklyFrom(BiweeklyFromSchedule),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MethodRecord {
+    Hold,
+    Direct(DirectMethod),
+    Mail(MailMethod),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AffiliationRecord {
+    None,
+    Union(UnionAffiliation),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmployeeRecord {
+    id: EmployeeId,
+    name: String,
+    address: String,
+    // 源泉徴収の条件を持つ前に書かれたジャーナルも読めるように省略可能にしておく
+    #[serde(default)]
+    tax: TaxProfile,
+    // 残業の規則も同様 (None なら会社全体の規則)
+    #[serde(default)]
+    overtime: Option<OvertimePolicy>,
+    // 入社日も同様 (None なら制限なし)
+    #[serde(default)]
+    hire_date: Option<NaiveDate>,
+    // 在籍状況の履歴も同様 (空なら在籍中)
+    #[serde(default)]
+    status_changes: Vec<StatusChange>,
+    // 在籍状況の履歴を持つ前のジャーナルの退職日 (読むだけで書かない)
+    #[serde(default, skip_serializing)]
+    termination_date: Option<NaiveDate>,
+    // 支払いスケジュールだけを変えたときの最初の支払い期間の初日も同様
+    #[serde(default)]
+    schedule_start: Option<NaiveDate>,
+
+    classification: ClassificationRecord,
+    schedule: ScheduleRecord,
+    method: MethodRecord,
+    affiliation: AffiliationRecord,
+}
+impl EmployeeRecord {
+    pub fn id(&self) -> EmployeeId {
+        self.id
+    }
+    pub fn from_employee(emp: &Employee) -> Result<Self, DaoError> {
+        let unsupported = |kind: &str| {
+            DaoError::UnexpectedError(format!(
+                "unsupported {} for emp_id={}: can't persist it",
+                kind,
+                emp.id()
+            ))
+        };
+
+        let classification = {
+            let c = emp.classification();
+            let c = c.lock().unwrap();
+            let any = c.as_any();
+            if let Some(c) = any.downcast_ref::<SalariedClassification>() {
+                ClassificationRecord::Salaried(c.clone())
+            } else if let Some(c) = any.downcast_ref::<HourlyClassification>() {
+                ClassificationRecord::Hourly(c.clone())
+            } else if let Some(c) = any.downcast_ref::<CommissionedClassification>() {
+                ClassificationRecord::Commissioned(c.clone())
+            } else {
+                return Err(unsupported("classification"));
+            }
+        };
+        let schedule = {
+            let s = emp.schedule();
+            let s = s.lock().unwrap();
+            let any = s.as_any();
+            if any.is::<MonthlySchedule>() {
+                ScheduleRecord::Monthly
+            } else if any.is::<WeeklySchedule>() {
+                ScheduleRecord::Weekly
+            } else if any.is::<SemimonthlySchedule>() {
+                ScheduleRecord::Semimonthly
+            } else if let Some(s) = any.downcast_ref::<MonthlyOnSchedule>() {
+                ScheduleRecord::MonthlyOn(s.clone())
+            } else if let Some(s) = any.downcast_ref::<WeeklyOnSchedule>() {
+                ScheduleRecord::WeeklyOn(s.clone())
+            } else if let Some(s) = any.downcast_ref::<BiweeklyFromSchedule>() {
+                ScheduleRecord::BiweeklyFrom(s.clone())
+            } else {
+                return Err(unsupported("schedule"));
+            }
+        };
+        let method = {
+            let m = emp.method();
+            let m = m.lock().unwrap();
+            let any = m.as_any();
+            if any.is::<HoldMethod>() {
+                MethodRecord::Hold
+            } else if let Some(m) = any.downcast_ref::<DirectMethod>() {
+                MethodRecord::Direct(m.clone())
+            } else if let Some(m) = any.downcast_ref::<MailMethod>() {
+                MethodRecord::Mail(m.clone())
+            } else {
+                return Err(unsupported("method"));
+            }
+        };
+        let affiliation = {
+            let a = emp.affiliation();
+            let a = a.lock().unwrap();
+            let any = a.as_any();
+            if any.is::<NoAffiliation>() {
+                AffiliationRecord::None
+            } else if let Some(a) = any.downcast_ref::<UnionAffiliation>() {
+                AffiliationRecord::Union(a.clone())
+            } else {
+                return Err(unsupported("affiliation"));
+            }
+        };
+
+        Ok(Self {
+            id: emp.id(),
+            name: emp.name().to_string(),
+            address: emp.address().to_string(),
+            tax: emp.tax_profile(),
+            overtime: emp.overtime_policy().cloned(),
+            hire_date: emp.hire_date(),
+            status_changes: emp.status_changes().to_vec(),
+            termination_date: None,
+            schedule_start: emp.schedule_start(),
+            classification,
+            schedule,
+            method,
+            affiliation,
+        })
+    }
+    // fetch のたびに新しい Employee を組み立てるので、呼び出し側の変更は update するまで反映されない
+    pub fn to_employee(&self) -> Employee {
+        let mut emp = Employee::new(
+            self.id,
+            &self.name,
+            &self.address,
+            match &self.classification {
+                ClassificationRecord::Salaried(c) => Arc::new(Mutex::new(c.clone())),
+                ClassificationRecord::Hourly(c) => Arc::new(Mutex::new(c.clone())),
+                ClassificationRecord::Commissioned(c) => Arc::new(Mutex::new(c.clone())),
+            },
+            match &self.schedule {
+                ScheduleRecord::Monthly => Arc::new(Mutex::new(MonthlySchedule)),
+                ScheduleRecord::Weekly => Arc::new(Mutex::new(WeeklySchedule)),
+                ScheduleRecord::Biweekly => Arc::new(Mutex::new(BiweeklyFromSchedule::default())),
+                ScheduleRecord::Semimonthly => Arc::new(Mutex::new(SemimonthlySchedule)),
+                ScheduleRecord::MonthlyOn(s) => Arc::new(Mutex::new(s.clone())),
+                ScheduleRecord::WeeklyOn(s) => Arc::new(Mutex::new(s.clone())),
+                ScheduleRecord::BiweeklyFrom(s) => Arc::new(Mutex::new(s.clone())),
+            },
+            match &self.method {
+                MethodRecord::Hold => Arc::new(Mutex::new(HoldMethod)),
+                MethodRecord::Direct(m) => Arc::new(Mutex::new(m.clone())),
+                MethodRecord::Mail(m) => Arc::new(Mutex::new(m.clone())),
+            },
+            match &self.affiliation {
+                AffiliationRecord::None => Arc::new(Mutex::new(NoAffiliation)),
+                AffiliationRecord::Union(a) => Arc::new(Mutex::new(a.clone())),
+            },
+        );
+        emp.set_tax_profile(self.tax);
+        emp.set_overtime_policy(self.overtime.clone());
+        emp.set_hire_date(self.hire_date);
+        emp.set_schedule_start(self.schedule_start);
+        let mut status_changes = self.status_changes.clone();
+        if let Some(date) = self.termination_date.filter(|_| status_changes.is_empty()) {
+            status_changes.push(StatusChange::new(
+                date + Days::new(1),
+                EmploymentStatus::Terminated,
+            ));
+        }
+        emp.set_status_changes(status_changes);
+        emp
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaycheckRecord {
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    // 種類を持つ前のジャーナルは定期の支払い
+    #[serde(default)]
+    run_type: RunType,
+
+    // 合計額は明細から求まるが、ジャーナルを読みやすくするために残しておく
+    gross_pay: Money,
+    deductions: Money,
+    net_pay: Money,
+    #[serde(default)]
+    earning_lines: Vec<EarningLine>,
+    #[serde(default)]
+    deduction_lines: Vec<DeductionLine>,
+    #[serde(default)]
+    year_to_date: Option<YearToDate>,
+}
+impl PaycheckRecord {
+    pub fn pay_date(&self) -> NaiveDate {
+        self.period_end
+    }
+    pub fn run_type(&self) -> RunType {
+        self.run_type
+    }
+    pub fn has_year_to_date(&self) -> bool {
+        self.year_to_date.is_some()
+    }
+    pub fn to_paycheck(&self) -> Paycheck {
+        let mut pc =
+            Paycheck::new(self.period_start..=self.period_end).with_run_type(self.run_type);
+        // 明細を記録する前のジャーナルでは合計額を 1 行の明細にする
+        let mut earning_lines = self.earning_lines.clone();
+        if earning_lines.is_empty() && !self.gross_pay.is_zero() {
+            earning_lines.push(EarningLine::new(EarningKind::Unitemized, self.gross_pay));
+        }
+        let mut deduction_lines = self.deduction_lines.clone();
+        if deduction_lines.is_empty() && !self.deductions.is_zero() {
+            deduction_lines.push(DeductionLine::new(
+                DeductionKind::Unitemized,
+                self.deductions,
+            ));
+        }
+        pc.set_earning_lines(earning_lines);
+        pc.set_deduction_lines(deduction_lines);
+        if let Some(ytd) = &self.year_to_date {
+            pc.set_year_to_date(ytd.clone());
+        }
+        pc
+    }
+}
+impl From<&Paycheck> for PaycheckRecord {
+    fn from(pc: &Paycheck) -> Self {
+        let period = pc.get_pay_period();
+        Self {
+            period_start: *period.start(),
+            period_end: *period.end(),
+            run_type: pc.run_type(),
+            gross_pay: pc.gross_pay(),
+            deductions: pc.deductions(),
+            net_pay: pc.net_pay(),
+            earning_lines: pc.earning_lines().to_vec(),
+            deduction_lines: pc.deduction_lines().to_vec(),
+            year_to_date: pc.year_to_date().cloned(),
+        }
+    }
+}
+
+// 1 トランザクション分の変更操作
+// コミット時にまとめてジャーナルへ 1 行として追記する
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Op {
+    AddEmployee(EmployeeRecord),
+    UpdateEmployee(EmployeeRecord),
+    DeleteEmployee(EmployeeId),
+    AddUnionMember(MemberId, EmployeeId),
+    DeleteUnionMember(MemberId),
+    RecordPaycheck(EmployeeId, PaycheckRecord),
+    UpdateYearToDate(EmployeeId, YearToDate),
+    RecordTimeCardAudit(EmployeeId, TimeCardAudit),
+}
@@ -1,35 +1,55 @@
 // dao の具体的な実装
-use log::trace;
+use chrono::NaiveDate;
+use log::{trace, warn};
 use std::{
-    collections::HashMap,
+    collections::{hash_map::Entry, HashMap},
+    ops::RangeInclusive,
     sync::{Arc, Mutex, MutexGuard},
 };
 
 use dao::{DaoError, EmployeeDao};
-use payroll_domain::{Employee, EmployeeId, MemberId, Paycheck};
+use payroll_domain::{
+    Employee, EmployeeId, MemberId, Paycheck, RunType, TimeCardAudit, YearToDate,
+};
 
 #[derive(Debug, Clone)]
 pub struct HashDB {
     // HashDB を DBMS として PayrollDb が DB(テーブルの集合) を表現
     payroll_db: Arc<Mutex<PayrollDb>>,
+    // run_tx の間はこの lock を保持して、スナップショットの取得から復元までを直列化する
+    tx_lock: Arc<Mutex<()>>,
+}
+
+impl Default for HashDB {
+    fn default() -> Self {
+        Self::new()
+    }
 }
+
 impl HashDB {
     pub fn new() -> Self {
         let db = PayrollDb {
             employees: HashMap::new(),
             union_members: HashMap::new(),
             paychecks: HashMap::new(),
+            year_to_dates: HashMap::new(),
+            timecard_audits: HashMap::new(),
         };
         Self {
             payroll_db: Arc::new(Mutex::new(db)),
+            tx_lock: Arc::new(Mutex::new(())),
         }
     }
 }
+// employees には他と Arc を共有しない Employee だけを入れる (add/update/fetch で deep_clone する)
+// そうしておけば PayrollDb の clone がそのままスナップショットになる
 #[derive(Debug, Clone)]
 pub struct PayrollDb {
     employees: HashMap<EmployeeId, Employee>,
     union_members: HashMap<MemberId, EmployeeId>,
     paychecks: HashMap<EmployeeId, Vec<Paycheck>>,
+    year_to_dates: HashMap<(EmployeeId, i32), YearToDate>,
+    timecard_audits: HashMap<EmployeeId, Vec<TimeCardAudit>>,
 }
 // DB の実装ごとに EmployeeDao トレイトを実装する
 impl EmployeeDao for HashDB {
@@ -40,9 +60,31 @@ impl EmployeeDao for HashDB {
         F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
     {
         trace!("run_tx called");
+        let _tx_lock = self.tx_lock.lock().unwrap();
+        // ロールバック用にトランザクション開始時点の状態を取っておく
+        let snapshot = self.payroll_db.lock().unwrap().clone();
+
         // Mutex の lock が RDB におけるトランザクションに相当
         let locked = self.payroll_db.lock().unwrap();
-        f(locked)
+        let result = f(locked);
+        if let Err(e) = &result {
+            warn!("run_tx: rollback: {}", e);
+            *self.payroll_db.lock().unwrap() = snapshot;
+        }
+        result
+    }
+    fn run_tx_rollback<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
+    {
+        trace!("run_tx_rollback called");
+        let _tx_lock = self.tx_lock.lock().unwrap();
+        let snapshot = self.payroll_db.lock().unwrap().clone();
+
+        let result = f(self.payroll_db.lock().unwrap());
+        // 成功しても失敗しても開始時点の状態に戻す
+        *self.payroll_db.lock().unwrap() = snapshot;
+        result
     }
 
     fn add<'a>(
@@ -56,7 +98,7 @@ impl EmployeeDao for HashDB {
             if tx.employees.contains_key(&emp_id) {
                 return Err(DaoError::EmployeeAlreadyExists(emp_id));
             }
-            tx.employees.insert(emp_id, emp);
+            tx.employees.insert(emp_id, emp.deep_clone());
             Ok(emp_id)
         })
     }
@@ -82,7 +124,7 @@ impl EmployeeDao for HashDB {
             trace!("fetch::with_tx called: id={}", id);
             tx.employees
                 .get(&id)
-                .cloned()
+                .map(Employee::deep_clone)
                 .ok_or(DaoError::EmployeeNotFound(id))
         })
     }
@@ -92,7 +134,11 @@ impl EmployeeDao for HashDB {
         trace!("fetch_all called");
         tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
             trace!("fetch_all::with_tx called");
-            Ok(tx.employees.iter().map(|(k, v)| (*k, v.clone())).collect())
+            Ok(tx
+                .employees
+                .iter()
+                .map(|(k, v)| (*k, v.deep_clone()))
+                .collect())
         })
     }
     fn update<'a>(
@@ -103,8 +149,8 @@ impl EmployeeDao for HashDB {
         tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
             let emp_id = emp.id();
             trace!("save::with_tx called: emp_id={},emp={:?}", emp_id, emp);
-            if tx.employees.contains_key(&emp_id) {
-                tx.employees.insert(emp_id, emp);
+            if let Entry::Occupied(mut e) = tx.employees.entry(emp_id) {
+                e.insert(emp.deep_clone());
                 return Ok(());
             }
             Err(DaoError::EmployeeNotFound(emp_id))
@@ -161,6 +207,23 @@ impl EmployeeDao for HashDB {
                 .ok_or(DaoError::MemberNotFound(member_id))
         })
     }
+    fn find_member_ids<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<MemberId>, Err = DaoError> {
+        trace!("find_member_ids called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("find_member_ids::with_tx called: emp_id={}", emp_id);
+            let mut found = tx
+                .union_members
+                .iter()
+                .filter(|(_, e)| **e == emp_id)
+                .map(|(m, _)| *m)
+                .collect::<Vec<_>>();
+            found.sort();
+            Ok(found)
+        })
+    }
     fn record_paycheck<'a>(
         &self,
         emp_id: EmployeeId,
@@ -173,8 +236,288 @@ impl EmployeeDao for HashDB {
                 emp_id,
                 pc
             );
-            tx.paychecks.entry(emp_id).or_insert_with(Vec::new).push(pc);
+            let (pay_date, run_type) = (pc.pay_date(), pc.run_type());
+            let pcs = tx.paychecks.entry(emp_id).or_default();
+            if pcs
+                .iter()
+                .any(|recorded| recorded.pay_date() == pay_date && recorded.run_type() == run_type)
+            {
+                return Err(DaoError::PaycheckAlreadyExists(emp_id, pay_date));
+            }
+            pcs.push(pc);
+            Ok(())
+        })
+    }
+    fn fetch_paycheck<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_date: NaiveDate,
+        run_type: RunType,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Paycheck, Err = DaoError> {
+        trace!("fetch_paycheck called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paycheck::with_tx called: emp_id={},pay_date={},run_type={}",
+                emp_id,
+                pay_date,
+                run_type
+            );
+            tx.paychecks
+                .get(&emp_id)
+                .and_then(|pcs| {
+                    pcs.iter()
+                        .find(|pc| pc.pay_date() == pay_date && pc.run_type() == run_type)
+                })
+                .cloned()
+                .ok_or(DaoError::PaycheckNotFound(emp_id, pay_date))
+        })
+    }
+    fn fetch_paychecks<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_dates: RangeInclusive<NaiveDate>,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<Paycheck>, Err = DaoError> {
+        trace!("fetch_paychecks called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paychecks::with_tx called: emp_id={},pay_dates={:?}",
+                emp_id,
+                pay_dates
+            );
+            Ok(tx
+                .paychecks
+                .get(&emp_id)
+                .map(|pcs| {
+                    pcs.iter()
+                        .filter(|pc| pc.run_type().pays_work())
+                        .filter(|pc| pay_dates.contains(&pc.pay_date()))
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default())
+        })
+    }
+    fn fetch_last_pay_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Option<NaiveDate>, Err = DaoError> {
+        trace!("fetch_last_pay_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_last_pay_date::with_tx called: emp_id={}", emp_id);
+            Ok(tx.paychecks.get(&emp_id).and_then(|pcs| {
+                pcs.iter()
+                    .filter(|pc| pc.run_type().pays_work())
+                    .map(|pc| pc.pay_date())
+                    .max()
+            }))
+        })
+    }
+    fn fetch_paychecks_for_run<'a>(
+        &self,
+        pay_date: NaiveDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Paycheck)>, Err = DaoError> {
+        trace!("fetch_paychecks_for_run called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paychecks_for_run::with_tx called: pay_date={}",
+                pay_date
+            );
+            let mut found = tx
+                .paychecks
+                .iter()
+                .flat_map(|(emp_id, pcs)| {
+                    pcs.iter()
+                        .filter(|pc| pc.run_type() == RunType::Regular && pc.pay_date() == pay_date)
+                        .map(|pc| (*emp_id, pc.clone()))
+                })
+                .collect::<Vec<_>>();
+            found.sort_by_key(|(emp_id, _)| *emp_id);
+            Ok(found)
+        })
+    }
+    fn fetch_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        year: i32,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = YearToDate, Err = DaoError> {
+        trace!("fetch_year_to_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_year_to_date::with_tx called: emp_id={},year={}",
+                emp_id,
+                year
+            );
+            Ok(tx
+                .year_to_dates
+                .get(&(emp_id, year))
+                .cloned()
+                .unwrap_or_else(|| YearToDate::new(year)))
+        })
+    }
+    fn update_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        ytd: YearToDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("update_year_to_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "update_year_to_date::with_tx called: emp_id={},ytd={:?}",
+                emp_id,
+                ytd
+            );
+            tx.year_to_dates.insert((emp_id, ytd.year), ytd);
+            Ok(())
+        })
+    }
+    fn record_timecard_audit<'a>(
+        &self,
+        emp_id: EmployeeId,
+        audit: TimeCardAudit,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("record_timecard_audit called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "record_timecard_audit::with_tx called: emp_id={},audit={:?}",
+                emp_id,
+                audit
+            );
+            tx.timecard_audits.entry(emp_id).or_default().push(audit);
+            Ok(())
+        })
+    }
+    fn fetch_timecard_audits<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<TimeCardAudit>, Err = DaoError> {
+        trace!("fetch_timecard_audits called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_timecard_audits::with_tx called: emp_id={}", emp_id);
+            Ok(tx.timecard_audits.get(&emp_id).cloned().unwrap_or_default())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dao_test::add_hourly;
+    use payroll_domain::{EarningKind, EarningLine, Money};
+    use payroll_impl::HourlyClassification;
+    use tx_rs::Tx;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    fn paycheck(start: NaiveDate, end: NaiveDate, gross_cents: i64) -> Paycheck {
+        let mut pc = Paycheck::new(start..=end);
+        pc.set_earning_lines(vec![EarningLine::new(
+            EarningKind::Salary,
+            Money::from_cents(gross_cents),
+        )]);
+        pc
+    }
+
+    dao_test::dao_conformance!(|_: &str| HashDB::new());
+
+    fn timecards(db: &HashDB, id: u32) -> Vec<(NaiveDate, f32)> {
+        let emp = db.run_tx(|mut ctx| db.fetch(id.into()).run(&mut ctx));
+        let classification = emp.unwrap().classification();
+        let classification = classification.lock().unwrap();
+        classification
+            .as_any()
+            .downcast_ref::<HourlyClassification>()
+            .unwrap()
+            .timecards()
+            .collect()
+    }
+
+    #[test]
+    fn test_rollback() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+
+        let result = db.run_tx(|mut ctx| {
+            db.add_union_member(7734.into(), 1.into()).run(&mut ctx)?;
+            let mut emp = db.fetch(1.into()).run(&mut ctx)?;
+            emp.set_name("Robert");
+            db.update(emp).run(&mut ctx)?;
+            db.record_paycheck(
+                1.into(),
+                paycheck(date(2025, 1, 25), date(2025, 1, 31), 100),
+            )
+            .run(&mut ctx)?;
+            db.delete(2.into()).run(&mut ctx)
+        });
+        assert!(matches!(result, Err(DaoError::EmployeeNotFound(_))));
+
+        let result = db.run_tx(|mut ctx| {
+            let emp = db.fetch(1.into()).run(&mut ctx)?;
+            assert_eq!(emp.name(), "Bob");
+            assert!(matches!(
+                db.find_union_member(7734.into()).run(&mut ctx),
+                Err(DaoError::MemberNotFound(_))
+            ));
+            db.fetch_paychecks_for_run(date(2025, 1, 31)).run(&mut ctx)
+        });
+        assert_eq!(result.unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_rollback_in_place_change() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+
+        // fetch した Employee の中身を直接書き換えても、失敗すれば元に戻る
+        let result: Result<(), DaoError> = db.run_tx(|mut ctx| {
+            let emp = db.fetch(1.into()).run(&mut ctx)?;
+            emp.classification()
+                .lock()
+                .unwrap()
+                .as_any_mut()
+                .downcast_mut::<HourlyClassification>()
+                .unwrap()
+                .add_timecard(date(2025, 1, 31), 8.0);
+            db.update(emp).run(&mut ctx)?;
+            Err(DaoError::UnexpectedError("abort".into()))
+        });
+        assert!(result.is_err());
+        assert_eq!(timecards(&db, 1), vec![]);
+
+        // update しなければ反映されない
+        db.run_tx(|mut ctx| {
+            let emp = db.fetch(1.into()).run(&mut ctx)?;
+            emp.classification()
+                .lock()
+                .unwrap()
+                .as_any_mut()
+                .downcast_mut::<HourlyClassification>()
+                .unwrap()
+                .add_timecard(date(2025, 1, 31), 8.0);
             Ok(())
         })
+        .unwrap();
+        assert_eq!(timecards(&db, 1), vec![]);
+    }
+
+    #[test]
+    fn test_run_tx_rollback() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+
+        // 成功しても変更は残らず、結果だけが返る
+        let result = db.run_tx_rollback(|mut ctx| {
+            let mut emp = db.fetch(1.into()).run(&mut ctx)?;
+            emp.set_name("Robert");
+            db.update(emp).run(&mut ctx)?;
+            Ok(db.fetch(1.into()).run(&mut ctx)?.name().to_string())
+        });
+        assert_eq!(result.unwrap(), "Robert");
+        let emp = db.run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx));
+        assert_eq!(emp.unwrap().name(), "Bob");
+
+        // 失敗したときは元のエラーが返る
+        let result = db.run_tx_rollback(|mut ctx| db.fetch(2.into()).run(&mut ctx));
+        assert!(matches!(result, Err(DaoError::EmployeeNotFound(_))));
     }
 }
@@ -1,10 +1,16 @@
 use getopts::Options;
 use log::{debug, error, trace};
-use std::{env, fmt};
+use std::{env, fmt, sync::Arc};
 
 use app::Application;
-use hs_db::HashDB;
-use payroll_impl::PayrollFactoryImpl;
+use dao::EmployeeDao;
+use payroll_domain::{
+    BusinessCalendar, DisbursementSink, NoWithholding, OvertimePolicy, Withholding,
+};
+use payroll_impl::{
+    load_business_calendar, load_overtime_policy, FileSink, PayrollFactoryImpl, StdoutSink,
+    TableWithholding,
+};
 use text_parser_tx_source::TextParserTxSource;
 use tx_app::{Runner, TxApp, TxSource};
 use tx_app_impl::{app_impl, reader_impl, runner_impl};
@@ -21,6 +27,11 @@ pub struct AppConfig {
     repl: bool,
     program: String,
     script_file: Option<String>,
+    db_file: Option<String>,
+    pay_records: Option<String>,
+    withholding: Option<String>,
+    overtime: Option<String>,
+    calendar: Option<String>,
     opts: Options,
 }
 impl fmt::Debug for AppConfig {
@@ -34,20 +45,55 @@ impl fmt::Debug for AppConfig {
             .field("repl", &self.repl)
             .field("program", &self.program)
             .field("script_file", &self.script_file)
+            .field("db_file", &self.db_file)
+            .field("pay_records", &self.pay_records)
+            .field("withholding", &self.withholding)
+            .field("overtime", &self.overtime)
+            .field("calendar", &self.calendar)
             .finish()
     }
 }
 impl AppConfig {
     pub fn new() -> Result<Self, anyhow::Error> {
         let args: Vec<String> = env::args().collect();
-        let program = args.get(0).expect("program name");
+        let program = args.first().expect("program name");
         let mut opts = Options::new();
         opts.optflag("?", "help", "Print this help menu")
             .optflag("q", "quiet", "Don't output unnecessary information")
             .optflag("f", "failopen-tx", "Transaction failopen")
             .optflag("s", "soft-landing", "Soft landing application")
             .optflag("c", "chronograph", "Enable chronograph for the application")
-            .optflag("r", "repl", "Run into REPL mode");
+            .optflag("r", "repl", "Run into REPL mode")
+            .optopt(
+                "d",
+                "db",
+                "Persist data into the file, or SQLite with sqlite:PATH (in-memory if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "o",
+                "pay-records",
+                "Append pay records into the file as JSON lines (stdout if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "w",
+                "withholding",
+                "Withhold taxes by the tables in the JSON file (no withholding if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "e",
+                "overtime",
+                "Pay overtime by the company-wide policy in the JSON file (over 8 hours a day if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "C",
+                "calendar",
+                "Pay on business days by the holiday calendar in the JSON file (no holidays if omitted)",
+                "PATH",
+            );
 
         let matches = match opts.parse(&args[1..]) {
             Ok(m) => m,
@@ -65,7 +111,12 @@ impl AppConfig {
             chronograph: matches.opt_present("c"),
             repl: matches.opt_present("r"),
             program: program.to_string(),
-            script_file: matches.free.get(0).cloned(),
+            script_file: matches.free.first().cloned(),
+            db_file: matches.opt_str("d"),
+            pay_records: matches.opt_str("o"),
+            withholding: matches.opt_str("w"),
+            overtime: matches.opt_str("e"),
+            calendar: matches.opt_str("C"),
             opts,
         })
     }
@@ -97,17 +148,112 @@ impl AppConfig {
         trace!("script_file called: {:?}", self.script_file);
         self.script_file.as_deref()
     }
+    pub fn db_file(&self) -> Option<&str> {
+        trace!("db_file called: {:?}", self.db_file);
+        self.db_file.as_deref()
+    }
+    pub fn pay_records(&self) -> Option<&str> {
+        trace!("pay_records called: {:?}", self.pay_records);
+        self.pay_records.as_deref()
+    }
+    pub fn withholding(&self) -> Option<&str> {
+        trace!("withholding called: {:?}", self.withholding);
+        self.withholding.as_deref()
+    }
+    pub fn overtime(&self) -> Option<&str> {
+        trace!("overtime called: {:?}", self.overtime);
+        self.overtime.as_deref()
+    }
+    pub fn calendar(&self) -> Option<&str> {
+        trace!("calendar called: {:?}", self.calendar);
+        self.calendar.as_deref()
+    }
     pub fn help_message(&self) -> String {
         trace!("help_message called");
         let brief = format!("Usage: {} [options] FILE", self.program);
         self.opts.usage(&brief)
     }
 
+    // Payday の支払い記録の送り先
+    pub fn make_sink(&self) -> Result<Arc<dyn DisbursementSink>, anyhow::Error> {
+        trace!("make_sink called");
+        match self.pay_records() {
+            Some(path) => {
+                debug!("make_sink: with file={}, using FileSink", path);
+                Ok(Arc::new(FileSink::open(path)?))
+            }
+            None => {
+                debug!("make_sink: file is None, using StdoutSink");
+                Ok(Arc::new(StdoutSink))
+            }
+        }
+    }
+
+    // Payday で使う源泉徴収の税率表
+    pub fn make_withholding(&self) -> Result<Arc<dyn Withholding>, anyhow::Error> {
+        trace!("make_withholding called");
+        match self.withholding() {
+            Some(path) => {
+                debug!(
+                    "make_withholding: with file={}, using TableWithholding",
+                    path
+                );
+                Ok(Arc::new(TableWithholding::load(path)?))
+            }
+            None => {
+                debug!("make_withholding: file is None, using NoWithholding");
+                Ok(Arc::new(NoWithholding))
+            }
+        }
+    }
+
+    // Payday で使う会社全体の残業の規則
+    pub fn make_overtime_policy(&self) -> Result<Arc<OvertimePolicy>, anyhow::Error> {
+        trace!("make_overtime_policy called");
+        match self.overtime() {
+            Some(path) => {
+                debug!("make_overtime_policy: with file={}", path);
+                Ok(Arc::new(load_overtime_policy(path)?))
+            }
+            None => {
+                debug!("make_overtime_policy: file is None, using the default policy");
+                Ok(Arc::new(OvertimePolicy::default()))
+            }
+        }
+    }
+
+    // 休日に当たる支払日をずらす会社の暦
+    pub fn make_calendar(&self) -> Result<Arc<BusinessCalendar>, anyhow::Error> {
+        trace!("make_calendar called");
+        match self.calendar() {
+            Some(path) => {
+                debug!("make_calendar: with file={}", path);
+                Ok(Arc::new(load_business_calendar(path)?))
+            }
+            None => {
+                debug!("make_calendar: file is None, using no holidays");
+                Ok(Arc::new(BusinessCalendar::none()))
+            }
+        }
+    }
+
     // db is expected to setup or initialized specially for the application or the test case.
-    pub fn build_tx_app(&self, db: HashDB) -> Box<dyn Application> {
+    pub fn build_tx_app<T>(
+        &self,
+        db: T,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+        calendar: Arc<BusinessCalendar>,
+    ) -> Box<dyn Application>
+    where
+        T: EmployeeDao + Clone + 'static,
+    {
         trace!("build_tx_app called");
-        let mut tx_app: Box<dyn Application> =
-            Box::new(TxApp::new(self.make_tx_source(db), self.make_tx_runner()));
+        let mut tx_app: Box<dyn Application> = Box::new(TxApp::new(
+            self.make_tx_source(db, sink, withholding, overtime, calendar),
+            self.make_tx_runner(),
+        ));
 
         if self.soft_landing {
             debug!("build_tx_app: should soft landing, using with_soft_landing");
@@ -121,9 +267,20 @@ impl AppConfig {
         tx_app
     }
 
-    fn make_tx_source(&self, db: HashDB) -> Box<dyn TxSource> {
+    fn make_tx_source<T>(
+        &self,
+        db: T,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+        calendar: Arc<BusinessCalendar>,
+    ) -> Box<dyn TxSource>
+    where
+        T: EmployeeDao + Clone + 'static,
+    {
         trace!("make_tx_source called");
-        let tx_factory = TxFactoryImpl::new(db, PayrollFactoryImpl);
+        let tx_factory = TxFactoryImpl::new(db, PayrollFactoryImpl, sink, withholding, overtime)
+            .with_calendar(calendar);
 
         if let Some(file) = self.script_file() {
             debug!("make_tx_source: with file={}, using file_reader", file);
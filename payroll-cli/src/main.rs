@@ -1,12 +1,17 @@
 use log::{debug, info, trace};
+use std::{fmt::Debug, sync::Arc};
 
+use dao::EmployeeDao;
+use file_db::FileDB;
 use hs_db::HashDB;
 use payroll_cli::AppConfig;
+use payroll_domain::{BusinessCalendar, DisbursementSink, OvertimePolicy, Withholding};
+use sqlite_db::SqliteDB;
 
 fn print_header(_app_conf: &AppConfig) {
     trace!("print_header called");
     // this banner generated by using `figlet -f slant 'payro$$'`
-    let lines = vec![
+    let lines = [
         r#"                                   __  __"#,
         r#"    ____  ____ ___  ___________  _/ /_/ /"#,
         r#"   / __ \/ __ `/ / / / ___/ __ \/ __/ __/"#,
@@ -38,10 +43,64 @@ fn main() -> Result<(), anyhow::Error> {
         print_header(&app_conf);
     }
 
-    let db = HashDB::new();
+    let sink = app_conf.make_sink()?;
+    let withholding = app_conf.make_withholding()?;
+    let overtime = app_conf.make_overtime_policy()?;
+    let calendar = app_conf.make_calendar()?;
+    match app_conf.db_file() {
+        Some(url) if url.starts_with("sqlite:") => {
+            let path = url.trim_start_matches("sqlite:");
+            debug!("main: with db file={}, using SqliteDB", path);
+            run(
+                &app_conf,
+                SqliteDB::open(path)?,
+                sink,
+                withholding,
+                overtime,
+                calendar,
+            )?;
+        }
+        Some(path) => {
+            debug!("main: with db file={}, using FileDB", path);
+            run(
+                &app_conf,
+                FileDB::open(path)?,
+                sink,
+                withholding,
+                overtime,
+                calendar,
+            )?;
+        }
+        None => {
+            debug!("main: db file is None, using HashDB");
+            run(
+                &app_conf,
+                HashDB::new(),
+                sink,
+                withholding,
+                overtime,
+                calendar,
+            )?;
+        }
+    }
+
+    info!("main finished");
+    Ok(())
+}
 
+fn run<T>(
+    app_conf: &AppConfig,
+    db: T,
+    sink: Arc<dyn DisbursementSink>,
+    withholding: Arc<dyn Withholding>,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
+) -> Result<(), anyhow::Error>
+where
+    T: EmployeeDao + Clone + Debug + 'static,
+{
     trace!("main: TxApp building");
-    let mut tx_app = app_conf.build_tx_app(db.clone());
+    let mut tx_app = app_conf.build_tx_app(db.clone(), sink, withholding, overtime, calendar);
     trace!("main: TxApp running");
     tx_app.run()?;
     trace!("main: TxApp finished");
@@ -52,6 +111,5 @@ fn main() -> Result<(), anyhow::Error> {
         println!("{:#?}", db);
     }
 
-    info!("main finished");
     Ok(())
 }
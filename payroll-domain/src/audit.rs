@@ -0,0 +1,30 @@
+// 支払い済みの期間のタイムカードを訂正・取り消したときの監査記録
+// 支払い済みの Paycheck はそのまま残るので、差額の精算はこの記録をもとに行う
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeCardAudit {
+    // 変更したタイムカードの日付
+    pub date: NaiveDate,
+    pub old_hours: f32,
+    // None なら取り消し
+    pub new_hours: Option<f32>,
+    // その日を含む支払い期間の Paycheck の支払日
+    pub pay_date: NaiveDate,
+}
+impl TimeCardAudit {
+    pub fn new(
+        date: NaiveDate,
+        old_hours: f32,
+        new_hours: Option<f32>,
+        pay_date: NaiveDate,
+    ) -> Self {
+        Self {
+            date,
+            old_hours,
+            new_hours,
+            pay_date,
+        }
+    }
+}
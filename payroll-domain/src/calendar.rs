@@ -0,0 +1,217 @@
+// 支払日を営業日に合わせるための暦
+// 月末や金曜日の支払日が休日に当たったら、rollover の規則で前後の営業日に支払う
+// 暦そのものはただのデータで、設定ファイルからの読み込みは payroll-impl 側に置く
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid business calendar: {0}")]
+pub struct InvalidBusinessCalendar(String);
+
+// 休日に当たった支払日をずらす先
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Rollover {
+    // 直前の営業日
+    #[default]
+    Previous,
+    // 直後の営業日
+    Next,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusinessCalendar {
+    // 毎週の休日
+    #[serde(default = "default_weekend")]
+    pub weekend: Vec<Weekday>,
+    // 年ごとの祝日
+    #[serde(default)]
+    pub holidays: BTreeMap<i32, Vec<NaiveDate>>,
+    #[serde(default)]
+    pub rollover: Rollover,
+}
+fn default_weekend() -> Vec<Weekday> {
+    vec![Weekday::Sat, Weekday::Sun]
+}
+impl BusinessCalendar {
+    // 休日のない暦 (支払日をずらさない)
+    pub fn none() -> Self {
+        Self {
+            weekend: vec![],
+            holidays: BTreeMap::new(),
+            rollover: Rollover::Previous,
+        }
+    }
+    pub fn validate(&self) -> Result<(), InvalidBusinessCalendar> {
+        let invalid = |msg: String| Err(InvalidBusinessCalendar(msg));
+        if (0..7).all(|i| self.weekend.iter().any(|w| w.num_days_from_monday() == i)) {
+            return invalid("weekend: no business day in a week".to_string());
+        }
+        for (year, dates) in &self.holidays {
+            if let Some(d) = dates.iter().find(|d| d.year() != *year) {
+                return invalid(format!("holidays: {} is not in {}", d, year));
+            }
+        }
+        Ok(())
+    }
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.weekend.contains(&date.weekday()) || self.is_public_holiday(date)
+    }
+    // 毎週の休日を除いた、年ごとの祝日だけ (残業の祝日の割増や営業日での按分に使う)
+    pub fn is_public_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays
+            .get(&date.year())
+            .is_some_and(|dates| dates.contains(&date))
+    }
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.is_holiday(date)
+    }
+    // 予定の支払日に実際に支払う日
+    pub fn roll(&self, date: NaiveDate) -> NaiveDate {
+        let mut day = date;
+        while self.is_holiday(day) {
+            day = match self.rollover {
+                Rollover::Previous => day - Days::new(1),
+                Rollover::Next => day + Days::new(1),
+            };
+        }
+        day
+    }
+    // 実際に date に支払うことになる予定の支払日の候補 (古い順)
+    // date が休日なら何もなく、営業日なら date と、date にずれてくる続きの休日
+    pub fn scheduled_dates(&self, date: NaiveDate) -> Vec<NaiveDate> {
+        if self.is_holiday(date) {
+            return vec![];
+        }
+        let mut dates = vec![date];
+        loop {
+            let next = match self.rollover {
+                Rollover::Previous => *dates.last().unwrap() + Days::new(1),
+                Rollover::Next => *dates.first().unwrap() - Days::new(1),
+            };
+            if !self.is_holiday(next) {
+                break;
+            }
+            match self.rollover {
+                Rollover::Previous => dates.push(next),
+                Rollover::Next => dates.insert(0, next),
+            }
+        }
+        dates
+    }
+}
+// デフォルトは休日のない暦で、どの支払日もずらさない
+impl Default for BusinessCalendar {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    fn calendar(rollover: Rollover) -> BusinessCalendar {
+        BusinessCalendar {
+            weekend: default_weekend(),
+            holidays: BTreeMap::from([
+                (2025, vec![date(2025, 12, 25), date(2025, 12, 26)]),
+                (2026, vec![date(2026, 1, 1)]),
+            ]),
+            rollover,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(BusinessCalendar::none().validate().is_ok());
+        assert!(calendar(Rollover::Previous).validate().is_ok());
+        let cal = BusinessCalendar {
+            holidays: BTreeMap::from([(2026, vec![date(2025, 12, 25)])]),
+            ..BusinessCalendar::none()
+        };
+        assert!(cal.validate().is_err());
+        let cal = BusinessCalendar {
+            weekend: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ],
+            ..BusinessCalendar::none()
+        };
+        assert!(cal.validate().is_err());
+    }
+
+    #[test]
+    fn test_roll() {
+        let cal = calendar(Rollover::Previous);
+        assert_eq!(cal.roll(date(2025, 12, 24)), date(2025, 12, 24));
+        // 金曜日の祝日は、前の日の祝日も飛ばして水曜日に
+        assert_eq!(cal.roll(date(2025, 12, 26)), date(2025, 12, 24));
+        // 土曜日の月末は金曜日に
+        assert_eq!(cal.roll(date(2026, 1, 31)), date(2026, 1, 30));
+        // 元日は年をまたいで前の年の大晦日に
+        assert_eq!(cal.roll(date(2026, 1, 1)), date(2025, 12, 31));
+        let cal = calendar(Rollover::Next);
+        assert_eq!(cal.roll(date(2025, 12, 25)), date(2025, 12, 29));
+        assert_eq!(cal.roll(date(2026, 1, 1)), date(2026, 1, 2));
+    }
+
+    #[test]
+    fn test_scheduled_dates() {
+        let cal = calendar(Rollover::Previous);
+        assert_eq!(
+            cal.scheduled_dates(date(2025, 12, 24)),
+            vec![
+                date(2025, 12, 24),
+                date(2025, 12, 25),
+                date(2025, 12, 26),
+                date(2025, 12, 27),
+                date(2025, 12, 28)
+            ]
+        );
+        assert!(cal.scheduled_dates(date(2025, 12, 25)).is_empty());
+        assert_eq!(
+            cal.scheduled_dates(date(2025, 12, 23)),
+            vec![date(2025, 12, 23)]
+        );
+        let cal = calendar(Rollover::Next);
+        assert_eq!(
+            cal.scheduled_dates(date(2025, 12, 29)),
+            vec![
+                date(2025, 12, 25),
+                date(2025, 12, 26),
+                date(2025, 12, 27),
+                date(2025, 12, 28),
+                date(2025, 12, 29)
+            ]
+        );
+        assert_eq!(
+            BusinessCalendar::none().scheduled_dates(date(2025, 12, 27)),
+            vec![date(2025, 12, 27)]
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let cal: BusinessCalendar =
+            serde_json::from_str(r#"{"holidays": {"2026": ["2026-01-01"]}, "rollover": "Next"}"#)
+                .unwrap();
+        assert_eq!(
+            cal,
+            BusinessCalendar {
+                weekend: vec![Weekday::Sat, Weekday::Sun],
+                holidays: BTreeMap::from([(2026, vec![date(2026, 1, 1)])]),
+                rollover: Rollover::Next,
+            }
+        );
+    }
+}
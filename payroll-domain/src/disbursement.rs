@@ -0,0 +1,53 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use thiserror::Error;
+
+use crate::{
+    DeductionLine, EarningLine, EmployeeId, MethodInfo, Money, Paycheck, RunType, YearToDate,
+};
+
+// 支払い 1 件分の記録: PaymentMethod::pay が作り、Payday が支払い先 (DisbursementSink) へ渡す
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayRecord {
+    pub emp_id: EmployeeId,
+    pub pay_date: NaiveDate,
+    // 種類を持つ前の記録は定期の支払い
+    #[serde(default)]
+    pub run_type: RunType,
+    pub method: MethodInfo,
+
+    pub gross_pay: Money,
+    pub deductions: Money,
+    pub net_pay: Money,
+
+    pub earning_lines: Vec<EarningLine>,
+    pub deduction_lines: Vec<DeductionLine>,
+    pub year_to_date: Option<YearToDate>,
+}
+impl PayRecord {
+    pub fn new(emp_id: EmployeeId, pc: &Paycheck, method: MethodInfo) -> Self {
+        Self {
+            emp_id,
+            pay_date: pc.pay_date(),
+            run_type: pc.run_type(),
+            method,
+            gross_pay: pc.gross_pay(),
+            deductions: pc.deductions(),
+            net_pay: pc.net_pay(),
+            earning_lines: pc.earning_lines().to_vec(),
+            deduction_lines: pc.deduction_lines().to_vec(),
+            year_to_date: pc.year_to_date().cloned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("disbursement failed: {0}")]
+pub struct DisbursementError(pub String);
+
+// 支払い記録の送り先
+// Payday の記録が確定してから、その回の分をまとめて渡される
+pub trait DisbursementSink: Debug + Send + Sync {
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), DisbursementError>;
+}
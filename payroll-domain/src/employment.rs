@@ -0,0 +1,68 @@
+// 在籍状況の履歴: 入社日から効力発生日ごとに状況が変わる
+// 休職中も勤務の記録どおりに支払い、退職すると最後の Paycheck を支払って Payday の対象から外す
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EmploymentStatus {
+    #[default]
+    Active,
+    OnLeave,
+    Terminated,
+}
+impl EmploymentStatus {
+    pub const ALL: [EmploymentStatus; 3] = [Self::Active, Self::OnLeave, Self::Terminated];
+}
+impl fmt::Display for EmploymentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+// effective はその状況になった最初の日 (退職なら退職日の翌日)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub effective: NaiveDate,
+    pub status: EmploymentStatus,
+}
+impl StatusChange {
+    pub fn new(effective: NaiveDate, status: EmploymentStatus) -> Self {
+        Self { effective, status }
+    }
+}
+
+// 効力発生日の順に並べた変更から、その日の状況を求める (変更がなければ Active)
+pub fn status_on(changes: &[StatusChange], date: NaiveDate) -> EmploymentStatus {
+    changes
+        .iter()
+        .take_while(|c| c.effective <= date)
+        .last()
+        .map_or(EmploymentStatus::default(), |c| c.status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_status_on() {
+        let changes = [
+            StatusChange::new(date(2, 1), EmploymentStatus::OnLeave),
+            StatusChange::new(date(3, 1), EmploymentStatus::Active),
+            StatusChange::new(date(3, 15), EmploymentStatus::Terminated),
+        ];
+        assert_eq!(status_on(&changes, date(1, 31)), EmploymentStatus::Active);
+        assert_eq!(status_on(&changes, date(2, 1)), EmploymentStatus::OnLeave);
+        assert_eq!(status_on(&changes, date(3, 14)), EmploymentStatus::Active);
+        assert_eq!(
+            status_on(&changes, date(3, 15)),
+            EmploymentStatus::Terminated
+        );
+        assert_eq!(status_on(&[], date(3, 15)), EmploymentStatus::Active);
+    }
+}
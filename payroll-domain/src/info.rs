@@ -0,0 +1,238 @@
+// 問い合わせ結果として外に出すための従業員情報
+// Employee は dyn トレイトを抱えているので、比較や複製ができるただのデータに落としておく
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{
+    EmployeeId, EmploymentStatus, MemberId, Money, OvertimePolicy, StatusChange, TaxProfile,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmployeeInfo {
+    pub id: EmployeeId,
+    pub name: String,
+    pub address: String,
+    pub classification: ClassificationInfo,
+    pub schedule: ScheduleInfo,
+    pub method: MethodInfo,
+    pub affiliation: AffiliationInfo,
+    // 古いクライアントや記録との互換のため、省略されたらデフォルトの条件とする
+    #[serde(default)]
+    pub tax: TaxProfile,
+    // 従業員ごとの残業の規則 (None なら会社全体の規則)
+    #[serde(default)]
+    pub overtime: Option<OvertimePolicy>,
+    // 入社日と退職日 (どちらも None なら期間の制限なし)
+    #[serde(default)]
+    pub hire_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub termination_date: Option<NaiveDate>,
+    // 在籍状況の変更 (効力発生日の順)
+    #[serde(default)]
+    pub status_changes: Vec<StatusChange>,
+}
+impl EmployeeInfo {
+    // 最後の変更での状況 (効力発生日がまだ来ていない変更も含める)
+    pub fn status(&self) -> EmploymentStatus {
+        self.status_changes
+            .last()
+            .map_or(EmploymentStatus::default(), |c| c.status)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClassificationInfo {
+    Salaried { salary: Money },
+    Hourly { hourly_rate: Money },
+    Commissioned { salary: Money, commission_rate: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleInfo {
+    // 月末
+    Monthly,
+    // 金曜日
+    Weekly,
+    // 起算日を指定しない隔週払い (DEFAULT_BIWEEKLY_ANCHOR を起算日とする BiweeklyFrom として作る)
+    Biweekly,
+    // 15 日と月末
+    Semimonthly,
+    // 毎月 day 日 (その日がない月は月末)
+    MonthlyOn { day: u32 },
+    // 毎週 weekday
+    WeeklyOn { weekday: Weekday },
+    // anchor を最初の支払い期間の初日として 2 週ごと
+    BiweeklyFrom { anchor: NaiveDate },
+}
+impl ScheduleInfo {
+    // 起算日を持つ前の隔週払い (偶数週の金曜日) と同じ 2025/1/10 を支払日にする起算日
+    pub const DEFAULT_BIWEEKLY_ANCHOR: NaiveDate = NaiveDate::from_ymd_opt(2024, 12, 28).unwrap();
+
+    // 年間の支払い回数
+    pub fn pays_per_year(&self) -> u32 {
+        match self {
+            Self::Monthly | Self::MonthlyOn { .. } => 12,
+            Self::Weekly | Self::WeeklyOn { .. } => 52,
+            Self::Biweekly | Self::BiweeklyFrom { .. } => 26,
+            Self::Semimonthly => 24,
+        }
+    }
+    // 支払日にできない日を指定していないか
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::MonthlyOn { day } => (1..=31).contains(day),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MethodInfo {
+    Hold,
+    Direct { bank: String, account: String },
+    Mail { address: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AffiliationInfo {
+    None,
+    Union { member_id: MemberId, dues: Money },
+}
+
+// ListEmployees の絞り込み条件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EmployeeFilter {
+    #[default]
+    All,
+    Salaried,
+    Hourly,
+    Commissioned,
+    Monthly,
+    Weekly,
+    Biweekly,
+    Semimonthly,
+    Hold,
+    Direct,
+    Mail,
+    Member,
+    NoMember,
+    Active,
+    OnLeave,
+    Terminated,
+}
+impl EmployeeFilter {
+    pub fn matches(&self, emp: &EmployeeInfo) -> bool {
+        match self {
+            Self::All => true,
+            Self::Salaried => matches!(emp.classification, ClassificationInfo::Salaried { .. }),
+            Self::Hourly => matches!(emp.classification, ClassificationInfo::Hourly { .. }),
+            Self::Commissioned => {
+                matches!(emp.classification, ClassificationInfo::Commissioned { .. })
+            }
+            // 支払日を指定したスケジュールも同じ頻度として扱う
+            Self::Monthly => matches!(
+                emp.schedule,
+                ScheduleInfo::Monthly | ScheduleInfo::MonthlyOn { .. }
+            ),
+            Self::Weekly => matches!(
+                emp.schedule,
+                ScheduleInfo::Weekly | ScheduleInfo::WeeklyOn { .. }
+            ),
+            Self::Biweekly => matches!(
+                emp.schedule,
+                ScheduleInfo::Biweekly | ScheduleInfo::BiweeklyFrom { .. }
+            ),
+            Self::Semimonthly => emp.schedule == ScheduleInfo::Semimonthly,
+            Self::Hold => emp.method == MethodInfo::Hold,
+            Self::Direct => matches!(emp.method, MethodInfo::Direct { .. }),
+            Self::Mail => matches!(emp.method, MethodInfo::Mail { .. }),
+            Self::Member => matches!(emp.affiliation, AffiliationInfo::Union { .. }),
+            Self::NoMember => emp.affiliation == AffiliationInfo::None,
+            Self::Active => emp.status() == EmploymentStatus::Active,
+            Self::OnLeave => emp.status() == EmploymentStatus::OnLeave,
+            Self::Terminated => emp.status() == EmploymentStatus::Terminated,
+        }
+    }
+}
+impl fmt::Display for EmployeeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hourly_member() -> EmployeeInfo {
+        EmployeeInfo {
+            id: 1.into(),
+            name: "Bob".to_string(),
+            address: "Home".to_string(),
+            classification: ClassificationInfo::Hourly {
+                hourly_rate: Money::from_cents(1525),
+            },
+            schedule: ScheduleInfo::Weekly,
+            method: MethodInfo::Hold,
+            affiliation: AffiliationInfo::Union {
+                member_id: 7234.into(),
+                dues: Money::from_cents(945),
+            },
+            tax: TaxProfile::default(),
+            overtime: None,
+            hire_date: None,
+            termination_date: None,
+            status_changes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let emp = hourly_member();
+        for filter in [
+            EmployeeFilter::All,
+            EmployeeFilter::Hourly,
+            EmployeeFilter::Weekly,
+            EmployeeFilter::Hold,
+            EmployeeFilter::Member,
+            EmployeeFilter::Active,
+        ] {
+            assert!(filter.matches(&emp), "{} should match", filter);
+        }
+        for filter in [
+            EmployeeFilter::Salaried,
+            EmployeeFilter::Commissioned,
+            EmployeeFilter::Monthly,
+            EmployeeFilter::Biweekly,
+            EmployeeFilter::Semimonthly,
+            EmployeeFilter::Direct,
+            EmployeeFilter::Mail,
+            EmployeeFilter::NoMember,
+            EmployeeFilter::OnLeave,
+            EmployeeFilter::Terminated,
+        ] {
+            assert!(!filter.matches(&emp), "{} should not match", filter);
+        }
+
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let emp = EmployeeInfo {
+            status_changes: vec![
+                StatusChange::new(date(2, 1), EmploymentStatus::OnLeave),
+                StatusChange::new(date(3, 1), EmploymentStatus::Terminated),
+            ],
+            ..hourly_member()
+        };
+        assert!(EmployeeFilter::Terminated.matches(&emp));
+        assert!(!EmployeeFilter::Active.matches(&emp));
+
+        let emp = EmployeeInfo {
+            schedule: ScheduleInfo::WeeklyOn {
+                weekday: Weekday::Mon,
+            },
+            ..hourly_member()
+        };
+        assert!(EmployeeFilter::Weekly.matches(&emp));
+        assert!(!EmployeeFilter::Biweekly.matches(&emp));
+    }
+}
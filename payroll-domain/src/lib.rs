@@ -1,4 +1,4 @@
-use chrono::NaiveDate;
+use chrono::{Days, NaiveDate};
 use dyn_clone::DynClone;
 use log::{debug, trace};
 use std::{
@@ -8,14 +8,43 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+mod audit;
+mod calendar;
+mod disbursement;
+mod employment;
+mod info;
+mod line_item;
+mod overtime;
+mod preview;
 mod types;
+mod withholding;
+mod year_to_date;
+pub use audit::*;
+pub use calendar::*;
+pub use disbursement::*;
+pub use employment::*;
+pub use info::*;
+pub use line_item::*;
+pub use overtime::*;
+pub use preview::*;
 pub use types::*;
+pub use withholding::*;
+pub use year_to_date::*;
 
 #[derive(Debug, Clone)]
 pub struct Employee {
     id: EmployeeId,
     name: String,
     address: String,
+    tax_profile: TaxProfile,
+    // None なら会社全体の残業の規則に従う
+    overtime_policy: Option<OvertimePolicy>,
+    // 入社日より前と退職日より後の日の分は支払わない
+    hire_date: Option<NaiveDate>,
+    // 在籍状況の変更 (効力発生日の順)
+    status_changes: Vec<StatusChange>,
+    // 支払いスケジュールを変えたときの、新しいスケジュールでの最初の支払い期間の初日
+    schedule_start: Option<NaiveDate>,
 
     classification: Arc<Mutex<dyn PaymentClassification>>,
     schedule: Arc<Mutex<dyn PaymentSchedule>>,
@@ -37,6 +66,11 @@ impl Employee {
             id,
             name: name.to_string(),
             address: address.to_string(),
+            tax_profile: TaxProfile::default(),
+            overtime_policy: None,
+            hire_date: None,
+            status_changes: vec![],
+            schedule_start: None,
             classification,
             schedule,
             method,
@@ -53,6 +87,34 @@ impl Employee {
     pub fn address(&self) -> &str {
         &self.address
     }
+    pub fn tax_profile(&self) -> TaxProfile {
+        self.tax_profile
+    }
+    pub fn overtime_policy(&self) -> Option<&OvertimePolicy> {
+        self.overtime_policy.as_ref()
+    }
+    pub fn hire_date(&self) -> Option<NaiveDate> {
+        self.hire_date
+    }
+    pub fn status_changes(&self) -> &[StatusChange] {
+        &self.status_changes
+    }
+    pub fn status_on(&self, date: NaiveDate) -> EmploymentStatus {
+        status_on(&self.status_changes, date)
+    }
+    // 退職日 (Terminated になった日の前日)
+    pub fn termination_date(&self) -> Option<NaiveDate> {
+        self.status_changes
+            .iter()
+            .find(|c| c.status == EmploymentStatus::Terminated)
+            .and_then(|c| c.effective.pred_opt())
+    }
+    pub fn is_terminated(&self) -> bool {
+        self.termination_date().is_some()
+    }
+    pub fn schedule_start(&self) -> Option<NaiveDate> {
+        self.schedule_start
+    }
     pub fn classification(&self) -> Arc<Mutex<dyn PaymentClassification>> {
         Arc::clone(&self.classification)
     }
@@ -71,6 +133,60 @@ impl Employee {
     pub fn set_address(&mut self, address: &str) {
         self.address = address.to_string();
     }
+    pub fn set_tax_profile(&mut self, tax_profile: TaxProfile) {
+        self.tax_profile = tax_profile;
+    }
+    pub fn set_overtime_policy(&mut self, overtime_policy: Option<OvertimePolicy>) {
+        self.overtime_policy = overtime_policy;
+    }
+    // 保存してあった日付を戻すときに使う (Classification には既に反映されている)
+    pub fn set_hire_date(&mut self, hire_date: Option<NaiveDate>) {
+        self.hire_date = hire_date;
+    }
+    pub fn set_status_changes(&mut self, status_changes: Vec<StatusChange>) {
+        self.status_changes = status_changes;
+    }
+    // 保存してあった日付を戻すときに使う
+    pub fn set_schedule_start(&mut self, schedule_start: Option<NaiveDate>) {
+        self.schedule_start = schedule_start;
+    }
+    // 支払いスケジュールだけを変える
+    // start は前のスケジュールで支払った日の翌日で、新しいスケジュールの最初の支払い期間をそこから始める
+    pub fn change_schedule(
+        &mut self,
+        schedule: Arc<Mutex<dyn PaymentSchedule>>,
+        start: Option<NaiveDate>,
+    ) {
+        self.schedule = schedule;
+        self.schedule_start = start;
+    }
+    // 同じ効力発生日の変更は置き換える
+    pub fn change_status(&mut self, effective: NaiveDate, status: EmploymentStatus) {
+        let i = self
+            .status_changes
+            .partition_point(|c| c.effective < effective);
+        match self.status_changes.get_mut(i) {
+            Some(c) if c.effective == effective => c.status = status,
+            _ => self
+                .status_changes
+                .insert(i, StatusChange::new(effective, status)),
+        }
+    }
+    // 入社日を決めて、その前の日の分を支払わないようにする
+    pub fn hire_on(&mut self, date: NaiveDate) {
+        self.hire_date = Some(date);
+        self.classification.lock().unwrap().start_on(date);
+    }
+    // 退職日を決めて、その翌日からの分を支払わないようにする
+    // 退職日より後の状況の変更は取り消す
+    // Classification を入れ替えたときにもかけ直す
+    pub fn terminate_on(&mut self, date: NaiveDate) {
+        let next_day = date.succ_opt().unwrap();
+        self.status_changes.retain(|c| c.effective <= date);
+        self.status_changes
+            .push(StatusChange::new(next_day, EmploymentStatus::Terminated));
+        self.classification.lock().unwrap().close(next_day);
+    }
     pub fn set_classification(&mut self, classification: Arc<Mutex<dyn PaymentClassification>>) {
         self.classification = classification;
     }
@@ -83,100 +199,324 @@ impl Employee {
     pub fn set_affiliation(&mut self, affiliation: Arc<Mutex<dyn Affiliation>>) {
         self.affiliation = affiliation;
     }
-    pub fn is_pay_date(&self, date: NaiveDate) -> bool {
-        self.schedule.lock().unwrap().is_pay_date(date)
+    // 支払い期間が入社前なら支払日にしない
+    // 退職したら最後の Paycheck を支払い済みなので、それ以降の支払日もない
+    pub fn is_pay_date(&self, date: NaiveDate, calendar: &BusinessCalendar) -> bool {
+        let schedule = self.schedule.lock().unwrap();
+        !self.is_terminated()
+            && schedule.is_pay_date_in(date, calendar)
+            && self.is_employed_in(&schedule.get_pay_period_in(date, calendar))
     }
-    pub fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate> {
-        self.schedule.lock().unwrap().get_pay_period(pay_date)
+    pub fn is_employed_in(&self, period: &RangeInclusive<NaiveDate>) -> bool {
+        self.hire_date.is_none_or(|d| d <= *period.end())
+            && self.termination_date().is_none_or(|d| *period.start() <= d)
     }
-    pub fn payday(&self, pc: &mut Paycheck) {
+    // スケジュールを変えた直後の支払い期間は、前のスケジュールで支払った日と重ならないように縮める
+    pub fn get_pay_period(
+        &self,
+        pay_date: NaiveDate,
+        calendar: &BusinessCalendar,
+    ) -> RangeInclusive<NaiveDate> {
+        let period = self
+            .schedule
+            .lock()
+            .unwrap()
+            .get_pay_period_in(pay_date, calendar);
+        match self.schedule_start {
+            Some(start) if period.contains(&start) => start..=*period.end(),
+            _ => period,
+        }
+    }
+    // overtime は会社全体の残業の規則で、従業員ごとの規則があればそちらを使う
+    // calendar は祝日の割増や営業日での按分に使う
+    // ytd はこの Paycheck の支払日の年の累計で、この Paycheck の分を積み上げる
+    pub fn payday(
+        &self,
+        pc: &mut Paycheck,
+        withholding: &dyn Withholding,
+        overtime: &OvertimePolicy,
+        calendar: &BusinessCalendar,
+        ytd: &mut YearToDate,
+    ) -> PayRecord {
         trace!("payday called");
-        let gross_pay = self.classification.lock().unwrap().calculate_pay(pc);
-        debug!("gross_pay: {}", gross_pay);
-        let deductions = self.affiliation.lock().unwrap().calculate_deductions(pc);
-        debug!("deductions: {}", deductions);
-        let net_pay = gross_pay - deductions;
-        debug!("net_pay: {}", net_pay);
-        pc.set_gross_pay(gross_pay);
-        pc.set_deductions(deductions);
-        pc.set_net_pay(net_pay);
+        let overtime = self.overtime_policy.as_ref().unwrap_or(overtime);
+        let earning_lines = self
+            .classification
+            .lock()
+            .unwrap()
+            .calculate_pay(pc, overtime, calendar);
+        self.pay(pc, earning_lines, withholding, ytd)
+    }
+    // 定期外の Paycheck (退職したときの最後の支払い・賞与・精算)
+    // 支給の行は呼び出し側が決めておく (最後の支払いなら定期の支払い期間ごとに計算する)
+    pub fn off_cycle_payday(
+        &self,
+        pc: &mut Paycheck,
+        earning_lines: Vec<EarningLine>,
+        withholding: &dyn Withholding,
+        ytd: &mut YearToDate,
+    ) -> PayRecord {
+        trace!("off_cycle_payday called");
+        self.pay(pc, earning_lines, withholding, ytd)
+    }
+    fn pay(
+        &self,
+        pc: &mut Paycheck,
+        earning_lines: Vec<EarningLine>,
+        withholding: &dyn Withholding,
+        ytd: &mut YearToDate,
+    ) -> PayRecord {
+        debug!("earning lines: {:?}", earning_lines);
+        pc.set_earning_lines(earning_lines);
+        debug!("gross_pay: {}", pc.gross_pay());
+
+        // 組合費やサービス料は勤務の期間に対する支払いからだけ差し引く
+        let mut deduction_lines = if pc.run_type().pays_work() {
+            self.affiliation.lock().unwrap().calculate_deductions(pc)
+        } else {
+            vec![]
+        };
+        // 源泉徴収は総支給額が決まってから計算する
+        let pays_per_year = self.schedule.lock().unwrap().info().pays_per_year();
+        deduction_lines.extend(withholding.withhold(&self.tax_profile, pays_per_year, pc));
+        debug!("deduction lines: {:?}", deduction_lines);
+        pc.set_deduction_lines(deduction_lines);
+        debug!("deductions: {}", pc.deductions());
+        debug!("net_pay: {}", pc.net_pay());
+
+        ytd.add(pc);
+        debug!("year to date: {:?}", ytd);
+        pc.set_year_to_date(ytd.clone());
         debug!("updated paycheck: {:?}", pc);
-        self.method.lock().unwrap().pay(self.id, pc);
+        self.method.lock().unwrap().pay(self.id, pc)
+    }
+    // clone は Arc を共有するので、片方の変更がもう片方にも見えてしまう
+    // 状態を切り離したいときはこちらを使う
+    pub fn deep_clone(&self) -> Self {
+        Self {
+            id: self.id,
+            name: self.name.clone(),
+            address: self.address.clone(),
+            tax_profile: self.tax_profile,
+            overtime_policy: self.overtime_policy.clone(),
+            hire_date: self.hire_date,
+            status_changes: self.status_changes.clone(),
+            schedule_start: self.schedule_start,
+            classification: self.classification.lock().unwrap().clone_classification(),
+            schedule: self.schedule.lock().unwrap().clone_schedule(),
+            method: self.method.lock().unwrap().clone_method(),
+            affiliation: self.affiliation.lock().unwrap().clone_affiliation(),
+        }
+    }
+    pub fn info(&self) -> EmployeeInfo {
+        EmployeeInfo {
+            id: self.id,
+            name: self.name.clone(),
+            address: self.address.clone(),
+            classification: self.classification.lock().unwrap().info(),
+            schedule: self.schedule.lock().unwrap().info(),
+            method: self.method.lock().unwrap().info(),
+            affiliation: self.affiliation.lock().unwrap().info(),
+            tax: self.tax_profile,
+            overtime: self.overtime_policy.clone(),
+            hire_date: self.hire_date,
+            termination_date: self.termination_date(),
+            status_changes: self.status_changes.clone(),
+        }
+    }
+    pub fn repay(&self, pc: &Paycheck) -> PayRecord {
+        trace!("repay called");
+        // 記録済みの Paycheck を再計算せずに支払い方法へ再送する
+        self.method.lock().unwrap().pay(self.id, pc)
     }
 }
 
-#[derive(Debug, Clone)]
+// 総支給額・控除額・差引支給額は明細から求める
+#[derive(Debug, Clone, PartialEq)]
 pub struct Paycheck {
     period: RangeInclusive<NaiveDate>,
+    run_type: RunType,
 
-    gross_pay: f32,
-    deductions: f32,
-    net_pay: f32,
+    earning_lines: Vec<EarningLine>,
+    deduction_lines: Vec<DeductionLine>,
+    // この Paycheck を含めた年初来の累計 (累計を取る前に記録された Paycheck にはない)
+    year_to_date: Option<YearToDate>,
 }
 impl Paycheck {
     pub fn new(period: RangeInclusive<NaiveDate>) -> Self {
         Self {
             period,
-            gross_pay: 0.0,
-            deductions: 0.0,
-            net_pay: 0.0,
+            run_type: RunType::Regular,
+            earning_lines: vec![],
+            deduction_lines: vec![],
+            year_to_date: None,
         }
     }
+    // 定期の支払いでなければ種類を指定する
+    pub fn with_run_type(mut self, run_type: RunType) -> Self {
+        self.run_type = run_type;
+        self
+    }
     pub fn get_pay_period(&self) -> RangeInclusive<NaiveDate> {
         self.period.clone()
     }
-    pub fn gross_pay(&self) -> f32 {
-        self.gross_pay
+    pub fn run_type(&self) -> RunType {
+        self.run_type
+    }
+    pub fn pay_date(&self) -> NaiveDate {
+        *self.period.end()
+    }
+    pub fn earning_lines(&self) -> &[EarningLine] {
+        &self.earning_lines
+    }
+    pub fn deduction_lines(&self) -> &[DeductionLine] {
+        &self.deduction_lines
+    }
+    pub fn year_to_date(&self) -> Option<&YearToDate> {
+        self.year_to_date.as_ref()
     }
-    pub fn deductions(&self) -> f32 {
-        self.deductions
+    pub fn gross_pay(&self) -> Money {
+        self.earning_lines.iter().map(|line| line.amount).sum()
     }
-    pub fn net_pay(&self) -> f32 {
-        self.net_pay
+    pub fn deductions(&self) -> Money {
+        self.deduction_lines.iter().map(|line| line.amount).sum()
     }
-    pub fn set_gross_pay(&mut self, gross_pay: f32) {
-        self.gross_pay = gross_pay;
+    pub fn net_pay(&self) -> Money {
+        self.gross_pay() - self.deductions()
     }
-    pub fn set_deductions(&mut self, deductions: f32) {
-        self.deductions = deductions;
+    pub fn set_earning_lines(&mut self, earning_lines: Vec<EarningLine>) {
+        self.earning_lines = earning_lines;
     }
-    pub fn set_net_pay(&mut self, net_pay: f32) {
-        self.net_pay = net_pay;
+    pub fn set_deduction_lines(&mut self, deduction_lines: Vec<DeductionLine>) {
+        self.deduction_lines = deduction_lines;
+    }
+    pub fn set_year_to_date(&mut self, year_to_date: YearToDate) {
+        self.year_to_date = Some(year_to_date);
     }
     pub fn is_pay_date(&self, pay_date: NaiveDate) -> bool {
         self.period.contains(&pay_date)
     }
 }
 
-pub trait PaymentClassification: Debug + DynClone + Send + Sync {
+pub trait PaymentClassification:
+    Debug + DynClone + PaymentClassificationClone + Send + Sync
+{
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
-    fn calculate_pay(&self, pc: &Paycheck) -> f32;
+    // overtime と calendar は時給の従業員の勤務時間の振り分けや、給与の按分に使う
+    fn calculate_pay(
+        &self,
+        pc: &Paycheck,
+        overtime: &OvertimePolicy,
+        calendar: &BusinessCalendar,
+    ) -> Vec<EarningLine>;
+    fn info(&self) -> ClassificationInfo;
+    // 勤務の記録 (タイムカードや取り消していない売上) で最も早い日付と遅い日付 (記録がなければ None)
+    fn first_record_date(&self) -> Option<NaiveDate>;
+    fn last_record_date(&self) -> Option<NaiveDate>;
+    // effective より前の日の分は支払わない (入社日や給与の種類を変えた日から始める)
+    fn start_on(&mut self, effective: NaiveDate);
+    // effective 以降の分を支払わないようにする (給与の種類を変えたときや退職したとき)
+    fn close(&mut self, effective: NaiveDate);
 }
 dyn_clone::clone_trait_object!(PaymentClassification);
+// Arc<Mutex<dyn ..>> を共有せずに中身ごと複製するためのトレイト (Employee::deep_clone で使う)
+pub trait PaymentClassificationClone {
+    fn clone_classification(&self) -> Arc<Mutex<dyn PaymentClassification>>;
+}
+impl<T> PaymentClassificationClone for T
+where
+    T: PaymentClassification + Clone + 'static,
+{
+    fn clone_classification(&self) -> Arc<Mutex<dyn PaymentClassification>> {
+        Arc::new(Mutex::new(self.clone()))
+    }
+}
 
-pub trait PaymentSchedule: Debug + DynClone + Send + Sync {
+pub trait PaymentSchedule: Debug + DynClone + PaymentScheduleClone + Send + Sync {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
     fn is_pay_date(&self, date: NaiveDate) -> bool;
     fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate>;
+    fn info(&self) -> ScheduleInfo;
+
+    // 休日に当たる予定の支払日は calendar の規則で前後の営業日に支払う
+    fn is_pay_date_in(&self, date: NaiveDate, calendar: &BusinessCalendar) -> bool {
+        calendar
+            .scheduled_dates(date)
+            .into_iter()
+            .any(|d| self.is_pay_date(d))
+    }
+    // 支払い期間は実際の支払日で締め、前の予定の支払日を実際に支払った日の翌日から始める
+    // 休日が続いていくつかの支払日が同じ日にずれたら、その期間をまとめて支払う
+    fn get_pay_period_in(
+        &self,
+        pay_date: NaiveDate,
+        calendar: &BusinessCalendar,
+    ) -> RangeInclusive<NaiveDate> {
+        match calendar
+            .scheduled_dates(pay_date)
+            .into_iter()
+            .find(|d| self.is_pay_date(*d))
+        {
+            Some(scheduled) => {
+                let prev = *self.get_pay_period(scheduled).start() - Days::new(1);
+                calendar.roll(prev) + Days::new(1)..=pay_date
+            }
+            None => self.get_pay_period(pay_date),
+        }
+    }
 }
 dyn_clone::clone_trait_object!(PaymentSchedule);
+pub trait PaymentScheduleClone {
+    fn clone_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>>;
+}
+impl<T> PaymentScheduleClone for T
+where
+    T: PaymentSchedule + Clone + 'static,
+{
+    fn clone_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>> {
+        Arc::new(Mutex::new(self.clone()))
+    }
+}
 
-pub trait PaymentMethod: Debug + DynClone + Send + Sync {
+pub trait PaymentMethod: Debug + DynClone + PaymentMethodClone + Send + Sync {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
-    // TODO: return type
-    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck);
+    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck) -> PayRecord;
+    fn info(&self) -> MethodInfo;
 }
 dyn_clone::clone_trait_object!(PaymentMethod);
+pub trait PaymentMethodClone {
+    fn clone_method(&self) -> Arc<Mutex<dyn PaymentMethod>>;
+}
+impl<T> PaymentMethodClone for T
+where
+    T: PaymentMethod + Clone + 'static,
+{
+    fn clone_method(&self) -> Arc<Mutex<dyn PaymentMethod>> {
+        Arc::new(Mutex::new(self.clone()))
+    }
+}
 
-pub trait Affiliation: Debug + DynClone + Send + Sync {
+pub trait Affiliation: Debug + DynClone + AffiliationClone + Send + Sync {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
-    fn calculate_deductions(&self, pc: &Paycheck) -> f32;
+    fn calculate_deductions(&self, pc: &Paycheck) -> Vec<DeductionLine>;
+    fn info(&self) -> AffiliationInfo;
 }
 dyn_clone::clone_trait_object!(Affiliation);
+pub trait AffiliationClone {
+    fn clone_affiliation(&self) -> Arc<Mutex<dyn Affiliation>>;
+}
+impl<T> AffiliationClone for T
+where
+    T: Affiliation + Clone + 'static,
+{
+    fn clone_affiliation(&self) -> Arc<Mutex<dyn Affiliation>> {
+        Arc::new(Mutex::new(self.clone()))
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct NoAffiliation;
@@ -187,8 +527,11 @@ impl Affiliation for NoAffiliation {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn calculate_deductions(&self, _pc: &Paycheck) -> f32 {
-        0.0
+    fn calculate_deductions(&self, _pc: &Paycheck) -> Vec<DeductionLine> {
+        vec![]
+    }
+    fn info(&self) -> AffiliationInfo {
+        AffiliationInfo::None
     }
 }
 
@@ -204,6 +547,25 @@ mod tests {
         );
         let aff = NoAffiliation;
         let deductions = aff.calculate_deductions(&pc);
-        assert_eq!(deductions, 0.0);
+        assert_eq!(deductions, vec![]);
+    }
+
+    #[test]
+    fn test_totals_from_lines() {
+        let mut pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        pc.set_earning_lines(vec![
+            EarningLine::new(EarningKind::Regular, Money::from_cents(32000)),
+            EarningLine::new(EarningKind::Overtime, Money::from_cents(5250)),
+        ]);
+        pc.set_deduction_lines(vec![
+            DeductionLine::new(DeductionKind::Dues, Money::from_cents(945)),
+            DeductionLine::new(DeductionKind::ServiceCharge, Money::from_cents(1995)),
+        ]);
+        assert_eq!(pc.gross_pay(), Money::from_cents(37250));
+        assert_eq!(pc.deductions(), Money::from_cents(2940));
+        assert_eq!(pc.net_pay(), Money::from_cents(34310));
     }
 }
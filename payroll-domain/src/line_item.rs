@@ -0,0 +1,110 @@
+// Paycheck の明細: 総支給額と控除額はここに並ぶ行の合計になる
+// 支給の行は PaymentClassification が、控除の行は Affiliation と Withholding が作る
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::Money;
+
+// 支給の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EarningKind {
+    Salary,
+    // 時給の通常勤務分
+    Regular,
+    Overtime,
+    // 1 日の勤務時間が長すぎる分の倍額
+    DoubleTime,
+    // 土日・祝日の割増分
+    Premium,
+    Commission,
+    // 支払い済みの期間の売上を取り消したときに差し戻す歩合 (負の額)
+    Clawback,
+    // 支払い済みの期間にさかのぼってレートを変えたときの差額
+    RetroPay,
+    // 定期外の支払いの賞与
+    Bonus,
+    // 定期外の支払いの過不足の精算 (負の額なら回収)
+    Adjustment,
+    // 明細を記録するようになる前の Paycheck の総支給額
+    Unitemized,
+}
+impl EarningKind {
+    pub const ALL: [EarningKind; 11] = [
+        Self::Salary,
+        Self::Regular,
+        Self::Overtime,
+        Self::DoubleTime,
+        Self::Premium,
+        Self::Commission,
+        Self::Clawback,
+        Self::RetroPay,
+        Self::Bonus,
+        Self::Adjustment,
+        Self::Unitemized,
+    ];
+}
+impl fmt::Display for EarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EarningLine {
+    pub kind: EarningKind,
+    pub amount: Money,
+}
+impl EarningLine {
+    pub fn new(kind: EarningKind, amount: Money) -> Self {
+        Self { kind, amount }
+    }
+}
+
+// 控除の種類
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeductionKind {
+    // 組合費 (支払い期間中の金曜日の回数分)
+    Dues,
+    // 組合のサービス料 (1 件ごとに 1 行)
+    ServiceCharge,
+    // 支払い済みの期間のサービス料を取り消したときの払い戻し (負の額)
+    ServiceChargeRefund,
+    // 源泉徴収 (設定ファイルのルール名を持つ)
+    Withholding(String),
+    // 明細を記録するようになる前の Paycheck の控除額
+    Unitemized,
+}
+impl fmt::Display for DeductionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Withholding(name) => write!(f, "{}", name),
+            _ => fmt::Debug::fmt(self, f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeductionLine {
+    pub kind: DeductionKind,
+    pub amount: Money,
+}
+impl DeductionLine {
+    pub fn new(kind: DeductionKind, amount: Money) -> Self {
+        Self { kind, amount }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(EarningKind::Overtime.to_string(), "Overtime");
+        assert_eq!(DeductionKind::ServiceCharge.to_string(), "ServiceCharge");
+        assert_eq!(
+            DeductionKind::Withholding("Income Tax".to_string()).to_string(),
+            "Income Tax"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+// 時給の従業員の残業の規則
+// 規則そのものはただのデータで、勤務時間を振り分ける計算は payroll-impl 側に置く
+// 従業員ごとに設定でき、設定のない従業員には会社全体の規則が使われる
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid overtime policy: {0}")]
+pub struct InvalidOvertimePolicy(String);
+
+// 省略された規則は適用しない (None)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OvertimePolicy {
+    // 1 日の勤務時間がこれを超えた分は残業
+    #[serde(default)]
+    pub daily_after: Option<f32>,
+    // 1 週間の勤務時間 (1 日ごとの残業の分を除く) がこれを超えた分は残業
+    #[serde(default)]
+    pub weekly_after: Option<f32>,
+    // 1 日の勤務時間がこれを超えた分は倍額
+    #[serde(default)]
+    pub double_time_after: Option<f32>,
+    // 土日の残業でない時間に掛ける倍率
+    #[serde(default)]
+    pub weekend_rate: Option<f32>,
+    // 祝日 (会社の暦の holidays) の残業でない時間に掛ける倍率 (土日と重なったらこちらが優先)
+    #[serde(default)]
+    pub holiday_rate: Option<f32>,
+    // 1 週間の勤務時間を数え始める曜日
+    #[serde(default = "default_week_start")]
+    pub week_start: Weekday,
+    // 月給などの基本給を支払い期間の途中で按分するときの日数の数え方
+    #[serde(default)]
+    pub proration: Proration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Proration {
+    #[default]
+    CalendarDays,
+    // 土日と会社の暦の祝日を除いた日数
+    WorkingDays,
+}
+fn default_week_start() -> Weekday {
+    Weekday::Mon
+}
+impl OvertimePolicy {
+    pub const OVERTIME_RATE: f32 = 1.5;
+    pub const DOUBLE_TIME_RATE: f32 = 2.0;
+
+    // 何の規則もない (すべて通常の勤務時間になる)
+    pub fn none() -> Self {
+        Self {
+            daily_after: None,
+            weekly_after: None,
+            double_time_after: None,
+            weekend_rate: None,
+            holiday_rate: None,
+            week_start: default_week_start(),
+            proration: Proration::CalendarDays,
+        }
+    }
+    pub fn validate(&self) -> Result<(), InvalidOvertimePolicy> {
+        let invalid = |msg: String| Err(InvalidOvertimePolicy(msg));
+        for (name, hours) in [
+            ("daily_after", self.daily_after),
+            ("weekly_after", self.weekly_after),
+            ("double_time_after", self.double_time_after),
+        ] {
+            if let Some(h) = hours.filter(|h| !(h.is_finite() && *h >= 0.0)) {
+                return invalid(format!("{}: invalid hours {}", name, h));
+            }
+        }
+        for (name, rate) in [
+            ("weekend_rate", self.weekend_rate),
+            ("holiday_rate", self.holiday_rate),
+        ] {
+            if let Some(r) = rate.filter(|r| !(r.is_finite() && *r >= 1.0)) {
+                return invalid(format!("{}: invalid rate {}", name, r));
+            }
+        }
+        Ok(())
+    }
+}
+// デフォルトは 1 日 8 時間を超えた分を 1.5 倍にするだけ
+impl Default for OvertimePolicy {
+    fn default() -> Self {
+        Self {
+            daily_after: Some(8.0),
+            ..Self::none()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(OvertimePolicy::default().validate().is_ok());
+        let policy = OvertimePolicy {
+            weekly_after: Some(-1.0),
+            ..OvertimePolicy::none()
+        };
+        assert!(policy.validate().is_err());
+        let policy = OvertimePolicy {
+            weekend_rate: Some(0.5),
+            ..OvertimePolicy::none()
+        };
+        assert!(policy.validate().is_err());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        // 祝日は会社の暦に移したので、前の設定ファイルの holidays は読み飛ばす
+        let policy: OvertimePolicy =
+            serde_json::from_str(r#"{"weekly_after": 40, "holidays": ["2025-12-25"]}"#).unwrap();
+        assert_eq!(
+            policy,
+            OvertimePolicy {
+                weekly_after: Some(40.0),
+                ..OvertimePolicy::none()
+            }
+        );
+    }
+}
@@ -0,0 +1,196 @@
+// Payday の試算: 記録も送り出しもせずに、誰にいくら支払うかを直近の支払いと比べて見る
+use chrono::NaiveDate;
+use std::{fmt, ops::Sub};
+
+use crate::{EmployeeId, Money, PayRecord, Paycheck};
+
+// 総支給額・控除額・差引支給額の組
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayAmounts {
+    pub gross_pay: Money,
+    pub deductions: Money,
+    pub net_pay: Money,
+}
+impl From<&PayRecord> for PayAmounts {
+    fn from(record: &PayRecord) -> Self {
+        Self {
+            gross_pay: record.gross_pay,
+            deductions: record.deductions,
+            net_pay: record.net_pay,
+        }
+    }
+}
+impl From<&Paycheck> for PayAmounts {
+    fn from(pc: &Paycheck) -> Self {
+        Self {
+            gross_pay: pc.gross_pay(),
+            deductions: pc.deductions(),
+            net_pay: pc.net_pay(),
+        }
+    }
+}
+impl Sub for PayAmounts {
+    type Output = PayAmounts;
+    fn sub(self, rhs: PayAmounts) -> PayAmounts {
+        PayAmounts {
+            gross_pay: self.gross_pay - rhs.gross_pay,
+            deductions: self.deductions - rhs.deductions,
+            net_pay: self.net_pay - rhs.net_pay,
+        }
+    }
+}
+
+// 直近の (定期または最後の) 支払い
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastPay {
+    pub pay_date: NaiveDate,
+    pub amounts: PayAmounts,
+}
+
+// 試算で支払うことになった従業員 1 人分
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayPreview {
+    pub emp_id: EmployeeId,
+    pub name: String,
+    pub amounts: PayAmounts,
+    // まだ一度も支払っていなければ None
+    pub last: Option<LastPay>,
+}
+impl PayPreview {
+    // 直近の支払いからの増減
+    pub fn difference(&self) -> Option<PayAmounts> {
+        self.last.as_ref().map(|last| self.amounts - last.amounts)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaydayPreview {
+    pub pay_date: NaiveDate,
+    // 従業員番号の順
+    pub previews: Vec<PayPreview>,
+    // この支払日の分を支払い済みで、実行しても支払われない従業員
+    pub skipped: Vec<EmployeeId>,
+}
+impl PaydayPreview {
+    pub fn total(&self) -> PayAmounts {
+        self.previews
+            .iter()
+            .fold(PayAmounts::default(), |acc, p| PayAmounts {
+                gross_pay: acc.gross_pay + p.amounts.gross_pay,
+                deductions: acc.deductions + p.amounts.deductions,
+                net_pay: acc.net_pay + p.amounts.net_pay,
+            })
+    }
+}
+// CLI で見るための表 (増減は直近の支払いとの差)
+impl fmt::Display for PaydayPreview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Payday preview for {}", self.pay_date)?;
+        writeln!(
+            f,
+            "{:>6} {:<16} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            "emp_id", "name", "gross", "deductions", "net", "last paid", "net diff"
+        )?;
+        for p in &self.previews {
+            let (last_paid, diff) = match (&p.last, p.difference()) {
+                (Some(last), Some(diff)) if diff.net_pay < Money::ZERO => {
+                    (last.pay_date.to_string(), diff.net_pay.to_string())
+                }
+                (Some(last), Some(diff)) => {
+                    (last.pay_date.to_string(), format!("+{}", diff.net_pay))
+                }
+                _ => ("-".to_string(), "-".to_string()),
+            };
+            writeln!(
+                f,
+                "{:>6} {:<16} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                u32::from(p.emp_id),
+                p.name,
+                p.amounts.gross_pay.to_string(),
+                p.amounts.deductions.to_string(),
+                p.amounts.net_pay.to_string(),
+                last_paid,
+                diff
+            )?;
+        }
+        let total = self.total();
+        if !self.skipped.is_empty() {
+            let ids = self.skipped.iter().map(|id| u32::from(*id).to_string());
+            writeln!(f, "already paid: {}", ids.collect::<Vec<_>>().join(", "))?;
+        }
+        write!(
+            f,
+            "{:>6} {:<16} {:>10} {:>10} {:>10}",
+            "",
+            "total",
+            total.gross_pay.to_string(),
+            total.deductions.to_string(),
+            total.net_pay.to_string()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amounts(gross: i64, deductions: i64) -> PayAmounts {
+        PayAmounts {
+            gross_pay: Money::from_cents(gross),
+            deductions: Money::from_cents(deductions),
+            net_pay: Money::from_cents(gross - deductions),
+        }
+    }
+
+    #[test]
+    fn test_difference_from_last_pay() {
+        let date = NaiveDate::from_ymd_opt(2024, 8, 30).unwrap();
+        let mut p = PayPreview {
+            emp_id: 1.into(),
+            name: "Bob".to_string(),
+            amounts: amounts(100000, 2000),
+            last: None,
+        };
+        assert_eq!(p.difference(), None);
+
+        p.last = Some(LastPay {
+            pay_date: date.pred_opt().unwrap(),
+            amounts: amounts(90000, 2500),
+        });
+        assert_eq!(p.difference(), Some(amounts(10000, -500)));
+    }
+
+    #[test]
+    fn test_table() {
+        let date = NaiveDate::from_ymd_opt(2024, 8, 30).unwrap();
+        let preview = PaydayPreview {
+            pay_date: date,
+            previews: vec![
+                PayPreview {
+                    emp_id: 1.into(),
+                    name: "Bob".to_string(),
+                    amounts: amounts(100000, 2000),
+                    last: Some(LastPay {
+                        pay_date: NaiveDate::from_ymd_opt(2024, 7, 31).unwrap(),
+                        amounts: amounts(90000, 2000),
+                    }),
+                },
+                PayPreview {
+                    emp_id: 2.into(),
+                    name: "Alice".to_string(),
+                    amounts: amounts(50000, 0),
+                    last: None,
+                },
+            ],
+            skipped: vec![3.into()],
+        };
+        assert_eq!(preview.total(), amounts(150000, 2000));
+        let table = preview.to_string();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[2].contains("1000.00") && lines[2].contains("+100.00"));
+        assert!(lines[3].trim_end().ends_with('-'));
+        assert_eq!(lines[4], "already paid: 3");
+        assert!(lines[5].contains("1500.00"));
+    }
+}
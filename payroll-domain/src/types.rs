@@ -1,6 +1,13 @@
 use core::fmt;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct EmployeeId(u32);
 impl fmt::Display for EmployeeId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -18,7 +25,7 @@ impl From<EmployeeId> for u32 {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct MemberId(u32);
 impl fmt::Display for MemberId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -30,3 +37,398 @@ impl From<u32> for MemberId {
         Self(id)
     }
 }
+impl From<MemberId> for u32 {
+    fn from(id: MemberId) -> Self {
+        id.0
+    }
+}
+
+// 売上の番号: 従業員ごとに登録した順に 1 から振る
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ReceiptId(u32);
+impl fmt::Display for ReceiptId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ReceiptId({})", self.0)
+    }
+}
+impl From<u32> for ReceiptId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+impl From<ReceiptId> for u32 {
+    fn from(id: ReceiptId) -> Self {
+        id.0
+    }
+}
+
+// サービス料の番号: 組合員ごとに登録した順に 1 から振る
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ChargeId(u32);
+impl fmt::Display for ChargeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ChargeId({})", self.0)
+    }
+}
+impl From<u32> for ChargeId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+impl From<ChargeId> for u32 {
+    fn from(id: ChargeId) -> Self {
+        id.0
+    }
+}
+
+// 同じ支払日の Payday が再実行されたときの扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RerunPolicy {
+    // 既に支払済みの従業員がいれば Payday 全体をエラーにする
+    #[default]
+    Reject,
+    // 支払済みの従業員は飛ばして、未払いの従業員だけ支払う
+    Skip,
+    // 支払済みの従業員には記録済みの Paycheck をそのまま支払い方法に再送する
+    Replay,
+}
+impl fmt::Display for RerunPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Reject => write!(f, "Reject"),
+            Self::Skip => write!(f, "Skip"),
+            Self::Replay => write!(f, "Replay"),
+        }
+    }
+}
+
+// Paycheck を支払った回の種類
+// 同じ従業員・支払日でも種類が違えば別の Paycheck として記録する
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RunType {
+    // Payday による定期の支払い
+    #[default]
+    Regular,
+    // 金額を指定した賞与
+    Bonus,
+    // 金額を指定した過不足の精算 (負の額なら払い過ぎの回収)
+    Correction,
+    // 退職日までの最後の支払い
+    Final,
+}
+impl RunType {
+    pub const ALL: [RunType; 4] = [Self::Regular, Self::Bonus, Self::Correction, Self::Final];
+
+    // 勤務の期間に対する支払いか (賞与や精算は支払い済みの期間を進めない)
+    pub fn pays_work(&self) -> bool {
+        matches!(self, Self::Regular | Self::Final)
+    }
+}
+impl fmt::Display for RunType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+// 金額: 1 セント単位の固定小数点数
+// 端数は常に銀行丸め(最近接偶数への丸め)でセントに丸める
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Self(cents)
+    }
+    pub const fn cents(&self) -> i64 {
+        self.0
+    }
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid money: {0:?}")]
+pub struct ParseMoneyError(String);
+
+// n / d を銀行丸めした整数 (d > 0)
+fn div_half_even(n: i128, d: i128) -> i128 {
+    let (q, r) = (n.div_euclid(d), n.rem_euclid(d));
+    match (r * 2).cmp(&d) {
+        std::cmp::Ordering::Less => q,
+        std::cmp::Ordering::Greater => q + 1,
+        std::cmp::Ordering::Equal if q % 2 == 0 => q,
+        std::cmp::Ordering::Equal => q + 1,
+    }
+}
+
+// 10 進数の仮数 digits を 10^scale で割ってセント単位に銀行丸めする
+fn to_cents_half_even(digits: i128, scale: u32) -> Option<i64> {
+    let scaled = digits.checked_mul(100)?;
+    let divisor = 10i128.checked_pow(scale)?;
+    i64::try_from(div_half_even(scaled, divisor)).ok()
+}
+
+// "-123.456" を (-123456, 3) のような仮数と小数桁数に分解する
+fn parse_decimal(s: &str) -> Option<(i128, u32)> {
+    let (neg, body) = match s.strip_prefix('-') {
+        Some(body) => (true, body),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int, frac) = body.split_once('.').unwrap_or((body, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    if !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let digits = format!("{}{}", int, frac).parse::<i128>().ok()?;
+    let scale = u32::try_from(frac.len()).ok()?;
+    Some((if neg { -digits } else { digits }, scale))
+}
+
+impl FromStr for Money {
+    type Err = ParseMoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_decimal(s)
+            .and_then(|(digits, scale)| to_cents_half_even(digits, scale))
+            .map(Money)
+            .ok_or_else(|| ParseMoneyError(s.to_string()))
+    }
+}
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+}
+impl Serialize for Money {
+    // JSON の数値は浮動小数点として扱われがちなので、金額は文字列で出力する
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+        impl de::Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a decimal amount such as \"1234.56\"")
+            }
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+                v.parse().map_err(E::custom)
+            }
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+                v.to_string().parse().map_err(E::custom)
+            }
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+                v.to_string().parse().map_err(E::custom)
+            }
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Money, E> {
+                v.to_string().parse().map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+impl Mul<i64> for Money {
+    type Output = Money;
+    fn mul(self, rhs: i64) -> Money {
+        Money(self.0 * rhs)
+    }
+}
+impl Mul<f32> for Money {
+    type Output = Money;
+    // 時間数や歩合率との積
+    // 掛ける値は validate_hours / validate_commission_rate で検証済みであること
+    // f32 の 2 進誤差を持ち込まないよう、f32 を最短の 10 進表現として掛けてから銀行丸めする
+    fn mul(self, rhs: f32) -> Money {
+        let (digits, scale) = parse_decimal(&rhs.to_string()).expect("finite multiplier");
+        let product = digits * self.0 as i128;
+        // product はセント単位なので、to_cents_half_even が掛ける 100 を打ち消すために 2 桁多く割る
+        to_cents_half_even(product, scale + 2)
+            .map(Money)
+            .expect("money overflow")
+    }
+}
+impl Div<i64> for Money {
+    type Output = Money;
+    // 年額を支払い 1 回分に割り戻すときなどに使う
+    fn div(self, rhs: i64) -> Money {
+        assert!(rhs != 0, "money divided by zero");
+        let (n, d) = (self.0 as i128, rhs as i128);
+        let q = if d < 0 {
+            div_half_even(-n, -d)
+        } else {
+            div_half_even(n, d)
+        };
+        Money(i64::try_from(q).expect("money overflow"))
+    }
+}
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid input: {0}")]
+pub struct InvalidInput(String);
+impl InvalidInput {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+// 1 日の勤務時間は 0 より大きく 24 以下
+// NaN や無限大を Money に掛けると桁あふれするので、トランザクションの手前で弾く
+pub fn validate_hours(hours: f32) -> Result<(), InvalidInput> {
+    if hours.is_finite() && hours > 0.0 && hours <= 24.0 {
+        return Ok(());
+    }
+    Err(InvalidInput(format!("invalid hours {}", hours)))
+}
+// 歩合率は 0 以上 1 以下
+pub fn validate_commission_rate(rate: f32) -> Result<(), InvalidInput> {
+    if rate.is_finite() && (0.0..=1.0).contains(&rate) {
+        return Ok(());
+    }
+    Err(InvalidInput(format!("invalid commission rate {}", rate)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(s: &str) -> Money {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(money("3215.88"), Money::from_cents(321588));
+        assert_eq!(money("100"), Money::from_cents(10000));
+        assert_eq!(money("100."), Money::from_cents(10000));
+        assert_eq!(money(".5"), Money::from_cents(50));
+        assert_eq!(money("-9.45"), Money::from_cents(-945));
+        assert!("".parse::<Money>().is_err());
+        assert!("1.2.3".parse::<Money>().is_err());
+        assert!("abc".parse::<Money>().is_err());
+    }
+
+    #[test]
+    fn test_parse_rounds_half_even() {
+        assert_eq!(money("0.125"), Money::from_cents(12));
+        assert_eq!(money("0.135"), Money::from_cents(14));
+        assert_eq!(money("0.1251"), Money::from_cents(13));
+        assert_eq!(money("-0.125"), Money::from_cents(-12));
+        assert_eq!(money("-0.135"), Money::from_cents(-14));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Money::from_cents(321588).to_string(), "3215.88");
+        assert_eq!(Money::from_cents(5).to_string(), "0.05");
+        assert_eq!(Money::from_cents(-945).to_string(), "-9.45");
+        assert_eq!(Money::ZERO.to_string(), "0.00");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(money("1.10") + money("2.20"), money("3.30"));
+        assert_eq!(money("1.10") - money("2.20"), money("-1.10"));
+        assert_eq!(money("9.45") * 5, money("47.25"));
+        assert_eq!(
+            vec![money("0.10"), money("0.20")]
+                .into_iter()
+                .sum::<Money>(),
+            money("0.30")
+        );
+    }
+
+    #[test]
+    fn test_div() {
+        assert_eq!(money("120") / 12, money("10"));
+        assert_eq!(money("100") / 3, money("33.33"));
+        assert_eq!(money("0.05") / 2, money("0.02"));
+        assert_eq!(money("0.15") / 2, money("0.08"));
+        assert_eq!(money("-0.15") / 2, money("-0.08"));
+        assert_eq!(money("0.15") / -2, money("-0.08"));
+    }
+
+    #[test]
+    fn test_mul_f32() {
+        assert_eq!(money("1234") * 0.1, money("123.40"));
+        assert_eq!(money("10") * 11.0, money("110"));
+        assert_eq!(money("15.25") * 7.5, money("114.38"));
+        assert_eq!(money("0.25") * 0.5, money("0.12"));
+        assert_eq!(money("0.35") * 0.5, money("0.18"));
+    }
+
+    #[test]
+    fn test_validate_input() {
+        assert!(validate_hours(8.0).is_ok());
+        assert!(validate_hours(24.0).is_ok());
+        for hours in [0.0, -1.0, 24.5, f32::NAN, f32::INFINITY, f32::MAX] {
+            assert!(validate_hours(hours).is_err(), "{}", hours);
+        }
+        assert!(validate_commission_rate(0.0).is_ok());
+        assert!(validate_commission_rate(0.1).is_ok());
+        assert!(validate_commission_rate(1.0).is_ok());
+        for rate in [-0.1, 1.5, f32::NAN, f32::NEG_INFINITY] {
+            assert!(validate_commission_rate(rate).is_err(), "{}", rate);
+        }
+    }
+
+    #[test]
+    fn test_serde() {
+        let json = serde_json::to_string(&money("3215.88")).unwrap();
+        assert_eq!(json, r#""3215.88""#);
+        let m: Money = serde_json::from_str(r#""3215.88""#).unwrap();
+        assert_eq!(m, money("3215.88"));
+        let m: Money = serde_json::from_str("3215.88").unwrap();
+        assert_eq!(m, money("3215.88"));
+    }
+}
@@ -0,0 +1,95 @@
+// 源泉徴収: 総支給額から差し引く所得税や社会保険料など
+// 税率表などの具体的な計算は payroll-impl 側に置き、ここではインターフェースだけを定める
+use serde::{Deserialize, Serialize};
+use std::{fmt, fmt::Debug, str::FromStr};
+use thiserror::Error;
+
+use crate::{DeductionLine, Paycheck};
+
+// 申告区分
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FilingStatus {
+    #[default]
+    Single,
+    Married,
+    HeadOfHousehold,
+}
+impl FilingStatus {
+    pub const ALL: [FilingStatus; 3] = [Self::Single, Self::Married, Self::HeadOfHousehold];
+}
+impl fmt::Display for FilingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid filing status: {0:?}")]
+pub struct ParseFilingStatusError(String);
+
+impl FromStr for FilingStatus {
+    type Err = ParseFilingStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.to_string() == s)
+            .ok_or_else(|| ParseFilingStatusError(s.to_string()))
+    }
+}
+
+// 従業員ごとの源泉徴収の条件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TaxProfile {
+    pub filing_status: FilingStatus,
+    // 扶養控除などの控除の数
+    pub allowances: u32,
+}
+impl TaxProfile {
+    pub fn new(filing_status: FilingStatus, allowances: u32) -> Self {
+        Self {
+            filing_status,
+            allowances,
+        }
+    }
+}
+
+// 源泉徴収額を計算するエンジン
+// Employee::payday で総支給額を計算した後に呼ばれる
+pub trait Withholding: Debug + Send + Sync {
+    // pc には支給の明細が設定済み
+    // pays_per_year は年間の支払い回数で、年額の税率表を支払い 1 回分に割り戻すのに使う
+    fn withhold(
+        &self,
+        profile: &TaxProfile,
+        pays_per_year: u32,
+        pc: &Paycheck,
+    ) -> Vec<DeductionLine>;
+}
+
+// 何も源泉徴収しない (設定がないときのデフォルト)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoWithholding;
+impl Withholding for NoWithholding {
+    fn withhold(
+        &self,
+        _profile: &TaxProfile,
+        _pays_per_year: u32,
+        _pc: &Paycheck,
+    ) -> Vec<DeductionLine> {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filing_status_round_trip() {
+        for status in FilingStatus::ALL {
+            assert_eq!(status.to_string().parse::<FilingStatus>(), Ok(status));
+        }
+        assert!("single".parse::<FilingStatus>().is_err());
+    }
+}
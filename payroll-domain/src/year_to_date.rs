@@ -0,0 +1,97 @@
+// 年初来 (YTD) の累計: 従業員ごと・暦年ごとに Payday で積み上げる
+// どの年に計上するかは支払日で決まるので、年をまたぐ支払い期間の Paycheck は支払日の年に入る
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{DeductionKind, DeductionLine, Money, Paycheck};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct YearToDate {
+    pub year: i32,
+    pub gross_pay: Money,
+    // 控除の種類ごとの累計 (その年に初めて現れた順)
+    pub deductions: Vec<DeductionLine>,
+    pub net_pay: Money,
+}
+impl YearToDate {
+    // 年初は 0 から
+    pub fn new(year: i32) -> Self {
+        Self {
+            year,
+            gross_pay: Money::ZERO,
+            deductions: vec![],
+            net_pay: Money::ZERO,
+        }
+    }
+    pub fn year_of(pay_date: NaiveDate) -> i32 {
+        pay_date.year()
+    }
+    pub fn total_deductions(&self) -> Money {
+        self.deductions.iter().map(|line| line.amount).sum()
+    }
+    pub fn deduction(&self, kind: &DeductionKind) -> Money {
+        self.deductions
+            .iter()
+            .find(|line| line.kind == *kind)
+            .map_or(Money::ZERO, |line| line.amount)
+    }
+    pub fn add(&mut self, pc: &Paycheck) {
+        debug_assert_eq!(Self::year_of(pc.pay_date()), self.year);
+        self.gross_pay += pc.gross_pay();
+        for line in pc.deduction_lines() {
+            match self.deductions.iter_mut().find(|d| d.kind == line.kind) {
+                Some(d) => d.amount += line.amount,
+                None => self.deductions.push(line.clone()),
+            }
+        }
+        self.net_pay += pc.net_pay();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EarningKind, EarningLine};
+
+    fn paycheck(pay_date: NaiveDate, gross: i64, lines: &[(DeductionKind, i64)]) -> Paycheck {
+        let mut pc = Paycheck::new(pay_date..=pay_date);
+        pc.set_earning_lines(vec![EarningLine::new(
+            EarningKind::Salary,
+            Money::from_cents(gross),
+        )]);
+        pc.set_deduction_lines(
+            lines
+                .iter()
+                .map(|(kind, cents)| DeductionLine::new(kind.clone(), Money::from_cents(*cents)))
+                .collect(),
+        );
+        pc
+    }
+
+    #[test]
+    fn test_add() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let tax = DeductionKind::Withholding("Income Tax".to_string());
+        let mut ytd = YearToDate::new(2025);
+        ytd.add(&paycheck(
+            date(1, 31),
+            100000,
+            &[(DeductionKind::Dues, 945), (tax.clone(), 10000)],
+        ));
+        ytd.add(&paycheck(
+            date(2, 28),
+            100000,
+            &[(tax.clone(), 10000), (DeductionKind::ServiceCharge, 1995)],
+        ));
+
+        assert_eq!(ytd.gross_pay, Money::from_cents(200000));
+        assert_eq!(ytd.deduction(&DeductionKind::Dues), Money::from_cents(945));
+        assert_eq!(ytd.deduction(&tax), Money::from_cents(20000));
+        assert_eq!(
+            ytd.deduction(&DeductionKind::ServiceCharge),
+            Money::from_cents(1995)
+        );
+        assert_eq!(ytd.total_deductions(), Money::from_cents(22940));
+        assert_eq!(ytd.net_pay, Money::from_cents(200000 - 22940));
+    }
+}
@@ -1,19 +1,20 @@
+use chrono::{NaiveDate, Weekday};
 use std::sync::{Arc, Mutex};
 
 use payroll_domain::{
-    Affiliation, MemberId, PaymentClassification, PaymentMethod, PaymentSchedule,
+    Affiliation, MemberId, Money, PaymentClassification, PaymentMethod, PaymentSchedule,
 };
 
 pub trait SalariedClassificationFactory {
-    fn mk_classification(&self, salary: f32) -> Arc<Mutex<dyn PaymentClassification>>;
+    fn mk_classification(&self, salary: Money) -> Arc<Mutex<dyn PaymentClassification>>;
 }
 pub trait HourlyClassificationFactory {
-    fn mk_classification(&self, hourly_rate: f32) -> Arc<Mutex<dyn PaymentClassification>>;
+    fn mk_classification(&self, hourly_rate: Money) -> Arc<Mutex<dyn PaymentClassification>>;
 }
 pub trait CommissionedClassificationFactory {
     fn mk_classification(
         &self,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
     ) -> Arc<Mutex<dyn PaymentClassification>>;
 }
@@ -23,9 +24,18 @@ pub trait MonthlyScheduleFactory {
 pub trait WeeklyScheduleFactory {
     fn mk_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>>;
 }
-pub trait BiweeklyScheduleFactory {
+pub trait SemimonthlyScheduleFactory {
     fn mk_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>>;
 }
+pub trait MonthlyOnScheduleFactory {
+    fn mk_schedule(&self, day: u32) -> Arc<Mutex<dyn PaymentSchedule>>;
+}
+pub trait WeeklyOnScheduleFactory {
+    fn mk_schedule(&self, weekday: Weekday) -> Arc<Mutex<dyn PaymentSchedule>>;
+}
+pub trait BiweeklyFromScheduleFactory {
+    fn mk_schedule(&self, anchor: NaiveDate) -> Arc<Mutex<dyn PaymentSchedule>>;
+}
 pub trait HoldMethodFactory {
     fn mk_method(&self) -> Arc<Mutex<dyn PaymentMethod>>;
 }
@@ -36,7 +46,7 @@ pub trait MailMethodFactory {
     fn mk_method(&self, address: &str) -> Arc<Mutex<dyn PaymentMethod>>;
 }
 pub trait UnionAffiliationFactory {
-    fn mk_affiliation(&self, member_id: MemberId, dues: f32) -> Arc<Mutex<dyn Affiliation>>;
+    fn mk_affiliation(&self, member_id: MemberId, dues: Money) -> Arc<Mutex<dyn Affiliation>>;
 }
 pub trait NoAffiliationFactory {
     fn mk_affiliation(&self) -> Arc<Mutex<dyn Affiliation>>;
@@ -1,28 +1,43 @@
 use chrono::{Datelike, NaiveDate, Weekday};
 use log::{debug, trace};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
-use payroll_domain::{Affiliation, MemberId, Paycheck};
+use payroll_domain::{
+    Affiliation, AffiliationInfo, ChargeId, DeductionKind, DeductionLine, MemberId, Money, Paycheck,
+};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::Reversal;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct ServiceCharge {
     date: NaiveDate,
-    amount: f32,
+    amount: Money,
+    #[serde(default)]
+    reversal: Option<Reversal>,
 }
 impl ServiceCharge {
-    fn new(date: NaiveDate, amount: f32) -> Self {
-        Self { date, amount }
+    fn new(date: NaiveDate, amount: Money) -> Self {
+        Self {
+            date,
+            amount,
+            reversal: None,
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+fn charge_id(index: usize) -> ChargeId {
+    ChargeId::from(index as u32 + 1)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UnionAffiliation {
     member_id: MemberId,
-    dues: f32,
+    dues: Money,
     service_charges: Vec<ServiceCharge>,
 }
 impl UnionAffiliation {
-    pub fn new(member_id: MemberId, dues: f32) -> Self {
+    pub fn new(member_id: MemberId, dues: Money) -> Self {
         Self {
             member_id,
             dues,
@@ -32,9 +47,59 @@ impl UnionAffiliation {
     pub fn member_id(&self) -> MemberId {
         self.member_id
     }
-    pub fn add_service_charge(&mut self, date: NaiveDate, amount: f32) {
+    pub fn dues(&self) -> Money {
+        self.dues
+    }
+    // 取り消したものも含めて番号順に返す
+    pub fn service_charges(
+        &self,
+    ) -> impl Iterator<Item = (ChargeId, NaiveDate, Money, Option<Reversal>)> + '_ {
+        self.service_charges
+            .iter()
+            .enumerate()
+            .map(|(i, sc)| (charge_id(i), sc.date, sc.amount, sc.reversal))
+    }
+    pub fn add_service_charge(&mut self, date: NaiveDate, amount: Money) -> ChargeId {
         let sc = ServiceCharge::new(date, amount);
         self.service_charges.push(sc);
+        charge_id(self.service_charges.len() - 1)
+    }
+    // 保存してあったサービス料を取り消しの状態ごと戻す
+    pub fn restore_service_charge(
+        &mut self,
+        date: NaiveDate,
+        amount: Money,
+        reversal: Option<Reversal>,
+    ) {
+        let sc = ServiceCharge {
+            reversal,
+            ..ServiceCharge::new(date, amount)
+        };
+        self.service_charges.push(sc);
+    }
+    // まだ取り消していないサービス料の日付
+    pub fn service_charge_date(&self, id: ChargeId) -> Option<NaiveDate> {
+        let i = (u32::from(id) as usize).checked_sub(1)?;
+        self.service_charges
+            .get(i)
+            .filter(|sc| sc.reversal.is_none())
+            .map(|sc| sc.date)
+    }
+    // last_paid は Reversal::new を参照
+    // 見つからないか取り消し済みなら None
+    pub fn void_service_charge(
+        &mut self,
+        id: ChargeId,
+        last_paid: Option<NaiveDate>,
+    ) -> Option<Reversal> {
+        let i = (u32::from(id) as usize).checked_sub(1)?;
+        let sc = self
+            .service_charges
+            .get_mut(i)
+            .filter(|sc| sc.reversal.is_none())?;
+        let reversal = Reversal::new(last_paid);
+        sc.reversal = Some(reversal);
+        Some(reversal)
     }
 }
 impl Affiliation for UnionAffiliation {
@@ -44,7 +109,7 @@ impl Affiliation for UnionAffiliation {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn calculate_deductions(&self, pc: &Paycheck) -> f32 {
+    fn calculate_deductions(&self, pc: &Paycheck) -> Vec<DeductionLine> {
         trace!("calculate_deductions called");
         let pay_period = pc.get_pay_period();
         debug!("pay_period: {} - {}", pay_period.start(), pay_period.end());
@@ -53,22 +118,54 @@ impl Affiliation for UnionAffiliation {
             .iter_days()
             .take_while(|d| *d <= *pay_period.end())
             .filter(|d| d.weekday() == Weekday::Fri)
-            .fold(0f32, |acc, _| acc + self.dues);
+            .map(|_| self.dues)
+            .sum::<Money>();
         debug!("dues_amount: {}", dues_amount);
-        let service_amount = self
-            .service_charges
-            .iter()
-            .filter(|sc| pay_period.contains(&sc.date))
-            .fold(0f32, |acc, sc| acc + sc.amount);
-        debug!("service_amount: {}", service_amount);
+        let mut lines = vec![];
+        if !dues_amount.is_zero() {
+            lines.push(DeductionLine::new(DeductionKind::Dues, dues_amount));
+        }
+        // サービス料は 1 件ずつ明細に載せる
+        lines.extend(
+            self.service_charges
+                .iter()
+                .filter(|sc| sc.reversal.is_none() && pay_period.contains(&sc.date))
+                .map(|sc| DeductionLine::new(DeductionKind::ServiceCharge, sc.amount)),
+        );
+        // 支払い済みのサービス料を取り消した分は、差し戻す日を含む期間で払い戻す
+        lines.extend(
+            self.service_charges
+                .iter()
+                .filter(|sc| {
+                    sc.reversal
+                        .and_then(|r| r.carried_to())
+                        .is_some_and(|d| pay_period.contains(&d))
+                })
+                .map(|sc| DeductionLine::new(DeductionKind::ServiceChargeRefund, -sc.amount)),
+        );
+        debug!("deduction lines: {:?}", lines);
 
-        dues_amount + service_amount
+        lines
+    }
+    fn info(&self) -> AffiliationInfo {
+        AffiliationInfo::Union {
+            member_id: self.member_id,
+            dues: self.dues,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::money;
+
+    fn dues(s: &str) -> DeductionLine {
+        DeductionLine::new(DeductionKind::Dues, money(s))
+    }
+    fn service_charge(s: &str) -> DeductionLine {
+        DeductionLine::new(DeductionKind::ServiceCharge, money(s))
+    }
 
     #[test]
     fn test_no_service_charge() {
@@ -76,9 +173,9 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let aff = UnionAffiliation::new(1.into(), 10.0);
+        let aff = UnionAffiliation::new(1.into(), money("10.00"));
         let deductions = aff.calculate_deductions(&pc);
-        assert_eq!(deductions, 50.0);
+        assert_eq!(deductions, vec![dues("50.00")]);
     }
 
     #[test]
@@ -87,10 +184,13 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let mut aff = UnionAffiliation::new(1.into(), 10.0);
-        aff.add_service_charge(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap(), 105.0);
+        let mut aff = UnionAffiliation::new(1.into(), money("10.00"));
+        aff.add_service_charge(
+            NaiveDate::from_ymd_opt(2025, 1, 15).unwrap(),
+            money("105.00"),
+        );
         let deductions = aff.calculate_deductions(&pc);
-        assert_eq!(deductions, 155.0);
+        assert_eq!(deductions, vec![dues("50.00"), service_charge("105.00")]);
     }
 
     #[test]
@@ -99,11 +199,24 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let mut aff = UnionAffiliation::new(1.into(), 10.0);
-        aff.add_service_charge(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap(), 100.5);
-        aff.add_service_charge(NaiveDate::from_ymd_opt(2025, 1, 20).unwrap(), 200.5);
+        let mut aff = UnionAffiliation::new(1.into(), money("10.00"));
+        aff.add_service_charge(
+            NaiveDate::from_ymd_opt(2025, 1, 15).unwrap(),
+            money("100.50"),
+        );
+        aff.add_service_charge(
+            NaiveDate::from_ymd_opt(2025, 1, 20).unwrap(),
+            money("200.50"),
+        );
         let deductions = aff.calculate_deductions(&pc);
-        assert_eq!(deductions, 351.0);
+        assert_eq!(
+            deductions,
+            vec![
+                dues("50.00"),
+                service_charge("100.50"),
+                service_charge("200.50")
+            ]
+        );
     }
 
     #[test]
@@ -112,9 +225,48 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let mut aff = UnionAffiliation::new(1.into(), 10.0);
-        aff.add_service_charge(NaiveDate::from_ymd_opt(2025, 2, 15).unwrap(), 100.5);
+        let mut aff = UnionAffiliation::new(1.into(), money("10.00"));
+        aff.add_service_charge(
+            NaiveDate::from_ymd_opt(2025, 2, 15).unwrap(),
+            money("100.50"),
+        );
+        let deductions = aff.calculate_deductions(&pc);
+        assert_eq!(deductions, vec![dues("50.00")]);
+    }
+
+    #[test]
+    fn test_void_service_charges() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let mut aff = UnionAffiliation::new(1.into(), money("10.00"));
+        let paid = aff.add_service_charge(date(1, 15), money("100.50"));
+        let unpaid = aff.add_service_charge(date(2, 10), money("200.50"));
+        assert_eq!(unpaid, ChargeId::from(2));
+
+        assert_eq!(
+            aff.void_service_charge(paid, Some(date(1, 31))),
+            Some(Reversal::CarriedTo(date(2, 1)))
+        );
+        assert_eq!(aff.void_service_charge(paid, None), None);
+        assert_eq!(aff.service_charge_date(paid), None);
+        assert_eq!(aff.service_charge_date(unpaid), Some(date(2, 10)));
+
+        let pc = Paycheck::new(date(2, 1)..=date(2, 28));
+        let deductions = aff.calculate_deductions(&pc);
+        assert_eq!(
+            deductions,
+            vec![
+                dues("40.00"),
+                service_charge("200.50"),
+                DeductionLine::new(DeductionKind::ServiceChargeRefund, money("-100.50")),
+            ]
+        );
+
+        // 支払い前に取り消したものはどこにも載らない
+        assert_eq!(
+            aff.void_service_charge(unpaid, None),
+            Some(Reversal::Voided)
+        );
         let deductions = aff.calculate_deductions(&pc);
-        assert_eq!(deductions, 50.0);
+        assert_eq!(deductions.len(), 2);
     }
 }
@@ -0,0 +1,140 @@
+// 支払日をずらす会社の暦を設定ファイルから読み込む
+use log::{debug, trace};
+use std::{fs, io, path::Path};
+use thiserror::Error;
+
+use payroll_domain::{BusinessCalendar, InvalidBusinessCalendar};
+
+#[derive(Debug, Error)]
+pub enum CalendarConfigError {
+    #[error("failed to read calendar config: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to parse calendar config: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error(transparent)]
+    Invalid(#[from] InvalidBusinessCalendar),
+}
+
+pub fn load_business_calendar<P: AsRef<Path>>(
+    path: P,
+) -> Result<BusinessCalendar, CalendarConfigError> {
+    trace!("load_business_calendar called");
+    debug!("load_business_calendar: config={}", path.as_ref().display());
+    let calendar: BusinessCalendar = serde_json::from_str(&fs::read_to_string(path)?)?;
+    calendar.validate()?;
+    Ok(calendar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use payroll_domain::PaymentSchedule;
+
+    use crate::{MonthlySchedule, WeeklySchedule};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_sample_config() {
+        let calendar = load_business_calendar(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../config/calendar.json"
+        ));
+        assert!(calendar.is_ok(), "{:?}", calendar);
+    }
+
+    #[test]
+    fn test_monthly_rollover() {
+        let mut calendar: BusinessCalendar = serde_json::from_str("{}").unwrap();
+        let ms = MonthlySchedule;
+        // 2025-05-31 は土曜日なので、金曜日に 5 月分を支払い、次の期間は土曜日から
+        assert!(ms.is_pay_date_in(date(2025, 5, 30), &calendar));
+        assert!(!ms.is_pay_date_in(date(2025, 5, 31), &calendar));
+        assert_eq!(
+            ms.get_pay_period_in(date(2025, 5, 30), &calendar),
+            date(2025, 5, 1)..=date(2025, 5, 30)
+        );
+        assert_eq!(
+            ms.get_pay_period_in(date(2025, 6, 30), &calendar),
+            date(2025, 5, 31)..=date(2025, 6, 30)
+        );
+        // 大晦日が祝日なら前の日に
+        calendar.holidays.insert(2025, vec![date(2025, 12, 31)]);
+        assert!(ms.is_pay_date_in(date(2025, 12, 30), &calendar));
+        assert!(!ms.is_pay_date_in(date(2025, 12, 31), &calendar));
+        // 後ろにずらすと翌月 (年をまたいで翌年) の最初の営業日に
+        calendar.rollover = payroll_domain::Rollover::Next;
+        assert!(ms.is_pay_date_in(date(2025, 6, 2), &calendar));
+        assert_eq!(
+            ms.get_pay_period_in(date(2025, 6, 2), &calendar),
+            date(2025, 5, 1)..=date(2025, 6, 2)
+        );
+        // 日曜日の 11/30 の分は 12/1 に支払い済み
+        assert!(ms.is_pay_date_in(date(2026, 1, 1), &calendar));
+        assert_eq!(
+            ms.get_pay_period_in(date(2026, 1, 1), &calendar),
+            date(2025, 12, 2)..=date(2026, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_weekly_rollover() {
+        let calendar: BusinessCalendar =
+            serde_json::from_str(r#"{"holidays": {"2025": ["2025-12-26"]}}"#).unwrap();
+        let ws = WeeklySchedule;
+        assert!(ws.is_pay_date_in(date(2025, 12, 19), &calendar));
+        // 祝日の金曜日の分は木曜日までを木曜日に支払い、金曜日は次の期間に入る
+        assert!(!ws.is_pay_date_in(date(2025, 12, 26), &calendar));
+        assert!(ws.is_pay_date_in(date(2025, 12, 25), &calendar));
+        assert_eq!(
+            ws.get_pay_period_in(date(2025, 12, 25), &calendar),
+            date(2025, 12, 20)..=date(2025, 12, 25)
+        );
+        assert_eq!(
+            ws.get_pay_period_in(date(2026, 1, 2), &calendar),
+            date(2025, 12, 26)..=date(2026, 1, 2)
+        );
+        // 後ろにずらすと月曜日までを月曜日に支払い、次の期間は火曜日から
+        let mut next = calendar.clone();
+        next.rollover = payroll_domain::Rollover::Next;
+        assert!(!ws.is_pay_date_in(date(2025, 12, 26), &next));
+        assert!(ws.is_pay_date_in(date(2025, 12, 29), &next));
+        assert_eq!(
+            ws.get_pay_period_in(date(2025, 12, 29), &next),
+            date(2025, 12, 20)..=date(2025, 12, 29)
+        );
+        assert_eq!(
+            ws.get_pay_period_in(date(2026, 1, 2), &next),
+            date(2025, 12, 30)..=date(2026, 1, 2)
+        );
+        // 休日がなければずらさない
+        let calendar = BusinessCalendar::none();
+        assert!(ws.is_pay_date_in(date(2025, 12, 26), &calendar));
+        assert_eq!(
+            ws.get_pay_period_in(date(2025, 12, 26), &calendar),
+            date(2025, 12, 20)..=date(2025, 12, 26)
+        );
+    }
+
+    #[test]
+    fn test_merged_pay_periods() {
+        // 2 週間続く休みで 2 回分の金曜日が同じ日にずれたら、まとめて支払う
+        let calendar: BusinessCalendar = serde_json::from_str(
+            r#"{"weekend": [], "holidays": {"2025": ["2025-08-08", "2025-08-09", "2025-08-10", "2025-08-11", "2025-08-12", "2025-08-13", "2025-08-14", "2025-08-15"]}}"#,
+        )
+        .unwrap();
+        let ws = WeeklySchedule;
+        assert!(ws.is_pay_date_in(date(2025, 8, 7), &calendar));
+        assert_eq!(
+            ws.get_pay_period_in(date(2025, 8, 7), &calendar),
+            date(2025, 8, 2)..=date(2025, 8, 7)
+        );
+        assert_eq!(
+            ws.get_pay_period_in(date(2025, 8, 22), &calendar),
+            date(2025, 8, 8)..=date(2025, 8, 22)
+        );
+    }
+}
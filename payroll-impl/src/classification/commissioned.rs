@@ -1,40 +1,169 @@
 use chrono::NaiveDate;
 use log::{debug, trace};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
-use payroll_domain::{Paycheck, PaymentClassification};
+use payroll_domain::{
+    BusinessCalendar, ClassificationInfo, EarningKind, EarningLine, Money, OvertimePolicy,
+    Paycheck, PaymentClassification, ReceiptId,
+};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::{
+    add_final_pay_lines, prorated_salary, retro_pay_line, FinalPay, RateHistory, RetroPay, Reversal,
+};
+
+// 返品は負の額の売上にする
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct SalesReceipt {
     date: NaiveDate,
-    amount: f32,
+    amount: Money,
+    #[serde(default)]
+    reversal: Option<Reversal>,
 }
 impl SalesReceipt {
-    fn new(date: NaiveDate, amount: f32) -> Self {
-        Self { date, amount }
+    fn new(date: NaiveDate, amount: Money) -> Self {
+        Self {
+            date,
+            amount,
+            reversal: None,
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+fn receipt_id(index: usize) -> ReceiptId {
+    ReceiptId::from(index as u32 + 1)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CommissionedClassification {
-    salary: f32,
+    // 効力発生日つきの変更より前の給与と歩合率
+    salary: Money,
     commission_rate: f32,
     sales_receipts: Vec<SalesReceipt>,
+    #[serde(default)]
+    rate_history: RateHistory<(Money, f32)>,
+    #[serde(default)]
+    retro_pays: Vec<RetroPay>,
+    #[serde(default)]
+    final_pays: Vec<FinalPay>,
 }
 impl CommissionedClassification {
-    pub fn new(salary: f32, commission_rate: f32) -> Self {
+    pub fn new(salary: Money, commission_rate: f32) -> Self {
         Self {
             salary,
             commission_rate,
             sales_receipts: vec![],
+            rate_history: RateHistory::default(),
+            retro_pays: vec![],
+            final_pays: vec![],
+        }
+    }
+    // 効力発生日が最も遅い変更の給与
+    pub fn salary(&self) -> Money {
+        self.latest().0
+    }
+    // 効力発生日が最も遅い変更の歩合率
+    pub fn commission_rate(&self) -> f32 {
+        self.latest().1
+    }
+    fn latest(&self) -> (Money, f32) {
+        self.rate_history
+            .latest((self.salary, self.commission_rate))
+    }
+    // 最初の給与と歩合率を効力発生日 None として、変更を効力発生日の順に返す
+    pub fn rate_history(&self) -> impl Iterator<Item = (Option<NaiveDate>, Money, f32)> + '_ {
+        let changes = self.rate_history.iter().map(|(d, (s, r))| (Some(d), s, r));
+        [(None, self.salary, self.commission_rate)]
+            .into_iter()
+            .chain(changes)
+    }
+    // effective が None なら履歴ごと給与と歩合率を置き換える
+    pub fn change_rate(
+        &mut self,
+        effective: Option<NaiveDate>,
+        salary: Money,
+        commission_rate: f32,
+    ) {
+        match effective {
+            Some(effective) => self
+                .rate_history
+                .change(effective, (salary, commission_rate)),
+            None => {
+                self.salary = salary;
+                self.commission_rate = commission_rate;
+                self.rate_history = RateHistory::default();
+            }
         }
     }
-    pub fn add_sales_receipt(&mut self, date: NaiveDate, amount: f32) {
+    pub fn retro_pays(&self) -> impl Iterator<Item = RetroPay> + '_ {
+        self.retro_pays.iter().copied()
+    }
+    pub fn add_retro_pay(&mut self, retro_pay: RetroPay) {
+        self.retro_pays.push(retro_pay);
+    }
+    pub fn final_pays(&self) -> impl Iterator<Item = &FinalPay> + '_ {
+        self.final_pays.iter()
+    }
+    pub fn add_final_pay(&mut self, final_pay: FinalPay) {
+        self.final_pays.push(final_pay);
+    }
+    // 取り消したものも含めて番号順に返す
+    pub fn sales_receipts(
+        &self,
+    ) -> impl Iterator<Item = (ReceiptId, NaiveDate, Money, Option<Reversal>)> + '_ {
+        self.sales_receipts
+            .iter()
+            .enumerate()
+            .map(|(i, sr)| (receipt_id(i), sr.date, sr.amount, sr.reversal))
+    }
+    pub fn add_sales_receipt(&mut self, date: NaiveDate, amount: Money) -> ReceiptId {
         let sr = SalesReceipt::new(date, amount);
         self.sales_receipts.push(sr);
+        receipt_id(self.sales_receipts.len() - 1)
+    }
+    // 保存してあった売上を取り消しの状態ごと戻す
+    pub fn restore_sales_receipt(
+        &mut self,
+        date: NaiveDate,
+        amount: Money,
+        reversal: Option<Reversal>,
+    ) {
+        let sr = SalesReceipt {
+            reversal,
+            ..SalesReceipt::new(date, amount)
+        };
+        self.sales_receipts.push(sr);
+    }
+    // まだ取り消していない売上の日付
+    pub fn sales_receipt_date(&self, id: ReceiptId) -> Option<NaiveDate> {
+        let i = (u32::from(id) as usize).checked_sub(1)?;
+        self.sales_receipts
+            .get(i)
+            .filter(|sr| sr.reversal.is_none())
+            .map(|sr| sr.date)
+    }
+    // last_paid は Reversal::new を参照
+    // 見つからないか取り消し済みなら None
+    pub fn void_sales_receipt(
+        &mut self,
+        id: ReceiptId,
+        last_paid: Option<NaiveDate>,
+    ) -> Option<Reversal> {
+        let sr = self.receipt_mut(id).filter(|sr| sr.reversal.is_none())?;
+        let reversal = Reversal::new(last_paid);
+        sr.reversal = Some(reversal);
+        Some(reversal)
     }
-    fn calculate_pay_for_sales_receipt(&self, sr: &SalesReceipt) -> f32 {
-        sr.amount * self.commission_rate
+    fn receipt_mut(&mut self, id: ReceiptId) -> Option<&mut SalesReceipt> {
+        let i = (u32::from(id) as usize).checked_sub(1)?;
+        self.sales_receipts.get_mut(i)
+    }
+    // 売上の日に効いている歩合率を掛ける
+    fn calculate_pay_for_sales_receipt(&self, sr: &SalesReceipt) -> Money {
+        let (_, commission_rate) = self
+            .rate_history
+            .rate_on((self.salary, self.commission_rate), sr.date);
+        sr.amount * commission_rate
     }
 }
 impl PaymentClassification for CommissionedClassification {
@@ -44,26 +173,99 @@ impl PaymentClassification for CommissionedClassification {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn calculate_pay(&self, pc: &Paycheck) -> f32 {
+    fn calculate_pay(
+        &self,
+        pc: &Paycheck,
+        overtime: &OvertimePolicy,
+        calendar: &BusinessCalendar,
+    ) -> Vec<EarningLine> {
         trace!("calculate_pay called");
         let pay_period = pc.get_pay_period();
         debug!("pay_period: {} - {}", pay_period.start(), pay_period.end());
         let commissioned_amount = self
             .sales_receipts
             .iter()
-            .filter(|sr| pay_period.contains(&sr.date))
-            .fold(0f32, |acc, sr| {
-                acc + self.calculate_pay_for_sales_receipt(sr)
-            });
+            .filter(|sr| sr.reversal.is_none() && pay_period.contains(&sr.date))
+            .map(|sr| self.calculate_pay_for_sales_receipt(sr))
+            .sum::<Money>();
         debug!("commissioned_amount: {}", commissioned_amount);
+        // 支払い済みの売上を取り消した分は、差し戻す日を含む期間で引く
+        let clawback_amount = -self
+            .sales_receipts
+            .iter()
+            .filter(|sr| {
+                sr.reversal
+                    .and_then(|r| r.carried_to())
+                    .is_some_and(|d| pay_period.contains(&d))
+            })
+            .map(|sr| self.calculate_pay_for_sales_receipt(sr))
+            .sum::<Money>();
+        debug!("clawback_amount: {}", clawback_amount);
 
-        self.salary + commissioned_amount
+        let salary_history = self.rate_history.map(|(salary, _)| salary);
+        let salary = prorated_salary(
+            &salary_history,
+            self.salary,
+            &pay_period,
+            overtime,
+            calendar,
+        );
+        let mut lines = vec![EarningLine::new(EarningKind::Salary, salary)];
+        if !commissioned_amount.is_zero() {
+            lines.push(EarningLine::new(
+                EarningKind::Commission,
+                commissioned_amount,
+            ));
+        }
+        if !clawback_amount.is_zero() {
+            lines.push(EarningLine::new(EarningKind::Clawback, clawback_amount));
+        }
+        lines.extend(retro_pay_line(&self.retro_pays, &pay_period));
+        add_final_pay_lines(&mut lines, &self.final_pays, &pay_period);
+        lines
+    }
+    fn info(&self) -> ClassificationInfo {
+        ClassificationInfo::Commissioned {
+            salary: self.salary(),
+            commission_rate: self.commission_rate(),
+        }
+    }
+    fn first_record_date(&self) -> Option<NaiveDate> {
+        self.sales_receipts
+            .iter()
+            .filter(|sr| sr.reversal.is_none())
+            .map(|sr| sr.date)
+            .min()
+    }
+    fn last_record_date(&self) -> Option<NaiveDate> {
+        self.sales_receipts
+            .iter()
+            .filter(|sr| sr.reversal.is_none())
+            .map(|sr| sr.date)
+            .max()
+    }
+    // 給与だけでなく、その前後の日の売上の歩合も支払わない
+    fn start_on(&mut self, effective: NaiveDate) {
+        self.rate_history
+            .start_on((self.salary, self.commission_rate), effective);
+        (self.salary, self.commission_rate) = (Money::ZERO, 0.0);
+    }
+    fn close(&mut self, effective: NaiveDate) {
+        self.rate_history.close(effective, (Money::ZERO, 0.0));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::money;
+
+    fn salary(s: &str) -> EarningLine {
+        EarningLine::new(EarningKind::Salary, money(s))
+    }
+    fn commission(s: &str) -> EarningLine {
+        EarningLine::new(EarningKind::Commission, money(s))
+    }
 
     #[test]
     fn test_no_sales_receipts() {
@@ -71,9 +273,9 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 18).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let cc = CommissionedClassification::new(100.0, 0.1);
-        let pay = cc.calculate_pay(&pc);
-        assert_eq!(pay, 100.0); // salary only
+        let cc = CommissionedClassification::new(money("100.00"), 0.1);
+        let pay = cc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![salary("100.00")]); // salary only
     }
 
     #[test]
@@ -82,10 +284,13 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 18).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let mut cc = CommissionedClassification::new(100.0, 0.1);
-        cc.add_sales_receipt(NaiveDate::from_ymd_opt(2025, 1, 25).unwrap(), 1234.0);
-        let pay = cc.calculate_pay(&pc);
-        assert_eq!(pay, 223.4); // 100 + 1234 * 0.1
+        let mut cc = CommissionedClassification::new(money("100.00"), 0.1);
+        cc.add_sales_receipt(
+            NaiveDate::from_ymd_opt(2025, 1, 25).unwrap(),
+            money("1234.00"),
+        );
+        let pay = cc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![salary("100.00"), commission("123.40")]); // 100 + 1234 * 0.1
     }
 
     #[test]
@@ -94,11 +299,17 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 18).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let mut cc = CommissionedClassification::new(100.0, 0.1);
-        cc.add_sales_receipt(NaiveDate::from_ymd_opt(2025, 1, 25).unwrap(), 1234.0);
-        cc.add_sales_receipt(NaiveDate::from_ymd_opt(2025, 1, 26).unwrap(), 5678.0);
-        let pay = cc.calculate_pay(&pc);
-        assert_eq!(pay, 791.2); // 100 + 1234 * 0.1 + 5678 * 0.1
+        let mut cc = CommissionedClassification::new(money("100.00"), 0.1);
+        cc.add_sales_receipt(
+            NaiveDate::from_ymd_opt(2025, 1, 25).unwrap(),
+            money("1234.00"),
+        );
+        cc.add_sales_receipt(
+            NaiveDate::from_ymd_opt(2025, 1, 26).unwrap(),
+            money("5678.00"),
+        );
+        let pay = cc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![salary("100.00"), commission("691.20")]); // 100 + 1234 * 0.1 + 5678 * 0.1
     }
 
     #[test]
@@ -107,9 +318,64 @@ mod tests {
             NaiveDate::from_ymd_opt(2025, 1, 18).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let mut cc = CommissionedClassification::new(100.0, 0.1);
-        cc.add_sales_receipt(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap(), 1234.0);
-        let pay = cc.calculate_pay(&pc);
-        assert_eq!(pay, 100.0); // salary only
+        let mut cc = CommissionedClassification::new(money("100.00"), 0.1);
+        cc.add_sales_receipt(
+            NaiveDate::from_ymd_opt(2025, 1, 15).unwrap(),
+            money("1234.00"),
+        );
+        let pay = cc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![salary("100.00")]); // salary only
+    }
+
+    #[test]
+    fn test_void_and_refund_sales_receipts() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let mut cc = CommissionedClassification::new(money("100.00"), 0.1);
+        let paid = cc.add_sales_receipt(date(1, 10), money("1000.00"));
+        let unpaid = cc.add_sales_receipt(date(1, 20), money("500.00"));
+        let refund = cc.add_sales_receipt(date(1, 21), money("-200.00"));
+        assert_eq!(paid, ReceiptId::from(1));
+        assert_eq!(refund, ReceiptId::from(3));
+
+        // 1/17 に支払い済みの売上は次の期間で差し戻す
+        assert_eq!(
+            cc.void_sales_receipt(paid, Some(date(1, 17))),
+            Some(Reversal::CarriedTo(date(1, 18)))
+        );
+        assert_eq!(cc.void_sales_receipt(unpaid, None), Some(Reversal::Voided));
+        assert_eq!(cc.void_sales_receipt(unpaid, None), None);
+        assert_eq!(cc.void_sales_receipt(4.into(), None), None);
+        assert_eq!(cc.sales_receipt_date(paid), None);
+        assert_eq!(cc.sales_receipt_date(refund), Some(date(1, 21)));
+
+        let pc = Paycheck::new(date(1, 18)..=date(1, 31));
+        let pay = cc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(
+            pay,
+            vec![
+                salary("100.00"),
+                commission("-20.00"),
+                EarningLine::new(EarningKind::Clawback, money("-100.00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rate_change_mid_period() {
+        let pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 11).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 24).unwrap(),
+        );
+        let mut cc = CommissionedClassification::new(money("1400.00"), 0.1);
+        cc.add_sales_receipt(NaiveDate::from_ymd_opt(2025, 1, 13).unwrap(), money("1000"));
+        cc.add_sales_receipt(NaiveDate::from_ymd_opt(2025, 1, 20).unwrap(), money("1000"));
+        // 1/18 から給与 2100.00、歩合率 15% にしても売上は残る
+        cc.change_rate(NaiveDate::from_ymd_opt(2025, 1, 18), money("2100.00"), 0.15);
+        assert_eq!(cc.salary(), money("2100.00"));
+        assert_eq!(cc.commission_rate(), 0.15);
+        assert_eq!(cc.sales_receipts().count(), 2);
+        let pay = cc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        // 1400 * 7 / 14 + 2100 * 7 / 14, 1000 * 0.1 + 1000 * 0.15
+        assert_eq!(pay, vec![salary("1750.00"), commission("250.00")]);
     }
 }
@@ -1,10 +1,19 @@
 use chrono::NaiveDate;
 use log::{debug, trace};
-use std::any::Any;
+use serde::{Deserialize, Serialize};
+use std::{any::Any, collections::BTreeMap};
 
-use payroll_domain::{Paycheck, PaymentClassification};
+use payroll_domain::{
+    BusinessCalendar, ClassificationInfo, EarningKind, EarningLine, Money, OvertimePolicy,
+    Paycheck, PaymentClassification,
+};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::{
+    add_final_pay_lines, retro_pay_line, split_hours, week_start_of, DailyHours, FinalPay,
+    RateHistory, RetroPay,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct TimeCard {
     date: NaiveDate,
     hours: f32,
@@ -15,29 +24,118 @@ impl TimeCard {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HourlyClassification {
-    hourly_rate: f32,
+    // 効力発生日つきの変更より前の時給
+    hourly_rate: Money,
     timecards: Vec<TimeCard>,
+    #[serde(default)]
+    rate_history: RateHistory<Money>,
+    #[serde(default)]
+    retro_pays: Vec<RetroPay>,
+    #[serde(default)]
+    final_pays: Vec<FinalPay>,
 }
 impl HourlyClassification {
-    pub fn new(hourly_rate: f32) -> Self {
+    pub fn new(hourly_rate: Money) -> Self {
         Self {
             hourly_rate,
             timecards: vec![],
+            rate_history: RateHistory::default(),
+            retro_pays: vec![],
+            final_pays: vec![],
+        }
+    }
+    // 効力発生日が最も遅い変更の時給
+    pub fn hourly_rate(&self) -> Money {
+        self.rate_history.latest(self.hourly_rate)
+    }
+    // 最初の時給を効力発生日 None として、変更を効力発生日の順に返す
+    pub fn rate_history(&self) -> impl Iterator<Item = (Option<NaiveDate>, Money)> + '_ {
+        let changes = self.rate_history.iter().map(|(d, r)| (Some(d), r));
+        [(None, self.hourly_rate)].into_iter().chain(changes)
+    }
+    // effective が None なら履歴ごと時給を置き換える
+    pub fn change_rate(&mut self, effective: Option<NaiveDate>, hourly_rate: Money) {
+        match effective {
+            Some(effective) => self.rate_history.change(effective, hourly_rate),
+            None => {
+                self.hourly_rate = hourly_rate;
+                self.rate_history = RateHistory::default();
+            }
         }
     }
+    pub fn retro_pays(&self) -> impl Iterator<Item = RetroPay> + '_ {
+        self.retro_pays.iter().copied()
+    }
+    pub fn add_retro_pay(&mut self, retro_pay: RetroPay) {
+        self.retro_pays.push(retro_pay);
+    }
+    pub fn final_pays(&self) -> impl Iterator<Item = &FinalPay> + '_ {
+        self.final_pays.iter()
+    }
+    pub fn add_final_pay(&mut self, final_pay: FinalPay) {
+        self.final_pays.push(final_pay);
+    }
+    pub fn timecards(&self) -> impl Iterator<Item = (NaiveDate, f32)> + '_ {
+        self.timecards.iter().map(|tc| (tc.date, tc.hours))
+    }
     pub fn add_timecard(&mut self, date: NaiveDate, hours: f32) {
         self.timecards.push(TimeCard::new(date, hours));
     }
-    fn calculate_pay_for_timecard(&self, tc: &TimeCard) -> f32 {
-        trace!("calculate_pay_for_timecard called");
-        let overtime = (tc.hours - 8.0).max(0.0);
-        debug!("overtime: {}", overtime);
-        let straight_time = tc.hours - overtime;
-        debug!("straight_time: {}", straight_time);
-
-        (straight_time + overtime * 1.5) * self.hourly_rate
+    // その日のタイムカードの合計 (1 枚もなければ None)
+    pub fn timecard(&self, date: NaiveDate) -> Option<f32> {
+        self.timecards
+            .iter()
+            .filter(|tc| tc.date == date)
+            .map(|tc| tc.hours)
+            .reduce(|a, b| a + b)
+    }
+    // その日のタイムカードを 1 枚に置き換えて、変更前の合計を返す
+    // その日のタイムカードがなければ何もせず None
+    pub fn amend_timecard(&mut self, date: NaiveDate, hours: f32) -> Option<f32> {
+        let old = self.void_timecard(date)?;
+        self.timecards.push(TimeCard::new(date, hours));
+        Some(old)
+    }
+    // その日のタイムカードを取り消して、取り消した合計を返す
+    pub fn void_timecard(&mut self, date: NaiveDate) -> Option<f32> {
+        let old = self.timecard(date)?;
+        self.timecards.retain(|tc| tc.date != date);
+        Some(old)
+    }
+    // 1 日分の支給額を明細の種類ごとに返す
+    // 各行はそこまでの累計との差にして、明細に分けても 1 日分の合計の丸めが変わらないようにする
+    fn calculate_pay_for_day(
+        &self,
+        date: NaiveDate,
+        daily: &DailyHours,
+    ) -> [(EarningKind, Money); 4] {
+        trace!("calculate_pay_for_day called");
+        let hourly_rate = self.rate_history.rate_on(self.hourly_rate, date);
+        let mut weighted_hours = 0.0;
+        let mut paid = Money::ZERO;
+        [
+            (EarningKind::Regular, daily.regular, 1.0),
+            (EarningKind::Premium, daily.premium, daily.premium_rate),
+            (
+                EarningKind::Overtime,
+                daily.overtime,
+                OvertimePolicy::OVERTIME_RATE,
+            ),
+            (
+                EarningKind::DoubleTime,
+                daily.double_time,
+                OvertimePolicy::DOUBLE_TIME_RATE,
+            ),
+        ]
+        .map(|(kind, hours, rate)| {
+            weighted_hours += hours * rate;
+            let total = hourly_rate * weighted_hours;
+            let amount = total - paid;
+            paid = total;
+            (kind, amount)
+        })
     }
 }
 impl PaymentClassification for HourlyClassification {
@@ -47,24 +145,75 @@ impl PaymentClassification for HourlyClassification {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn calculate_pay(&self, pc: &Paycheck) -> f32 {
+    fn calculate_pay(
+        &self,
+        pc: &Paycheck,
+        overtime: &OvertimePolicy,
+        calendar: &BusinessCalendar,
+    ) -> Vec<EarningLine> {
         trace!("calculate_pay called");
         let pay_period = pc.get_pay_period();
         debug!("pay_period: {} - {}", pay_period.start(), pay_period.end());
-        let hourly_amount = self
+        // 週ごとの残業を数えるため、支払い期間の最初の日を含む週の頭からの勤務時間を日ごとに集計する
+        // 支払われるのは支払い期間の中の日の分だけ
+        let week_start = week_start_of(*pay_period.start(), overtime.week_start);
+        let mut hours = BTreeMap::new();
+        for tc in self
             .timecards
             .iter()
-            .filter(|tc| pay_period.contains(&tc.date))
-            .fold(0f32, |acc, tc| acc + self.calculate_pay_for_timecard(tc));
-        debug!("hourly_amount: {}", hourly_amount);
+            .filter(|tc| (week_start..=*pay_period.end()).contains(&tc.date))
+        {
+            *hours.entry(tc.date).or_insert(0.0) += tc.hours;
+        }
 
-        hourly_amount
+        let mut lines: Vec<EarningLine> = vec![];
+        for (kind, amount) in split_hours(overtime, calendar, &hours)
+            .iter()
+            .filter(|(date, _)| pay_period.contains(date))
+            .flat_map(|(&date, daily)| self.calculate_pay_for_day(date, daily))
+        {
+            match lines.iter_mut().find(|line| line.kind == kind) {
+                Some(line) => line.amount += amount,
+                None => lines.push(EarningLine::new(kind, amount)),
+            }
+        }
+        debug!("lines: {:?}", lines);
+        lines.retain(|line| !line.amount.is_zero());
+        lines.extend(retro_pay_line(&self.retro_pays, &pay_period));
+        add_final_pay_lines(&mut lines, &self.final_pays, &pay_period);
+        lines
+    }
+    fn info(&self) -> ClassificationInfo {
+        ClassificationInfo::Hourly {
+            hourly_rate: self.hourly_rate(),
+        }
+    }
+    fn first_record_date(&self) -> Option<NaiveDate> {
+        self.timecards.iter().map(|tc| tc.date).min()
+    }
+    fn last_record_date(&self) -> Option<NaiveDate> {
+        self.timecards.iter().map(|tc| tc.date).max()
+    }
+    fn start_on(&mut self, effective: NaiveDate) {
+        self.rate_history.start_on(self.hourly_rate, effective);
+        self.hourly_rate = Money::ZERO;
+    }
+    fn close(&mut self, effective: NaiveDate) {
+        self.rate_history.close(effective, Money::ZERO);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::money;
+
+    fn regular(s: &str) -> EarningLine {
+        EarningLine::new(EarningKind::Regular, money(s))
+    }
+    fn overtime(s: &str) -> EarningLine {
+        EarningLine::new(EarningKind::Overtime, money(s))
+    }
 
     #[test]
     fn test_no_timecard() {
@@ -72,9 +221,9 @@ mod tests {
             NaiveDate::from_ymd_opt(2021, 1, 25).unwrap()
                 ..=NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
         );
-        let hc = HourlyClassification::new(10.0);
-        let pay = hc.calculate_pay(&pc);
-        assert_eq!(pay, 0.0);
+        let hc = HourlyClassification::new(money("10.00"));
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![]);
     }
 
     #[test]
@@ -83,10 +232,10 @@ mod tests {
             NaiveDate::from_ymd_opt(2021, 1, 25).unwrap()
                 ..=NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
         );
-        let mut hc = HourlyClassification::new(10.0);
+        let mut hc = HourlyClassification::new(money("10.00"));
         hc.add_timecard(NaiveDate::from_ymd_opt(2021, 1, 25).unwrap(), 8.0);
-        let pay = hc.calculate_pay(&pc);
-        assert_eq!(pay, 80.0); // 8 * 10
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![regular("80.00")]); // 8 * 10
     }
 
     #[test]
@@ -95,11 +244,11 @@ mod tests {
             NaiveDate::from_ymd_opt(2021, 1, 25).unwrap()
                 ..=NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
         );
-        let mut hc = HourlyClassification::new(10.0);
+        let mut hc = HourlyClassification::new(money("10.00"));
         hc.add_timecard(NaiveDate::from_ymd_opt(2021, 1, 25).unwrap(), 8.0);
         hc.add_timecard(NaiveDate::from_ymd_opt(2021, 1, 26).unwrap(), 8.0);
-        let pay = hc.calculate_pay(&pc);
-        assert_eq!(pay, 160.0); // 8 * 10 + 8 * 10
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![regular("160.00")]); // 8 * 10 + 8 * 10
     }
 
     #[test]
@@ -108,12 +257,12 @@ mod tests {
             NaiveDate::from_ymd_opt(2021, 1, 25).unwrap()
                 ..=NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
         );
-        let mut hc = HourlyClassification::new(10.0);
+        let mut hc = HourlyClassification::new(money("10.00"));
         hc.add_timecard(NaiveDate::from_ymd_opt(2021, 1, 25).unwrap(), 8.0);
         hc.add_timecard(NaiveDate::from_ymd_opt(2021, 1, 26).unwrap(), 8.0);
         hc.add_timecard(NaiveDate::from_ymd_opt(2021, 2, 1).unwrap(), 8.0);
-        let pay = hc.calculate_pay(&pc);
-        assert_eq!(pay, 160.0); // (8 + 8) * 10
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![regular("160.00")]); // (8 + 8) * 10
     }
 
     #[test]
@@ -122,9 +271,167 @@ mod tests {
             NaiveDate::from_ymd_opt(2021, 1, 25).unwrap()
                 ..=NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
         );
-        let mut hc = HourlyClassification::new(10.0);
+        let mut hc = HourlyClassification::new(money("10.00"));
         hc.add_timecard(NaiveDate::from_ymd_opt(2021, 1, 25).unwrap(), 10.0);
-        let pay = hc.calculate_pay(&pc);
-        assert_eq!(pay, 110.0); // (8 + 2 * 1.5) * 10
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![regular("80.00"), overtime("30.00")]); // 8 * 10 + 2 * 1.5 * 10
+    }
+
+    #[test]
+    fn test_overtime_rounding() {
+        let pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2021, 1, 25).unwrap()
+                ..=NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
+        );
+        let mut hc = HourlyClassification::new(money("15.75"));
+        hc.add_timecard(NaiveDate::from_ymd_opt(2021, 1, 25).unwrap(), 9.5);
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        // 明細に分けても合計は (8 + 1.5 * 1.5) * 15.75 = 161.4375 を丸めたものと同じ
+        assert_eq!(pay, vec![regular("126.00"), overtime("35.44")]);
+        assert_eq!(
+            pay.iter().map(|line| line.amount).sum::<Money>(),
+            money("161.44")
+        );
+    }
+
+    #[test]
+    fn test_weekly_overtime_across_pay_periods() {
+        let policy = OvertimePolicy {
+            daily_after: None,
+            weekly_after: Some(40.0),
+            ..OvertimePolicy::none()
+        };
+        // 2025-01-27 (月) から 1/31 (金) までで 40 時間働き、2/1 (土) は翌月の支払い期間
+        let mut hc = HourlyClassification::new(money("10.00"));
+        for d in 27..=31 {
+            hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, d).unwrap(), 8.0);
+        }
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(), 4.0);
+
+        let january = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        assert_eq!(
+            hc.calculate_pay(&january, &policy, &BusinessCalendar::none()),
+            vec![regular("400.00")]
+        );
+        // 前の支払い期間の分と合わせて 40 時間を超えるので 2/1 は残業
+        let february = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 2, 28).unwrap(),
+        );
+        assert_eq!(
+            hc.calculate_pay(&february, &policy, &BusinessCalendar::none()),
+            vec![overtime("60.00")]
+        );
+    }
+
+    #[test]
+    fn test_amend_and_void_timecard() {
+        let pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2021, 1, 25).unwrap()
+                ..=NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
+        );
+        let d25 = NaiveDate::from_ymd_opt(2021, 1, 25).unwrap();
+        let d26 = NaiveDate::from_ymd_opt(2021, 1, 26).unwrap();
+        let mut hc = HourlyClassification::new(money("10.00"));
+        hc.add_timecard(d25, 4.0);
+        hc.add_timecard(d25, 4.0);
+        hc.add_timecard(d26, 8.0);
+        assert_eq!(hc.timecard(d25), Some(8.0));
+
+        assert_eq!(hc.amend_timecard(d25, 6.0), Some(8.0));
+        assert_eq!(hc.timecard(d25), Some(6.0));
+        assert_eq!(hc.void_timecard(d26), Some(8.0));
+        assert_eq!(hc.timecard(d26), None);
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![regular("60.00")]);
+
+        // タイムカードのない日は変更できない
+        assert_eq!(hc.amend_timecard(d26, 8.0), None);
+        assert_eq!(hc.void_timecard(d26), None);
+        assert_eq!(hc.timecard(d26), None);
+    }
+
+    #[test]
+    fn test_double_time_and_premium() {
+        let policy = OvertimePolicy {
+            double_time_after: Some(12.0),
+            weekend_rate: Some(1.25),
+            ..OvertimePolicy::default()
+        };
+        let pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 12).unwrap(),
+        );
+        let mut hc = HourlyClassification::new(money("10.00"));
+        // 同じ日のタイムカードは合わせて 1 日分として数える
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), 7.0);
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), 7.0);
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, 11).unwrap(), 4.0);
+        let pay = hc.calculate_pay(&pc, &policy, &BusinessCalendar::none());
+        assert_eq!(
+            pay,
+            vec![
+                regular("80.00"),
+                EarningLine::new(EarningKind::Premium, money("50.00")),
+                overtime("60.00"),
+                EarningLine::new(EarningKind::DoubleTime, money("40.00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rate_change_mid_period() {
+        let pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 12).unwrap(),
+        );
+        let mut hc = HourlyClassification::new(money("10.00"));
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), 8.0);
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, 9).unwrap(), 10.0);
+        // 1/8 から時給 12.00 にしてもタイムカードは残る
+        hc.change_rate(NaiveDate::from_ymd_opt(2025, 1, 8), money("12.00"));
+        assert_eq!(hc.hourly_rate(), money("12.00"));
+        assert_eq!(hc.timecards().count(), 2);
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        // 8 * 10 + 8 * 12, 2 * 1.5 * 12
+        assert_eq!(pay, vec![regular("176.00"), overtime("36.00")]);
+
+        hc.add_retro_pay(RetroPay::new(
+            NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(),
+            money("16.00"),
+        ));
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(
+            pay[2],
+            EarningLine::new(EarningKind::RetroPay, money("16.00"))
+        );
+
+        // 効力発生日がなければ履歴ごと置き換える
+        hc.change_rate(None, money("11.00"));
+        assert_eq!(
+            hc.rate_history().collect::<Vec<_>>(),
+            vec![(None, money("11.00"))]
+        );
+    }
+
+    #[test]
+    fn test_close() {
+        let pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 12).unwrap(),
+        );
+        let mut hc = HourlyClassification::new(money("10.00"));
+        assert_eq!(hc.last_record_date(), None);
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, 9).unwrap(), 8.0);
+        hc.add_timecard(NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(), 8.0);
+        assert_eq!(hc.first_record_date(), NaiveDate::from_ymd_opt(2025, 1, 6));
+        assert_eq!(hc.last_record_date(), NaiveDate::from_ymd_opt(2025, 1, 9));
+        // 1/8 で閉じれば 1/9 のタイムカードは支払わない
+        hc.close(NaiveDate::from_ymd_opt(2025, 1, 8).unwrap());
+        let pay = hc.calculate_pay(&pc, &OvertimePolicy::default(), &BusinessCalendar::none());
+        assert_eq!(pay, vec![regular("80.00")]);
     }
 }
@@ -1,15 +1,112 @@
+use chrono::{Datelike, NaiveDate, Weekday};
 use log::{debug, trace};
-use std::any::Any;
+use serde::{Deserialize, Serialize};
+use std::{any::Any, ops::RangeInclusive};
 
-use payroll_domain::{Paycheck, PaymentClassification};
+use payroll_domain::{
+    BusinessCalendar, ClassificationInfo, EarningKind, EarningLine, Money, OvertimePolicy,
+    Paycheck, PaymentClassification, Proration,
+};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::{add_final_pay_lines, retro_pay_line, FinalPay, RateHistory, RetroPay};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SalariedClassification {
-    salary: f32,
+    // 効力発生日つきの変更より前の給与
+    salary: Money,
+    #[serde(default)]
+    rate_history: RateHistory<Money>,
+    #[serde(default)]
+    retro_pays: Vec<RetroPay>,
+    #[serde(default)]
+    final_pays: Vec<FinalPay>,
 }
 impl SalariedClassification {
-    pub fn new(salary: f32) -> Self {
-        Self { salary }
+    pub fn new(salary: Money) -> Self {
+        Self {
+            salary,
+            rate_history: RateHistory::default(),
+            retro_pays: vec![],
+            final_pays: vec![],
+        }
+    }
+    // 効力発生日が最も遅い変更の給与
+    pub fn salary(&self) -> Money {
+        self.rate_history.latest(self.salary)
+    }
+    // 最初の給与を効力発生日 None として、変更を効力発生日の順に返す
+    pub fn rate_history(&self) -> impl Iterator<Item = (Option<NaiveDate>, Money)> + '_ {
+        let changes = self.rate_history.iter().map(|(d, r)| (Some(d), r));
+        [(None, self.salary)].into_iter().chain(changes)
+    }
+    // effective が None なら履歴ごと給与を置き換える
+    pub fn change_rate(&mut self, effective: Option<NaiveDate>, salary: Money) {
+        match effective {
+            Some(effective) => self.rate_history.change(effective, salary),
+            None => {
+                self.salary = salary;
+                self.rate_history = RateHistory::default();
+            }
+        }
+    }
+    pub fn retro_pays(&self) -> impl Iterator<Item = RetroPay> + '_ {
+        self.retro_pays.iter().copied()
+    }
+    pub fn add_retro_pay(&mut self, retro_pay: RetroPay) {
+        self.retro_pays.push(retro_pay);
+    }
+    pub fn final_pays(&self) -> impl Iterator<Item = &FinalPay> + '_ {
+        self.final_pays.iter()
+    }
+    pub fn add_final_pay(&mut self, final_pay: FinalPay) {
+        self.final_pays.push(final_pay);
+    }
+}
+
+// 支払い期間の途中で給与が変わったら、それぞれの給与を日数で按分する
+// 入社や退職で期間の途中から始まる・終わるときも、その前後を給与 0 として按分する
+pub(crate) fn prorated_salary(
+    history: &RateHistory<Money>,
+    initial: Money,
+    period: &RangeInclusive<NaiveDate>,
+    policy: &OvertimePolicy,
+    calendar: &BusinessCalendar,
+) -> Money {
+    let segments = history.segments(initial, period);
+    if let [(_, salary)] = segments[..] {
+        return salary;
+    }
+    debug!("prorated_salary: segments={:?}", segments);
+    // 営業日のない期間は暦日で按分する
+    let proration = match policy.proration {
+        Proration::WorkingDays if days(period, Proration::WorkingDays, calendar) == 0 => {
+            Proration::CalendarDays
+        }
+        proration => proration,
+    };
+    segments
+        .iter()
+        .map(|(segment, salary)| *salary * days(segment, proration, calendar))
+        .sum::<Money>()
+        / days(period, proration, calendar)
+}
+
+// 区間の日数
+// 暦の毎週の休日は支払日をずらすための設定で、暦がなければ空なので、営業日は暦によらず土日を除く
+fn days(
+    period: &RangeInclusive<NaiveDate>,
+    proration: Proration,
+    calendar: &BusinessCalendar,
+) -> i64 {
+    match proration {
+        Proration::CalendarDays => (*period.end() - *period.start()).num_days() + 1,
+        Proration::WorkingDays => period
+            .start()
+            .iter_days()
+            .take_while(|d| d <= period.end())
+            .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+            .filter(|d| !calendar.is_public_holiday(*d))
+            .count() as i64,
     }
 }
 impl PaymentClassification for SalariedClassification {
@@ -19,27 +116,198 @@ impl PaymentClassification for SalariedClassification {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn calculate_pay(&self, pc: &Paycheck) -> f32 {
+    fn calculate_pay(
+        &self,
+        pc: &Paycheck,
+        overtime: &OvertimePolicy,
+        calendar: &BusinessCalendar,
+    ) -> Vec<EarningLine> {
         trace!("calculate_pay called");
         let pay_period = pc.get_pay_period();
         debug!("pay_period: {} - {}", pay_period.start(), pay_period.end());
-        self.salary
+        let salary = prorated_salary(
+            &self.rate_history,
+            self.salary,
+            &pay_period,
+            overtime,
+            calendar,
+        );
+        let mut lines = vec![EarningLine::new(EarningKind::Salary, salary)];
+        lines.extend(retro_pay_line(&self.retro_pays, &pay_period));
+        add_final_pay_lines(&mut lines, &self.final_pays, &pay_period);
+        lines
+    }
+    fn info(&self) -> ClassificationInfo {
+        ClassificationInfo::Salaried {
+            salary: self.salary(),
+        }
+    }
+    fn first_record_date(&self) -> Option<NaiveDate> {
+        None
+    }
+    fn last_record_date(&self) -> Option<NaiveDate> {
+        None
+    }
+    fn start_on(&mut self, effective: NaiveDate) {
+        self.rate_history.start_on(self.salary, effective);
+        self.salary = Money::ZERO;
+    }
+    fn close(&mut self, effective: NaiveDate) {
+        self.rate_history.close(effective, Money::ZERO);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use crate::money;
 
     #[test]
     fn test() {
-        let pc = SalariedClassification::new(1000.0);
+        let pc = SalariedClassification::new(money("1000.00"));
         let paycheck = Paycheck::new(
             NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
                 ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
         );
-        let pay = pc.calculate_pay(&paycheck);
-        assert_eq!(pay, 1000.0); // salary only
+        let pay = pc.calculate_pay(
+            &paycheck,
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+        );
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("1000.00"))]
+        ); // salary only
+    }
+
+    #[test]
+    fn test_rate_change_mid_period() {
+        let mut sc = SalariedClassification::new(money("3100.00"));
+        sc.change_rate(NaiveDate::from_ymd_opt(2025, 1, 11), money("3720.00"));
+        let january = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        // 3100 * 10 / 31 + 3720 * 21 / 31
+        let pay = sc.calculate_pay(
+            &january,
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+        );
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("3520.00"))]
+        );
+        let february = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 2, 28).unwrap(),
+        );
+        let pay = sc.calculate_pay(
+            &february,
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+        );
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("3720.00"))]
+        );
+        assert_eq!(
+            sc.info(),
+            ClassificationInfo::Salaried {
+                salary: money("3720.00")
+            }
+        );
+    }
+
+    #[test]
+    fn test_hire_and_termination() {
+        let mut sc = SalariedClassification::new(money("2300.00"));
+        sc.start_on(NaiveDate::from_ymd_opt(2025, 1, 20).unwrap());
+        let january = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        // 暦日では 2300 * 12 / 31
+        let pay = sc.calculate_pay(
+            &january,
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+        );
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("890.32"))]
+        );
+        // 営業日では 2300 * 10 / 23
+        let working_days = OvertimePolicy {
+            proration: Proration::WorkingDays,
+            ..OvertimePolicy::default()
+        };
+        let pay = sc.calculate_pay(&january, &working_days, &BusinessCalendar::none());
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("1000.00"))]
+        );
+        // 会社の暦の祝日も営業日から除く: 2300 * 10 / 22
+        let calendar = BusinessCalendar {
+            holidays: [(2025, vec![NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()])].into(),
+            ..BusinessCalendar::none()
+        };
+        let pay = sc.calculate_pay(&january, &working_days, &calendar);
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("1045.45"))]
+        );
+
+        // 2/14 で退職: 2300 * 14 / 28
+        sc.close(NaiveDate::from_ymd_opt(2025, 2, 15).unwrap());
+        let february = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 2, 28).unwrap(),
+        );
+        let pay = sc.calculate_pay(
+            &february,
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+        );
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("1150.00"))]
+        );
+    }
+
+    #[test]
+    fn test_close_and_start_on() {
+        let effective = NaiveDate::from_ymd_opt(2025, 1, 11).unwrap();
+        let january = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        // 3100 * 10 / 31
+        let mut old = SalariedClassification::new(money("3100.00"));
+        old.close(effective);
+        let final_pay = old.calculate_pay(
+            &january,
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+        );
+        assert_eq!(
+            final_pay,
+            vec![EarningLine::new(EarningKind::Salary, money("1000.00"))]
+        );
+
+        // 3100 * 21 / 31 に変更前の分を足す
+        let mut sc = SalariedClassification::new(money("3100.00"));
+        sc.start_on(effective);
+        assert_eq!(sc.salary(), money("3100.00"));
+        sc.add_final_pay(FinalPay::new(effective, final_pay));
+        let pay = sc.calculate_pay(
+            &january,
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+        );
+        assert_eq!(
+            pay,
+            vec![EarningLine::new(EarningKind::Salary, money("3100.00"))]
+        );
     }
 }
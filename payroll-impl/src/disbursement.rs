@@ -0,0 +1,143 @@
+// 支払い記録の送り先の実装
+use log::{debug, trace};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use payroll_domain::{DisbursementError, DisbursementSink, PayRecord};
+
+// 標準出力に 1 件 1 行の JSON で書き出す
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+impl DisbursementSink for StdoutSink {
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), DisbursementError> {
+        trace!("disburse called");
+        let mut stdout = io::stdout().lock();
+        write_json_lines(&mut stdout, records).map_err(|e| DisbursementError(e.to_string()))
+    }
+}
+
+// ファイルの末尾に 1 件 1 行の JSON で追記する
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+impl FileSink {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        trace!("open called");
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())?;
+        debug!("open: pay records file={}", path.as_ref().display());
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+impl DisbursementSink for FileSink {
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), DisbursementError> {
+        trace!("disburse called");
+        let mut file = self.file.lock().unwrap();
+        write_json_lines(&mut *file, records)
+            .and_then(|_| file.sync_data())
+            .map_err(|e| DisbursementError(e.to_string()))
+    }
+}
+
+// メモリ上に溜めておく (テストや、結果を後から取り出したい場合に使う)
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    records: Arc<Mutex<Vec<PayRecord>>>,
+}
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn records(&self) -> Vec<PayRecord> {
+        self.records.lock().unwrap().clone()
+    }
+}
+impl DisbursementSink for MemorySink {
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), DisbursementError> {
+        trace!("disburse called");
+        self.records.lock().unwrap().extend_from_slice(records);
+        Ok(())
+    }
+}
+
+fn write_json_lines<W: Write>(w: &mut W, records: &[PayRecord]) -> Result<(), io::Error> {
+    for record in records {
+        let json = serde_json::to_string(record).expect("serialize PayRecord as JSON");
+        debug!("disburse: {}", json);
+        writeln!(w, "{}", json)?;
+    }
+    w.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::fs;
+
+    use payroll_domain::{
+        DeductionKind, DeductionLine, EarningKind, EarningLine, MethodInfo, Money, Paycheck,
+    };
+
+    fn record(emp_id: u32) -> PayRecord {
+        let mut pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        pc.set_earning_lines(vec![EarningLine::new(
+            EarningKind::Salary,
+            Money::from_cents(100000),
+        )]);
+        pc.set_deduction_lines(vec![DeductionLine::new(
+            DeductionKind::Dues,
+            Money::from_cents(2000),
+        )]);
+        PayRecord::new(emp_id.into(), &pc, MethodInfo::Hold)
+    }
+
+    #[test]
+    fn test_memory_sink() {
+        let sink = MemorySink::new();
+        sink.disburse(&[record(1), record(2)]).unwrap();
+        sink.clone().disburse(&[record(3)]).unwrap();
+        assert_eq!(sink.records(), vec![record(1), record(2), record(3)]);
+    }
+
+    #[test]
+    fn test_file_sink() {
+        let path = std::env::temp_dir().join(format!("pay-records-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        FileSink::open(&path)
+            .unwrap()
+            .disburse(&[record(1)])
+            .unwrap();
+        // 開き直しても追記される
+        FileSink::open(&path)
+            .unwrap()
+            .disburse(&[record(2)])
+            .unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        let records = text
+            .lines()
+            .map(|line| serde_json::from_str::<PayRecord>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records, vec![record(1), record(2)]);
+        let json: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(json["emp_id"], 1);
+        assert_eq!(json["pay_date"], "2025-01-31");
+        assert_eq!(json["net_pay"], "980.00");
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,78 @@
+// 給与の種類を変えたときの、変更前の Classification での最後の支払い
+// 変更後の Classification が持ち、carried_to (効力発生日) を含む支払い期間で支給する
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+use payroll_domain::EarningLine;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinalPay {
+    pub carried_to: NaiveDate,
+    pub lines: Vec<EarningLine>,
+}
+impl FinalPay {
+    pub fn new(carried_to: NaiveDate, lines: Vec<EarningLine>) -> Self {
+        Self { carried_to, lines }
+    }
+}
+
+// 支払い期間で支給する最後の支払いを、同じ種類の行に足す
+pub(crate) fn add_final_pay_lines(
+    lines: &mut Vec<EarningLine>,
+    final_pays: &[FinalPay],
+    period: &RangeInclusive<NaiveDate>,
+) {
+    for line in final_pays
+        .iter()
+        .filter(|fp| period.contains(&fp.carried_to))
+        .flat_map(|fp| &fp.lines)
+    {
+        match lines.iter_mut().find(|l| l.kind == line.kind) {
+            Some(l) => l.amount += line.amount,
+            None => lines.push(line.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use payroll_domain::{EarningKind, Money};
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+    fn line(kind: EarningKind, s: &str) -> EarningLine {
+        EarningLine::new(kind, s.parse::<Money>().unwrap())
+    }
+
+    #[test]
+    fn test_add_final_pay_lines() {
+        let final_pays = [
+            FinalPay::new(
+                date(1, 15),
+                vec![
+                    line(EarningKind::Salary, "1500.00"),
+                    line(EarningKind::RetroPay, "20.00"),
+                ],
+            ),
+            FinalPay::new(date(2, 1), vec![line(EarningKind::Regular, "80.00")]),
+        ];
+        let mut lines = vec![line(EarningKind::Regular, "160.00")];
+        add_final_pay_lines(&mut lines, &final_pays, &(date(1, 11)..=date(1, 17)));
+        assert_eq!(
+            lines,
+            [
+                line(EarningKind::Regular, "160.00"),
+                line(EarningKind::Salary, "1500.00"),
+                line(EarningKind::RetroPay, "20.00"),
+            ]
+        );
+
+        // 同じ種類の行には足す
+        let mut lines = vec![line(EarningKind::Regular, "160.00")];
+        add_final_pay_lines(&mut lines, &final_pays, &(date(1, 25)..=date(2, 7)));
+        assert_eq!(lines, [line(EarningKind::Regular, "240.00")]);
+    }
+}
@@ -1,11 +1,31 @@
 mod affiliation;
+mod calendar;
 mod classification;
+mod disbursement;
+mod final_pay;
 mod method;
+mod overtime;
 mod payroll_factory_impl;
+mod rate_history;
+mod reversal;
 mod schedule;
+mod withholding;
 
 pub use affiliation::*;
+pub use calendar::*;
 pub use classification::*;
+pub use disbursement::*;
+pub use final_pay::*;
 pub use method::*;
+pub use overtime::*;
 pub use payroll_factory_impl::*;
+pub use rate_history::*;
+pub use reversal::*;
 pub use schedule::*;
+pub use withholding::*;
+
+// テストで金額を文字列から作る
+#[cfg(test)]
+pub(crate) fn money(s: &str) -> payroll_domain::Money {
+    s.parse().unwrap()
+}
@@ -1,23 +1,10 @@
 use log::{debug, trace};
 use serde::{Deserialize, Serialize};
-use serde_json;
 use std::any::Any;
 
-use payroll_domain::{EmployeeId, Paycheck, PaymentMethod};
+use payroll_domain::{EmployeeId, MethodInfo, PayRecord, Paycheck, PaymentMethod};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct DirectPay {
-    emp_id: u32,
-
-    bank: String,
-    account: String,
-
-    gross_pay: f32,
-    deductions: f32,
-    net_pay: f32,
-}
-
-#[derive(Debug, Clone, PartialEq)]
 pub struct DirectMethod {
     bank: String,
     account: String,
@@ -29,6 +16,12 @@ impl DirectMethod {
             account: account.to_string(),
         }
     }
+    pub fn bank(&self) -> &str {
+        &self.bank
+    }
+    pub fn account(&self) -> &str {
+        &self.account
+    }
 }
 impl PaymentMethod for DirectMethod {
     fn as_any(&self) -> &dyn Any {
@@ -37,20 +30,16 @@ impl PaymentMethod for DirectMethod {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck) {
+    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck) -> PayRecord {
         trace!("pay called");
-        let direct_pay = DirectPay {
-            emp_id: emp_id.into(),
-
+        let record = PayRecord::new(emp_id, pc, self.info());
+        debug!("pay: {:?}", record);
+        record
+    }
+    fn info(&self) -> MethodInfo {
+        MethodInfo::Direct {
             bank: self.bank.clone(),
             account: self.account.clone(),
-
-            gross_pay: pc.gross_pay(),
-            deductions: pc.deductions(),
-            net_pay: pc.net_pay(),
-        };
-        let json = serde_json::to_string(&direct_pay).expect("serialize DirectPay as JSON");
-        debug!("pay: {}", json);
-        println!("{}", json);
+        }
     }
 }
@@ -1,20 +1,10 @@
 use log::{debug, trace};
 use serde::{Deserialize, Serialize};
-use serde_json;
 use std::any::Any;
 
-use payroll_domain::{EmployeeId, Paycheck, PaymentMethod};
+use payroll_domain::{EmployeeId, MethodInfo, PayRecord, Paycheck, PaymentMethod};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct HoldPay {
-    emp_id: u32,
-
-    gross_pay: f32,
-    deductions: f32,
-    net_pay: f32,
-}
-
-#[derive(Debug, Clone, PartialEq)]
 pub struct HoldMethod;
 impl PaymentMethod for HoldMethod {
     fn as_any(&self) -> &dyn Any {
@@ -23,17 +13,13 @@ impl PaymentMethod for HoldMethod {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck) {
+    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck) -> PayRecord {
         trace!("pay called");
-        let hold_pay = HoldPay {
-            emp_id: emp_id.into(),
-
-            gross_pay: pc.gross_pay(),
-            deductions: pc.deductions(),
-            net_pay: pc.net_pay(),
-        };
-        let json = serde_json::to_string(&hold_pay).expect("serialize HoldPay as JSON");
-        debug!("pay: {}", json);
-        println!("{}", json);
+        let record = PayRecord::new(emp_id, pc, self.info());
+        debug!("pay: {:?}", record);
+        record
+    }
+    fn info(&self) -> MethodInfo {
+        MethodInfo::Hold
     }
 }
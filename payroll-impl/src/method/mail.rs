@@ -1,22 +1,10 @@
 use log::{debug, trace};
 use serde::{Deserialize, Serialize};
-use serde_json;
 use std::any::Any;
 
-use payroll_domain::{EmployeeId, Paycheck, PaymentMethod};
+use payroll_domain::{EmployeeId, MethodInfo, PayRecord, Paycheck, PaymentMethod};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct MailPay {
-    emp_id: u32,
-
-    address: String,
-
-    gross_pay: f32,
-    deductions: f32,
-    net_pay: f32,
-}
-
-#[derive(Debug, Clone, PartialEq)]
 pub struct MailMethod {
     address: String,
 }
@@ -26,6 +14,9 @@ impl MailMethod {
             address: address.to_string(),
         }
     }
+    pub fn address(&self) -> &str {
+        &self.address
+    }
 }
 impl PaymentMethod for MailMethod {
     fn as_any(&self) -> &dyn Any {
@@ -34,19 +25,15 @@ impl PaymentMethod for MailMethod {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck) {
+    fn pay(&self, emp_id: EmployeeId, pc: &Paycheck) -> PayRecord {
         trace!("pay called");
-        let mail_pay = MailPay {
-            emp_id: emp_id.into(),
-
+        let record = PayRecord::new(emp_id, pc, self.info());
+        debug!("pay: {:?}", record);
+        record
+    }
+    fn info(&self) -> MethodInfo {
+        MethodInfo::Mail {
             address: self.address.clone(),
-
-            gross_pay: pc.gross_pay(),
-            deductions: pc.deductions(),
-            net_pay: pc.net_pay(),
-        };
-        let json = serde_json::to_string(&mail_pay).expect("serialize MailPay as JSON");
-        debug!("pay: {}", json);
-        println!("{}", json);
+        }
     }
 }
@@ -0,0 +1,248 @@
+// 残業の規則に従って 1 日ごとの勤務時間を通常・割増・残業・倍額に振り分ける
+use chrono::{Datelike, NaiveDate, Weekday};
+use log::{debug, trace};
+use std::{collections::BTreeMap, fs, io, path::Path};
+use thiserror::Error;
+
+use payroll_domain::{BusinessCalendar, InvalidOvertimePolicy, OvertimePolicy};
+
+#[derive(Debug, Error)]
+pub enum OvertimeConfigError {
+    #[error("failed to read overtime config: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to parse overtime config: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error(transparent)]
+    Invalid(#[from] InvalidOvertimePolicy),
+}
+
+// 会社全体の残業の規則を設定ファイルから読み込む
+pub fn load_overtime_policy<P: AsRef<Path>>(
+    path: P,
+) -> Result<OvertimePolicy, OvertimeConfigError> {
+    trace!("load_overtime_policy called");
+    debug!("load_overtime_policy: config={}", path.as_ref().display());
+    let policy: OvertimePolicy = serde_json::from_str(&fs::read_to_string(path)?)?;
+    policy.validate()?;
+    Ok(policy)
+}
+
+// date を含む週の最初の日
+pub fn week_start_of(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    date.week(week_start).first_day()
+}
+
+// 1 日分の勤務時間の振り分け
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DailyHours {
+    pub regular: f32,
+    // 土日・祝日の残業でない時間 (premium_rate 倍)
+    pub premium: f32,
+    pub premium_rate: f32,
+    pub overtime: f32,
+    pub double_time: f32,
+}
+
+// hours は日付ごとの勤務時間の合計
+// 週ごとの残業は週の最初の日から数えるので、支払い期間をまたぐ週は期間の前の分も hours に含めておくこと
+// 祝日は会社の暦 calendar の祝日
+pub fn split_hours(
+    policy: &OvertimePolicy,
+    calendar: &BusinessCalendar,
+    hours: &BTreeMap<NaiveDate, f32>,
+) -> BTreeMap<NaiveDate, DailyHours> {
+    trace!("split_hours called");
+    let mut week = None;
+    let mut week_straight = 0.0;
+    hours
+        .iter()
+        .map(|(&date, &h)| {
+            let this_week = week_start_of(date, policy.week_start);
+            if week != Some(this_week) {
+                week = Some(this_week);
+                week_straight = 0.0;
+            }
+
+            let double_time = policy.double_time_after.map_or(0.0, |a| (h - a).max(0.0));
+            let rest = h - double_time;
+            let mut overtime = policy.daily_after.map_or(0.0, |a| (rest - a).max(0.0));
+            let mut straight = rest - overtime;
+            if let Some(weekly_after) = policy.weekly_after {
+                let room = (weekly_after - week_straight).max(0.0);
+                let moved = (straight - room).max(0.0);
+                straight -= moved;
+                overtime += moved;
+            }
+            week_straight += straight;
+
+            let premium_rate = if calendar.is_public_holiday(date) {
+                policy.holiday_rate
+            } else if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                policy.weekend_rate
+            } else {
+                None
+            };
+            let daily = match premium_rate {
+                Some(rate) => DailyHours {
+                    premium: straight,
+                    premium_rate: rate,
+                    overtime,
+                    double_time,
+                    ..Default::default()
+                },
+                None => DailyHours {
+                    regular: straight,
+                    overtime,
+                    double_time,
+                    ..Default::default()
+                },
+            };
+            debug!("split_hours: {} {} => {:?}", date, h, daily);
+            (date, daily)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+    fn hours(cards: &[(NaiveDate, f32)]) -> BTreeMap<NaiveDate, f32> {
+        cards.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let split = split_hours(
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+            &hours(&[(date(1, 6), 10.0), (date(1, 11), 8.0)]),
+        );
+        assert_eq!(
+            split[&date(1, 6)],
+            DailyHours {
+                regular: 8.0,
+                overtime: 2.0,
+                ..Default::default()
+            }
+        );
+        // 規則がなければ土曜日も通常の勤務時間
+        assert_eq!(
+            split[&date(1, 11)],
+            DailyHours {
+                regular: 8.0,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_weekly_and_double_time() {
+        let policy = OvertimePolicy {
+            daily_after: Some(8.0),
+            weekly_after: Some(40.0),
+            double_time_after: Some(12.0),
+            ..OvertimePolicy::none()
+        };
+        // 2025-01-06 (月) から 13, 9, 9, 9, 9, 8 時間
+        let cards: Vec<_> = [13.0, 9.0, 9.0, 9.0, 9.0, 8.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, h)| (date(1, 6 + i as u32), h))
+            .collect();
+        let split = split_hours(&policy, &BusinessCalendar::none(), &hours(&cards));
+        assert_eq!(
+            split[&date(1, 6)],
+            DailyHours {
+                regular: 8.0,
+                overtime: 4.0,
+                double_time: 1.0,
+                ..Default::default()
+            }
+        );
+        // 金曜日までで通常の勤務時間が 40 時間になるので、土曜日はすべて残業
+        assert_eq!(split[&date(1, 10)].regular, 8.0);
+        assert_eq!(
+            split[&date(1, 11)],
+            DailyHours {
+                overtime: 8.0,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_week_start() {
+        let policy = OvertimePolicy {
+            daily_after: None,
+            weekly_after: Some(10.0),
+            week_start: Weekday::Sun,
+            ..OvertimePolicy::none()
+        };
+        // 土曜日 (1/11) と日曜日 (1/12) は別の週
+        let split = split_hours(
+            &policy,
+            &BusinessCalendar::none(),
+            &hours(&[(date(1, 11), 8.0), (date(1, 12), 8.0)]),
+        );
+        assert_eq!(split[&date(1, 11)].regular, 8.0);
+        assert_eq!(split[&date(1, 12)].regular, 8.0);
+        assert_eq!(week_start_of(date(1, 11), Weekday::Sun), date(1, 5));
+    }
+
+    #[test]
+    fn test_weekend_and_holiday() {
+        let policy = OvertimePolicy {
+            weekend_rate: Some(1.25),
+            holiday_rate: Some(2.0),
+            ..OvertimePolicy::default()
+        };
+        let calendar = BusinessCalendar {
+            holidays: [(2025, vec![date(1, 1), date(1, 4)])].into(),
+            ..BusinessCalendar::none()
+        };
+        let split = split_hours(
+            &policy,
+            &calendar,
+            &hours(&[(date(1, 1), 4.0), (date(1, 4), 9.0), (date(1, 5), 6.0)]),
+        );
+        assert_eq!(
+            split[&date(1, 1)],
+            DailyHours {
+                premium: 4.0,
+                premium_rate: 2.0,
+                ..Default::default()
+            }
+        );
+        // 土曜日の祝日は祝日の倍率
+        assert_eq!(
+            split[&date(1, 4)],
+            DailyHours {
+                premium: 8.0,
+                premium_rate: 2.0,
+                overtime: 1.0,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            split[&date(1, 5)],
+            DailyHours {
+                premium: 6.0,
+                premium_rate: 1.25,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_sample_config() {
+        let policy = load_overtime_policy(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../config/overtime.json"
+        ));
+        assert!(policy.is_ok(), "{:?}", policy);
+    }
+}
@@ -1,18 +1,24 @@
+use chrono::{NaiveDate, Weekday};
 use std::sync::{Arc, Mutex};
 
 use crate::{
     affiliation::UnionAffiliation,
     classification::{CommissionedClassification, HourlyClassification, SalariedClassification},
     method::{DirectMethod, HoldMethod, MailMethod},
-    schedule::{BiweeklySchedule, MonthlySchedule, WeeklySchedule},
+    schedule::{
+        BiweeklyFromSchedule, MonthlyOnSchedule, MonthlySchedule, SemimonthlySchedule,
+        WeeklyOnSchedule, WeeklySchedule,
+    },
 };
 use payroll_domain::{
-    Affiliation, MemberId, NoAffiliation, PaymentClassification, PaymentMethod, PaymentSchedule,
+    Affiliation, MemberId, Money, NoAffiliation, PaymentClassification, PaymentMethod,
+    PaymentSchedule,
 };
 use payroll_factory::{
-    BiweeklyScheduleFactory, CommissionedClassificationFactory, DirectMethodFactory,
-    HoldMethodFactory, HourlyClassificationFactory, MailMethodFactory, MonthlyScheduleFactory,
-    NoAffiliationFactory, SalariedClassificationFactory, UnionAffiliationFactory,
+    BiweeklyFromScheduleFactory, CommissionedClassificationFactory, DirectMethodFactory,
+    HoldMethodFactory, HourlyClassificationFactory, MailMethodFactory, MonthlyOnScheduleFactory,
+    MonthlyScheduleFactory, NoAffiliationFactory, SalariedClassificationFactory,
+    SemimonthlyScheduleFactory, UnionAffiliationFactory, WeeklyOnScheduleFactory,
     WeeklyScheduleFactory,
 };
 
@@ -20,19 +26,19 @@ use payroll_factory::{
 pub struct PayrollFactoryImpl;
 
 impl SalariedClassificationFactory for PayrollFactoryImpl {
-    fn mk_classification(&self, salary: f32) -> Arc<Mutex<dyn PaymentClassification>> {
+    fn mk_classification(&self, salary: Money) -> Arc<Mutex<dyn PaymentClassification>> {
         Arc::new(Mutex::new(SalariedClassification::new(salary)))
     }
 }
 impl HourlyClassificationFactory for PayrollFactoryImpl {
-    fn mk_classification(&self, hourly_rate: f32) -> Arc<Mutex<dyn PaymentClassification>> {
+    fn mk_classification(&self, hourly_rate: Money) -> Arc<Mutex<dyn PaymentClassification>> {
         Arc::new(Mutex::new(HourlyClassification::new(hourly_rate)))
     }
 }
 impl CommissionedClassificationFactory for PayrollFactoryImpl {
     fn mk_classification(
         &self,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
     ) -> Arc<Mutex<dyn PaymentClassification>> {
         Arc::new(Mutex::new(CommissionedClassification::new(
@@ -51,9 +57,24 @@ impl WeeklyScheduleFactory for PayrollFactoryImpl {
         Arc::new(Mutex::new(WeeklySchedule))
     }
 }
-impl BiweeklyScheduleFactory for PayrollFactoryImpl {
+impl SemimonthlyScheduleFactory for PayrollFactoryImpl {
     fn mk_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>> {
-        Arc::new(Mutex::new(BiweeklySchedule))
+        Arc::new(Mutex::new(SemimonthlySchedule))
+    }
+}
+impl MonthlyOnScheduleFactory for PayrollFactoryImpl {
+    fn mk_schedule(&self, day: u32) -> Arc<Mutex<dyn PaymentSchedule>> {
+        Arc::new(Mutex::new(MonthlyOnSchedule::new(day)))
+    }
+}
+impl WeeklyOnScheduleFactory for PayrollFactoryImpl {
+    fn mk_schedule(&self, weekday: Weekday) -> Arc<Mutex<dyn PaymentSchedule>> {
+        Arc::new(Mutex::new(WeeklyOnSchedule::new(weekday)))
+    }
+}
+impl BiweeklyFromScheduleFactory for PayrollFactoryImpl {
+    fn mk_schedule(&self, anchor: NaiveDate) -> Arc<Mutex<dyn PaymentSchedule>> {
+        Arc::new(Mutex::new(BiweeklyFromSchedule::new(anchor)))
     }
 }
 impl HoldMethodFactory for PayrollFactoryImpl {
@@ -72,7 +93,7 @@ impl MailMethodFactory for PayrollFactoryImpl {
     }
 }
 impl UnionAffiliationFactory for PayrollFactoryImpl {
-    fn mk_affiliation(&self, member_id: MemberId, dues: f32) -> Arc<Mutex<dyn Affiliation>> {
+    fn mk_affiliation(&self, member_id: MemberId, dues: Money) -> Arc<Mutex<dyn Affiliation>> {
         Arc::new(Mutex::new(UnionAffiliation::new(member_id, dues)))
     }
 }
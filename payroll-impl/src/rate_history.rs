@@ -0,0 +1,195 @@
+// 効力発生日つきのレートの変更と、支払い済みの期間にさかのぼった変更の差額
+// 最初のレートは各 Classification が持ち、ここには変更だけを効力発生日の順に並べる
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+use payroll_domain::{EarningKind, EarningLine, Money};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct RateChange<T> {
+    effective: NaiveDate,
+    rate: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RateHistory<T>(Vec<RateChange<T>>);
+impl<T> Default for RateHistory<T> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+impl<T: Copy> RateHistory<T> {
+    pub fn iter(&self) -> impl Iterator<Item = (NaiveDate, T)> + '_ {
+        self.0.iter().map(|rc| (rc.effective, rc.rate))
+    }
+    // 効力発生日が最も遅い変更のレート (変更がなければ initial)
+    pub fn latest(&self, initial: T) -> T {
+        self.0.last().map_or(initial, |rc| rc.rate)
+    }
+    pub fn rate_on(&self, initial: T, date: NaiveDate) -> T {
+        self.0
+            .iter()
+            .take_while(|rc| rc.effective <= date)
+            .last()
+            .map_or(initial, |rc| rc.rate)
+    }
+    pub fn map<U>(&self, f: impl Fn(T) -> U) -> RateHistory<U> {
+        let changes = self.0.iter().map(|rc| RateChange {
+            effective: rc.effective,
+            rate: f(rc.rate),
+        });
+        RateHistory(changes.collect())
+    }
+    // 同じ効力発生日の変更は置き換える
+    pub fn change(&mut self, effective: NaiveDate, rate: T) {
+        let i = self.0.partition_point(|rc| rc.effective < effective);
+        match self.0.get_mut(i) {
+            Some(rc) if rc.effective == effective => rc.rate = rate,
+            _ => self.0.insert(i, RateChange { effective, rate }),
+        }
+    }
+    // effective の時点のレートで始めて、それより前の変更を除く
+    // effective より前のレート (initial) は呼び出し側で 0 にする
+    pub fn start_on(&mut self, initial: T, effective: NaiveDate) {
+        let rate = self.rate_on(initial, effective);
+        self.0.retain(|rc| rc.effective > effective);
+        self.0.insert(0, RateChange { effective, rate });
+    }
+    // effective から zero にして、それより後の変更を除く
+    pub fn close(&mut self, effective: NaiveDate, zero: T) {
+        self.0.retain(|rc| rc.effective < effective);
+        self.0.push(RateChange {
+            effective,
+            rate: zero,
+        });
+    }
+    // period を変更の効力発生日で区切り、区間ごとのレートを返す
+    pub fn segments(
+        &self,
+        initial: T,
+        period: &RangeInclusive<NaiveDate>,
+    ) -> Vec<(RangeInclusive<NaiveDate>, T)> {
+        let mut segments = vec![];
+        let mut start = *period.start();
+        let mut rate = self.rate_on(initial, start);
+        let inside =
+            |rc: &&RateChange<T>| *period.start() < rc.effective && rc.effective <= *period.end();
+        for rc in self.0.iter().filter(inside) {
+            segments.push((start..=rc.effective - Days::new(1), rate));
+            (start, rate) = (rc.effective, rc.rate);
+        }
+        segments.push((start..=*period.end(), rate));
+        segments
+    }
+}
+
+// 支払い済みの期間にさかのぼってレートを変えたときの差額で、carried_to を含む支払い期間で支給する
+// 引き下げなら負の額になる
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RetroPay {
+    pub carried_to: NaiveDate,
+    pub amount: Money,
+}
+impl RetroPay {
+    pub fn new(carried_to: NaiveDate, amount: Money) -> Self {
+        Self { carried_to, amount }
+    }
+}
+
+// 支払い期間で支給する差額をまとめて 1 行にする
+pub(crate) fn retro_pay_line(
+    retro_pays: &[RetroPay],
+    period: &RangeInclusive<NaiveDate>,
+) -> Option<EarningLine> {
+    let amount = retro_pays
+        .iter()
+        .filter(|rp| period.contains(&rp.carried_to))
+        .map(|rp| rp.amount)
+        .sum::<Money>();
+    (!amount.is_zero()).then(|| EarningLine::new(EarningKind::RetroPay, amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_rate_on_and_segments() {
+        let mut history = RateHistory::default();
+        history.change(date(2, 1), 30);
+        history.change(date(1, 15), 20);
+        assert_eq!(
+            history.iter().collect::<Vec<_>>(),
+            [(date(1, 15), 20), (date(2, 1), 30)]
+        );
+        assert_eq!(history.rate_on(10, date(1, 14)), 10);
+        assert_eq!(history.rate_on(10, date(1, 15)), 20);
+        assert_eq!(history.rate_on(10, date(3, 1)), 30);
+        assert_eq!(history.latest(10), 30);
+
+        assert_eq!(
+            history.segments(10, &(date(1, 1)..=date(1, 31))),
+            [
+                (date(1, 1)..=date(1, 14), 10),
+                (date(1, 15)..=date(1, 31), 20)
+            ]
+        );
+        // 期間の初日に効くものは区切らない
+        assert_eq!(
+            history.segments(10, &(date(2, 1)..=date(2, 28))),
+            [(date(2, 1)..=date(2, 28), 30)]
+        );
+
+        // 同じ効力発生日なら置き換える
+        history.change(date(1, 15), 25);
+        assert_eq!(history.rate_on(10, date(1, 20)), 25);
+        assert_eq!(history.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_start_on_and_close() {
+        let mut history = RateHistory::default();
+        history.change(date(1, 10), 20);
+        history.change(date(2, 1), 30);
+        history.start_on(10, date(1, 15));
+        assert_eq!(
+            history.iter().collect::<Vec<_>>(),
+            [(date(1, 15), 20), (date(2, 1), 30)]
+        );
+        assert_eq!(history.rate_on(0, date(1, 14)), 0);
+
+        history.close(date(1, 21), 0);
+        assert_eq!(
+            history.iter().collect::<Vec<_>>(),
+            [(date(1, 15), 20), (date(1, 21), 0)]
+        );
+        assert_eq!(history.rate_on(0, date(1, 20)), 20);
+        assert_eq!(history.rate_on(0, date(3, 1)), 0);
+    }
+
+    #[test]
+    fn test_retro_pay_line() {
+        let retro_pays = [
+            RetroPay::new(date(1, 11), Money::from_cents(1000)),
+            RetroPay::new(date(1, 18), Money::from_cents(-250)),
+            RetroPay::new(date(2, 1), Money::from_cents(500)),
+        ];
+        assert_eq!(
+            retro_pay_line(&retro_pays, &(date(1, 1)..=date(1, 31))),
+            Some(EarningLine::new(
+                EarningKind::RetroPay,
+                Money::from_cents(750)
+            ))
+        );
+        assert_eq!(
+            retro_pay_line(&retro_pays, &(date(3, 1)..=date(3, 31))),
+            None
+        );
+    }
+}
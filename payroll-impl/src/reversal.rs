@@ -0,0 +1,28 @@
+// 売上・サービス料の取り消し
+// 取り消したものも番号を保つために消さずに残し、取り消しの状態だけを持たせる
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reversal {
+    // 支払い前に取り消したので、どの Paycheck にも載らない
+    Voided,
+    // 支払い済みのものを取り消したので、この日を含む支払い期間で差し戻す
+    CarriedTo(NaiveDate),
+}
+impl Reversal {
+    // last_paid はそのものを支払った Paycheck 以降で最後の支払日 (未払いなら None)
+    // 最後の支払日の翌日は次の支払い期間の初日になる
+    pub fn new(last_paid: Option<NaiveDate>) -> Self {
+        match last_paid {
+            Some(pay_date) => Self::CarriedTo(pay_date + Days::new(1)),
+            None => Self::Voided,
+        }
+    }
+    pub fn carried_to(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Voided => None,
+            Self::CarriedTo(date) => Some(*date),
+        }
+    }
+}
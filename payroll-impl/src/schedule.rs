@@ -1,7 +1,75 @@
-mod biweekly;
+mod biweekly_from;
 mod monthly;
+mod monthly_on;
+mod semimonthly;
 mod weekly;
+mod weekly_on;
 
-pub use biweekly::*;
+pub use biweekly_from::*;
 pub use monthly::*;
+pub use monthly_on::*;
+pub use semimonthly::*;
 pub use weekly::*;
+pub use weekly_on::*;
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{money, HoldMethod, HourlyClassification};
+    use payroll_domain::{BusinessCalendar, Employee, NoAffiliation};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_change_schedule() {
+        let cal = BusinessCalendar::none();
+        let mut emp = Employee::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Arc::new(Mutex::new(HourlyClassification::new(money("10")))),
+            Arc::new(Mutex::new(WeeklySchedule)),
+            Arc::new(Mutex::new(HoldMethod)),
+            Arc::new(Mutex::new(NoAffiliation)),
+        );
+
+        // 1/10 まで支払ってから 15 日と月末に変える
+        emp.change_schedule(
+            Arc::new(Mutex::new(SemimonthlySchedule)),
+            Some(date(2025, 1, 11)),
+        );
+        assert!(!emp.is_pay_date(date(2025, 1, 17), &cal));
+        assert!(emp.is_pay_date(date(2025, 1, 15), &cal));
+        // 最初の支払い期間は前の支払日の翌日からに縮める
+        assert_eq!(
+            emp.get_pay_period(date(2025, 1, 15), &cal),
+            date(2025, 1, 11)..=date(2025, 1, 15)
+        );
+        // 次からはいつもの期間
+        assert_eq!(
+            emp.get_pay_period(date(2025, 1, 31), &cal),
+            date(2025, 1, 16)..=date(2025, 1, 31)
+        );
+
+        // 1/31 まで支払ってから毎月 25 日払いに変えると、2/25 の期間は 1/26 からではなく 2/1 から
+        emp.change_schedule(
+            Arc::new(Mutex::new(MonthlyOnSchedule::new(25))),
+            Some(date(2025, 2, 1)),
+        );
+        assert_eq!(
+            emp.get_pay_period(date(2025, 2, 25), &cal),
+            date(2025, 2, 1)..=date(2025, 2, 25)
+        );
+        // まだ一度も支払っていなければ縮めない
+        emp.change_schedule(Arc::new(Mutex::new(MonthlySchedule)), None);
+        assert_eq!(
+            emp.get_pay_period(date(2025, 2, 28), &cal),
+            date(2025, 2, 1)..=date(2025, 2, 28)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{any::Any, ops::RangeInclusive};
+
+use payroll_domain::{PaymentSchedule, ScheduleInfo};
+
+// 起算日を最初の支払い期間の初日として、2 週ごとにその期間の最終日に支払う
+// 起算日より前の日付にも同じ間隔で支払日がある
+// ISO 週番号の偶奇で決めると 53 週ある年をまたぐところで間隔が 1 週や 3 週になるので、日数で数える
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BiweeklyFromSchedule {
+    anchor: NaiveDate,
+}
+impl BiweeklyFromSchedule {
+    pub fn new(anchor: NaiveDate) -> Self {
+        Self { anchor }
+    }
+    pub fn anchor(&self) -> NaiveDate {
+        self.anchor
+    }
+}
+impl Default for BiweeklyFromSchedule {
+    fn default() -> Self {
+        Self::new(ScheduleInfo::DEFAULT_BIWEEKLY_ANCHOR)
+    }
+}
+impl PaymentSchedule for BiweeklyFromSchedule {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn is_pay_date(&self, date: NaiveDate) -> bool {
+        (date - self.anchor).num_days().rem_euclid(14) == 13
+    }
+    fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate> {
+        pay_date.checked_sub_days(Days::new(13)).unwrap()..=pay_date
+    }
+    fn info(&self) -> ScheduleInfo {
+        ScheduleInfo::BiweeklyFrom {
+            anchor: self.anchor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Weekday};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_is_pay_date() {
+        let bs = BiweeklyFromSchedule::new(date(2025, 1, 6));
+        assert!(bs.is_pay_date(date(2025, 1, 19)));
+        assert!(!bs.is_pay_date(date(2025, 1, 12)));
+        assert!(!bs.is_pay_date(date(2025, 1, 26)));
+        assert!(bs.is_pay_date(date(2025, 2, 2)));
+        // 起算日より前
+        assert!(bs.is_pay_date(date(2025, 1, 5)));
+        assert!(!bs.is_pay_date(date(2024, 12, 29)));
+    }
+
+    #[test]
+    fn test_default_anchor() {
+        // 起算日を持つ前と同じ偶数週の金曜日に支払う
+        let bs = BiweeklyFromSchedule::default();
+        assert!(bs.is_pay_date(date(2025, 1, 10)));
+        assert!(!bs.is_pay_date(date(2025, 1, 17)));
+        assert!(bs.is_pay_date(date(2025, 1, 24)));
+        assert!(bs.is_pay_date(date(2024, 12, 27)));
+    }
+
+    #[test]
+    fn test_year_boundaries() {
+        let bs = BiweeklyFromSchedule::default();
+        // 2026 年は 53 週あるので、偶数週の金曜日だと 12/25 の次が 3 週後の 2027/1/15 になる
+        assert!(bs.is_pay_date(date(2026, 12, 25)));
+        assert!(!bs.is_pay_date(date(2027, 1, 1)));
+        assert!(bs.is_pay_date(date(2027, 1, 8)));
+        assert!(!bs.is_pay_date(date(2027, 1, 15)));
+
+        // 53 週ある年をいくつまたいでも、支払日はいつも 2 週おきの金曜日
+        let pay_dates = date(2019, 1, 1)
+            .iter_days()
+            .take_while(|d| d.year() < 2033)
+            .filter(|d| bs.is_pay_date(*d))
+            .collect::<Vec<_>>();
+        assert!(pay_dates.iter().all(|d| d.weekday() == Weekday::Fri));
+        assert!(pay_dates.windows(2).all(|w| (w[1] - w[0]).num_days() == 14));
+    }
+
+    #[test]
+    fn test_pay_period() {
+        let bs = BiweeklyFromSchedule::new(date(2025, 1, 6));
+        assert_eq!(
+            bs.get_pay_period(date(2025, 1, 19)),
+            date(2025, 1, 6)..=date(2025, 1, 19)
+        );
+        // 年をまたぐ期間も暦日で 2 週間
+        let bs = BiweeklyFromSchedule::default();
+        assert_eq!(
+            bs.get_pay_period(date(2027, 1, 8)),
+            date(2026, 12, 26)..=date(2027, 1, 8)
+        );
+    }
+}
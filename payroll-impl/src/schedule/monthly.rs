@@ -1,9 +1,10 @@
 use chrono::{Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
 use std::{any::Any, ops::RangeInclusive};
 
-use payroll_domain::PaymentSchedule;
+use payroll_domain::{PaymentSchedule, ScheduleInfo};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MonthlySchedule;
 impl MonthlySchedule {
     pub fn is_last_day_of_month(&self, date: NaiveDate) -> bool {
@@ -23,6 +24,9 @@ impl PaymentSchedule for MonthlySchedule {
     fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate> {
         pay_date.with_day(1).unwrap()..=pay_date
     }
+    fn info(&self) -> ScheduleInfo {
+        ScheduleInfo::Monthly
+    }
 }
 
 #[cfg(test)]
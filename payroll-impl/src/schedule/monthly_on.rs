@@ -0,0 +1,114 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{any::Any, ops::RangeInclusive};
+
+use payroll_domain::{PaymentSchedule, ScheduleInfo};
+
+// 毎月決まった日に支払う (その日がない月は月末)
+// 支払い期間は前の月の支払日の翌日から
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyOnSchedule {
+    day: u32,
+}
+impl MonthlyOnSchedule {
+    pub fn new(day: u32) -> Self {
+        assert!(
+            (1..=31).contains(&day),
+            "day of month out of range: {}",
+            day
+        );
+        Self { day }
+    }
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+    // date と同じ月の支払日
+    fn pay_date_in_month(&self, date: NaiveDate) -> NaiveDate {
+        let first = date.with_day(1).unwrap();
+        let last = first
+            .checked_add_months(Months::new(1))
+            .unwrap()
+            .pred_opt()
+            .unwrap();
+        first.with_day(self.day.min(last.day())).unwrap()
+    }
+}
+impl PaymentSchedule for MonthlyOnSchedule {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn is_pay_date(&self, date: NaiveDate) -> bool {
+        date == self.pay_date_in_month(date)
+    }
+    fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate> {
+        // pay_date より前の直近の支払日の翌日から
+        let this_month = self.pay_date_in_month(pay_date);
+        let last_pay_date = if this_month < pay_date {
+            this_month
+        } else {
+            let prev_month = pay_date.with_day(1).unwrap().pred_opt().unwrap();
+            self.pay_date_in_month(prev_month)
+        };
+        last_pay_date.succ_opt().unwrap()..=pay_date
+    }
+    fn info(&self) -> ScheduleInfo {
+        ScheduleInfo::MonthlyOn { day: self.day }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_is_pay_date() {
+        let ms = MonthlyOnSchedule::new(25);
+        assert!(ms.is_pay_date(date(2025, 1, 25)));
+        assert!(!ms.is_pay_date(date(2025, 1, 24)));
+        assert!(!ms.is_pay_date(date(2025, 1, 31)));
+
+        // 30 日の支払いは 2 月だけ月末になる
+        let ms = MonthlyOnSchedule::new(30);
+        assert!(ms.is_pay_date(date(2025, 1, 30)));
+        assert!(ms.is_pay_date(date(2025, 2, 28)));
+        assert!(!ms.is_pay_date(date(2028, 2, 28)));
+        assert!(ms.is_pay_date(date(2028, 2, 29)));
+    }
+
+    #[test]
+    fn test_pay_period() {
+        let ms = MonthlyOnSchedule::new(25);
+        assert_eq!(
+            ms.get_pay_period(date(2025, 1, 25)),
+            date(2024, 12, 26)..=date(2025, 1, 25)
+        );
+        // 支払日でなければ直近の支払日の翌日から
+        assert_eq!(
+            ms.get_pay_period(date(2025, 1, 31)),
+            date(2025, 1, 26)..=date(2025, 1, 31)
+        );
+
+        let ms = MonthlyOnSchedule::new(30);
+        assert_eq!(
+            ms.get_pay_period(date(2025, 2, 28)),
+            date(2025, 1, 31)..=date(2025, 2, 28)
+        );
+        assert_eq!(
+            ms.get_pay_period(date(2025, 3, 30)),
+            date(2025, 3, 1)..=date(2025, 3, 30)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_day() {
+        MonthlyOnSchedule::new(32);
+    }
+}
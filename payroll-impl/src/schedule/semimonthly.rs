@@ -0,0 +1,62 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{any::Any, ops::RangeInclusive};
+
+use crate::MonthlySchedule;
+use payroll_domain::{PaymentSchedule, ScheduleInfo};
+
+// 15 日と月末に支払う (支払い期間は 1 日から 15 日までと 16 日から月末まで)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SemimonthlySchedule;
+impl PaymentSchedule for SemimonthlySchedule {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn is_pay_date(&self, date: NaiveDate) -> bool {
+        date.day() == 15 || MonthlySchedule.is_last_day_of_month(date)
+    }
+    fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate> {
+        let first_day = if pay_date.day() <= 15 { 1 } else { 16 };
+        pay_date.with_day(first_day).unwrap()..=pay_date
+    }
+    fn info(&self) -> ScheduleInfo {
+        ScheduleInfo::Semimonthly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_is_pay_date() {
+        let ss = SemimonthlySchedule;
+        assert!(ss.is_pay_date(date(2025, 1, 15)));
+        assert!(ss.is_pay_date(date(2025, 1, 31)));
+        assert!(!ss.is_pay_date(date(2025, 1, 16)));
+        assert!(!ss.is_pay_date(date(2025, 1, 30)));
+        assert!(ss.is_pay_date(date(2025, 2, 28)));
+        assert!(!ss.is_pay_date(date(2028, 2, 28)));
+        assert!(ss.is_pay_date(date(2028, 2, 29)));
+    }
+
+    #[test]
+    fn test_pay_period() {
+        let ss = SemimonthlySchedule;
+        assert_eq!(
+            ss.get_pay_period(date(2025, 1, 15)),
+            date(2025, 1, 1)..=date(2025, 1, 15)
+        );
+        assert_eq!(
+            ss.get_pay_period(date(2025, 2, 28)),
+            date(2025, 2, 16)..=date(2025, 2, 28)
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
 use std::{any::Any, ops::RangeInclusive};
 
-use payroll_domain::PaymentSchedule;
+use payroll_domain::{PaymentSchedule, ScheduleInfo};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WeeklySchedule;
 impl PaymentSchedule for WeeklySchedule {
     fn as_any(&self) -> &dyn Any {
@@ -18,6 +19,9 @@ impl PaymentSchedule for WeeklySchedule {
     fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate> {
         pay_date.checked_sub_days(Days::new(6)).unwrap()..=pay_date
     }
+    fn info(&self) -> ScheduleInfo {
+        ScheduleInfo::Weekly
+    }
 }
 
 #[cfg(test)]
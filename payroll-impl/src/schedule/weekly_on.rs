@@ -0,0 +1,64 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{any::Any, ops::RangeInclusive};
+
+use payroll_domain::{PaymentSchedule, ScheduleInfo};
+
+// 毎週決まった曜日に支払う
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyOnSchedule {
+    weekday: Weekday,
+}
+impl WeeklyOnSchedule {
+    pub fn new(weekday: Weekday) -> Self {
+        Self { weekday }
+    }
+    pub fn weekday(&self) -> Weekday {
+        self.weekday
+    }
+}
+impl PaymentSchedule for WeeklyOnSchedule {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn is_pay_date(&self, date: NaiveDate) -> bool {
+        date.weekday() == self.weekday
+    }
+    fn get_pay_period(&self, pay_date: NaiveDate) -> RangeInclusive<NaiveDate> {
+        pay_date.checked_sub_days(Days::new(6)).unwrap()..=pay_date
+    }
+    fn info(&self) -> ScheduleInfo {
+        ScheduleInfo::WeeklyOn {
+            weekday: self.weekday,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_is_pay_date() {
+        let ws = WeeklyOnSchedule::new(Weekday::Mon);
+        assert!(ws.is_pay_date(date(2025, 1, 6)));
+        assert!(!ws.is_pay_date(date(2025, 1, 10)));
+        assert!(ws.is_pay_date(date(2025, 1, 13)));
+    }
+
+    #[test]
+    fn test_pay_period() {
+        let ws = WeeklyOnSchedule::new(Weekday::Mon);
+        assert_eq!(
+            ws.get_pay_period(date(2025, 1, 6)),
+            date(2024, 12, 31)..=date(2025, 1, 6)
+        );
+    }
+}
@@ -0,0 +1,278 @@
+// 設定ファイルで与えた税率表に従って源泉徴収する
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, io, path::Path};
+use thiserror::Error;
+
+use payroll_domain::{
+    DeductionKind, DeductionLine, FilingStatus, Money, Paycheck, TaxProfile, Withholding,
+};
+
+#[derive(Debug, Error)]
+pub enum WithholdingConfigError {
+    #[error("failed to read withholding config: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to parse withholding config: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("invalid withholding config: {0}")]
+    Invalid(String),
+}
+
+// 累進税率表の 1 段: 年額の課税所得のうち over を超える部分に rate を掛ける
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bracket {
+    pub over: Money,
+    pub rate: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WithholdingRule {
+    // 総支給額を年額に換算し、控除の数 x allowance を差し引いてから申告区分ごとの累進税率表を適用する
+    Bracket {
+        name: String,
+        allowance: Money,
+        tables: HashMap<FilingStatus, Vec<Bracket>>,
+    },
+    // 総支給額に一定率を掛ける (社会保険料や年金など)
+    Flat {
+        name: String,
+        rate: f32,
+    },
+}
+impl WithholdingRule {
+    fn name(&self) -> &str {
+        match self {
+            Self::Bracket { name, .. } | Self::Flat { name, .. } => name,
+        }
+    }
+    fn validate(&self) -> Result<(), WithholdingConfigError> {
+        let invalid = |msg: String| Err(WithholdingConfigError::Invalid(msg));
+        let valid_rate = |rate: f32| (0.0..=1.0).contains(&rate);
+        match self {
+            Self::Bracket {
+                name,
+                allowance,
+                tables,
+            } => {
+                if allowance.is_negative() {
+                    return invalid(format!("{}: negative allowance {}", name, allowance));
+                }
+                for status in FilingStatus::ALL {
+                    let Some(brackets) = tables.get(&status).filter(|bs| !bs.is_empty()) else {
+                        return invalid(format!("{}: no brackets for {}", name, status));
+                    };
+                    if !brackets.windows(2).all(|w| w[0].over < w[1].over) {
+                        return invalid(format!("{}: brackets for {} not ascending", name, status));
+                    }
+                    if let Some(b) = brackets.iter().find(|b| !valid_rate(b.rate)) {
+                        return invalid(format!("{}: invalid rate {}", name, b.rate));
+                    }
+                }
+                Ok(())
+            }
+            Self::Flat { name, rate } if !valid_rate(*rate) => {
+                invalid(format!("{}: invalid rate {}", name, rate))
+            }
+            Self::Flat { .. } => Ok(()),
+        }
+    }
+    fn calculate(&self, profile: &TaxProfile, pays_per_year: u32, gross_pay: Money) -> Money {
+        match self {
+            Self::Bracket {
+                allowance, tables, ..
+            } => {
+                let pays_per_year = i64::from(pays_per_year);
+                let exemption = *allowance * i64::from(profile.allowances);
+                let taxable = (gross_pay * pays_per_year - exemption).max(Money::ZERO);
+                debug!("calculate: annual taxable={}", taxable);
+                let brackets = &tables[&profile.filing_status];
+                let annual_tax = brackets
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| taxable > b.over)
+                    .map(|(i, b)| {
+                        let upper = brackets.get(i + 1).map_or(taxable, |next| next.over);
+                        (taxable.min(upper) - b.over) * b.rate
+                    })
+                    .sum::<Money>();
+                debug!("calculate: annual tax={}", annual_tax);
+                annual_tax / pays_per_year
+            }
+            Self::Flat { rate, .. } => gross_pay * *rate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableWithholding {
+    rules: Vec<WithholdingRule>,
+}
+impl TableWithholding {
+    pub fn new(rules: Vec<WithholdingRule>) -> Result<Self, WithholdingConfigError> {
+        rules.iter().try_for_each(WithholdingRule::validate)?;
+        Ok(Self { rules })
+    }
+    pub fn from_json(json: &str) -> Result<Self, WithholdingConfigError> {
+        let Self { rules } = serde_json::from_str(json)?;
+        Self::new(rules)
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, WithholdingConfigError> {
+        trace!("load called");
+        debug!("load: withholding config={}", path.as_ref().display());
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+impl Withholding for TableWithholding {
+    fn withhold(
+        &self,
+        profile: &TaxProfile,
+        pays_per_year: u32,
+        pc: &Paycheck,
+    ) -> Vec<DeductionLine> {
+        trace!("withhold called");
+        self.rules
+            .iter()
+            .map(|rule| {
+                let amount = rule.calculate(profile, pays_per_year, pc.gross_pay());
+                DeductionLine::new(DeductionKind::Withholding(rule.name().to_string()), amount)
+            })
+            .filter(|line| !line.amount.is_zero())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::sync::{Arc, Mutex};
+
+    use payroll_domain::{
+        BusinessCalendar, EarningKind, EarningLine, Employee, NoAffiliation, OvertimePolicy,
+        YearToDate,
+    };
+
+    use crate::{money, HoldMethod, MonthlySchedule, SalariedClassification, UnionAffiliation};
+
+    fn tax(name: &str, amount: &str) -> DeductionLine {
+        DeductionLine::new(DeductionKind::Withholding(name.to_string()), money(amount))
+    }
+
+    fn paycheck(gross_pay: Money) -> Paycheck {
+        let mut pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        pc.set_earning_lines(vec![EarningLine::new(EarningKind::Salary, gross_pay)]);
+        pc
+    }
+
+    fn engine() -> TableWithholding {
+        TableWithholding::from_json(
+            r#"{
+                "rules": [
+                    {"Bracket": {
+                        "name": "Income Tax",
+                        "allowance": "1200",
+                        "tables": {
+                            "Single": [{"over": "0", "rate": 0.1}, {"over": "12000", "rate": 0.2}],
+                            "Married": [{"over": "12000", "rate": 0.1}],
+                            "HeadOfHousehold": [{"over": "6000", "rate": 0.1}]
+                        }
+                    }},
+                    {"Flat": {"name": "Pension", "rate": 0.05}}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_withhold() {
+        let e = engine();
+        // 年額 24000 = 12000 * 10% + 12000 * 20% = 3600, 月額 300
+        let lines = e.withhold(&TaxProfile::default(), 12, &paycheck(money("2000")));
+        assert_eq!(
+            lines,
+            vec![tax("Income Tax", "300"), tax("Pension", "100"),]
+        );
+
+        // 控除 5 つで課税所得 18000 = 1200 + 6000 * 20% = 2400, 月額 200
+        let profile = TaxProfile::new(FilingStatus::Single, 5);
+        let lines = e.withhold(&profile, 12, &paycheck(money("2000")));
+        assert_eq!(lines[0], tax("Income Tax", "200"));
+
+        // 12000 以下は課税されないので、その行は出さない
+        let profile = TaxProfile::new(FilingStatus::Married, 0);
+        let lines = e.withhold(&profile, 12, &paycheck(money("1000")));
+        assert_eq!(lines, vec![tax("Pension", "50")]);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let missing = r#"{"rules": [{"Bracket": {"name": "Tax", "allowance": "0",
+            "tables": {"Single": [{"over": "0", "rate": 0.1}]}}}]}"#;
+        assert!(matches!(
+            TableWithholding::from_json(missing),
+            Err(WithholdingConfigError::Invalid(_))
+        ));
+        let rate = r#"{"rules": [{"Flat": {"name": "Pension", "rate": 1.5}}]}"#;
+        assert!(matches!(
+            TableWithholding::from_json(rate),
+            Err(WithholdingConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            TableWithholding::from_json("{"),
+            Err(WithholdingConfigError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_sample_config() {
+        let e = TableWithholding::from_json(include_str!("../../config/withholding.json"));
+        assert!(e.is_ok(), "{:?}", e);
+    }
+
+    #[test]
+    fn test_payday_with_withholding() {
+        let mut emp = Employee::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Arc::new(Mutex::new(SalariedClassification::new(money("2000")))),
+            Arc::new(Mutex::new(MonthlySchedule)),
+            Arc::new(Mutex::new(HoldMethod)),
+            Arc::new(Mutex::new(NoAffiliation)),
+        );
+        let mut union = UnionAffiliation::new(7234.into(), money("9.45"));
+        union.add_service_charge(NaiveDate::from_ymd_opt(2025, 1, 10).unwrap(), money("10"));
+        emp.set_affiliation(Arc::new(Mutex::new(union)));
+
+        let mut pc = Paycheck::new(
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                ..=NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        );
+        let record = emp.payday(
+            &mut pc,
+            &engine(),
+            &OvertimePolicy::default(),
+            &BusinessCalendar::none(),
+            &mut YearToDate::new(2025),
+        );
+
+        // 2025 年 1 月の金曜日は 5 回
+        assert_eq!(
+            pc.deduction_lines(),
+            &[
+                DeductionLine::new(DeductionKind::Dues, money("47.25")),
+                DeductionLine::new(DeductionKind::ServiceCharge, money("10")),
+                tax("Income Tax", "300"),
+                tax("Pension", "100"),
+            ]
+        );
+        assert_eq!(pc.deductions(), money("457.25"));
+        assert_eq!(pc.net_pay(), money("1542.75"));
+        assert_eq!(record.net_pay, pc.net_pay());
+        assert_eq!(record.year_to_date.unwrap().net_pay, pc.net_pay());
+    }
+}
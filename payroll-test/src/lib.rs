@@ -131,7 +131,7 @@ impl TestRunner {
             return TestResult::Fail;
         }
 
-        let text = fs::read_to_string(&fp).expect("read script file");
+        let text = fs::read_to_string(fp).expect("read script file");
 
         let mut result = TestResult::Pass;
 
@@ -142,10 +142,10 @@ impl TestRunner {
             self.try_consume();
 
             match parser::tx_type(line) {
-                TxType::Payday => {
-                    trace!("Payday command");
+                TxType::Payday | TxType::OffCycle => {
+                    trace!("Payday or off-cycle command");
                     self.send(line);
-                    //  In case of Payday, collect outputs of Paycheck JSON data.
+                    //  In case of Payday or off-cycle runs, collect outputs of Paycheck JSON data.
                     self.try_collect_paychecks();
                 }
                 TxType::Verify => {
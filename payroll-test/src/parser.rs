@@ -1,11 +1,14 @@
 use log::{debug, trace};
-use parsec_rs::{float32, keyword, spaces, uint32, Parser};
+use parsec_rs::{char, keyword, pred, spaces, string, uint32, Parser};
 
-use crate::Verifier;
+use crate::{tester::LineKind, Verifier};
+use payroll_domain::{DeductionKind, EarningKind, Money};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TxType {
     Payday,
+    // 退職・賞与・精算は定期外の Paycheck を支払うので Payday と同じように集める
+    OffCycle,
     Verify,
     Other,
 }
@@ -15,6 +18,8 @@ pub fn tx_type(line: &str) -> TxType {
         TxType::Verify
     } else if is_payday(line) {
         TxType::Payday
+    } else if is_off_cycle(line) {
+        TxType::OffCycle
     } else {
         TxType::Other
     }
@@ -30,6 +35,8 @@ mod tx_type_test {
             TxType::Verify
         );
         assert_eq!(tx_type("Payday"), TxType::Payday);
+        assert_eq!(tx_type("Terminate 1 2025-02-14"), TxType::OffCycle);
+        assert_eq!(tx_type("Bonus 1 2025-03-14 500.00"), TxType::OffCycle);
         assert_eq!(tx_type("Some other line"), TxType::Other);
     }
 }
@@ -66,6 +73,27 @@ mod is_payday_test {
     }
 }
 
+pub fn is_off_cycle(line: &str) -> bool {
+    trace!("is_off_cycle called");
+    let command = keyword("Terminate")
+        .or(keyword("Bonus"))
+        .or(keyword("Correction"));
+    spaces().skip(command).parse(line).is_ok()
+}
+#[cfg(test)]
+mod is_off_cycle_test {
+    use super::*;
+
+    #[test]
+    fn test_is_off_cycle() {
+        assert!(is_off_cycle("Terminate 1 2025-02-14"));
+        assert!(is_off_cycle("Bonus 1 2025-03-14 500.00"));
+        assert!(is_off_cycle("Correction 1 2025-03-14 -25.50"));
+        assert!(!is_off_cycle("Payday"));
+        assert!(!is_off_cycle("Some other line"));
+    }
+}
+
 pub fn read_verify(line_num: usize, line: &str) -> Result<Verifier, String> {
     trace!("read_verify called");
     verify(line_num, line.to_string())
@@ -95,7 +123,7 @@ mod read_verify_test {
             verify,
             Verifier::GrossPay {
                 emp_id: 123,
-                gross_pay: 1000.0,
+                gross_pay: Money::from_cents(100000),
                 loc: (1, line.to_string())
             }
         );
@@ -136,8 +164,29 @@ mod employee_id_test {
     }
 }
 
-fn amount() -> impl Parser<Item = f32> {
-    float32().with(spaces()).label("<amount>".into())
+// 差し戻しや払い戻しの明細は負の額になる
+fn amount() -> impl Parser<Item = Money> {
+    let unsigned = || {
+        let digit = || pred(|c: char| c.is_ascii_digit());
+        let int = digit().many1();
+        let frac = char('.').skip(digit().many0()).or(digit().many0());
+
+        int.join(frac).map(|(int, frac)| {
+            let s = format!(
+                "{}.{}",
+                int.into_iter().collect::<String>(),
+                frac.into_iter().collect::<String>()
+            );
+            s.parse::<Money>().expect("amount")
+        })
+    };
+
+    char('-')
+        .skip(unsigned())
+        .map(|m| -m)
+        .or(unsigned())
+        .with(spaces())
+        .label("<amount>".into())
 }
 #[cfg(test)]
 mod amount_test {
@@ -149,7 +198,10 @@ mod amount_test {
         let result = amount().parse(line);
         assert!(result.is_ok());
         let (amt, _) = result.unwrap();
-        assert_eq!(amt, 1000.0);
+        assert_eq!(amt, Money::from_cents(100000));
+
+        let (amt, _) = amount().parse("-12.50").unwrap();
+        assert_eq!(amt, Money::from_cents(-1250));
     }
 
     #[test]
@@ -160,14 +212,94 @@ mod amount_test {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum Field {
+    GrossPay,
+    Deductions,
+    NetPay,
+    Line(LineKind),
+}
+
+fn line_kind() -> impl Parser<Item = LineKind> {
+    // Unitemized は移行前の記録にしか現れないので Payday の検証には使わない
+    let earning = |name: &'static str, kind: EarningKind| {
+        keyword(name)
+            .label(name.into())
+            .map(move |_| LineKind::Earning(kind))
+    };
+    let deduction = |name: &'static str, kind: DeductionKind| {
+        keyword(name)
+            .label(name.into())
+            .map(move |_| LineKind::Deduction(kind))
+    };
+    // 源泉徴収はルール名を文字列で書く: Withholding "Income Tax"
+    let withholding = keyword("Withholding")
+        .with(spaces())
+        .skip(string())
+        .label("Withholding".into())
+        .map(|name| LineKind::Deduction(DeductionKind::Withholding(name)));
+
+    earning("Salary", EarningKind::Salary)
+        .or(earning("Regular", EarningKind::Regular))
+        .or(earning("Overtime", EarningKind::Overtime))
+        .or(earning("DoubleTime", EarningKind::DoubleTime))
+        .or(earning("Premium", EarningKind::Premium))
+        .or(earning("Commission", EarningKind::Commission))
+        .or(earning("Clawback", EarningKind::Clawback))
+        .or(earning("RetroPay", EarningKind::RetroPay))
+        .or(earning("Bonus", EarningKind::Bonus))
+        .or(earning("Adjustment", EarningKind::Adjustment))
+        .or(deduction("Dues", DeductionKind::Dues))
+        // ServiceCharge が前方一致するので先に試す
+        .or(deduction(
+            "ServiceChargeRefund",
+            DeductionKind::ServiceChargeRefund,
+        ))
+        .or(deduction("ServiceCharge", DeductionKind::ServiceCharge))
+        .or(withholding)
+}
+#[cfg(test)]
+mod line_kind_test {
+    use super::*;
+
+    #[test]
+    fn test_line_kind() {
+        let (kind, _) = line_kind().parse("Overtime").unwrap();
+        assert_eq!(kind, LineKind::Earning(EarningKind::Overtime));
+        let (kind, _) = line_kind().parse("ServiceCharge").unwrap();
+        assert_eq!(kind, LineKind::Deduction(DeductionKind::ServiceCharge));
+        let (kind, _) = line_kind().parse("ServiceChargeRefund").unwrap();
+        assert_eq!(
+            kind,
+            LineKind::Deduction(DeductionKind::ServiceChargeRefund)
+        );
+        let (kind, _) = line_kind().parse(r#"Withholding "Income Tax""#).unwrap();
+        assert_eq!(
+            kind,
+            LineKind::Deduction(DeductionKind::Withholding("Income Tax".into()))
+        );
+    }
+
+    #[test]
+    fn test_line_kind_invalid() {
+        assert!(line_kind().parse("Tips").is_err());
+    }
+}
+
 fn verify(line_num: usize, line: String) -> impl Parser<Item = Verifier> {
     let verify = keyword("Verify").with(spaces()).label("Verify".into());
     let paycheck = keyword("Paycheck").with(spaces()).label("Paycheck".into());
     let empid = keyword("EmpId").with(spaces()).label("EmpId".into());
     let field = keyword("GrossPay")
         .label("GrossPay".into())
-        .or(keyword("Deductions").label("Deductions".into()))
-        .or(keyword("NetPay").label("NetPay".into()));
+        .map(|_| Field::GrossPay)
+        .or(keyword("Deductions")
+            .label("Deductions".into())
+            .map(|_| Field::Deductions))
+        .or(keyword("NetPay")
+            .label("NetPay".into())
+            .map(|_| Field::NetPay))
+        .or(line_kind().map(Field::Line));
 
     verify
         .skip(paycheck)
@@ -176,24 +308,29 @@ fn verify(line_num: usize, line: String) -> impl Parser<Item = Verifier> {
         .join(field.with(spaces()))
         .join(amount())
         .map(move |((emp_id, key), amount)| {
-            debug!("key: {}, emp_id: {}, amount: {}", key, emp_id, amount);
+            debug!("key: {:?}, emp_id: {}, amount: {}", key, emp_id, amount);
             match key {
-                "GrossPay" => Verifier::GrossPay {
+                Field::GrossPay => Verifier::GrossPay {
                     emp_id,
                     gross_pay: amount,
                     loc: (line_num, line),
                 },
-                "Deductions" => Verifier::Deductions {
+                Field::Deductions => Verifier::Deductions {
                     emp_id,
                     deductions: amount,
                     loc: (line_num, line),
                 },
-                "NetPay" => Verifier::NetPay {
+                Field::NetPay => Verifier::NetPay {
                     emp_id,
                     net_pay: amount,
                     loc: (line_num, line),
                 },
-                _ => panic!("unexpected key: {}", key),
+                Field::Line(kind) => Verifier::Line {
+                    emp_id,
+                    kind,
+                    amount,
+                    loc: (line_num, line),
+                },
             }
         })
 }
@@ -211,7 +348,24 @@ mod verify_test {
             verify,
             Verifier::GrossPay {
                 emp_id: 123,
-                gross_pay: 1000.0,
+                gross_pay: Money::from_cents(100000),
+                loc: (1, line.to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_verify_line() {
+        let line = "Verify Paycheck EmpId 57 Overtime 52.50";
+        let result = verify(1, line.to_string()).parse(line);
+        assert!(result.is_ok());
+        let (verify, _) = result.unwrap();
+        assert_eq!(
+            verify,
+            Verifier::Line {
+                emp_id: 57,
+                kind: LineKind::Earning(EarningKind::Overtime),
+                amount: Money::from_cents(5250),
                 loc: (1, line.to_string())
             }
         );
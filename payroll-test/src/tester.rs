@@ -3,31 +3,68 @@ use log::{debug, trace};
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use payroll_domain::{DeductionKind, DeductionLine, EarningKind, EarningLine, Money};
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Paycheck {
     pub emp_id: u32,
-    pub gross_pay: f32,
-    pub deductions: f32,
-    pub net_pay: f32,
+    pub gross_pay: Money,
+    pub deductions: Money,
+    pub net_pay: Money,
+    #[serde(default)]
+    pub earning_lines: Vec<EarningLine>,
+    #[serde(default)]
+    pub deduction_lines: Vec<DeductionLine>,
+}
+impl Paycheck {
+    // 同じ種類の明細が複数あれば合計する (サービス料など)
+    fn line_amount(&self, kind: &LineKind) -> Money {
+        match kind {
+            LineKind::Earning(kind) => self
+                .earning_lines
+                .iter()
+                .filter(|line| line.kind == *kind)
+                .map(|line| line.amount)
+                .sum(),
+            LineKind::Deduction(kind) => self
+                .deduction_lines
+                .iter()
+                .filter(|line| line.kind == *kind)
+                .map(|line| line.amount)
+                .sum(),
+        }
+    }
 }
 
 type Location = (usize, String);
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineKind {
+    Earning(EarningKind),
+    Deduction(DeductionKind),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Verifier {
     GrossPay {
         emp_id: u32,
-        gross_pay: f32,
+        gross_pay: Money,
         loc: Location,
     },
     Deductions {
         emp_id: u32,
-        deductions: f32,
+        deductions: Money,
         loc: Location,
     },
     NetPay {
         emp_id: u32,
-        net_pay: f32,
+        net_pay: Money,
+        loc: Location,
+    },
+    Line {
+        emp_id: u32,
+        kind: LineKind,
+        amount: Money,
         loc: Location,
     },
 }
@@ -42,6 +79,7 @@ impl Verifier {
             Verifier::GrossPay { emp_id, .. } => *emp_id,
             Verifier::Deductions { emp_id, .. } => *emp_id,
             Verifier::NetPay { emp_id, .. } => *emp_id,
+            Verifier::Line { emp_id, .. } => *emp_id,
         }
     }
     fn line_num(&self) -> usize {
@@ -49,6 +87,7 @@ impl Verifier {
             Verifier::GrossPay { loc, .. } => loc.0,
             Verifier::Deductions { loc, .. } => loc.0,
             Verifier::NetPay { loc, .. } => loc.0,
+            Verifier::Line { loc, .. } => loc.0,
         }
     }
     fn line(&self) -> &str {
@@ -56,6 +95,7 @@ impl Verifier {
             Verifier::GrossPay { loc, .. } => &loc.1,
             Verifier::Deductions { loc, .. } => &loc.1,
             Verifier::NetPay { loc, .. } => &loc.1,
+            Verifier::Line { loc, .. } => &loc.1,
         }
     }
     pub fn verify(&self, outputs: &HashMap<u32, Paycheck>) -> bool {
@@ -80,6 +120,15 @@ impl Verifier {
             Verifier::NetPay { net_pay, .. } => {
                 assert_eq!(actual.net_pay, *net_pay, "net_pay mismatch {}", info);
             }
+            Verifier::Line { kind, amount, .. } => {
+                assert_eq!(
+                    actual.line_amount(kind),
+                    *amount,
+                    "{:?} mismatch {}",
+                    kind,
+                    info
+                );
+            }
         }
         true
     }
@@ -93,18 +142,18 @@ mod tests {
     fn fail_for_empty_test() {
         let verify = Verifier::GrossPay {
             emp_id: 1234,
-            gross_pay: 1000.0,
+            gross_pay: Money::from_cents(100000),
             loc: (1, "L1".to_string()),
         };
         let outputs = HashMap::new();
-        assert_ne!(verify.verify(&outputs), false);
+        assert!(verify.verify(&outputs));
     }
 
     #[test]
     fn test_verify_gross_pay() {
         let verify = Verifier::GrossPay {
             emp_id: 1234,
-            gross_pay: 1000.0,
+            gross_pay: Money::from_cents(100000),
             loc: (1, "L1".to_string()),
         };
         let mut outputs = HashMap::new();
@@ -112,19 +161,21 @@ mod tests {
             1234,
             Paycheck {
                 emp_id: 1234,
-                gross_pay: 1000.0,
-                deductions: 200.0,
-                net_pay: 800.0,
+                gross_pay: Money::from_cents(100000),
+                deductions: Money::from_cents(20000),
+                net_pay: Money::from_cents(80000),
+                earning_lines: vec![],
+                deduction_lines: vec![],
             },
         );
-        assert_eq!(verify.verify(&outputs), true);
+        assert!(verify.verify(&outputs));
     }
 
     #[test]
     fn test_verify_deductions() {
         let verify = Verifier::Deductions {
             emp_id: 1234,
-            deductions: 200.0,
+            deductions: Money::from_cents(20000),
             loc: (1, "L1".to_string()),
         };
         let mut outputs = HashMap::new();
@@ -132,19 +183,21 @@ mod tests {
             1234,
             Paycheck {
                 emp_id: 1234,
-                gross_pay: 1000.0,
-                deductions: 200.0,
-                net_pay: 800.0,
+                gross_pay: Money::from_cents(100000),
+                deductions: Money::from_cents(20000),
+                net_pay: Money::from_cents(80000),
+                earning_lines: vec![],
+                deduction_lines: vec![],
             },
         );
-        assert_eq!(verify.verify(&outputs), true);
+        assert!(verify.verify(&outputs));
     }
 
     #[test]
     fn test_verify_net_pay() {
         let verify = Verifier::NetPay {
             emp_id: 1234,
-            net_pay: 800.0,
+            net_pay: Money::from_cents(80000),
             loc: (1, "L1".to_string()),
         };
         let mut outputs = HashMap::new();
@@ -152,11 +205,45 @@ mod tests {
             1234,
             Paycheck {
                 emp_id: 1234,
-                gross_pay: 1000.0,
-                deductions: 200.0,
-                net_pay: 800.0,
+                gross_pay: Money::from_cents(100000),
+                deductions: Money::from_cents(20000),
+                net_pay: Money::from_cents(80000),
+                earning_lines: vec![],
+                deduction_lines: vec![],
             },
         );
-        assert_eq!(verify.verify(&outputs), true);
+        assert!(verify.verify(&outputs));
+    }
+
+    #[test]
+    fn test_verify_line() {
+        let mut outputs = HashMap::new();
+        outputs.insert(
+            1234,
+            Paycheck {
+                emp_id: 1234,
+                gross_pay: Money::from_cents(37250),
+                deductions: Money::from_cents(3000),
+                net_pay: Money::from_cents(34250),
+                earning_lines: vec![
+                    EarningLine::new(EarningKind::Regular, Money::from_cents(32000)),
+                    EarningLine::new(EarningKind::Overtime, Money::from_cents(5250)),
+                ],
+                deduction_lines: vec![
+                    DeductionLine::new(DeductionKind::ServiceCharge, Money::from_cents(1000)),
+                    DeductionLine::new(DeductionKind::ServiceCharge, Money::from_cents(2000)),
+                ],
+            },
+        );
+        let verify = |kind, cents| Verifier::Line {
+            emp_id: 1234,
+            kind,
+            amount: Money::from_cents(cents),
+            loc: (1, "L1".to_string()),
+        };
+        assert!(verify(LineKind::Earning(EarningKind::Overtime), 5250).verify(&outputs));
+        assert!(verify(LineKind::Deduction(DeductionKind::ServiceCharge), 3000).verify(&outputs));
+        // 明細がなければ 0
+        assert!(verify(LineKind::Earning(EarningKind::Commission), 0).verify(&outputs));
     }
 }
@@ -0,0 +1,353 @@
+use log::{debug, error, trace};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::http::{Request, Status};
+use crate::route::{self, Route};
+use dao::EmployeeDao;
+use payroll_domain::{BusinessCalendar, DisbursementSink, OvertimePolicy, PayAmounts, Withholding};
+use payroll_impl::PayrollFactoryImpl;
+use tx_app::{Response, Runner, Tx};
+use tx_app_impl::runner_impl;
+use tx_impl::TxFactoryImpl;
+
+// リクエストを受けてトランザクションを実行し、JSON で結果を返す
+#[derive(Debug, Clone)]
+pub struct Api<T> {
+    db: T,
+    sink: Arc<dyn DisbursementSink>,
+    withholding: Arc<dyn Withholding>,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
+
+    quiet: bool,
+    chronograph: bool,
+}
+impl<T> Api<T>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    pub fn new(
+        db: T,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+        quiet: bool,
+        chronograph: bool,
+    ) -> Self {
+        Self {
+            db,
+            sink,
+            withholding,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
+            quiet,
+            chronograph,
+        }
+    }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
+
+    pub fn handle(&self, req: &Request) -> (Status, Value) {
+        trace!("handle called");
+        let route = match route::route(req) {
+            Ok(route) => route,
+            Err(e) => {
+                debug!("failed to route: {}", e);
+                return (e.status, e.to_json());
+            }
+        };
+
+        let runner = self.make_tx_runner();
+        match route {
+            Route::Single(tx, status) => match self.run(runner.as_ref(), tx) {
+                Ok(res) => (status, response_to_json(res)),
+                Err(e) => (e.status, e.to_json()),
+            },
+            Route::Script(txs) => {
+                // 失敗したところで打ち切り、それまでの結果と失敗の理由を返す
+                let mut status = Status::Ok;
+                let mut results = vec![];
+                for tx in txs {
+                    match self.run(runner.as_ref(), tx) {
+                        Ok(res) => results.push(json!({ "ok": response_to_json(res) })),
+                        Err(e) => {
+                            status = e.status;
+                            results.push(e.to_json());
+                            break;
+                        }
+                    }
+                }
+                (status, json!({ "results": results }))
+            }
+        }
+    }
+
+    fn run(&self, runner: &dyn Runner, tx: Tx) -> Result<Response, ApiError> {
+        trace!("run called");
+        let tx_factory = TxFactoryImpl::new(
+            self.db.clone(),
+            PayrollFactoryImpl,
+            self.sink.clone(),
+            self.withholding.clone(),
+            self.overtime.clone(),
+        )
+        .with_calendar(self.calendar.clone());
+        runner
+            .run(tx_factory::dispatch(&tx_factory, tx))
+            .map_err(|e| {
+                error!("transaction failed: {}", e);
+                ApiError::from(&e)
+            })
+    }
+
+    fn make_tx_runner(&self) -> Box<dyn Runner> {
+        trace!("make_tx_runner called");
+
+        let mut tx_runner = if self.quiet {
+            debug!("Quiet mode enabled");
+            runner_impl::silent_runner()
+        } else {
+            debug!("Echoback mode enabled");
+            runner_impl::echoback_runner()
+        };
+        if self.chronograph {
+            debug!("Chronograph mode enabled");
+            tx_runner = runner_impl::with_chronograph(tx_runner);
+        };
+
+        tx_runner
+    }
+}
+
+fn response_to_json(res: Response) -> Value {
+    match res {
+        Response::Void => Value::Null,
+        Response::EmployeeId(id) => json!({ "id": id }),
+        Response::Verified(verified) => json!({ "verified": verified }),
+        Response::Paycheck(pc) => json!({
+            "period_start": pc.get_pay_period().start(),
+            "pay_date": pc.pay_date(),
+            "run_type": pc.run_type(),
+            "gross_pay": pc.gross_pay(),
+            "deductions": pc.deductions(),
+            "net_pay": pc.net_pay(),
+            "earning_lines": pc.earning_lines(),
+            "deduction_lines": pc.deduction_lines(),
+            "year_to_date": pc.year_to_date(),
+        }),
+        Response::YearToDate(ytd) => json!({
+            "year": ytd.year,
+            "gross_pay": ytd.gross_pay,
+            "deductions": ytd.total_deductions(),
+            "net_pay": ytd.net_pay,
+            "deduction_lines": ytd.deductions,
+        }),
+        Response::Payday {
+            paid,
+            skipped,
+            replayed,
+            records,
+        } => json!({
+            "paid": paid,
+            "skipped": skipped,
+            "replayed": replayed,
+            "records": records,
+        }),
+        Response::PaydayPreview(preview) => json!({
+            "pay_date": preview.pay_date,
+            "previews": preview.previews.iter().map(|p| json!({
+                "emp_id": p.emp_id,
+                "name": p.name,
+                "amounts": amounts_to_json(p.amounts),
+                "last": p.last.as_ref().map(|last| json!({
+                    "pay_date": last.pay_date,
+                    "amounts": amounts_to_json(last.amounts),
+                })),
+                "difference": p.difference().map(amounts_to_json),
+            })).collect::<Vec<_>>(),
+            "skipped": preview.skipped,
+            "total": amounts_to_json(preview.total()),
+        }),
+        Response::Employee(emp) => json!(emp),
+        Response::Employees(emps) => json!(emps),
+        Response::UnionMember {
+            member_id,
+            employee,
+        } => json!({
+            "member_id": member_id,
+            "employee": employee,
+        }),
+    }
+}
+
+fn amounts_to_json(amounts: PayAmounts) -> Value {
+    json!({
+        "gross_pay": amounts.gross_pay,
+        "deductions": amounts.deductions,
+        "net_pay": amounts.net_pay,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hs_db::HashDB;
+    use payroll_domain::NoWithholding;
+    use payroll_impl::MemorySink;
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: None,
+            headers: vec![],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_employee_lifecycle() {
+        let sink = MemorySink::new();
+        let api = Api::new(
+            HashDB::new(),
+            Arc::new(sink.clone()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+            true,
+            false,
+        );
+
+        let (status, body) = api.handle(&request(
+            "POST",
+            "/employees",
+            r#"{"id": 1, "name": "Bob", "address": "Home", "classification": {"Salaried": {"salary": "3215.88"}}}"#,
+        ));
+        assert_eq!(status, Status::Created);
+        assert_eq!(body, json!({ "id": 1 }));
+
+        let (status, body) = api.handle(&request("GET", "/employees/1", ""));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body["name"], "Bob");
+        assert_eq!(
+            body["classification"],
+            json!({ "Salaried": { "salary": "3215.88" } })
+        );
+        assert_eq!(body["affiliation"], "None");
+
+        // 試算は何も記録せず、送り出しもしない
+        let (status, body) = api.handle(&request("GET", "/payday/2025-01-31/preview", ""));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body["previews"][0]["emp_id"], 1);
+        assert_eq!(body["previews"][0]["amounts"]["net_pay"], "3215.88");
+        assert_eq!(body["previews"][0]["last"], Value::Null);
+        assert_eq!(body["total"]["gross_pay"], "3215.88");
+        assert!(sink.records().is_empty());
+        let (status, _) = api.handle(&request("GET", "/employees/1/paychecks/2025-01-31", ""));
+        assert_eq!(status, Status::NotFound);
+
+        let (status, body) = api.handle(&request("POST", "/payday/2025-01-31", ""));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body["paid"], json!([1]));
+        assert_eq!(
+            body["records"],
+            json!([{
+                "emp_id": 1,
+                "pay_date": "2025-01-31",
+                "run_type": "Regular",
+                "method": "Hold",
+                "gross_pay": "3215.88",
+                "deductions": "0.00",
+                "net_pay": "3215.88",
+                "earning_lines": [{ "kind": "Salary", "amount": "3215.88" }],
+                "deduction_lines": [],
+                "year_to_date": {
+                    "year": 2025,
+                    "gross_pay": "3215.88",
+                    "deductions": [],
+                    "net_pay": "3215.88",
+                },
+            }])
+        );
+        assert_eq!(sink.records().len(), 1);
+
+        let (status, body) = api.handle(&request("GET", "/employees/1/paychecks/2025-01-31", ""));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body["period_start"], "2025-01-01");
+        assert_eq!(body["run_type"], "Regular");
+        assert_eq!(body["net_pay"], "3215.88");
+        assert_eq!(
+            body["earning_lines"],
+            json!([{ "kind": "Salary", "amount": "3215.88" }])
+        );
+
+        let (status, body) = api.handle(&request("GET", "/employees/1/ytd/2025", ""));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(
+            body,
+            json!({
+                "year": 2025,
+                "gross_pay": "3215.88",
+                "deductions": "0.00",
+                "net_pay": "3215.88",
+                "deduction_lines": [],
+            })
+        );
+
+        // 次の支払日の試算は直近の支払いと比べる
+        let (status, body) = api.handle(&request("GET", "/payday/2025-02-28/preview", ""));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(
+            body["previews"][0]["last"],
+            json!({
+                "pay_date": "2025-01-31",
+                "amounts": {
+                    "gross_pay": "3215.88",
+                    "deductions": "0.00",
+                    "net_pay": "3215.88",
+                },
+            })
+        );
+        assert_eq!(body["previews"][0]["difference"]["net_pay"], "0.00");
+        assert_eq!(sink.records().len(), 1);
+
+        let (status, body) = api.handle(&request("POST", "/payday/2025-01-31", ""));
+        assert_eq!(status, Status::Conflict);
+        assert_eq!(body["error"]["kind"], "PaycheckAlreadyExists");
+
+        let (status, _) = api.handle(&request("DELETE", "/employees/1", ""));
+        assert_eq!(status, Status::Ok);
+        let (status, body) = api.handle(&request("GET", "/employees/1", ""));
+        assert_eq!(status, Status::NotFound);
+        assert_eq!(body["error"]["kind"], "EmployeeNotFound");
+    }
+
+    #[test]
+    fn test_script() {
+        let api = Api::new(
+            HashDB::new(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+            true,
+            false,
+        );
+
+        let (status, body) = api.handle(&request(
+            "POST",
+            "/",
+            "AddEmp 1 \"Bob\" \"Home\" H 15.75\nAddEmp 1 \"Bob\" \"Home\" H 15.75\nDelEmp 1\n",
+        ));
+        assert_eq!(status, Status::Conflict);
+        assert_eq!(body["results"][0], json!({ "ok": { "id": 1 } }));
+        assert_eq!(body["results"][1]["error"]["kind"], "EmployeeAlreadyExists");
+        // 失敗したところで止まるので DelEmp は実行されない
+        assert_eq!(body["results"].as_array().unwrap().len(), 2);
+        let (status, _) = api.handle(&request("GET", "/employees/1", ""));
+        assert_eq!(status, Status::Ok);
+    }
+}
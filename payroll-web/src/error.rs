@@ -0,0 +1,145 @@
+use log::debug;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::http::{HttpError, Status};
+use abstract_tx::UsecaseError;
+use dao::DaoError;
+
+// API の利用者に返すエラー
+// kind は機械的に判別するための名前で、message は人が読むための説明
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{kind}: {message}")]
+pub struct ApiError {
+    pub status: Status,
+    pub kind: &'static str,
+    pub message: String,
+}
+impl ApiError {
+    pub fn new(status: Status, kind: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            kind,
+            message: message.into(),
+        }
+    }
+    pub fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "kind": self.kind,
+                "message": self.message,
+            }
+        })
+    }
+}
+impl From<HttpError> for ApiError {
+    fn from(e: HttpError) -> Self {
+        match e {
+            HttpError::BadRequest(_) | HttpError::Io(_) => {
+                Self::new(Status::BadRequest, "BadRequest", e.to_string())
+            }
+            HttpError::PayloadTooLarge(_) => {
+                Self::new(Status::PayloadTooLarge, "PayloadTooLarge", e.to_string())
+            }
+        }
+    }
+}
+impl From<&anyhow::Error> for ApiError {
+    fn from(e: &anyhow::Error) -> Self {
+        match e.downcast_ref::<UsecaseError>() {
+            Some(usecase_error) => {
+                let (status, kind) = match (usecase_error, usecase_error.dao_error()) {
+                    (_, Some(dao_error)) => classify(dao_error),
+                    (UsecaseError::InvalidInput(_), None) => (Status::BadRequest, "InvalidInput"),
+                    (_, None) => (Status::InternalServerError, "DisbursementFailed"),
+                };
+                Self::new(status, kind, usecase_error.to_string())
+            }
+            None => {
+                debug!("not a usecase error: {}", e);
+                Self::new(Status::InternalServerError, "InternalError", e.to_string())
+            }
+        }
+    }
+}
+
+fn classify(e: &DaoError) -> (Status, &'static str) {
+    match e {
+        DaoError::EmployeeNotFound(_) => (Status::NotFound, "EmployeeNotFound"),
+        DaoError::MemberNotFound(_) => (Status::NotFound, "MemberNotFound"),
+        DaoError::PaycheckNotFound(..) => (Status::NotFound, "PaycheckNotFound"),
+        DaoError::TimeCardNotFound(..) => (Status::NotFound, "TimeCardNotFound"),
+        DaoError::SalesReceiptNotFound(..) => (Status::NotFound, "SalesReceiptNotFound"),
+        DaoError::ServiceChargeNotFound(..) => (Status::NotFound, "ServiceChargeNotFound"),
+        DaoError::EmployeeAlreadyExists(_) => (Status::Conflict, "EmployeeAlreadyExists"),
+        DaoError::MemberAlreadyExists(..) => (Status::Conflict, "MemberAlreadyExists"),
+        DaoError::PaycheckAlreadyExists(..) => (Status::Conflict, "PaycheckAlreadyExists"),
+        DaoError::TimeCardAlreadyExists(..) => (Status::Conflict, "TimeCardAlreadyExists"),
+        DaoError::DanglingUnionMember(..) => (Status::Conflict, "DanglingUnionMember"),
+        DaoError::InvalidEffectiveDate(..) => (Status::Conflict, "InvalidEffectiveDate"),
+        DaoError::EmployeeTerminated(_) => (Status::Conflict, "EmployeeTerminated"),
+        DaoError::UnexpectedError(_) => (Status::InternalServerError, "UnexpectedError"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use payroll_domain::{DisbursementError, InvalidInput};
+
+    #[test]
+    fn test_from_usecase_error() {
+        let e = anyhow::Error::from(UsecaseError::AddEmployeeFailed(
+            DaoError::EmployeeAlreadyExists(1.into()),
+        ));
+        let e = ApiError::from(&e);
+        assert_eq!(e.status, Status::Conflict);
+        assert_eq!(e.kind, "EmployeeAlreadyExists");
+
+        let e = anyhow::Error::from(UsecaseError::FetchEmployeeFailed(
+            DaoError::EmployeeNotFound(1.into()),
+        ));
+        assert_eq!(ApiError::from(&e).status, Status::NotFound);
+
+        let e = anyhow::Error::from(UsecaseError::ChangeEmployeeFailed(
+            DaoError::InvalidEffectiveDate(1.into(), NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()),
+        ));
+        let e = ApiError::from(&e);
+        assert_eq!(e.status, Status::Conflict);
+        assert_eq!(e.kind, "InvalidEffectiveDate");
+
+        let e = anyhow::Error::from(UsecaseError::TerminateEmployeeFailed(
+            DaoError::EmployeeTerminated(1.into()),
+        ));
+        let e = ApiError::from(&e);
+        assert_eq!(e.status, Status::Conflict);
+        assert_eq!(e.kind, "EmployeeTerminated");
+
+        let e = anyhow::Error::from(UsecaseError::DisbursementFailed(DisbursementError(
+            "broken pipe".to_string(),
+        )));
+        let e = ApiError::from(&e);
+        assert_eq!(e.status, Status::InternalServerError);
+        assert_eq!(e.kind, "DisbursementFailed");
+
+        let e = anyhow::Error::from(UsecaseError::InvalidInput(InvalidInput::new(
+            "invalid hours NaN",
+        )));
+        let e = ApiError::from(&e);
+        assert_eq!(e.status, Status::BadRequest);
+        assert_eq!(e.kind, "InvalidInput");
+
+        let e = anyhow::anyhow!("something wrong");
+        assert_eq!(ApiError::from(&e).status, Status::InternalServerError);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let e = ApiError::new(Status::BadRequest, "BadRequest", "oops");
+        assert_eq!(
+            e.to_json(),
+            json!({"error": {"kind": "BadRequest", "message": "oops"}})
+        );
+    }
+}
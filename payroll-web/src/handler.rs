@@ -1,56 +1,48 @@
 use log::{debug, error, trace};
-use std::{io::prelude::*, net::TcpStream, str, sync::Arc};
+use std::{io::BufReader, net::TcpStream, sync::Arc};
 
-use crate::tx_app_builder::TxAppBuilder;
+use crate::api::Api;
+use crate::error::ApiError;
+use crate::http;
+use dao::EmployeeDao;
 
 pub trait Handler {
     fn handle_connection(&self, stream: TcpStream);
 }
 
 #[derive(Debug, Clone)]
-pub struct TcpHandler {
-    builder: TxAppBuilder,
+pub struct TcpHandler<T> {
+    api: Api<T>,
 }
-impl TcpHandler {
-    pub fn new(builder: TxAppBuilder) -> Self {
-        Self { builder }
+impl<T> TcpHandler<T> {
+    pub fn new(api: Api<T>) -> Self {
+        Self { api }
     }
 }
-impl Handler for TcpHandler {
-    fn handle_connection(&self, mut stream: TcpStream) {
-        trace!("Handling connection from {}", stream.peer_addr().unwrap());
-        let mut buffer = [0; 1024];
-        stream.read(&mut buffer).expect("read from stream");
-        // TODO: check Content-Length header for larger requests
-        let text = match str::from_utf8(&buffer) {
-            Ok(v) => v.trim_end_matches('\0'),
-            Err(e) => {
-                error!("Invalid UTF-8 sequence: {}", e);
-                return;
-            }
-        };
-        let mut split = text.splitn(2, "\r\n\r\n");
-
-        let header = split.next().unwrap_or_default();
-        debug!("Received header:\n{}", header);
-        let body = split.next().unwrap_or_default();
-        debug!("Received body:\n{}", body);
-
-        let mut tx_app = self.builder.build(body);
-        let response = match tx_app.run() {
-            Ok(_) => {
-                trace!("Transaction app ran successfully");
-                "HTTP/1.1 200 OK\r\n\r\n"
+impl<T> Handler for TcpHandler<T>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn handle_connection(&self, stream: TcpStream) {
+        trace!("Handling connection from {:?}", stream.peer_addr());
+        let mut reader = BufReader::new(&stream);
+        let (status, body) = match http::read_request(&mut reader) {
+            Ok(req) => {
+                debug!("Received request: {} {}", req.method, req.path);
+                self.api.handle(&req)
             }
             Err(e) => {
-                error!("Error running transaction app: {}", e);
-                "HTTP/1.1 500 Server Error\r\n\r\n"
+                error!("Error reading request: {}", e);
+                let e = ApiError::from(e);
+                (e.status, e.to_json())
             }
         };
-        trace!("sent response: {}", response);
+        let body = body.to_string();
+        trace!("sending response: {} {}", status.code(), body);
 
-        stream.write(response.as_bytes()).expect("write to stream");
-        stream.flush().expect("flush stream");
+        if let Err(e) = http::write_response(&mut &stream, status, &body) {
+            error!("Error writing response: {}", e);
+        }
     }
 }
 
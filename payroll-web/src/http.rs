@@ -0,0 +1,332 @@
+// 最小限の HTTP/1.1 リクエストの読み取りとレスポンスの書き出し
+use log::{debug, trace};
+use std::io::{self, BufRead, Read, Write};
+use thiserror::Error;
+
+// これより大きなボディは受け付けない
+pub const MAX_BODY_SIZE: usize = 1024 * 1024;
+const MAX_HEADERS: usize = 100;
+// リクエスト行やヘッダ、チャンクサイズの 1 行の長さの上限
+const MAX_LINE_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("payload too large: exceeds {0} bytes")]
+    PayloadTooLarge(usize),
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Created,
+    BadRequest,
+    NotFound,
+    MethodNotAllowed,
+    Conflict,
+    PayloadTooLarge,
+    UnprocessableEntity,
+    InternalServerError,
+}
+impl Status {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Ok => 200,
+            Self::Created => 201,
+            Self::BadRequest => 400,
+            Self::NotFound => 404,
+            Self::MethodNotAllowed => 405,
+            Self::Conflict => 409,
+            Self::PayloadTooLarge => 413,
+            Self::UnprocessableEntity => 422,
+            Self::InternalServerError => 500,
+        }
+    }
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Created => "Created",
+            Self::BadRequest => "Bad Request",
+            Self::NotFound => "Not Found",
+            Self::MethodNotAllowed => "Method Not Allowed",
+            Self::Conflict => "Conflict",
+            Self::PayloadTooLarge => "Payload Too Large",
+            Self::UnprocessableEntity => "Unprocessable Entity",
+            Self::InternalServerError => "Internal Server Error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+impl Request {
+    // ヘッダ名は大文字小文字を区別しない
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.as_deref()?.split('&').find_map(|kv| {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            (k == name).then_some(v)
+        })
+    }
+    // パスを `/` で区切った要素 (空要素は除く)
+    pub fn segments(&self) -> Vec<&str> {
+        self.path.split('/').filter(|s| !s.is_empty()).collect()
+    }
+}
+
+pub fn read_request<R: BufRead>(reader: &mut R) -> Result<Request, HttpError> {
+    trace!("read_request called");
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, target, version) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(m), Some(t), Some(v)) if parts.next().is_none() => (m, t, v),
+        _ => {
+            return Err(HttpError::BadRequest(format!(
+                "malformed request line: {:?}",
+                request_line
+            )))
+        }
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(HttpError::BadRequest(format!(
+            "unsupported version: {}",
+            version
+        )));
+    }
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+    debug!("read_request: method={}, path={}", method, path);
+
+    let headers = read_headers(reader)?;
+    let mut req = Request {
+        method: method.to_string(),
+        path,
+        query,
+        headers,
+        body: vec![],
+    };
+
+    let chunked = req
+        .header("Transfer-Encoding")
+        .is_some_and(|v| v.to_ascii_lowercase().contains("chunked"));
+    req.body = if chunked {
+        read_chunked_body(reader)?
+    } else if let Some(len) = req.header("Content-Length") {
+        let len = len
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| HttpError::BadRequest(format!("invalid Content-Length: {}", len)))?;
+        if len > MAX_BODY_SIZE {
+            return Err(HttpError::PayloadTooLarge(MAX_BODY_SIZE));
+        }
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body)?;
+        body
+    } else {
+        vec![]
+    };
+    debug!("read_request: body {} bytes", req.body.len());
+
+    Ok(req)
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> Result<String, HttpError> {
+    let mut line = String::new();
+    // 改行が来ないまま送り続けられてもメモリを食い潰さないよう、上限 + 1 バイトまでしか読まない
+    let len = reader
+        .by_ref()
+        .take(MAX_LINE_SIZE as u64 + 1)
+        .read_line(&mut line)?;
+    if len == 0 {
+        return Err(HttpError::BadRequest("unexpected end of request".into()));
+    }
+    if len > MAX_LINE_SIZE {
+        return Err(HttpError::PayloadTooLarge(MAX_LINE_SIZE));
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn read_headers<R: BufRead>(reader: &mut R) -> Result<Vec<(String, String)>, HttpError> {
+    let mut headers = vec![];
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            return Ok(headers);
+        }
+        if headers.len() >= MAX_HEADERS {
+            return Err(HttpError::BadRequest("too many headers".into()));
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| HttpError::BadRequest(format!("malformed header: {:?}", line)))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+}
+
+fn read_chunked_body<R: BufRead>(reader: &mut R) -> Result<Vec<u8>, HttpError> {
+    trace!("read_chunked_body called");
+    let mut body = vec![];
+    loop {
+        let line = read_line(reader)?;
+        // チャンク拡張 (`;name=value`) は無視する
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| HttpError::BadRequest(format!("invalid chunk size: {:?}", line)))?;
+        if size == 0 {
+            // トレーラは読み捨てる
+            read_headers(reader)?;
+            return Ok(body);
+        }
+        // size は任意の 16 進数なので、足し算であふれないように残りの大きさと比べる
+        if size > MAX_BODY_SIZE - body.len() {
+            return Err(HttpError::PayloadTooLarge(MAX_BODY_SIZE));
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        if !read_line(reader)?.is_empty() {
+            return Err(HttpError::BadRequest("missing CRLF after chunk".into()));
+        }
+    }
+}
+
+pub fn write_response<W: Write>(writer: &mut W, status: Status, body: &str) -> io::Result<()> {
+    trace!("write_response called");
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status.code(),
+        status.reason(),
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read(raw: &str) -> Result<Request, HttpError> {
+        read_request(&mut Cursor::new(raw.as_bytes()))
+    }
+
+    #[test]
+    fn test_content_length() {
+        let req = read(
+            "POST /employees?x=1&filter=Hourly HTTP/1.1\r\nHost: localhost\r\ncontent-length: 5\r\n\r\nhello, extra",
+        )
+        .unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.path, "/employees");
+        assert_eq!(req.segments(), vec!["employees"]);
+        assert_eq!(req.query_param("filter"), Some("Hourly"));
+        assert_eq!(req.query_param("y"), None);
+        assert_eq!(req.header("Content-Length"), Some("5"));
+        assert_eq!(req.body, b"hello");
+    }
+
+    #[test]
+    fn test_larger_than_single_read() {
+        let body = "x".repeat(4000);
+        let raw = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let req = read(&raw).unwrap();
+        assert_eq!(req.body, body.as_bytes());
+    }
+
+    #[test]
+    fn test_chunked() {
+        let req = read(
+            "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n7;ext=1\r\n, world\r\n0\r\nX-Trailer: 1\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(req.body, b"hello, world");
+    }
+
+    #[test]
+    fn test_huge_chunk_size() {
+        assert!(matches!(
+            read("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\nffffffffffffffff\r\nx\r\n0\r\n\r\n"),
+            Err(HttpError::PayloadTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_huge_line() {
+        let raw = format!(
+            "GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n",
+            "x".repeat(MAX_LINE_SIZE)
+        );
+        assert!(matches!(read(&raw), Err(HttpError::PayloadTooLarge(_))));
+        let raw = format!("GET /{}", "x".repeat(10 * MAX_LINE_SIZE));
+        assert!(matches!(read(&raw), Err(HttpError::PayloadTooLarge(_))));
+    }
+
+    #[test]
+    fn test_no_body() {
+        let req = read("GET /employees/1 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(req.segments(), vec!["employees", "1"]);
+        assert!(req.body.is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(read(""), Err(HttpError::BadRequest(_))));
+        assert!(matches!(
+            read("GET /\r\n\r\n"),
+            Err(HttpError::BadRequest(_))
+        ));
+        assert!(matches!(
+            read("GET / HTTP/1.1\r\nNoColon\r\n\r\n"),
+            Err(HttpError::BadRequest(_))
+        ));
+        assert!(matches!(
+            read("POST / HTTP/1.1\r\nContent-Length: abc\r\n\r\n"),
+            Err(HttpError::BadRequest(_))
+        ));
+        assert!(matches!(
+            read("POST / HTTP/1.1\r\nContent-Length: 9999999\r\n\r\n"),
+            Err(HttpError::PayloadTooLarge(_))
+        ));
+        assert!(matches!(
+            read("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n"),
+            Err(HttpError::BadRequest(_))
+        ));
+        // ボディが Content-Length より短い
+        assert!(matches!(
+            read("POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nabc"),
+            Err(HttpError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut buf = vec![];
+        write_response(&mut buf, Status::NotFound, r#"{"a":1}"#).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 7\r\nConnection: close\r\n\r\n{\"a\":1}"
+        );
+    }
+}
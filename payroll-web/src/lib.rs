@@ -2,10 +2,19 @@ use getopts::Options;
 use log::{debug, error, trace};
 use std::{env, fmt, sync::Arc};
 
-use hs_db::HashDB;
+use dao::EmployeeDao;
+use payroll_domain::{
+    BusinessCalendar, DisbursementSink, NoWithholding, OvertimePolicy, Withholding,
+};
+use payroll_impl::{
+    load_business_calendar, load_overtime_policy, FileSink, StdoutSink, TableWithholding,
+};
 
+mod api;
+mod error;
 mod handler;
-mod tx_app_builder;
+mod http;
+mod route;
 
 use handler::{with_chronograph, Handler, TcpHandler};
 
@@ -16,6 +25,11 @@ pub struct AppConfig {
     port: u16,
     threads: usize,
     chronograph: bool,
+    db_file: Option<String>,
+    pay_records: Option<String>,
+    withholding: Option<String>,
+    overtime: Option<String>,
+    calendar: Option<String>,
     program: String,
     opts: Options,
 }
@@ -28,6 +42,11 @@ impl fmt::Debug for AppConfig {
             .field("port", &self.port)
             .field("threads", &self.threads)
             .field("chronograph", &self.chronograph)
+            .field("db_file", &self.db_file)
+            .field("pay_records", &self.pay_records)
+            .field("withholding", &self.withholding)
+            .field("overtime", &self.overtime)
+            .field("calendar", &self.calendar)
             .field("program", &self.program)
             .finish()
     }
@@ -35,14 +54,44 @@ impl fmt::Debug for AppConfig {
 impl AppConfig {
     pub fn new() -> Result<Self, anyhow::Error> {
         let args: Vec<String> = env::args().collect();
-        let program = args.get(0).expect("program name");
+        let program = args.first().expect("program name");
         let mut opts = Options::new();
         opts.optflag("?", "help", "Print this help menu")
             .optopt("h", "host", "hostname or Ip address to connect to", "HOST")
             .optflag("q", "quiet", "run in quiet mode, non verbose")
             .optopt("p", "port", "port to connect to", "PORT")
             .optopt("t", "threads", "number of threadpool size", "THREADS")
-            .optflag("c", "chronograph", "enable chronograph mode");
+            .optflag("c", "chronograph", "enable chronograph mode")
+            .optopt(
+                "d",
+                "db",
+                "persist data into the file, or SQLite with sqlite:PATH (in-memory if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "o",
+                "pay-records",
+                "append pay records into the file as JSON lines (stdout if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "w",
+                "withholding",
+                "withhold taxes by the tables in the JSON file (no withholding if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "e",
+                "overtime",
+                "pay overtime by the company-wide policy in the JSON file (over 8 hours a day if omitted)",
+                "PATH",
+            )
+            .optopt(
+                "C",
+                "calendar",
+                "pay on business days by the holiday calendar in the JSON file (no holidays if omitted)",
+                "PATH",
+            );
         let matches = match opts.parse(&args[1..]) {
             Ok(m) => m,
             Err(e) => {
@@ -64,6 +113,11 @@ impl AppConfig {
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(4),
             chronograph: matches.opt_present("c"),
+            db_file: matches.opt_str("d"),
+            pay_records: matches.opt_str("o"),
+            withholding: matches.opt_str("w"),
+            overtime: matches.opt_str("e"),
+            calendar: matches.opt_str("C"),
             program: program.to_string(),
             opts,
         })
@@ -88,11 +142,103 @@ impl AppConfig {
     pub fn chronograph(&self) -> bool {
         self.chronograph
     }
-    pub fn build_handler(&self, db: HashDB) -> Arc<dyn Handler + Send + Sync> {
+    pub fn db_file(&self) -> Option<&str> {
+        self.db_file.as_deref()
+    }
+    pub fn pay_records(&self) -> Option<&str> {
+        self.pay_records.as_deref()
+    }
+    // Payday の支払い記録の送り先
+    pub fn make_sink(&self) -> Result<Arc<dyn DisbursementSink>, anyhow::Error> {
+        trace!("make_sink called");
+        match self.pay_records() {
+            Some(path) => {
+                debug!("make_sink: with file={}, using FileSink", path);
+                Ok(Arc::new(FileSink::open(path)?))
+            }
+            None => {
+                debug!("make_sink: file is None, using StdoutSink");
+                Ok(Arc::new(StdoutSink))
+            }
+        }
+    }
+    pub fn withholding(&self) -> Option<&str> {
+        self.withholding.as_deref()
+    }
+    // Payday で使う源泉徴収の税率表
+    pub fn make_withholding(&self) -> Result<Arc<dyn Withholding>, anyhow::Error> {
+        trace!("make_withholding called");
+        match self.withholding() {
+            Some(path) => {
+                debug!(
+                    "make_withholding: with file={}, using TableWithholding",
+                    path
+                );
+                Ok(Arc::new(TableWithholding::load(path)?))
+            }
+            None => {
+                debug!("make_withholding: file is None, using NoWithholding");
+                Ok(Arc::new(NoWithholding))
+            }
+        }
+    }
+    pub fn overtime(&self) -> Option<&str> {
+        self.overtime.as_deref()
+    }
+    pub fn calendar(&self) -> Option<&str> {
+        self.calendar.as_deref()
+    }
+    // Payday で使う会社全体の残業の規則
+    pub fn make_overtime_policy(&self) -> Result<Arc<OvertimePolicy>, anyhow::Error> {
+        trace!("make_overtime_policy called");
+        match self.overtime() {
+            Some(path) => {
+                debug!("make_overtime_policy: with file={}", path);
+                Ok(Arc::new(load_overtime_policy(path)?))
+            }
+            None => {
+                debug!("make_overtime_policy: file is None, using the default policy");
+                Ok(Arc::new(OvertimePolicy::default()))
+            }
+        }
+    }
+    // 休日に当たる支払日をずらす会社の暦
+    pub fn make_calendar(&self) -> Result<Arc<BusinessCalendar>, anyhow::Error> {
+        trace!("make_calendar called");
+        match self.calendar() {
+            Some(path) => {
+                debug!("make_calendar: with file={}", path);
+                Ok(Arc::new(load_business_calendar(path)?))
+            }
+            None => {
+                debug!("make_calendar: file is None, using no holidays");
+                Ok(Arc::new(BusinessCalendar::none()))
+            }
+        }
+    }
+    pub fn build_handler<T>(
+        &self,
+        db: T,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+        calendar: Arc<BusinessCalendar>,
+    ) -> Arc<dyn Handler + Send + Sync>
+    where
+        T: EmployeeDao + Clone + Send + Sync + 'static,
+    {
         trace!("build_handler called");
-        let builder = tx_app_builder::TxAppBuilder::new(db.clone(), self.quiet, self.chronograph);
+        let api = api::Api::new(
+            db,
+            sink,
+            withholding,
+            overtime,
+            self.quiet,
+            self.chronograph,
+        )
+        .with_calendar(calendar);
 
-        let mut handler: Arc<dyn Handler + Send + Sync> = Arc::new(TcpHandler::new(builder));
+        let mut handler: Arc<dyn Handler + Send + Sync> = Arc::new(TcpHandler::new(api));
         if self.chronograph {
             debug!("adding chronograph to handler");
             handler = with_chronograph(handler);
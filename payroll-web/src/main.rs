@@ -1,14 +1,16 @@
 use log::{debug, info, trace};
 use std::net::TcpListener;
 
+use file_db::FileDB;
 use hs_db::HashDB;
 use payroll_web::AppConfig;
+use sqlite_db::SqliteDB;
 use threadpool::ThreadPool;
 
 fn print_header(_app_conf: &payroll_web::AppConfig) {
     trace!("print_header called");
     // this banner generated by using `figlet -f slant 'payro$$'`
-    let lines = vec![
+    let lines = [
         r#"                                   __  __"#,
         r#"    ____  ____ ___  ___________  _/ /_/ /"#,
         r#"   / __ \/ __ `/ / / / ___/ __ \/ __/ __/"#,
@@ -41,9 +43,27 @@ fn main() -> Result<(), anyhow::Error> {
     }
 
     let pool = ThreadPool::new(app_conf.threads());
-    let handler = app_conf.build_handler(HashDB::new());
-    let listener = TcpListener::bind(&app_conf.sock_addr())
-        .expect(&format!("Bind to {}", app_conf.sock_addr()));
+    let sink = app_conf.make_sink()?;
+    let withholding = app_conf.make_withholding()?;
+    let overtime = app_conf.make_overtime_policy()?;
+    let calendar = app_conf.make_calendar()?;
+    let handler = match app_conf.db_file() {
+        Some(url) if url.starts_with("sqlite:") => {
+            let path = url.trim_start_matches("sqlite:");
+            debug!("main: with db file={}, using SqliteDB", path);
+            app_conf.build_handler(SqliteDB::open(path)?, sink, withholding, overtime, calendar)
+        }
+        Some(path) => {
+            debug!("main: with db file={}, using FileDB", path);
+            app_conf.build_handler(FileDB::open(path)?, sink, withholding, overtime, calendar)
+        }
+        None => {
+            debug!("main: db file is None, using HashDB");
+            app_conf.build_handler(HashDB::new(), sink, withholding, overtime, calendar)
+        }
+    };
+    let listener = TcpListener::bind(app_conf.sock_addr())
+        .unwrap_or_else(|_| panic!("Bind to {}", app_conf.sock_addr()));
 
     for stream in listener.incoming() {
         trace!("Incoming connection");
@@ -0,0 +1,1112 @@
+// HTTP のリクエストをトランザクション (Tx) に変換する
+//
+//   POST   /                                    テキストのスクリプト (payroll-cli と同じ文法)
+//   GET    /employees?filter=<filter>           ListEmp
+//   POST   /employees                           AddEmp
+//   GET    /employees/{id}                      GetEmp
+//   DELETE /employees/{id}                      DelEmp
+//   POST   /employees/{id}/termination          Terminate
+//   PUT    /employees/{id}/status?effective=<date>
+//                                               ChgEmp Status
+//   PUT    /employees/{id}/name                 ChgEmp Name
+//   PUT    /employees/{id}/address              ChgEmp Address
+//   PUT    /employees/{id}/classification?effective=<date>
+//                                               ChgEmp Hourly/Salaried/Commissioned
+//   PUT    /employees/{id}/schedule             ChgEmp Schedule
+//   PUT    /employees/{id}/method               ChgEmp Hold/Direct/Mail
+//   PUT    /employees/{id}/affiliation          ChgEmp Member/NoMember
+//   PUT    /employees/{id}/tax                  ChgEmp Tax
+//   PUT    /employees/{id}/overtime             ChgEmp Overtime (null なら Default)
+//   POST   /employees/{id}/timecards            TimeCard
+//   PUT    /employees/{id}/timecards/{date}     AmendTimeCard
+//   DELETE /employees/{id}/timecards/{date}     VoidTimeCard
+//   POST   /employees/{id}/sales-receipts       SalesReceipt
+//   DELETE /employees/{id}/sales-receipts/{rid} VoidSalesReceipt
+//   POST   /employees/{id}/paychecks            Bonus/Correction
+//   GET    /employees/{id}/paychecks/{date}?run=<run_type>
+//                                               GetPaycheck
+//   GET    /employees/{id}/ytd/{year}           GetYtd
+//   GET    /members/{member_id}                 GetMember
+//   POST   /members/{member_id}/service-charges ServiceCharge
+//   DELETE /members/{member_id}/service-charges/{cid}
+//                                               VoidServiceCharge
+//   POST   /payday/{date}?policy=<policy>       Payday
+//   GET    /payday/{date}/preview               PreviewPayday
+use chrono::NaiveDate;
+use log::{debug, trace};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::str;
+
+use crate::error::ApiError;
+use crate::http::{Request, Status};
+use payroll_domain::{
+    validate_commission_rate, validate_hours, AffiliationInfo, ChargeId, ClassificationInfo,
+    EmployeeFilter, EmployeeId, EmploymentStatus, InvalidInput, MemberId, MethodInfo, Money,
+    OvertimePolicy, ReceiptId, RerunPolicy, RunType, ScheduleInfo, TaxProfile,
+};
+use tx_app::Tx;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    // 上から順に実行して、トランザクションごとの結果を返す
+    Script(Vec<Tx>),
+    // 成功したときは指定のステータスで結果を返す
+    Single(Tx, Status),
+}
+
+#[derive(Debug, Deserialize)]
+struct NewEmployee {
+    id: EmployeeId,
+    name: String,
+    address: String,
+    classification: ClassificationInfo,
+    #[serde(default)]
+    hire_date: Option<NaiveDate>,
+}
+#[derive(Debug, Deserialize)]
+struct Termination {
+    date: NaiveDate,
+}
+#[derive(Debug, Deserialize)]
+struct OffCyclePay {
+    run_type: RunType,
+    date: NaiveDate,
+    amount: Money,
+    #[serde(default)]
+    period_start: Option<NaiveDate>,
+}
+#[derive(Debug, Deserialize)]
+struct NewName {
+    name: String,
+}
+#[derive(Debug, Deserialize)]
+struct NewAddress {
+    address: String,
+}
+#[derive(Debug, Deserialize)]
+struct NewTimecard {
+    date: NaiveDate,
+    hours: f32,
+}
+#[derive(Debug, Deserialize)]
+struct NewHours {
+    hours: f32,
+}
+#[derive(Debug, Deserialize)]
+struct NewAmount {
+    date: NaiveDate,
+    amount: Money,
+}
+
+pub fn route(req: &Request) -> Result<Route, ApiError> {
+    trace!("route called");
+    let method = req.method.as_str();
+    let segments = req.segments();
+    debug!("route: method={}, segments={:?}", method, segments);
+
+    let single = |tx| Ok(Route::Single(tx, Status::Ok));
+    let created = |tx| Ok(Route::Single(tx, Status::Created));
+    match (segments.as_slice(), method) {
+        ([], "POST") => script(req).map(Route::Script),
+
+        (["employees"], "GET") => single(Tx::ListEmployees {
+            filter: employee_filter(req.query_param("filter"))?,
+        }),
+        (["employees"], "POST") => {
+            let emp: NewEmployee = json_body(req)?;
+            created(add_employee(emp)?)
+        }
+
+        (["employees", id], "GET") => single(Tx::GetEmployee { id: emp_id(id)? }),
+        (["employees", id], "DELETE") => single(Tx::DeleteEmployee { id: emp_id(id)? }),
+        (["employees", id, "termination"], "POST") => {
+            let id = emp_id(id)?;
+            let Termination { date } = json_body(req)?;
+            single(Tx::TerminateEmployee { id, date })
+        }
+        (["employees", id, "status"], "PUT") => {
+            let id = emp_id(id)?;
+            let effective = match req.query_param("effective") {
+                Some(s) => pay_date(s)?,
+                None => {
+                    return Err(ApiError::new(
+                        Status::BadRequest,
+                        "BadRequest",
+                        "effective is required",
+                    ))
+                }
+            };
+            let status: EmploymentStatus = json_body(req)?;
+            // 退職は最後の Paycheck を支払う termination でしかできない
+            if status == EmploymentStatus::Terminated {
+                return Err(ApiError::new(
+                    Status::UnprocessableEntity,
+                    "InvalidData",
+                    "use POST /employees/{id}/termination to terminate",
+                ));
+            }
+            single(Tx::ChangeEmployeeStatus {
+                id,
+                status,
+                effective,
+            })
+        }
+
+        (["employees", id, "name"], "PUT") => {
+            let id = emp_id(id)?;
+            let NewName { name } = json_body(req)?;
+            single(Tx::ChangeEmployeeName { id, new_name: name })
+        }
+        (["employees", id, "address"], "PUT") => {
+            let id = emp_id(id)?;
+            let NewAddress { address } = json_body(req)?;
+            single(Tx::ChangeEmployeeAddress {
+                id,
+                new_address: address,
+            })
+        }
+        (["employees", id, "classification"], "PUT") => {
+            let id = emp_id(id)?;
+            let effective = req.query_param("effective").map(pay_date).transpose()?;
+            single(change_classification(id, json_body(req)?, effective)?)
+        }
+        (["employees", id, "schedule"], "PUT") => {
+            let id = emp_id(id)?;
+            let schedule: ScheduleInfo = json_body(req)?;
+            if !schedule.is_valid() {
+                return Err(ApiError::new(
+                    Status::UnprocessableEntity,
+                    "InvalidData",
+                    format!("invalid schedule: {:?}", schedule),
+                ));
+            }
+            single(Tx::ChangeEmployeeSchedule { id, schedule })
+        }
+        (["employees", id, "method"], "PUT") => {
+            let id = emp_id(id)?;
+            single(change_method(id, json_body(req)?))
+        }
+        (["employees", id, "affiliation"], "PUT") => {
+            let id = emp_id(id)?;
+            single(change_affiliation(id, json_body(req)?))
+        }
+        (["employees", id, "tax"], "PUT") => {
+            let id = emp_id(id)?;
+            let tax: TaxProfile = json_body(req)?;
+            single(Tx::ChangeEmployeeTax { id, tax })
+        }
+        (["employees", id, "overtime"], "PUT") => {
+            let id = emp_id(id)?;
+            let overtime: Option<OvertimePolicy> = json_body(req)?;
+            if let Some(policy) = &overtime {
+                policy.validate().map_err(|e| {
+                    ApiError::new(Status::UnprocessableEntity, "InvalidData", e.to_string())
+                })?;
+            }
+            single(Tx::ChangeEmployeeOvertime { id, overtime })
+        }
+        (["employees", id, "timecards"], "POST") => {
+            let id = emp_id(id)?;
+            let NewTimecard { date, hours } = json_body(req)?;
+            validate_hours(hours).map_err(invalid_input)?;
+            created(Tx::AddTimeCard { id, date, hours })
+        }
+        (["employees", id, "timecards", date], "PUT") => {
+            let (id, date) = (emp_id(id)?, pay_date(date)?);
+            let NewHours { hours } = json_body(req)?;
+            validate_hours(hours).map_err(invalid_input)?;
+            single(Tx::AmendTimeCard { id, date, hours })
+        }
+        (["employees", id, "timecards", date], "DELETE") => single(Tx::VoidTimeCard {
+            id: emp_id(id)?,
+            date: pay_date(date)?,
+        }),
+        (["employees", id, "sales-receipts"], "POST") => {
+            let id = emp_id(id)?;
+            let NewAmount { date, amount } = json_body(req)?;
+            created(Tx::AddSalesReceipt { id, date, amount })
+        }
+        (["employees", id, "sales-receipts", rid], "DELETE") => single(Tx::VoidSalesReceipt {
+            id: emp_id(id)?,
+            receipt_id: ReceiptId::from(entry_id(rid)?),
+        }),
+        (["employees", id, "paychecks"], "POST") => {
+            let id = emp_id(id)?;
+            let pay: OffCyclePay = json_body(req)?;
+            // 定期の支払いは payday で、最後の支払いは termination で行う
+            if pay.run_type.pays_work() {
+                return Err(ApiError::new(
+                    Status::UnprocessableEntity,
+                    "InvalidData",
+                    format!("run_type must be Bonus or Correction: {}", pay.run_type),
+                ));
+            }
+            created(Tx::PayOffCycle {
+                id,
+                run_type: pay.run_type,
+                date: pay.date,
+                amount: pay.amount,
+                period_start: pay.period_start,
+            })
+        }
+        (["employees", id, "paychecks", date], "GET") => single(Tx::GetPaycheck {
+            id: emp_id(id)?,
+            date: pay_date(date)?,
+            run_type: run_type(req.query_param("run"))?,
+        }),
+        (["employees", id, "ytd", y], "GET") => single(Tx::GetYearToDate {
+            id: emp_id(id)?,
+            year: year(y)?,
+        }),
+
+        (["members", member_id], "GET") => single(Tx::GetUnionMember {
+            member_id: mem_id(member_id)?,
+        }),
+        (["members", member_id, "service-charges"], "POST") => {
+            let member_id = mem_id(member_id)?;
+            let NewAmount { date, amount } = json_body(req)?;
+            created(Tx::AddServiceCharge {
+                member_id,
+                date,
+                amount,
+            })
+        }
+        (["members", member_id, "service-charges", cid], "DELETE") => {
+            single(Tx::VoidServiceCharge {
+                member_id: mem_id(member_id)?,
+                charge_id: ChargeId::from(entry_id(cid)?),
+            })
+        }
+
+        (["payday", date], "POST") => single(Tx::Payday {
+            date: pay_date(date)?,
+            policy: rerun_policy(req.query_param("policy"))?,
+        }),
+        (["payday", date, "preview"], "GET") => single(Tx::PreviewPayday {
+            date: pay_date(date)?,
+        }),
+
+        ([] | ["employees"] | ["employees", _] | ["members", _] | ["payday", _], _)
+        | (["employees", _, "name" | "address" | "classification" | "method" | "affiliation"], _)
+        | (["employees", _, "tax" | "overtime" | "termination" | "status" | "schedule"], _)
+        | (["employees", _, "timecards" | "sales-receipts" | "paychecks"], _)
+        | (["employees", _, "timecards" | "sales-receipts" | "paychecks" | "ytd", _], _)
+        | (["members", _, "service-charges"], _)
+        | (["members", _, "service-charges", _], _)
+        | (["payday", _, "preview"], _) => Err(ApiError::new(
+            Status::MethodNotAllowed,
+            "MethodNotAllowed",
+            format!("{} is not allowed for {}", method, req.path),
+        )),
+        _ => Err(ApiError::new(
+            Status::NotFound,
+            "NotFound",
+            format!("no route for {}", req.path),
+        )),
+    }
+}
+
+fn script(req: &Request) -> Result<Vec<Tx>, ApiError> {
+    trace!("script called");
+    let text = str::from_utf8(&req.body)
+        .map_err(|e| ApiError::new(Status::BadRequest, "BadRequest", e.to_string()))?;
+    let mut txs = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = format!("{}\n", line);
+        if text_parser_tx_source::ignoreable(&line) {
+            continue;
+        }
+        let tx = text_parser_tx_source::read_tx(&line).map_err(|e| {
+            ApiError::new(
+                Status::BadRequest,
+                "ParseError",
+                format!("line {}: {}", i + 1, e),
+            )
+        })?;
+        txs.push(tx);
+    }
+    Ok(txs)
+}
+
+fn json_body<T: DeserializeOwned>(req: &Request) -> Result<T, ApiError> {
+    serde_json::from_slice(&req.body).map_err(|e| {
+        // 構文として壊れているのか、形は正しいが中身がおかしいのかで分ける
+        if e.is_data() {
+            ApiError::new(Status::UnprocessableEntity, "InvalidData", e.to_string())
+        } else {
+            ApiError::new(Status::BadRequest, "InvalidJson", e.to_string())
+        }
+    })
+}
+
+fn emp_id(s: &str) -> Result<EmployeeId, ApiError> {
+    s.parse::<u32>().map(Into::into).map_err(|_| {
+        ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid employee id: {}", s),
+        )
+    })
+}
+
+fn mem_id(s: &str) -> Result<MemberId, ApiError> {
+    s.parse::<u32>().map(Into::into).map_err(|_| {
+        ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid member id: {}", s),
+        )
+    })
+}
+
+// 売上・サービス料の番号
+fn entry_id(s: &str) -> Result<u32, ApiError> {
+    s.parse::<u32>().map_err(|_| {
+        ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid receipt or charge id: {}", s),
+        )
+    })
+}
+
+fn pay_date(s: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
+        ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid date: {}", s),
+        )
+    })
+}
+
+fn year(s: &str) -> Result<i32, ApiError> {
+    s.parse::<i32>().map_err(|_| {
+        ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid year: {}", s),
+        )
+    })
+}
+
+fn employee_filter(s: Option<&str>) -> Result<EmployeeFilter, ApiError> {
+    match s {
+        None | Some("") | Some("All") => Ok(EmployeeFilter::All),
+        Some("Salaried") => Ok(EmployeeFilter::Salaried),
+        Some("Hourly") => Ok(EmployeeFilter::Hourly),
+        Some("Commissioned") => Ok(EmployeeFilter::Commissioned),
+        Some("Monthly") => Ok(EmployeeFilter::Monthly),
+        Some("Weekly") => Ok(EmployeeFilter::Weekly),
+        Some("Biweekly") => Ok(EmployeeFilter::Biweekly),
+        Some("Semimonthly") => Ok(EmployeeFilter::Semimonthly),
+        Some("Hold") => Ok(EmployeeFilter::Hold),
+        Some("Direct") => Ok(EmployeeFilter::Direct),
+        Some("Mail") => Ok(EmployeeFilter::Mail),
+        Some("Member") => Ok(EmployeeFilter::Member),
+        Some("NoMember") => Ok(EmployeeFilter::NoMember),
+        Some("Active") => Ok(EmployeeFilter::Active),
+        Some("OnLeave") => Ok(EmployeeFilter::OnLeave),
+        Some("Terminated") => Ok(EmployeeFilter::Terminated),
+        Some(s) => Err(ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid filter: {}", s),
+        )),
+    }
+}
+
+fn rerun_policy(s: Option<&str>) -> Result<RerunPolicy, ApiError> {
+    match s {
+        None | Some("") => Ok(RerunPolicy::default()),
+        Some("Reject") => Ok(RerunPolicy::Reject),
+        Some("Skip") => Ok(RerunPolicy::Skip),
+        Some("Replay") => Ok(RerunPolicy::Replay),
+        Some(s) => Err(ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid policy: {}", s),
+        )),
+    }
+}
+
+fn run_type(s: Option<&str>) -> Result<RunType, ApiError> {
+    match s {
+        None | Some("") => Ok(RunType::default()),
+        Some("Regular") => Ok(RunType::Regular),
+        Some("Bonus") => Ok(RunType::Bonus),
+        Some("Correction") => Ok(RunType::Correction),
+        Some("Final") => Ok(RunType::Final),
+        Some(s) => Err(ApiError::new(
+            Status::BadRequest,
+            "BadRequest",
+            format!("invalid run: {}", s),
+        )),
+    }
+}
+
+// JSON の 1e39 は f32 では無限大になるので、Money に掛ける値はここで弾く
+fn invalid_input(e: InvalidInput) -> ApiError {
+    ApiError::new(Status::BadRequest, "InvalidInput", e.to_string())
+}
+fn validate_classification(classification: &ClassificationInfo) -> Result<(), ApiError> {
+    if let ClassificationInfo::Commissioned {
+        commission_rate, ..
+    } = classification
+    {
+        validate_commission_rate(*commission_rate).map_err(invalid_input)?;
+    }
+    Ok(())
+}
+
+fn add_employee(emp: NewEmployee) -> Result<Tx, ApiError> {
+    let NewEmployee {
+        id,
+        name,
+        address,
+        classification,
+        hire_date,
+    } = emp;
+    validate_classification(&classification)?;
+    let tx = match classification {
+        ClassificationInfo::Salaried { salary } => Tx::AddSalariedEmployee {
+            id,
+            name,
+            address,
+            salary,
+            hire_date,
+        },
+        ClassificationInfo::Hourly { hourly_rate } => Tx::AddHourlyEmployee {
+            id,
+            name,
+            address,
+            hourly_rate,
+            hire_date,
+        },
+        ClassificationInfo::Commissioned {
+            salary,
+            commission_rate,
+        } => Tx::AddCommissionedEmployee {
+            id,
+            name,
+            address,
+            salary,
+            commission_rate,
+            hire_date,
+        },
+    };
+    Ok(tx)
+}
+
+fn change_classification(
+    id: EmployeeId,
+    classification: ClassificationInfo,
+    effective: Option<NaiveDate>,
+) -> Result<Tx, ApiError> {
+    validate_classification(&classification)?;
+    let tx = match classification {
+        ClassificationInfo::Salaried { salary } => Tx::ChangeEmployeeSalaried {
+            id,
+            salary,
+            effective,
+        },
+        ClassificationInfo::Hourly { hourly_rate } => Tx::ChangeEmployeeHourly {
+            id,
+            hourly_rate,
+            effective,
+        },
+        ClassificationInfo::Commissioned {
+            salary,
+            commission_rate,
+        } => Tx::ChangeEmployeeCommissioned {
+            id,
+            salary,
+            commission_rate,
+            effective,
+        },
+    };
+    Ok(tx)
+}
+
+fn change_method(id: EmployeeId, method: MethodInfo) -> Tx {
+    match method {
+        MethodInfo::Hold => Tx::ChangeEmployeeHold { id },
+        MethodInfo::Direct { bank, account } => Tx::ChangeEmployeeDirect { id, bank, account },
+        MethodInfo::Mail { address } => Tx::ChangeEmployeeMail { id, address },
+    }
+}
+
+fn change_affiliation(emp_id: EmployeeId, affiliation: AffiliationInfo) -> Tx {
+    match affiliation {
+        AffiliationInfo::None => Tx::ChangeEmployeeNoMember { emp_id },
+        AffiliationInfo::Union { member_id, dues } => Tx::ChangeEmployeeMember {
+            emp_id,
+            member_id,
+            dues,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, target: &str, body: &str) -> Request {
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(query.to_string())),
+            None => (target.to_string(), None),
+        };
+        Request {
+            method: method.to_string(),
+            path,
+            query,
+            headers: vec![],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_add_employee() {
+        let req = request(
+            "POST",
+            "/employees",
+            r#"{"id": 1, "name": "Bob", "address": "Home", "classification": {"Hourly": {"hourly_rate": "15.75"}}}"#,
+        );
+        assert_eq!(
+            route(&req).unwrap(),
+            Route::Single(
+                Tx::AddHourlyEmployee {
+                    id: 1.into(),
+                    name: "Bob".to_string(),
+                    address: "Home".to_string(),
+                    hourly_rate: Money::from_cents(1575),
+                    hire_date: None,
+                },
+                Status::Created
+            )
+        );
+
+        let req = request(
+            "POST",
+            "/employees",
+            r#"{"id": 2, "name": "Alice", "address": "Away", "classification": {"Salaried": {"salary": "2300.00"}}, "hire_date": "2025-01-20"}"#,
+        );
+        assert_eq!(
+            route(&req).unwrap(),
+            Route::Single(
+                Tx::AddSalariedEmployee {
+                    id: 2.into(),
+                    name: "Alice".to_string(),
+                    address: "Away".to_string(),
+                    salary: Money::from_cents(230000),
+                    hire_date: Some(date(2025, 1, 20)),
+                },
+                Status::Created
+            )
+        );
+    }
+
+    #[test]
+    fn test_employment() {
+        assert_eq!(
+            route(&request("DELETE", "/employees/1", "")).unwrap(),
+            Route::Single(Tx::DeleteEmployee { id: 1.into() }, Status::Ok)
+        );
+        assert_eq!(
+            route(&request(
+                "POST",
+                "/employees/1/termination",
+                r#"{"date":"2025-02-14"}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::TerminateEmployee {
+                    id: 1.into(),
+                    date: date(2025, 2, 14)
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/status?effective=2025-02-03",
+                r#""OnLeave""#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeStatus {
+                    id: 1.into(),
+                    status: EmploymentStatus::OnLeave,
+                    effective: date(2025, 2, 3)
+                },
+                Status::Ok
+            )
+        );
+
+        let status =
+            |method, target, body| route(&request(method, target, body)).unwrap_err().status;
+        // 退職は termination でしかできない
+        assert_eq!(
+            status(
+                "PUT",
+                "/employees/1/status?effective=2025-02-03",
+                r#""Terminated""#
+            ),
+            Status::UnprocessableEntity
+        );
+        assert_eq!(
+            status("PUT", "/employees/1/status", r#""Active""#),
+            Status::BadRequest
+        );
+        assert_eq!(
+            status("GET", "/employees/1/termination", ""),
+            Status::MethodNotAllowed
+        );
+    }
+
+    #[test]
+    fn test_off_cycle() {
+        assert_eq!(
+            route(&request(
+                "POST",
+                "/employees/1/paychecks",
+                r#"{"run_type":"Bonus","date":"2025-03-14","amount":"500.00"}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::PayOffCycle {
+                    id: 1.into(),
+                    run_type: RunType::Bonus,
+                    date: date(2025, 3, 14),
+                    amount: Money::from_cents(50000),
+                    period_start: None,
+                },
+                Status::Created
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "POST",
+                "/employees/1/paychecks",
+                r#"{"run_type":"Correction","date":"2025-03-14","amount":"-25.50","period_start":"2025-02-01"}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::PayOffCycle {
+                    id: 1.into(),
+                    run_type: RunType::Correction,
+                    date: date(2025, 3, 14),
+                    amount: Money::from_cents(-2550),
+                    period_start: Some(date(2025, 2, 1)),
+                },
+                Status::Created
+            )
+        );
+
+        let status =
+            |method, target, body| route(&request(method, target, body)).unwrap_err().status;
+        // 定期の支払いと最後の支払いは payday と termination で行う
+        assert_eq!(
+            status(
+                "POST",
+                "/employees/1/paychecks",
+                r#"{"run_type":"Final","date":"2025-03-14","amount":"500.00"}"#
+            ),
+            Status::UnprocessableEntity
+        );
+        assert_eq!(
+            status("GET", "/employees/1/paychecks/2025-03-14?run=Extra", ""),
+            Status::BadRequest
+        );
+        assert_eq!(
+            status("GET", "/employees/1/paychecks", ""),
+            Status::MethodNotAllowed
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        assert_eq!(
+            route(&request("GET", "/employees/42", "")).unwrap(),
+            Route::Single(Tx::GetEmployee { id: 42.into() }, Status::Ok)
+        );
+        assert_eq!(
+            route(&request("GET", "/employees?filter=Member", "")).unwrap(),
+            Route::Single(
+                Tx::ListEmployees {
+                    filter: EmployeeFilter::Member
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("GET", "/employees/42/paychecks/2025-01-31", "")).unwrap(),
+            Route::Single(
+                Tx::GetPaycheck {
+                    id: 42.into(),
+                    date: date(2025, 1, 31),
+                    run_type: RunType::Regular,
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "GET",
+                "/employees/42/paychecks/2025-01-31?run=Final",
+                ""
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::GetPaycheck {
+                    id: 42.into(),
+                    date: date(2025, 1, 31),
+                    run_type: RunType::Final,
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("GET", "/employees/42/ytd/2025", "")).unwrap(),
+            Route::Single(
+                Tx::GetYearToDate {
+                    id: 42.into(),
+                    year: 2025
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("GET", "/members/7234", "")).unwrap(),
+            Route::Single(
+                Tx::GetUnionMember {
+                    member_id: 7234.into()
+                },
+                Status::Ok
+            )
+        );
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/classification?effective=2025-01-15",
+                r#"{"Hourly": {"hourly_rate": "17.50"}}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeHourly {
+                    id: 1.into(),
+                    hourly_rate: Money::from_cents(1750),
+                    effective: Some(date(2025, 1, 15)),
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/classification",
+                r#"{"Salaried": {"salary": "3720"}}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeSalaried {
+                    id: 1.into(),
+                    salary: Money::from_cents(372000),
+                    effective: None,
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/schedule",
+                r#"{"MonthlyOn": {"day": 25}}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeSchedule {
+                    id: 1.into(),
+                    schedule: ScheduleInfo::MonthlyOn { day: 25 },
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("PUT", "/employees/1/schedule", r#""Semimonthly""#)).unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeSchedule {
+                    id: 1.into(),
+                    schedule: ScheduleInfo::Semimonthly,
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "POST",
+                "/employees/1/timecards",
+                r#"{"date": "2025-01-03", "hours": 8.0}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::AddTimeCard {
+                    id: 1.into(),
+                    date: date(2025, 1, 3),
+                    hours: 8.0
+                },
+                Status::Created
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/timecards/2025-01-03",
+                r#"{"hours": 7.5}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::AmendTimeCard {
+                    id: 1.into(),
+                    date: date(2025, 1, 3),
+                    hours: 7.5
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("DELETE", "/employees/1/timecards/2025-01-03", "")).unwrap(),
+            Route::Single(
+                Tx::VoidTimeCard {
+                    id: 1.into(),
+                    date: date(2025, 1, 3)
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("DELETE", "/employees/1/sales-receipts/2", "")).unwrap(),
+            Route::Single(
+                Tx::VoidSalesReceipt {
+                    id: 1.into(),
+                    receipt_id: 2.into()
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("DELETE", "/members/7234/service-charges/1", "")).unwrap(),
+            Route::Single(
+                Tx::VoidServiceCharge {
+                    member_id: 7234.into(),
+                    charge_id: 1.into()
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("POST", "/payday/2025-01-31?policy=Skip", "")).unwrap(),
+            Route::Single(
+                Tx::Payday {
+                    date: date(2025, 1, 31),
+                    policy: RerunPolicy::Skip
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("GET", "/payday/2025-01-31/preview", "")).unwrap(),
+            Route::Single(
+                Tx::PreviewPayday {
+                    date: date(2025, 1, 31)
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/affiliation",
+                r#"{"Union": {"member_id": 7234, "dues": "9.45"}}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeMember {
+                    emp_id: 1.into(),
+                    member_id: 7234.into(),
+                    dues: Money::from_cents(945)
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/tax",
+                r#"{"filing_status": "Married", "allowances": 2}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeTax {
+                    id: 1.into(),
+                    tax: TaxProfile::new(payroll_domain::FilingStatus::Married, 2)
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request(
+                "PUT",
+                "/employees/1/overtime",
+                r#"{"weekly_after": 40, "weekend_rate": 1.5}"#
+            ))
+            .unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeOvertime {
+                    id: 1.into(),
+                    overtime: Some(OvertimePolicy {
+                        weekly_after: Some(40.0),
+                        weekend_rate: Some(1.5),
+                        ..OvertimePolicy::none()
+                    })
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("PUT", "/employees/1/overtime", "null")).unwrap(),
+            Route::Single(
+                Tx::ChangeEmployeeOvertime {
+                    id: 1.into(),
+                    overtime: None
+                },
+                Status::Ok
+            )
+        );
+        assert_eq!(
+            route(&request("PUT", "/employees/1/method", r#""Hold""#)).unwrap(),
+            Route::Single(Tx::ChangeEmployeeHold { id: 1.into() }, Status::Ok)
+        );
+    }
+
+    #[test]
+    fn test_script() {
+        let req = request("POST", "/", "# comment\nDelEmp 1\n\nGetEmp 2\n");
+        assert_eq!(
+            route(&req).unwrap(),
+            Route::Script(vec![
+                Tx::DeleteEmployee { id: 1.into() },
+                Tx::GetEmployee { id: 2.into() }
+            ])
+        );
+
+        let req = request("POST", "/", "DelEmp 1\nDelEmp x\n");
+        let e = route(&req).unwrap_err();
+        assert_eq!(e.status, Status::BadRequest);
+        assert_eq!(e.kind, "ParseError");
+        assert!(e.message.starts_with("line 2:"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let status =
+            |method, target, body| route(&request(method, target, body)).unwrap_err().status;
+        assert_eq!(status("GET", "/nowhere", ""), Status::NotFound);
+        assert_eq!(
+            status("PATCH", "/employees/1", ""),
+            Status::MethodNotAllowed
+        );
+        assert_eq!(status("GET", "/employees/abc", ""), Status::BadRequest);
+        assert_eq!(status("POST", "/payday/2025-13-01", ""), Status::BadRequest);
+        assert_eq!(
+            status("GET", "/employees?filter=Foo", ""),
+            Status::BadRequest
+        );
+        assert_eq!(status("POST", "/employees", "{"), Status::BadRequest);
+        assert_eq!(
+            status("POST", "/employees", r#"{"id": 1}"#),
+            Status::UnprocessableEntity
+        );
+        assert_eq!(
+            status("PUT", "/employees/1/overtime", r#"{"weekend_rate": 0.5}"#),
+            Status::UnprocessableEntity
+        );
+        assert_eq!(
+            status("GET", "/employees/1/overtime", ""),
+            Status::MethodNotAllowed
+        );
+        assert_eq!(
+            status(
+                "PUT",
+                "/employees/1/schedule",
+                r#"{"MonthlyOn": {"day": 32}}"#
+            ),
+            Status::UnprocessableEntity
+        );
+        assert_eq!(
+            status("GET", "/employees/1/schedule", ""),
+            Status::MethodNotAllowed
+        );
+        assert_eq!(
+            status("POST", "/employees/1/timecards/2025-01-03", ""),
+            Status::MethodNotAllowed
+        );
+        assert_eq!(
+            status("DELETE", "/employees/1/timecards/2025-02-30", ""),
+            Status::BadRequest
+        );
+        assert_eq!(
+            status("GET", "/members/7234/service-charges/1", ""),
+            Status::MethodNotAllowed
+        );
+        assert_eq!(
+            status("DELETE", "/employees/1/sales-receipts/x", ""),
+            Status::BadRequest
+        );
+        assert_eq!(
+            status("POST", "/payday/2025-01-31/preview", ""),
+            Status::MethodNotAllowed
+        );
+        // f32 に収まらない 1e39 は無限大になる
+        assert_eq!(
+            status(
+                "POST",
+                "/employees/1/timecards",
+                r#"{"date": "2025-01-06", "hours": 1e39}"#
+            ),
+            Status::BadRequest
+        );
+        assert_eq!(
+            status(
+                "PUT",
+                "/employees/1/timecards/2025-01-06",
+                r#"{"hours": -8.0}"#
+            ),
+            Status::BadRequest
+        );
+        assert_eq!(
+            status(
+                "POST",
+                "/employees",
+                r#"{"id": 1, "name": "Bob", "address": "Home", "classification": {"Commissioned": {"salary": "1000", "commission_rate": -0.1}}}"#
+            ),
+            Status::BadRequest
+        );
+        assert_eq!(
+            status(
+                "PUT",
+                "/employees/1/classification",
+                r#"{"Commissioned": {"salary": "1000", "commission_rate": 1e39}}"#
+            ),
+            Status::BadRequest
+        );
+    }
+}
@@ -0,0 +1,75 @@
+-- 金額はすべてセント単位の整数で持つ
+CREATE TABLE employees (
+    id      INTEGER PRIMARY KEY,
+    name    TEXT NOT NULL,
+    address TEXT NOT NULL
+);
+
+CREATE TABLE classifications (
+    emp_id          INTEGER PRIMARY KEY REFERENCES employees (id) ON DELETE CASCADE,
+    kind            TEXT NOT NULL CHECK (kind IN ('salaried', 'hourly', 'commissioned')),
+    salary          INTEGER,
+    hourly_rate     INTEGER,
+    commission_rate REAL
+);
+
+CREATE TABLE timecards (
+    id     INTEGER PRIMARY KEY,
+    emp_id INTEGER NOT NULL REFERENCES classifications (emp_id) ON DELETE CASCADE,
+    date   TEXT NOT NULL,
+    hours  REAL NOT NULL
+);
+CREATE INDEX timecards_emp_id ON timecards (emp_id);
+
+CREATE TABLE sales_receipts (
+    id     INTEGER PRIMARY KEY,
+    emp_id INTEGER NOT NULL REFERENCES classifications (emp_id) ON DELETE CASCADE,
+    date   TEXT NOT NULL,
+    amount INTEGER NOT NULL
+);
+CREATE INDEX sales_receipts_emp_id ON sales_receipts (emp_id);
+
+CREATE TABLE schedules (
+    emp_id INTEGER PRIMARY KEY REFERENCES employees (id) ON DELETE CASCADE,
+    kind   TEXT NOT NULL CHECK (kind IN ('monthly', 'weekly', 'biweekly'))
+);
+
+CREATE TABLE methods (
+    emp_id  INTEGER PRIMARY KEY REFERENCES employees (id) ON DELETE CASCADE,
+    kind    TEXT NOT NULL CHECK (kind IN ('hold', 'direct', 'mail')),
+    bank    TEXT,
+    account TEXT,
+    address TEXT
+);
+
+-- 組合に加入している従業員だけ行がある
+CREATE TABLE affiliations (
+    emp_id    INTEGER PRIMARY KEY REFERENCES employees (id) ON DELETE CASCADE,
+    member_id INTEGER NOT NULL,
+    dues      INTEGER NOT NULL
+);
+
+CREATE TABLE service_charges (
+    id     INTEGER PRIMARY KEY,
+    emp_id INTEGER NOT NULL REFERENCES affiliations (emp_id) ON DELETE CASCADE,
+    date   TEXT NOT NULL,
+    amount INTEGER NOT NULL
+);
+CREATE INDEX service_charges_emp_id ON service_charges (emp_id);
+
+CREATE TABLE union_members (
+    member_id INTEGER PRIMARY KEY,
+    emp_id    INTEGER NOT NULL
+);
+
+-- 支払い履歴は従業員が削除されても残す
+CREATE TABLE paychecks (
+    emp_id       INTEGER NOT NULL,
+    pay_date     TEXT NOT NULL,
+    period_start TEXT NOT NULL,
+    gross_pay    INTEGER NOT NULL,
+    deductions   INTEGER NOT NULL,
+    net_pay      INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date)
+);
+CREATE INDEX paychecks_pay_date ON paychecks (pay_date);
@@ -0,0 +1,16 @@
+-- 源泉徴収の条件 (既存の従業員はデフォルトの条件になる)
+ALTER TABLE employees ADD COLUMN filing_status TEXT NOT NULL DEFAULT 'single'
+    CHECK (filing_status IN ('single', 'married', 'head_of_household'));
+ALTER TABLE employees ADD COLUMN allowances INTEGER NOT NULL DEFAULT 0 CHECK (allowances >= 0);
+
+-- Paycheck の控除の内訳 (seq は記録した順)
+-- これより前に記録された Paycheck には内訳がない
+CREATE TABLE paycheck_deductions (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    name     TEXT NOT NULL,
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
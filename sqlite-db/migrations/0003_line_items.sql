@@ -0,0 +1,27 @@
+-- Paycheck の支給の明細 (seq は記録した順)
+CREATE TABLE paycheck_earnings (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('salary', 'regular', 'overtime', 'commission', 'unitemized')),
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
+
+-- 控除の種類 (name は源泉徴収のルール名で、それ以外は空)
+-- 0002 の内訳は組合の分をまとめた 'Affiliation' の 1 行と、源泉徴収の行だった
+ALTER TABLE paycheck_deductions ADD COLUMN kind TEXT NOT NULL DEFAULT 'withholding'
+    CHECK (kind IN ('dues', 'service_charge', 'withholding', 'unitemized'));
+UPDATE paycheck_deductions SET kind = 'unitemized', name = '' WHERE name = 'Affiliation';
+
+-- 明細のない Paycheck は合計額を 1 行の明細にしておく
+INSERT INTO paycheck_earnings (emp_id, pay_date, seq, kind, amount)
+    SELECT emp_id, pay_date, 0, 'unitemized', gross_pay FROM paychecks WHERE gross_pay <> 0;
+INSERT INTO paycheck_deductions (emp_id, pay_date, seq, name, amount, kind)
+    SELECT emp_id, pay_date, 0, '', deductions, 'unitemized' FROM paychecks AS p
+    WHERE deductions <> 0 AND NOT EXISTS (
+        SELECT 1 FROM paycheck_deductions AS d
+        WHERE d.emp_id = p.emp_id AND d.pay_date = p.pay_date
+    );
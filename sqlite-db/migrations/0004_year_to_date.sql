@@ -0,0 +1,54 @@
+-- 年初来の累計 (従業員・暦年ごと、年は支払日で決まる)
+CREATE TABLE year_to_dates (
+    emp_id    INTEGER NOT NULL,
+    year      INTEGER NOT NULL,
+    gross_pay INTEGER NOT NULL,
+    net_pay   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, year)
+);
+
+-- 控除の種類ごとの累計 (seq はその年に初めて現れた順、kind と name は paycheck_deductions と同じ)
+CREATE TABLE year_to_date_deductions (
+    emp_id INTEGER NOT NULL,
+    year   INTEGER NOT NULL,
+    seq    INTEGER NOT NULL,
+    kind   TEXT NOT NULL
+        CHECK (kind IN ('dues', 'service_charge', 'withholding', 'unitemized')),
+    name   TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, year, seq),
+    FOREIGN KEY (emp_id, year) REFERENCES year_to_dates (emp_id, year) ON DELETE CASCADE
+);
+
+-- Paycheck ごとの、その Paycheck を含めた累計
+-- これより前に記録された Paycheck は NULL
+ALTER TABLE paychecks ADD COLUMN ytd_gross_pay INTEGER;
+ALTER TABLE paychecks ADD COLUMN ytd_net_pay INTEGER;
+CREATE TABLE paycheck_ytd_deductions (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('dues', 'service_charge', 'withholding', 'unitemized')),
+    name     TEXT NOT NULL,
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
+
+-- 記録済みの Paycheck から累計を作っておく
+INSERT INTO year_to_dates (emp_id, year, gross_pay, net_pay)
+    SELECT emp_id, CAST(strftime('%Y', pay_date) AS INTEGER), SUM(gross_pay), SUM(net_pay)
+    FROM paychecks
+    GROUP BY 1, 2;
+INSERT INTO year_to_date_deductions (emp_id, year, seq, kind, name, amount)
+    SELECT emp_id, year,
+           ROW_NUMBER() OVER (PARTITION BY emp_id, year ORDER BY MIN(first_seen)) - 1,
+           kind, name, SUM(amount)
+    FROM (
+        -- 初めて現れた (pay_date, seq) の順に並べるための並び替えキー
+        SELECT emp_id, CAST(strftime('%Y', pay_date) AS INTEGER) AS year,
+               pay_date || printf(':%06d', seq) AS first_seen, kind, name, amount
+        FROM paycheck_deductions
+    )
+    GROUP BY emp_id, year, kind, name;
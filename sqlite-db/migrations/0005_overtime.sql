@@ -0,0 +1,35 @@
+-- 支給の種類に倍額 ('double_time') と土日・祝日の割増 ('premium') を足す
+-- CHECK 制約は変更できないので作り直す
+CREATE TABLE paycheck_earnings_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('salary', 'regular', 'overtime', 'double_time', 'premium',
+                        'commission', 'unitemized')),
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
+INSERT INTO paycheck_earnings_new SELECT emp_id, pay_date, seq, kind, amount FROM paycheck_earnings;
+DROP TABLE paycheck_earnings;
+ALTER TABLE paycheck_earnings_new RENAME TO paycheck_earnings;
+
+-- 従業員ごとの残業の規則 (行がなければ会社全体の規則に従う)
+-- NULL の列はその規則を適用しない
+CREATE TABLE overtime_policies (
+    emp_id            INTEGER PRIMARY KEY REFERENCES employees (id) ON DELETE CASCADE,
+    daily_after       REAL,
+    weekly_after      REAL,
+    double_time_after REAL,
+    weekend_rate      REAL,
+    holiday_rate      REAL,
+    week_start        TEXT NOT NULL
+        CHECK (week_start IN ('Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun'))
+);
+
+CREATE TABLE overtime_holidays (
+    emp_id INTEGER NOT NULL REFERENCES overtime_policies (emp_id) ON DELETE CASCADE,
+    date   TEXT NOT NULL,
+    PRIMARY KEY (emp_id, date)
+);
@@ -0,0 +1,12 @@
+-- 支払い済みの期間のタイムカードの訂正・取り消しの記録
+-- 支払い履歴と同じく従業員を削除しても残す
+CREATE TABLE timecard_audits (
+    id        INTEGER PRIMARY KEY,
+    emp_id    INTEGER NOT NULL,
+    date      TEXT NOT NULL,
+    old_hours REAL NOT NULL,
+    -- NULL なら取り消し
+    new_hours REAL,
+    pay_date  TEXT NOT NULL
+);
+CREATE INDEX timecard_audits_emp_id ON timecard_audits (emp_id);
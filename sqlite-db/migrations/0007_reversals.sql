@@ -0,0 +1,75 @@
+-- 売上・サービス料の取り消し
+-- 番号 (従業員ごとの id の順) を保つために行は消さずに voided を立てる
+-- 支払い済みのものは carried_to に差し戻す日 (次の支払い期間の初日) を持つ
+ALTER TABLE sales_receipts ADD COLUMN voided INTEGER NOT NULL DEFAULT 0 CHECK (voided IN (0, 1));
+ALTER TABLE sales_receipts ADD COLUMN carried_to TEXT;
+ALTER TABLE service_charges ADD COLUMN voided INTEGER NOT NULL DEFAULT 0 CHECK (voided IN (0, 1));
+ALTER TABLE service_charges ADD COLUMN carried_to TEXT;
+
+-- 支給の種類に歩合の差し戻し ('clawback') を、控除の種類にサービス料の払い戻し ('service_charge_refund') を足す
+-- CHECK 制約は変更できないので作り直す
+CREATE TABLE paycheck_earnings_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('salary', 'regular', 'overtime', 'double_time', 'premium',
+                        'commission', 'clawback', 'unitemized')),
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
+INSERT INTO paycheck_earnings_new SELECT emp_id, pay_date, seq, kind, amount FROM paycheck_earnings;
+DROP TABLE paycheck_earnings;
+ALTER TABLE paycheck_earnings_new RENAME TO paycheck_earnings;
+
+CREATE TABLE paycheck_deductions_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    name     TEXT NOT NULL,
+    amount   INTEGER NOT NULL,
+    kind     TEXT NOT NULL DEFAULT 'withholding'
+        CHECK (kind IN ('dues', 'service_charge', 'service_charge_refund', 'withholding',
+                        'unitemized')),
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
+INSERT INTO paycheck_deductions_new (emp_id, pay_date, seq, name, amount, kind)
+    SELECT emp_id, pay_date, seq, name, amount, kind FROM paycheck_deductions;
+DROP TABLE paycheck_deductions;
+ALTER TABLE paycheck_deductions_new RENAME TO paycheck_deductions;
+
+CREATE TABLE year_to_date_deductions_new (
+    emp_id INTEGER NOT NULL,
+    year   INTEGER NOT NULL,
+    seq    INTEGER NOT NULL,
+    kind   TEXT NOT NULL
+        CHECK (kind IN ('dues', 'service_charge', 'service_charge_refund', 'withholding',
+                        'unitemized')),
+    name   TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, year, seq),
+    FOREIGN KEY (emp_id, year) REFERENCES year_to_dates (emp_id, year) ON DELETE CASCADE
+);
+INSERT INTO year_to_date_deductions_new (emp_id, year, seq, kind, name, amount)
+    SELECT emp_id, year, seq, kind, name, amount FROM year_to_date_deductions;
+DROP TABLE year_to_date_deductions;
+ALTER TABLE year_to_date_deductions_new RENAME TO year_to_date_deductions;
+
+CREATE TABLE paycheck_ytd_deductions_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('dues', 'service_charge', 'service_charge_refund', 'withholding',
+                        'unitemized')),
+    name     TEXT NOT NULL,
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
+INSERT INTO paycheck_ytd_deductions_new (emp_id, pay_date, seq, kind, name, amount)
+    SELECT emp_id, pay_date, seq, kind, name, amount FROM paycheck_ytd_deductions;
+DROP TABLE paycheck_ytd_deductions;
+ALTER TABLE paycheck_ytd_deductions_new RENAME TO paycheck_ytd_deductions;
@@ -0,0 +1,37 @@
+-- 効力発生日つきのレートの変更 (最初のレートは classifications に持つ)
+-- 使う列は classifications と同じく種類ごとに決まる
+CREATE TABLE rate_changes (
+    emp_id          INTEGER NOT NULL REFERENCES classifications (emp_id) ON DELETE CASCADE,
+    effective       TEXT NOT NULL,
+    salary          INTEGER,
+    hourly_rate     INTEGER,
+    commission_rate REAL,
+    PRIMARY KEY (emp_id, effective)
+);
+
+-- 支払い済みの期間にさかのぼってレートを変えたときの差額
+-- carried_to (最後の支払日の翌日) を含む支払い期間で支給する
+CREATE TABLE retro_pays (
+    id         INTEGER PRIMARY KEY,
+    emp_id     INTEGER NOT NULL REFERENCES classifications (emp_id) ON DELETE CASCADE,
+    carried_to TEXT NOT NULL,
+    amount     INTEGER NOT NULL
+);
+CREATE INDEX retro_pays_emp_id ON retro_pays (emp_id);
+
+-- 支給の種類にレートの変更の差額 ('retro_pay') を足す
+-- CHECK 制約は変更できないので作り直す
+CREATE TABLE paycheck_earnings_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('salary', 'regular', 'overtime', 'double_time', 'premium',
+                        'commission', 'clawback', 'retro_pay', 'unitemized')),
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, seq),
+    FOREIGN KEY (emp_id, pay_date) REFERENCES paychecks (emp_id, pay_date)
+);
+INSERT INTO paycheck_earnings_new SELECT emp_id, pay_date, seq, kind, amount FROM paycheck_earnings;
+DROP TABLE paycheck_earnings;
+ALTER TABLE paycheck_earnings_new RENAME TO paycheck_earnings;
@@ -0,0 +1,12 @@
+-- 給与の種類を変えたときの、変更前の種類での最後の支払い (変更後の classifications に持つ)
+-- carried_to (効力発生日) を含む支払い期間で支給し、明細の行ごとに 1 行にする
+CREATE TABLE final_pays (
+    id         INTEGER PRIMARY KEY,
+    emp_id     INTEGER NOT NULL REFERENCES classifications (emp_id) ON DELETE CASCADE,
+    carried_to TEXT NOT NULL,
+    kind       TEXT NOT NULL
+        CHECK (kind IN ('salary', 'regular', 'overtime', 'double_time', 'premium',
+                        'commission', 'clawback', 'retro_pay', 'unitemized')),
+    amount     INTEGER NOT NULL
+);
+CREATE INDEX final_pays_emp_id ON final_pays (emp_id);
@@ -0,0 +1,8 @@
+-- 入社日と退職日 (NULL なら制限なし)
+-- 退職日を指定した DelEmp では行を消さずに退職日を記録する
+ALTER TABLE employees ADD COLUMN hire_date TEXT;
+ALTER TABLE employees ADD COLUMN termination_date TEXT;
+
+-- 月給を日割りするときの日数の数え方
+ALTER TABLE overtime_policies ADD COLUMN proration TEXT NOT NULL DEFAULT 'calendar_days'
+    CHECK (proration IN ('calendar_days', 'working_days'));
@@ -0,0 +1,14 @@
+-- 在籍状況の履歴 (休職・復職・退職)
+-- effective はその状況になった最初の日で、退職なら退職日の翌日になる
+CREATE TABLE employment_statuses (
+    emp_id    INTEGER NOT NULL REFERENCES employees (id) ON DELETE CASCADE,
+    effective TEXT NOT NULL,
+    status    TEXT NOT NULL CHECK (status IN ('active', 'on_leave', 'terminated')),
+    PRIMARY KEY (emp_id, effective)
+);
+
+-- 退職日は履歴から求めるので、記録済みの退職日を履歴に移して列を消す
+INSERT INTO employment_statuses (emp_id, effective, status)
+    SELECT id, date(termination_date, '+1 day'), 'terminated' FROM employees
+    WHERE termination_date IS NOT NULL;
+ALTER TABLE employees DROP COLUMN termination_date;
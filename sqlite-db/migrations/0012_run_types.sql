@@ -0,0 +1,81 @@
+-- Paycheck を支払った回の種類: 同じ支払日でも種類が違えば別の Paycheck になる
+-- 支給の種類に賞与 ('bonus') と精算 ('adjustment') を足す
+-- 主キーと CHECK 制約は変更できないので、明細のテーブルも含めて作り直す
+CREATE TABLE paychecks_new (
+    emp_id        INTEGER NOT NULL,
+    pay_date      TEXT NOT NULL,
+    run_type      TEXT NOT NULL DEFAULT 'regular'
+        CHECK (run_type IN ('regular', 'bonus', 'correction', 'final')),
+    period_start  TEXT NOT NULL,
+    gross_pay     INTEGER NOT NULL,
+    deductions    INTEGER NOT NULL,
+    net_pay       INTEGER NOT NULL,
+    ytd_gross_pay INTEGER,
+    ytd_net_pay   INTEGER,
+    PRIMARY KEY (emp_id, pay_date, run_type)
+);
+INSERT INTO paychecks_new
+        (emp_id, pay_date, period_start, gross_pay, deductions, net_pay, ytd_gross_pay, ytd_net_pay)
+    SELECT emp_id, pay_date, period_start, gross_pay, deductions, net_pay, ytd_gross_pay, ytd_net_pay
+    FROM paychecks;
+
+CREATE TABLE paycheck_earnings_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    run_type TEXT NOT NULL DEFAULT 'regular',
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('salary', 'regular', 'overtime', 'double_time', 'premium',
+                        'commission', 'clawback', 'retro_pay', 'bonus', 'adjustment',
+                        'unitemized')),
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, run_type, seq),
+    FOREIGN KEY (emp_id, pay_date, run_type) REFERENCES paychecks_new (emp_id, pay_date, run_type)
+);
+INSERT INTO paycheck_earnings_new (emp_id, pay_date, seq, kind, amount)
+    SELECT emp_id, pay_date, seq, kind, amount FROM paycheck_earnings;
+
+CREATE TABLE paycheck_deductions_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    run_type TEXT NOT NULL DEFAULT 'regular',
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('dues', 'service_charge', 'service_charge_refund', 'withholding',
+                        'unitemized')),
+    name     TEXT NOT NULL,
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, run_type, seq),
+    FOREIGN KEY (emp_id, pay_date, run_type) REFERENCES paychecks_new (emp_id, pay_date, run_type)
+);
+INSERT INTO paycheck_deductions_new (emp_id, pay_date, seq, kind, name, amount)
+    SELECT emp_id, pay_date, seq, kind, name, amount FROM paycheck_deductions;
+
+CREATE TABLE paycheck_ytd_deductions_new (
+    emp_id   INTEGER NOT NULL,
+    pay_date TEXT NOT NULL,
+    run_type TEXT NOT NULL DEFAULT 'regular',
+    seq      INTEGER NOT NULL,
+    kind     TEXT NOT NULL
+        CHECK (kind IN ('dues', 'service_charge', 'service_charge_refund', 'withholding',
+                        'unitemized')),
+    name     TEXT NOT NULL,
+    amount   INTEGER NOT NULL,
+    PRIMARY KEY (emp_id, pay_date, run_type, seq),
+    FOREIGN KEY (emp_id, pay_date, run_type) REFERENCES paychecks_new (emp_id, pay_date, run_type)
+);
+INSERT INTO paycheck_ytd_deductions_new (emp_id, pay_date, seq, kind, name, amount)
+    SELECT emp_id, pay_date, seq, kind, name, amount FROM paycheck_ytd_deductions;
+
+-- 明細のテーブルを先に消してから Paycheck のテーブルを消す
+-- paychecks_new を改名すると、明細のテーブルの外部キーも新しい名前を指すようになる
+DROP TABLE paycheck_earnings;
+DROP TABLE paycheck_deductions;
+DROP TABLE paycheck_ytd_deductions;
+DROP TABLE paychecks;
+ALTER TABLE paychecks_new RENAME TO paychecks;
+ALTER TABLE paycheck_earnings_new RENAME TO paycheck_earnings;
+ALTER TABLE paycheck_deductions_new RENAME TO paycheck_deductions;
+ALTER TABLE paycheck_ytd_deductions_new RENAME TO paycheck_ytd_deductions;
+CREATE INDEX paychecks_pay_date ON paychecks (pay_date);
+
@@ -0,0 +1,18 @@
+-- 支払いスケジュールに 15 日と月末 ('semimonthly')、日付指定の月払い ('monthly_on')、
+-- 曜日指定の週払い ('weekly_on')、起算日指定の隔週払い ('biweekly_from') を足す
+-- day, weekday, anchor はそれぞれのスケジュールの設定で、ほかの種類では NULL
+-- start_date はスケジュールだけを変えたときの最初の支払い期間の初日
+-- CHECK 制約は変更できないので作り直す
+CREATE TABLE schedules_new (
+    emp_id     INTEGER PRIMARY KEY REFERENCES employees (id) ON DELETE CASCADE,
+    kind       TEXT NOT NULL
+        CHECK (kind IN ('monthly', 'weekly', 'biweekly', 'semimonthly', 'monthly_on',
+                        'weekly_on', 'biweekly_from')),
+    day        INTEGER CHECK (day BETWEEN 1 AND 31),
+    weekday    TEXT CHECK (weekday IN ('Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun')),
+    anchor     TEXT,
+    start_date TEXT
+);
+INSERT INTO schedules_new (emp_id, kind) SELECT emp_id, kind FROM schedules;
+DROP TABLE schedules;
+ALTER TABLE schedules_new RENAME TO schedules;
@@ -0,0 +1,3 @@
+-- 隔週払い ('biweekly') は ISO 週番号の偶奇ではなく、起算日指定の隔週払い ('biweekly_from') として 2 週ごとに支払う
+-- 既存の従業員にはそれまでの支払日 (偶数週の金曜日) に合う起算日を入れる
+UPDATE schedules SET kind = 'biweekly_from', anchor = '2024-12-28' WHERE kind = 'biweekly';
@@ -0,0 +1,2 @@
+-- 祝日は会社の暦 (--calendar) にまとめたので、従業員ごとの残業の規則の祝日は持たない
+DROP TABLE overtime_holidays;
@@ -0,0 +1,733 @@
+// Employee とテーブルの行との相互変換
+// trait object の中身は as_any でダウンキャストして具体的な型ごとに行に落とす
+use chrono::NaiveDate;
+use log::trace;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+
+use dao::DaoError;
+use payroll_domain::{
+    Affiliation, EarningLine, Employee, EmployeeId, EmploymentStatus, FilingStatus, Money,
+    NoAffiliation, OvertimePolicy, PaymentClassification, PaymentMethod, PaymentSchedule,
+    Proration, StatusChange, TaxProfile,
+};
+use payroll_impl::{
+    BiweeklyFromSchedule, CommissionedClassification, DirectMethod, FinalPay, HoldMethod,
+    HourlyClassification, MailMethod, MonthlyOnSchedule, MonthlySchedule, RetroPay, Reversal,
+    SalariedClassification, SemimonthlySchedule, UnionAffiliation, WeeklyOnSchedule,
+    WeeklySchedule,
+};
+
+use crate::paycheck::{earning_kind, parse_earning_kind};
+
+// employees の行 (name, address, filing_status, allowances, hire_date)
+type EmployeeRow = (String, String, String, u32, Option<NaiveDate>);
+// schedules の行 (kind, day, weekday, anchor)
+type ScheduleRow = (String, Option<u32>, Option<String>, Option<NaiveDate>);
+
+pub fn db_error(e: rusqlite::Error) -> DaoError {
+    DaoError::UnexpectedError(format!("sqlite: {}", e))
+}
+
+fn unsupported(kind: &str, emp_id: EmployeeId) -> DaoError {
+    DaoError::UnexpectedError(format!(
+        "unsupported {} for emp_id={}: can't persist it",
+        kind, emp_id
+    ))
+}
+
+pub fn unknown(kind: &str, value: &str, emp_id: EmployeeId) -> DaoError {
+    DaoError::UnexpectedError(format!(
+        "unknown {} '{}' for emp_id={}",
+        kind, value, emp_id
+    ))
+}
+
+fn filing_status_kind(status: FilingStatus) -> &'static str {
+    match status {
+        FilingStatus::Single => "single",
+        FilingStatus::Married => "married",
+        FilingStatus::HeadOfHousehold => "head_of_household",
+    }
+}
+
+fn proration_kind(proration: Proration) -> &'static str {
+    match proration {
+        Proration::CalendarDays => "calendar_days",
+        Proration::WorkingDays => "working_days",
+    }
+}
+
+fn employment_status_kind(status: EmploymentStatus) -> &'static str {
+    match status {
+        EmploymentStatus::Active => "active",
+        EmploymentStatus::OnLeave => "on_leave",
+        EmploymentStatus::Terminated => "terminated",
+    }
+}
+
+pub fn insert_employee(conn: &Connection, emp: &Employee) -> Result<(), DaoError> {
+    trace!("insert_employee called: emp_id={}", emp.id());
+    let id = u32::from(emp.id());
+    let tax = emp.tax_profile();
+    conn.execute(
+        "INSERT INTO employees (id, name, address, filing_status, allowances, hire_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            id,
+            emp.name(),
+            emp.address(),
+            filing_status_kind(tax.filing_status),
+            tax.allowances,
+            emp.hire_date()
+        ],
+    )
+    .map_err(db_error)?;
+    insert_parts(conn, emp)
+}
+
+pub fn update_employee(conn: &Connection, emp: &Employee) -> Result<(), DaoError> {
+    trace!("update_employee called: emp_id={}", emp.id());
+    let id = u32::from(emp.id());
+    let tax = emp.tax_profile();
+    conn.execute(
+        "UPDATE employees SET name = ?2, address = ?3, filing_status = ?4, allowances = ?5, hire_date = ?6 WHERE id = ?1",
+        params![
+            id,
+            emp.name(),
+            emp.address(),
+            filing_status_kind(tax.filing_status),
+            tax.allowances,
+            emp.hire_date()
+        ],
+    )
+    .map_err(db_error)?;
+    // 明細行ごと入れ替える (timecards などは ON DELETE CASCADE で消える)
+    for table in [
+        "classifications",
+        "schedules",
+        "methods",
+        "affiliations",
+        "overtime_policies",
+        "employment_statuses",
+    ] {
+        conn.execute(&format!("DELETE FROM {} WHERE emp_id = ?1", table), [id])
+            .map_err(db_error)?;
+    }
+    insert_parts(conn, emp)
+}
+
+fn insert_parts(conn: &Connection, emp: &Employee) -> Result<(), DaoError> {
+    let id = u32::from(emp.id());
+    {
+        let c = emp.classification();
+        let c = c.lock().unwrap();
+        insert_classification(conn, emp.id(), &*c)?;
+    }
+    {
+        let s = emp.schedule();
+        let s = s.lock().unwrap();
+        let any = s.as_any();
+        let (kind, day, weekday, anchor) = if any.is::<MonthlySchedule>() {
+            ("monthly", None, None, None)
+        } else if any.is::<WeeklySchedule>() {
+            ("weekly", None, None, None)
+        } else if any.is::<SemimonthlySchedule>() {
+            ("semimonthly", None, None, None)
+        } else if let Some(s) = any.downcast_ref::<MonthlyOnSchedule>() {
+            ("monthly_on", Some(s.day()), None, None)
+        } else if let Some(s) = any.downcast_ref::<WeeklyOnSchedule>() {
+            ("weekly_on", None, Some(s.weekday().to_string()), None)
+        } else if let Some(s) = any.downcast_ref::<BiweeklyFromSchedule>() {
+            ("biweekly_from", None, None, Some(s.anchor()))
+        } else {
+            return Err(unsupported("schedule", emp.id()));
+        };
+        conn.execute(
+            "INSERT INTO schedules (emp_id, kind, day, weekday, anchor, start_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![id, kind, day, weekday, anchor, emp.schedule_start()],
+        )
+        .map_err(db_error)?;
+    }
+    {
+        let m = emp.method();
+        let m = m.lock().unwrap();
+        insert_method(conn, emp.id(), &*m)?;
+    }
+    {
+        let a = emp.affiliation();
+        let a = a.lock().unwrap();
+        insert_affiliation(conn, emp.id(), &*a)?;
+    }
+    if let Some(policy) = emp.overtime_policy() {
+        insert_overtime_policy(conn, emp.id(), policy)?;
+    }
+    for change in emp.status_changes() {
+        conn.execute(
+            "INSERT INTO employment_statuses (emp_id, effective, status) VALUES (?1, ?2, ?3)",
+            params![id, change.effective, employment_status_kind(change.status)],
+        )
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+fn insert_classification(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    c: &dyn PaymentClassification,
+) -> Result<(), DaoError> {
+    let id = u32::from(emp_id);
+    let any = c.as_any();
+    // 最初のレートは classifications に、効力発生日つきの変更は rate_changes に入れる
+    if let Some(c) = any.downcast_ref::<SalariedClassification>() {
+        for (effective, salary) in c.rate_history() {
+            let sql = match effective {
+                None => {
+                    "INSERT INTO classifications (emp_id, kind, salary) VALUES (?1, 'salaried', ?3)"
+                }
+                Some(_) => {
+                    "INSERT INTO rate_changes (emp_id, effective, salary) VALUES (?1, ?2, ?3)"
+                }
+            };
+            conn.execute(sql, params![id, effective, salary.cents()])
+                .map_err(db_error)?;
+        }
+        insert_retro_pays(conn, emp_id, c.retro_pays())?;
+        insert_final_pays(conn, emp_id, c.final_pays())?;
+    } else if let Some(c) = any.downcast_ref::<HourlyClassification>() {
+        for (effective, hourly_rate) in c.rate_history() {
+            let sql = match effective {
+                None => "INSERT INTO classifications (emp_id, kind, hourly_rate) VALUES (?1, 'hourly', ?3)",
+                Some(_) => "INSERT INTO rate_changes (emp_id, effective, hourly_rate) VALUES (?1, ?2, ?3)",
+            };
+            conn.execute(sql, params![id, effective, hourly_rate.cents()])
+                .map_err(db_error)?;
+        }
+        insert_retro_pays(conn, emp_id, c.retro_pays())?;
+        insert_final_pays(conn, emp_id, c.final_pays())?;
+        for (date, hours) in c.timecards() {
+            conn.execute(
+                "INSERT INTO timecards (emp_id, date, hours) VALUES (?1, ?2, ?3)",
+                params![id, date, hours],
+            )
+            .map_err(db_error)?;
+        }
+    } else if let Some(c) = any.downcast_ref::<CommissionedClassification>() {
+        for (effective, salary, commission_rate) in c.rate_history() {
+            let sql = match effective {
+                None => "INSERT INTO classifications (emp_id, kind, salary, commission_rate) VALUES (?1, 'commissioned', ?3, ?4)",
+                Some(_) => "INSERT INTO rate_changes (emp_id, effective, salary, commission_rate) VALUES (?1, ?2, ?3, ?4)",
+            };
+            conn.execute(sql, params![id, effective, salary.cents(), commission_rate])
+                .map_err(db_error)?;
+        }
+        insert_retro_pays(conn, emp_id, c.retro_pays())?;
+        insert_final_pays(conn, emp_id, c.final_pays())?;
+        for (_, date, amount, reversal) in c.sales_receipts() {
+            let (voided, carried_to) = reversal_columns(reversal);
+            conn.execute(
+                "INSERT INTO sales_receipts (emp_id, date, amount, voided, carried_to)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, date, amount.cents(), voided, carried_to],
+            )
+            .map_err(db_error)?;
+        }
+    } else {
+        return Err(unsupported("classification", emp_id));
+    }
+    Ok(())
+}
+
+fn insert_retro_pays(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    retro_pays: impl Iterator<Item = RetroPay>,
+) -> Result<(), DaoError> {
+    for rp in retro_pays {
+        conn.execute(
+            "INSERT INTO retro_pays (emp_id, carried_to, amount) VALUES (?1, ?2, ?3)",
+            params![u32::from(emp_id), rp.carried_to, rp.amount.cents()],
+        )
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+// 最後の支払いは明細の行ごとに 1 行にする
+fn insert_final_pays<'a>(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    final_pays: impl Iterator<Item = &'a FinalPay>,
+) -> Result<(), DaoError> {
+    for fp in final_pays {
+        for line in &fp.lines {
+            conn.execute(
+                "INSERT INTO final_pays (emp_id, carried_to, kind, amount) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    u32::from(emp_id),
+                    fp.carried_to,
+                    earning_kind(line.kind),
+                    line.amount.cents()
+                ],
+            )
+            .map_err(db_error)?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_method(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    m: &dyn PaymentMethod,
+) -> Result<(), DaoError> {
+    let id = u32::from(emp_id);
+    let any = m.as_any();
+    if any.is::<HoldMethod>() {
+        conn.execute(
+            "INSERT INTO methods (emp_id, kind) VALUES (?1, 'hold')",
+            params![id],
+        )
+    } else if let Some(m) = any.downcast_ref::<DirectMethod>() {
+        conn.execute(
+            "INSERT INTO methods (emp_id, kind, bank, account) VALUES (?1, 'direct', ?2, ?3)",
+            params![id, m.bank(), m.account()],
+        )
+    } else if let Some(m) = any.downcast_ref::<MailMethod>() {
+        conn.execute(
+            "INSERT INTO methods (emp_id, kind, address) VALUES (?1, 'mail', ?2)",
+            params![id, m.address()],
+        )
+    } else {
+        return Err(unsupported("method", emp_id));
+    }
+    .map_err(db_error)?;
+    Ok(())
+}
+
+fn insert_affiliation(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    a: &dyn Affiliation,
+) -> Result<(), DaoError> {
+    let id = u32::from(emp_id);
+    let any = a.as_any();
+    if any.is::<NoAffiliation>() {
+        return Ok(());
+    }
+    let Some(a) = any.downcast_ref::<UnionAffiliation>() else {
+        return Err(unsupported("affiliation", emp_id));
+    };
+    conn.execute(
+        "INSERT INTO affiliations (emp_id, member_id, dues) VALUES (?1, ?2, ?3)",
+        params![id, u32::from(a.member_id()), a.dues().cents()],
+    )
+    .map_err(db_error)?;
+    for (_, date, amount, reversal) in a.service_charges() {
+        let (voided, carried_to) = reversal_columns(reversal);
+        conn.execute(
+            "INSERT INTO service_charges (emp_id, date, amount, voided, carried_to)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id, date, amount.cents(), voided, carried_to],
+        )
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+fn insert_overtime_policy(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    policy: &OvertimePolicy,
+) -> Result<(), DaoError> {
+    let id = u32::from(emp_id);
+    conn.execute(
+        "INSERT INTO overtime_policies (emp_id, daily_after, weekly_after, double_time_after, weekend_rate, holiday_rate, week_start, proration) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            id,
+            policy.daily_after,
+            policy.weekly_after,
+            policy.double_time_after,
+            policy.weekend_rate,
+            policy.holiday_rate,
+            policy.week_start.to_string(),
+            proration_kind(policy.proration)
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+pub fn select_employee(conn: &Connection, emp_id: EmployeeId) -> Result<Employee, DaoError> {
+    trace!("select_employee called: emp_id={}", emp_id);
+    let id = u32::from(emp_id);
+    let (name, address, filing_status, allowances, hire_date): EmployeeRow = conn
+        .query_row(
+            "SELECT name, address, filing_status, allowances, hire_date FROM employees WHERE id = ?1",
+            [id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(db_error)?
+        .ok_or(DaoError::EmployeeNotFound(emp_id))?;
+    let filing_status = FilingStatus::ALL
+        .into_iter()
+        .find(|s| filing_status_kind(*s) == filing_status)
+        .ok_or_else(|| unknown("filing status", &filing_status, emp_id))?;
+
+    let mut emp = Employee::new(
+        emp_id,
+        &name,
+        &address,
+        select_classification(conn, emp_id)?,
+        select_schedule(conn, emp_id)?,
+        select_method(conn, emp_id)?,
+        select_affiliation(conn, emp_id)?,
+    );
+    emp.set_tax_profile(TaxProfile::new(filing_status, allowances));
+    emp.set_overtime_policy(select_overtime_policy(conn, emp_id)?);
+    emp.set_hire_date(hire_date);
+    emp.set_schedule_start(select_schedule_start(conn, emp_id)?);
+    emp.set_status_changes(select_status_changes(conn, emp_id)?);
+    Ok(emp)
+}
+
+// 効力発生日の順に読む
+fn select_status_changes(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Vec<StatusChange>, DaoError> {
+    let rows: Vec<(NaiveDate, String)> = select_dated(
+        conn,
+        "SELECT effective, status FROM employment_statuses WHERE emp_id = ?1 ORDER BY effective",
+        emp_id,
+    )?;
+    rows.into_iter()
+        .map(|(effective, status)| {
+            let status = EmploymentStatus::ALL
+                .into_iter()
+                .find(|s| employment_status_kind(*s) == status)
+                .ok_or_else(|| unknown("employment status", &status, emp_id))?;
+            Ok(StatusChange::new(effective, status))
+        })
+        .collect()
+}
+
+fn select_overtime_policy(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Option<OvertimePolicy>, DaoError> {
+    let id = u32::from(emp_id);
+    let Some((policy, week_start, proration)) = conn
+        .query_row(
+            "SELECT daily_after, weekly_after, double_time_after, weekend_rate, holiday_rate, week_start, proration FROM overtime_policies WHERE emp_id = ?1",
+            [id],
+            |row| {
+                let policy = OvertimePolicy {
+                    daily_after: row.get(0)?,
+                    weekly_after: row.get(1)?,
+                    double_time_after: row.get(2)?,
+                    weekend_rate: row.get(3)?,
+                    holiday_rate: row.get(4)?,
+                    ..OvertimePolicy::none()
+                };
+                Ok((
+                    policy,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, String>(6)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(db_error)?
+    else {
+        return Ok(None);
+    };
+    let week_start = week_start
+        .parse()
+        .map_err(|_| unknown("week start", &week_start, emp_id))?;
+    let proration = [Proration::CalendarDays, Proration::WorkingDays]
+        .into_iter()
+        .find(|p| proration_kind(*p) == proration)
+        .ok_or_else(|| unknown("proration", &proration, emp_id))?;
+    Ok(Some(OvertimePolicy {
+        week_start,
+        proration,
+        ..policy
+    }))
+}
+
+fn select_dated<T: rusqlite::types::FromSql>(
+    conn: &Connection,
+    sql: &str,
+    emp_id: EmployeeId,
+) -> Result<Vec<(NaiveDate, T)>, DaoError> {
+    let mut stmt = conn.prepare(sql).map_err(db_error)?;
+    let rows = stmt
+        .query_map([u32::from(emp_id)], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(db_error)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(db_error)
+}
+
+// 売上・サービス料の取り消しは (voided, carried_to) の 2 列に持つ
+fn reversal_columns(reversal: Option<Reversal>) -> (bool, Option<NaiveDate>) {
+    (reversal.is_some(), reversal.and_then(|r| r.carried_to()))
+}
+
+// 売上・サービス料を番号 (= id の順) に並べて読む
+fn select_reversible(
+    conn: &Connection,
+    table: &str,
+    emp_id: EmployeeId,
+) -> Result<Vec<(NaiveDate, Money, Option<Reversal>)>, DaoError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT date, amount, voided, carried_to FROM {} WHERE emp_id = ?1 ORDER BY id",
+            table
+        ))
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map([u32::from(emp_id)], |row| {
+            let reversal = match (row.get::<_, bool>(2)?, row.get(3)?) {
+                (false, _) => None,
+                (true, None) => Some(Reversal::Voided),
+                (true, Some(date)) => Some(Reversal::CarriedTo(date)),
+            };
+            Ok((row.get(0)?, Money::from_cents(row.get(1)?), reversal))
+        })
+        .map_err(db_error)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(db_error)
+}
+
+// (効力発生日, salary, hourly_rate, commission_rate)
+type RateChangeRow = (NaiveDate, Option<i64>, Option<i64>, Option<f32>);
+
+// 効力発生日の順に読む
+fn select_rate_changes(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Vec<RateChangeRow>, DaoError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT effective, salary, hourly_rate, commission_rate FROM rate_changes
+             WHERE emp_id = ?1 ORDER BY effective",
+        )
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map([u32::from(emp_id)], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .map_err(db_error)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(db_error)
+}
+
+// 同じ carried_to の行をまとめて 1 つの最後の支払いにする
+fn select_final_pays(conn: &Connection, emp_id: EmployeeId) -> Result<Vec<FinalPay>, DaoError> {
+    let mut stmt = conn
+        .prepare("SELECT carried_to, kind, amount FROM final_pays WHERE emp_id = ?1 ORDER BY id")
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map([u32::from(emp_id)], |row| {
+            Ok((
+                row.get::<_, NaiveDate>(0)?,
+                row.get::<_, String>(1)?,
+                row.get(2)?,
+            ))
+        })
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    let mut final_pays: Vec<FinalPay> = vec![];
+    for (carried_to, kind, amount) in rows {
+        let line = EarningLine::new(
+            parse_earning_kind(&kind, emp_id)?,
+            Money::from_cents(amount),
+        );
+        match final_pays.last_mut() {
+            Some(fp) if fp.carried_to == carried_to => fp.lines.push(line),
+            _ => final_pays.push(FinalPay::new(carried_to, vec![line])),
+        }
+    }
+    Ok(final_pays)
+}
+
+fn select_classification(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Arc<Mutex<dyn PaymentClassification>>, DaoError> {
+    let (kind, salary, hourly_rate, commission_rate): (
+        String,
+        Option<i64>,
+        Option<i64>,
+        Option<f32>,
+    ) = conn
+        .query_row(
+            "SELECT kind, salary, hourly_rate, commission_rate FROM classifications WHERE emp_id = ?1",
+            [u32::from(emp_id)],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(db_error)?;
+    let money = |cents: Option<i64>| Money::from_cents(cents.unwrap_or_default());
+    let rate_changes = select_rate_changes(conn, emp_id)?;
+    let retro_pays = select_dated::<i64>(
+        conn,
+        "SELECT carried_to, amount FROM retro_pays WHERE emp_id = ?1 ORDER BY id",
+        emp_id,
+    )?
+    .into_iter()
+    .map(|(carried_to, amount)| RetroPay::new(carried_to, Money::from_cents(amount)));
+    let final_pays = select_final_pays(conn, emp_id)?;
+
+    match kind.as_str() {
+        "salaried" => {
+            let mut c = SalariedClassification::new(money(salary));
+            for (effective, salary, _, _) in rate_changes {
+                c.change_rate(Some(effective), money(salary));
+            }
+            retro_pays.for_each(|rp| c.add_retro_pay(rp));
+            final_pays.into_iter().for_each(|fp| c.add_final_pay(fp));
+            Ok(Arc::new(Mutex::new(c)))
+        }
+        "hourly" => {
+            let mut c = HourlyClassification::new(money(hourly_rate));
+            for (effective, _, hourly_rate, _) in rate_changes {
+                c.change_rate(Some(effective), money(hourly_rate));
+            }
+            retro_pays.for_each(|rp| c.add_retro_pay(rp));
+            final_pays.into_iter().for_each(|fp| c.add_final_pay(fp));
+            let timecards = select_dated::<f32>(
+                conn,
+                "SELECT date, hours FROM timecards WHERE emp_id = ?1 ORDER BY id",
+                emp_id,
+            )?;
+            for (date, hours) in timecards {
+                c.add_timecard(date, hours);
+            }
+            Ok(Arc::new(Mutex::new(c)))
+        }
+        "commissioned" => {
+            let mut c =
+                CommissionedClassification::new(money(salary), commission_rate.unwrap_or_default());
+            for (effective, salary, _, commission_rate) in rate_changes {
+                c.change_rate(
+                    Some(effective),
+                    money(salary),
+                    commission_rate.unwrap_or_default(),
+                );
+            }
+            retro_pays.for_each(|rp| c.add_retro_pay(rp));
+            final_pays.into_iter().for_each(|fp| c.add_final_pay(fp));
+            for (date, amount, reversal) in select_reversible(conn, "sales_receipts", emp_id)? {
+                c.restore_sales_receipt(date, amount, reversal);
+            }
+            Ok(Arc::new(Mutex::new(c)))
+        }
+        _ => Err(unknown("classification", &kind, emp_id)),
+    }
+}
+
+fn select_schedule(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Arc<Mutex<dyn PaymentSchedule>>, DaoError> {
+    let (kind, day, weekday, anchor): ScheduleRow = conn
+        .query_row(
+            "SELECT kind, day, weekday, anchor FROM schedules WHERE emp_id = ?1",
+            [u32::from(emp_id)],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(db_error)?;
+
+    let missing =
+        |column: &str| unknown("schedule", &format!("{} without {}", kind, column), emp_id);
+    match kind.as_str() {
+        "monthly" => Ok(Arc::new(Mutex::new(MonthlySchedule))),
+        "weekly" => Ok(Arc::new(Mutex::new(WeeklySchedule))),
+        "semimonthly" => Ok(Arc::new(Mutex::new(SemimonthlySchedule))),
+        "monthly_on" => {
+            let day = day.ok_or_else(|| missing("day"))?;
+            Ok(Arc::new(Mutex::new(MonthlyOnSchedule::new(day))))
+        }
+        "weekly_on" => {
+            let weekday = weekday.ok_or_else(|| missing("weekday"))?;
+            let weekday = weekday
+                .parse()
+                .map_err(|_| unknown("weekday", &weekday, emp_id))?;
+            Ok(Arc::new(Mutex::new(WeeklyOnSchedule::new(weekday))))
+        }
+        "biweekly_from" => {
+            let anchor = anchor.ok_or_else(|| missing("anchor"))?;
+            Ok(Arc::new(Mutex::new(BiweeklyFromSchedule::new(anchor))))
+        }
+        _ => Err(unknown("schedule", &kind, emp_id)),
+    }
+}
+
+// スケジュールだけを変えたときの最初の支払い期間の初日
+fn select_schedule_start(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Option<NaiveDate>, DaoError> {
+    conn.query_row(
+        "SELECT start_date FROM schedules WHERE emp_id = ?1",
+        [u32::from(emp_id)],
+        |row| row.get(0),
+    )
+    .map_err(db_error)
+}
+
+fn select_method(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Arc<Mutex<dyn PaymentMethod>>, DaoError> {
+    let (kind, bank, account, address): (String, Option<String>, Option<String>, Option<String>) =
+        conn.query_row(
+            "SELECT kind, bank, account, address FROM methods WHERE emp_id = ?1",
+            [u32::from(emp_id)],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(db_error)?;
+
+    match kind.as_str() {
+        "hold" => Ok(Arc::new(Mutex::new(HoldMethod))),
+        "direct" => Ok(Arc::new(Mutex::new(DirectMethod::new(
+            &bank.unwrap_or_default(),
+            &account.unwrap_or_default(),
+        )))),
+        "mail" => Ok(Arc::new(Mutex::new(MailMethod::new(
+            &address.unwrap_or_default(),
+        )))),
+        _ => Err(unknown("method", &kind, emp_id)),
+    }
+}
+
+fn select_affiliation(
+    conn: &Connection,
+    emp_id: EmployeeId,
+) -> Result<Arc<Mutex<dyn Affiliation>>, DaoError> {
+    let union: Option<(u32, i64)> = conn
+        .query_row(
+            "SELECT member_id, dues FROM affiliations WHERE emp_id = ?1",
+            [u32::from(emp_id)],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(db_error)?;
+    let Some((member_id, dues)) = union else {
+        return Ok(Arc::new(Mutex::new(NoAffiliation)));
+    };
+
+    let mut a = UnionAffiliation::new(member_id.into(), Money::from_cents(dues));
+    for (date, amount, reversal) in select_reversible(conn, "service_charges", emp_id)? {
+        a.restore_service_charge(date, amount, reversal);
+    }
+    Ok(Arc::new(Mutex::new(a)))
+}
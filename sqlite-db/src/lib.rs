@@ -0,0 +1,958 @@
+// dao の具体的な実装
+// 組み込みの SQLite を使ってテーブルに永続化する
+use chrono::NaiveDate;
+use log::{debug, trace, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{
+    fmt,
+    ops::RangeInclusive,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use dao::{DaoError, EmployeeDao};
+use payroll_domain::{
+    Employee, EmployeeId, MemberId, Paycheck, RunType, TimeCardAudit, YearToDate,
+};
+
+mod employee;
+mod migration;
+mod paycheck;
+
+use employee::{db_error, insert_employee, select_employee, update_employee};
+use paycheck::{
+    fill_details, insert_details, paycheck_from_row, run_type_kind, select_year_to_date,
+    upsert_year_to_date,
+};
+
+#[derive(Clone)]
+pub struct SqliteDB {
+    // run_tx の間はこの lock を保持して、BEGIN から COMMIT/ROLLBACK までを直列化する
+    serial: Arc<Mutex<()>>,
+    conn: Arc<Mutex<Connection>>,
+}
+impl fmt::Debug for SqliteDB {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let conn = self.conn.lock().unwrap();
+        f.debug_struct("SqliteDB")
+            .field("path", &conn.path())
+            .finish()
+    }
+}
+impl SqliteDB {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DaoError> {
+        trace!("SqliteDB::open called: path={}", path.as_ref().display());
+        Self::setup(Connection::open(path).map_err(db_error)?)
+    }
+    pub fn open_in_memory() -> Result<Self, DaoError> {
+        trace!("SqliteDB::open_in_memory called");
+        Self::setup(Connection::open_in_memory().map_err(db_error)?)
+    }
+    fn setup(mut conn: Connection) -> Result<Self, DaoError> {
+        conn.pragma_update(None, "foreign_keys", true)
+            .map_err(db_error)?;
+        migration::migrate(&mut conn).map_err(db_error)?;
+        Ok(Self {
+            serial: Arc::new(Mutex::new(())),
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+}
+
+// DB の実装ごとに EmployeeDao トレイトを実装する
+impl EmployeeDao for SqliteDB {
+    type Ctx<'a> = MutexGuard<'a, Connection>;
+
+    fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
+    {
+        trace!("run_tx called");
+        let _serial = self.serial.lock().unwrap();
+        self.conn
+            .lock()
+            .unwrap()
+            .execute_batch("BEGIN")
+            .map_err(db_error)?;
+
+        let result = f(self.conn.lock().unwrap());
+
+        let conn = self.conn.lock().unwrap();
+        let result = result.and_then(|v| {
+            conn.execute_batch("COMMIT").map_err(db_error)?;
+            Ok(v)
+        });
+        if let Err(e) = &result {
+            warn!("run_tx: rollback: {}", e);
+            // COMMIT に失敗した場合でもトランザクションが残っていれば巻き戻す
+            if !conn.is_autocommit() {
+                conn.execute_batch("ROLLBACK").map_err(db_error)?;
+            }
+        }
+        result
+    }
+    fn run_tx_rollback<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
+    where
+        F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
+    {
+        trace!("run_tx_rollback called");
+        let _serial = self.serial.lock().unwrap();
+        self.conn
+            .lock()
+            .unwrap()
+            .execute_batch("BEGIN")
+            .map_err(db_error)?;
+
+        let result = f(self.conn.lock().unwrap());
+        // 成功しても失敗しても COMMIT せずに巻き戻す
+        self.conn
+            .lock()
+            .unwrap()
+            .execute_batch("ROLLBACK")
+            .map_err(db_error)?;
+        result
+    }
+
+    fn add<'a>(
+        &self,
+        emp: Employee,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
+        trace!("add called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            let emp_id = emp.id();
+            trace!("add::with_tx called: emp_id={},emp={:?}", emp_id, emp);
+            let exists = tx
+                .query_row(
+                    "SELECT 1 FROM employees WHERE id = ?1",
+                    [u32::from(emp_id)],
+                    |_| Ok(()),
+                )
+                .optional()
+                .map_err(db_error)?;
+            if exists.is_some() {
+                return Err(DaoError::EmployeeAlreadyExists(emp_id));
+            }
+            insert_employee(tx, &emp)?;
+            Ok(emp_id)
+        })
+    }
+    fn delete<'a>(
+        &self,
+        id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("delete called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("delete::with_tx called: id={}", id);
+            let deleted = tx
+                .execute("DELETE FROM employees WHERE id = ?1", [u32::from(id)])
+                .map_err(db_error)?;
+            if deleted > 0 {
+                return Ok(());
+            }
+            Err(DaoError::EmployeeNotFound(id))
+        })
+    }
+    fn fetch<'a>(
+        &self,
+        id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Employee, Err = DaoError> {
+        trace!("fetch called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch::with_tx called: id={}", id);
+            select_employee(tx, id)
+        })
+    }
+    fn fetch_all<'a>(
+        &self,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Employee)>, Err = DaoError> {
+        trace!("fetch_all called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_all::with_tx called");
+            let ids = {
+                let mut stmt = tx
+                    .prepare("SELECT id FROM employees ORDER BY id")
+                    .map_err(db_error)?;
+                let rows = stmt
+                    .query_map([], |row| row.get::<_, u32>(0))
+                    .map_err(db_error)?;
+                rows.collect::<Result<Vec<_>, _>>().map_err(db_error)?
+            };
+            ids.into_iter()
+                .map(|id| {
+                    let id = EmployeeId::from(id);
+                    select_employee(tx, id).map(|emp| (id, emp))
+                })
+                .collect()
+        })
+    }
+    fn update<'a>(
+        &self,
+        emp: Employee,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("save called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            let emp_id = emp.id();
+            trace!("save::with_tx called: emp_id={},emp={:?}", emp_id, emp);
+            let exists = tx
+                .query_row(
+                    "SELECT 1 FROM employees WHERE id = ?1",
+                    [u32::from(emp_id)],
+                    |_| Ok(()),
+                )
+                .optional()
+                .map_err(db_error)?;
+            if exists.is_some() {
+                return update_employee(tx, &emp);
+            }
+            Err(DaoError::EmployeeNotFound(emp_id))
+        })
+    }
+    fn add_union_member<'a>(
+        &self,
+        member_id: MemberId,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("add_union_member called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "add_union_member::with_tx called: member_id={},emp_id={}",
+                member_id,
+                emp_id
+            );
+            let inserted = tx
+                .execute(
+                    "INSERT OR IGNORE INTO union_members (member_id, emp_id) VALUES (?1, ?2)",
+                    params![u32::from(member_id), u32::from(emp_id)],
+                )
+                .map_err(db_error)?;
+            if inserted == 0 {
+                return Err(DaoError::MemberAlreadyExists(member_id, emp_id));
+            }
+            Ok(())
+        })
+    }
+    fn delete_union_member<'a>(
+        &self,
+        member_id: MemberId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("delete_union_member called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "delete_union_member::with_tx called: member_id={}",
+                member_id
+            );
+            let deleted = tx
+                .execute(
+                    "DELETE FROM union_members WHERE member_id = ?1",
+                    [u32::from(member_id)],
+                )
+                .map_err(db_error)?;
+            if deleted == 0 {
+                return Err(DaoError::MemberNotFound(member_id));
+            }
+            Ok(())
+        })
+    }
+    fn find_union_member<'a>(
+        &self,
+        member_id: MemberId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
+        trace!("find_union_members called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "find_union_members::with_tx called: member_id={}",
+                member_id
+            );
+            tx.query_row(
+                "SELECT emp_id FROM union_members WHERE member_id = ?1",
+                [u32::from(member_id)],
+                |row| row.get::<_, u32>(0),
+            )
+            .optional()
+            .map_err(db_error)?
+            .map(EmployeeId::from)
+            .ok_or(DaoError::MemberNotFound(member_id))
+        })
+    }
+    fn find_member_ids<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<MemberId>, Err = DaoError> {
+        trace!("find_member_ids called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("find_member_ids::with_tx called: emp_id={}", emp_id);
+            let mut stmt = tx
+                .prepare("SELECT member_id FROM union_members WHERE emp_id = ?1 ORDER BY member_id")
+                .map_err(db_error)?;
+            let rows = stmt
+                .query_map([u32::from(emp_id)], |row| row.get::<_, u32>(0))
+                .map_err(db_error)?;
+            rows.map(|r| r.map(MemberId::from))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(db_error)
+        })
+    }
+    fn record_paycheck<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pc: Paycheck,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("record_paycheck called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "record_paycheck::with_tx called: emp_id={},paycheck={:?}",
+                emp_id,
+                pc
+            );
+            let period = pc.get_pay_period();
+            let inserted = tx
+                .execute(
+                    "INSERT OR IGNORE INTO paychecks (emp_id, pay_date, run_type, period_start, gross_pay, deductions, net_pay)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        u32::from(emp_id),
+                        period.end(),
+                        run_type_kind(pc.run_type()),
+                        period.start(),
+                        pc.gross_pay().cents(),
+                        pc.deductions().cents(),
+                        pc.net_pay().cents(),
+                    ],
+                )
+                .map_err(db_error)?;
+            if inserted == 0 {
+                return Err(DaoError::PaycheckAlreadyExists(emp_id, pc.pay_date()));
+            }
+            insert_details(tx, emp_id, &pc)
+        })
+    }
+    fn fetch_paycheck<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_date: NaiveDate,
+        run_type: RunType,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Paycheck, Err = DaoError> {
+        trace!("fetch_paycheck called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paycheck::with_tx called: emp_id={},pay_date={},run_type={}",
+                emp_id,
+                pay_date,
+                run_type
+            );
+            let mut pc = tx
+                .query_row(
+                    "SELECT * FROM paychecks WHERE emp_id = ?1 AND pay_date = ?2 AND run_type = ?3",
+                    params![u32::from(emp_id), pay_date, run_type_kind(run_type)],
+                    paycheck_from_row,
+                )
+                .optional()
+                .map_err(db_error)?
+                .ok_or(DaoError::PaycheckNotFound(emp_id, pay_date))?;
+            fill_details(tx, emp_id, &mut pc)?;
+            Ok(pc)
+        })
+    }
+    fn fetch_paychecks<'a>(
+        &self,
+        emp_id: EmployeeId,
+        pay_dates: RangeInclusive<NaiveDate>,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<Paycheck>, Err = DaoError> {
+        trace!("fetch_paychecks called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paychecks::with_tx called: emp_id={},pay_dates={:?}",
+                emp_id,
+                pay_dates
+            );
+            let mut stmt = tx
+                .prepare(
+                    "SELECT * FROM paychecks WHERE emp_id = ?1 AND pay_date BETWEEN ?2 AND ?3
+                     AND run_type IN ('regular', 'final')
+                     ORDER BY pay_date",
+                )
+                .map_err(db_error)?;
+            let rows = stmt
+                .query_map(
+                    params![u32::from(emp_id), pay_dates.start(), pay_dates.end()],
+                    paycheck_from_row,
+                )
+                .map_err(db_error)?;
+            let mut pcs = rows.collect::<Result<Vec<_>, _>>().map_err(db_error)?;
+            for pc in pcs.iter_mut() {
+                fill_details(tx, emp_id, pc)?;
+            }
+            Ok(pcs)
+        })
+    }
+    fn fetch_last_pay_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Option<NaiveDate>, Err = DaoError> {
+        trace!("fetch_last_pay_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_last_pay_date::with_tx called: emp_id={}", emp_id);
+            tx.query_row(
+                "SELECT MAX(pay_date) FROM paychecks
+                 WHERE emp_id = ?1 AND run_type IN ('regular', 'final')",
+                [u32::from(emp_id)],
+                |row| row.get(0),
+            )
+            .map_err(db_error)
+        })
+    }
+    fn fetch_paychecks_for_run<'a>(
+        &self,
+        pay_date: NaiveDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Paycheck)>, Err = DaoError> {
+        trace!("fetch_paychecks_for_run called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_paychecks_for_run::with_tx called: pay_date={}",
+                pay_date
+            );
+            let mut stmt = tx
+                .prepare(
+                    "SELECT * FROM paychecks WHERE pay_date = ?1 AND run_type = 'regular'
+                     ORDER BY emp_id",
+                )
+                .map_err(db_error)?;
+            let rows = stmt
+                .query_map([pay_date], |row| {
+                    let emp_id: u32 = row.get("emp_id")?;
+                    Ok((emp_id.into(), paycheck_from_row(row)?))
+                })
+                .map_err(db_error)?;
+            let mut found = rows.collect::<Result<Vec<_>, _>>().map_err(db_error)?;
+            for (emp_id, pc) in found.iter_mut() {
+                fill_details(tx, *emp_id, pc)?;
+            }
+            debug!("fetch_paychecks_for_run: {} paychecks found", found.len());
+            Ok(found)
+        })
+    }
+    fn fetch_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        year: i32,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = YearToDate, Err = DaoError> {
+        trace!("fetch_year_to_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "fetch_year_to_date::with_tx called: emp_id={},year={}",
+                emp_id,
+                year
+            );
+            select_year_to_date(tx, emp_id, year)
+        })
+    }
+    fn update_year_to_date<'a>(
+        &self,
+        emp_id: EmployeeId,
+        ytd: YearToDate,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("update_year_to_date called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "update_year_to_date::with_tx called: emp_id={},ytd={:?}",
+                emp_id,
+                ytd
+            );
+            upsert_year_to_date(tx, emp_id, &ytd)
+        })
+    }
+    fn record_timecard_audit<'a>(
+        &self,
+        emp_id: EmployeeId,
+        audit: TimeCardAudit,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
+        trace!("record_timecard_audit called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!(
+                "record_timecard_audit::with_tx called: emp_id={},audit={:?}",
+                emp_id,
+                audit
+            );
+            tx.execute(
+                "INSERT INTO timecard_audits (emp_id, date, old_hours, new_hours, pay_date)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    u32::from(emp_id),
+                    audit.date,
+                    audit.old_hours,
+                    audit.new_hours,
+                    audit.pay_date
+                ],
+            )
+            .map_err(db_error)?;
+            Ok(())
+        })
+    }
+    fn fetch_timecard_audits<'a>(
+        &self,
+        emp_id: EmployeeId,
+    ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<TimeCardAudit>, Err = DaoError> {
+        trace!("fetch_timecard_audits called");
+        tx_rs::with_tx(move |tx: &mut Self::Ctx<'a>| {
+            trace!("fetch_timecard_audits::with_tx called: emp_id={}", emp_id);
+            let mut stmt = tx
+                .prepare(
+                    "SELECT date, old_hours, new_hours, pay_date FROM timecard_audits
+                     WHERE emp_id = ?1 ORDER BY id",
+                )
+                .map_err(db_error)?;
+            let rows = stmt
+                .query_map([u32::from(emp_id)], |row| {
+                    Ok(TimeCardAudit::new(
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                    ))
+                })
+                .map_err(db_error)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(db_error)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use payroll_domain::{
+        DeductionKind, DeductionLine, EarningKind, EarningLine, EmploymentStatus, Money,
+        NoAffiliation, NoWithholding, OvertimePolicy, Proration, RerunPolicy, StatusChange,
+    };
+    use payroll_impl::{
+        BiweeklyFromSchedule, CommissionedClassification, DirectMethod, FinalPay,
+        HourlyClassification, MailMethod, MemorySink, MonthlySchedule, PayrollFactoryImpl,
+        RetroPay, SalariedClassification, UnionAffiliation, WeeklyOnSchedule, WeeklySchedule,
+    };
+    use std::{fs, path::PathBuf};
+    use tx_app::Transaction;
+    use tx_impl::{
+        AmendTimeCardTx, ChangeEmployeeOvertimeTx, ChangeMemberTx, DeleteEmployeeTx, PaydayTx,
+    };
+    use tx_rs::Tx;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    fn db_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("sqlite-db-{}-{}.sqlite", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+    fn count(db: &SqliteDB, table: &str) -> i64 {
+        db.conn
+            .lock()
+            .unwrap()
+            .query_row(&format!("SELECT count(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    dao_test::dao_conformance!(|_: &str| SqliteDB::open_in_memory().unwrap());
+
+    #[test]
+    fn test_migrate() {
+        let path = db_path("migrate");
+        let version = |db: &SqliteDB| -> usize {
+            db.conn
+                .lock()
+                .unwrap()
+                .query_row("PRAGMA user_version", [], |row| row.get(0))
+                .unwrap()
+        };
+        let db = SqliteDB::open(&path).unwrap();
+        assert_eq!(version(&db), migration::MIGRATIONS.len());
+        drop(db);
+
+        // 適用済みのマイグレーションは再実行されない
+        let db = SqliteDB::open(&path).unwrap();
+        assert_eq!(version(&db), migration::MIGRATIONS.len());
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_unitemized_paychecks() {
+        let path = db_path("migrate-unitemized");
+        {
+            // 明細を記録する前のバージョンの DB を作る
+            let mut conn = Connection::open(&path).unwrap();
+            for sql in &migration::MIGRATIONS[..2] {
+                conn.execute_batch(sql).unwrap();
+            }
+            conn.pragma_update(None, "user_version", 2).unwrap();
+            let tx = conn.transaction().unwrap();
+            tx.execute_batch(
+                "INSERT INTO paychecks VALUES (1, '2025-01-31', '2025-01-25', 30000, 2000, 28000);
+                 INSERT INTO paycheck_deductions VALUES (1, '2025-01-31', 0, 'Affiliation', 500);
+                 INSERT INTO paycheck_deductions VALUES (1, '2025-01-31', 1, 'Pension', 1500);
+                 INSERT INTO paychecks VALUES (2, '2025-01-31', '2025-01-01', 100000, 900, 99100);",
+            )
+            .unwrap();
+            tx.commit().unwrap();
+        }
+
+        let db = SqliteDB::open(&path).unwrap();
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 1, 31), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(
+            pc.earning_lines(),
+            &[EarningLine::new(
+                EarningKind::Unitemized,
+                Money::from_cents(30000)
+            )]
+        );
+        assert_eq!(
+            pc.deduction_lines(),
+            &[
+                DeductionLine::new(DeductionKind::Unitemized, Money::from_cents(500)),
+                DeductionLine::new(
+                    DeductionKind::Withholding("Pension".to_string()),
+                    Money::from_cents(1500)
+                ),
+            ]
+        );
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(2.into(), date(2025, 1, 31), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(pc.gross_pay(), Money::from_cents(100000));
+        assert_eq!(pc.deductions(), Money::from_cents(900));
+        assert_eq!(pc.net_pay(), Money::from_cents(99100));
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_year_to_date() {
+        let path = db_path("migrate-ytd");
+        {
+            // 累計を記録する前のバージョンの DB を作る
+            let mut conn = Connection::open(&path).unwrap();
+            for sql in &migration::MIGRATIONS[..3] {
+                conn.execute_batch(sql).unwrap();
+            }
+            conn.pragma_update(None, "user_version", 3).unwrap();
+            let tx = conn.transaction().unwrap();
+            tx.execute_batch(
+                "INSERT INTO paychecks VALUES (1, '2024-12-31', '2024-12-01', 100000, 10000, 90000);
+                 INSERT INTO paycheck_deductions VALUES (1, '2024-12-31', 0, '', 10000, 'dues');
+                 INSERT INTO paychecks VALUES (1, '2025-01-31', '2025-01-01', 100000, 11000, 89000);
+                 INSERT INTO paycheck_deductions VALUES (1, '2025-01-31', 0, 'Pension', 5000, 'withholding');
+                 INSERT INTO paycheck_deductions VALUES (1, '2025-01-31', 1, '', 6000, 'dues');
+                 INSERT INTO paychecks VALUES (1, '2025-02-28', '2025-02-01', 120000, 7000, 113000);
+                 INSERT INTO paycheck_deductions VALUES (1, '2025-02-28', 0, '', 1000, 'dues');
+                 INSERT INTO paycheck_deductions VALUES (1, '2025-02-28', 1, 'Pension', 6000, 'withholding');",
+            )
+            .unwrap();
+            tx.commit().unwrap();
+        }
+
+        let db = SqliteDB::open(&path).unwrap();
+        let ytd = |year| {
+            db.run_tx(|mut ctx| db.fetch_year_to_date(1.into(), year).run(&mut ctx))
+                .unwrap()
+        };
+        let pension = DeductionKind::Withholding("Pension".to_string());
+        // 支払日の年ごとに積み上げ、控除はその年に初めて現れた順に並ぶ
+        assert_eq!(
+            ytd(2025),
+            YearToDate {
+                year: 2025,
+                gross_pay: Money::from_cents(220000),
+                deductions: vec![
+                    DeductionLine::new(pension.clone(), Money::from_cents(11000)),
+                    DeductionLine::new(DeductionKind::Dues, Money::from_cents(7000)),
+                ],
+                net_pay: Money::from_cents(202000),
+            }
+        );
+        assert_eq!(ytd(2024).gross_pay, Money::from_cents(100000));
+        assert_eq!(ytd(2023), YearToDate::new(2023));
+        // 移行前の Paycheck は累計のスナップショットを持たない
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 1, 31), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(pc.year_to_date(), None);
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_termination_date() {
+        let path = db_path("migrate-termination");
+        {
+            // 在籍状況の履歴を持つ前のバージョンの DB を作る
+            let mut conn = Connection::open(&path).unwrap();
+            for sql in &migration::MIGRATIONS[..10] {
+                conn.execute_batch(sql).unwrap();
+            }
+            conn.pragma_update(None, "user_version", 10).unwrap();
+            let tx = conn.transaction().unwrap();
+            tx.execute_batch(
+                "INSERT INTO employees (id, name, address, termination_date)
+                     VALUES (1, 'Bob', 'Home', '2025-02-14');
+                 INSERT INTO classifications (emp_id, kind, salary) VALUES (1, 'salaried', 230000);
+                 INSERT INTO schedules (emp_id, kind) VALUES (1, 'monthly');
+                 INSERT INTO methods (emp_id, kind) VALUES (1, 'hold');",
+            )
+            .unwrap();
+            tx.commit().unwrap();
+        }
+
+        let db = SqliteDB::open(&path).unwrap();
+        let emp = db
+            .run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx))
+            .unwrap();
+        // 退職日の翌日から退職になる
+        assert_eq!(
+            emp.status_changes(),
+            &[StatusChange::new(
+                date(2025, 2, 15),
+                EmploymentStatus::Terminated
+            )]
+        );
+        assert_eq!(emp.termination_date(), Some(date(2025, 2, 14)));
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_biweekly_epoch() {
+        let path = db_path("migrate-biweekly");
+        {
+            // 隔週払いに起算日を持つ前のバージョンの DB を作る
+            let mut conn = Connection::open(&path).unwrap();
+            for sql in &migration::MIGRATIONS[..13] {
+                conn.execute_batch(sql).unwrap();
+            }
+            conn.pragma_update(None, "user_version", 13).unwrap();
+            let tx = conn.transaction().unwrap();
+            tx.execute_batch(
+                "INSERT INTO employees (id, name, address) VALUES (1, 'Alice', 'Work');
+                 INSERT INTO classifications (emp_id, kind, salary, commission_rate)
+                     VALUES (1, 'commissioned', 250000, 3.2);
+                 INSERT INTO schedules (emp_id, kind) VALUES (1, 'biweekly');
+                 INSERT INTO methods (emp_id, kind) VALUES (1, 'hold');",
+            )
+            .unwrap();
+            tx.commit().unwrap();
+        }
+
+        let db = SqliteDB::open(&path).unwrap();
+        let emp = db
+            .run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx))
+            .unwrap();
+        // それまでと同じ偶数週の金曜日に支払い、年をまたいでも 2 週おきのまま
+        let schedule = emp.schedule();
+        let schedule = schedule.lock().unwrap();
+        assert_eq!(
+            schedule.as_any().downcast_ref::<BiweeklyFromSchedule>(),
+            Some(&BiweeklyFromSchedule::default())
+        );
+        assert!(schedule.is_pay_date(date(2025, 1, 24)));
+        assert!(schedule.is_pay_date(date(2027, 1, 8)));
+        drop(schedule);
+        drop(db);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = db_path("round-trip");
+        let mut hourly = HourlyClassification::new(Money::from_cents(1575));
+        hourly.add_timecard(date(2025, 1, 27), 8.0);
+        hourly.add_timecard(date(2025, 1, 28), 9.5);
+        hourly.change_rate(Some(date(2025, 1, 28)), Money::from_cents(1750));
+        hourly.add_retro_pay(RetroPay::new(date(2025, 2, 1), Money::from_cents(350)));
+        let mut commissioned = CommissionedClassification::new(Money::from_cents(250000), 3.2);
+        commissioned.change_rate(Some(date(2025, 2, 1)), Money::from_cents(260000), 3.5);
+        commissioned.change_rate(Some(date(2025, 1, 15)), Money::from_cents(255000), 3.3);
+        let mut salaried = SalariedClassification::new(Money::from_cents(300000));
+        salaried.change_rate(Some(date(2025, 1, 11)), Money::from_cents(310000));
+        salaried.add_retro_pay(RetroPay::new(date(2025, 2, 1), Money::from_cents(-1000)));
+        salaried.add_final_pay(FinalPay::new(
+            date(2025, 1, 6),
+            vec![
+                EarningLine::new(EarningKind::Regular, Money::from_cents(12000)),
+                EarningLine::new(EarningKind::Overtime, Money::from_cents(2250)),
+            ],
+        ));
+        commissioned.add_sales_receipt(date(2025, 1, 10), Money::from_cents(100000));
+        let refund = commissioned.add_sales_receipt(date(2025, 1, 11), Money::from_cents(-5000));
+        commissioned.void_sales_receipt(refund, Some(date(2025, 1, 24)));
+        let mut union = UnionAffiliation::new(7734.into(), Money::from_cents(925));
+        union.add_service_charge(date(2025, 1, 24), Money::from_cents(1995));
+        let charge = union.add_service_charge(date(2025, 1, 25), Money::from_cents(500));
+        union.void_service_charge(charge, None);
+
+        let mut emps = [
+            Employee::new(
+                1.into(),
+                "Bob",
+                "Home",
+                Arc::new(Mutex::new(hourly)),
+                Arc::new(Mutex::new(WeeklySchedule)),
+                Arc::new(Mutex::new(DirectMethod::new("mufg", "1234567"))),
+                Arc::new(Mutex::new(union)),
+            ),
+            Employee::new(
+                2.into(),
+                "Alice",
+                "Work",
+                Arc::new(Mutex::new(commissioned)),
+                Arc::new(Mutex::new(BiweeklyFromSchedule::default())),
+                Arc::new(Mutex::new(MailMethod::new("alice@example.com"))),
+                Arc::new(Mutex::new(NoAffiliation)),
+            ),
+            Employee::new(
+                3.into(),
+                "Carol",
+                "Office",
+                Arc::new(Mutex::new(salaried)),
+                Arc::new(Mutex::new(MonthlySchedule)),
+                Arc::new(Mutex::new(payroll_impl::HoldMethod)),
+                Arc::new(Mutex::new(NoAffiliation)),
+            ),
+            Employee::new(
+                4.into(),
+                "Dave",
+                "Shop",
+                Arc::new(Mutex::new(SalariedClassification::new(Money::from_cents(
+                    200000,
+                )))),
+                Arc::new(Mutex::new(MonthlySchedule)),
+                Arc::new(Mutex::new(payroll_impl::HoldMethod)),
+                Arc::new(Mutex::new(NoAffiliation)),
+            ),
+        ];
+        emps[0].set_overtime_policy(Some(OvertimePolicy {
+            weekly_after: Some(40.0),
+            double_time_after: Some(12.0),
+            holiday_rate: Some(2.0),
+            week_start: chrono::Weekday::Sun,
+            ..OvertimePolicy::none()
+        }));
+        emps[2].set_overtime_policy(Some(OvertimePolicy {
+            proration: Proration::WorkingDays,
+            ..OvertimePolicy::none()
+        }));
+        emps[2].hire_on(date(2025, 1, 6));
+        emps[2].change_status(date(2025, 2, 3), EmploymentStatus::OnLeave);
+        emps[2].terminate_on(date(2025, 3, 14));
+        emps[0].change_schedule(
+            Arc::new(Mutex::new(WeeklyOnSchedule::new(chrono::Weekday::Mon))),
+            Some(date(2025, 2, 1)),
+        );
+        emps[3].change_schedule(
+            Arc::new(Mutex::new(BiweeklyFromSchedule::new(date(2025, 1, 6)))),
+            Some(date(2025, 2, 1)),
+        );
+        {
+            let db = SqliteDB::open(&path).unwrap();
+            db.run_tx(|mut ctx| {
+                for emp in emps.iter() {
+                    db.add(emp.clone()).run(&mut ctx)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        let db = SqliteDB::open(&path).unwrap();
+        let fetched = db.run_tx(|mut ctx| db.fetch_all().run(&mut ctx)).unwrap();
+        assert_eq!(fetched.len(), emps.len());
+        for ((id, fetched), emp) in fetched.iter().zip(emps.iter()) {
+            assert_eq!(*id, emp.id());
+            assert_eq!(format!("{:?}", fetched), format!("{:?}", emp));
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rollback() {
+        let db = SqliteDB::open_in_memory().unwrap();
+        // 存在しない従業員への ChgEmp Member は組合員の登録ごと巻き戻る
+        let tx = ChangeMemberTx::new(
+            7234.into(),
+            999.into(),
+            Money::from_cents(945),
+            db.clone(),
+            PayrollFactoryImpl,
+        );
+        assert!(tx.execute().is_err());
+        assert_eq!(count(&db, "union_members"), 0);
+
+        let result = db.run_tx(|mut ctx| db.find_union_member(7234.into()).run(&mut ctx));
+        assert!(matches!(result, Err(DaoError::MemberNotFound(_))));
+    }
+
+    #[test]
+    fn test_delete_employee_cascades() {
+        let db = SqliteDB::open_in_memory().unwrap();
+        dao_test::add_member(&db);
+        PaydayTx::new(
+            date(2025, 1, 31),
+            RerunPolicy::Reject,
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .execute()
+        .unwrap();
+        AmendTimeCardTx::new(1.into(), date(2025, 1, 30), 7.5, db.clone())
+            .execute()
+            .unwrap();
+        ChangeEmployeeOvertimeTx::new(
+            1.into(),
+            Some(OvertimePolicy {
+                holiday_rate: Some(2.0),
+                ..OvertimePolicy::default()
+            }),
+            db.clone(),
+        )
+        .execute()
+        .unwrap();
+        assert_eq!(count(&db, "timecards"), 2);
+        assert_eq!(count(&db, "service_charges"), 1);
+
+        // 従業員を削除すると明細行も消えるが支払い履歴は残る
+        DeleteEmployeeTx::new(1.into(), db.clone())
+            .execute()
+            .unwrap();
+        for table in [
+            "classifications",
+            "timecards",
+            "affiliations",
+            "service_charges",
+            "overtime_policies",
+        ] {
+            assert_eq!(count(&db, table), 0, "{}", table);
+        }
+        assert_eq!(count(&db, "paychecks"), 1);
+        assert_eq!(count(&db, "year_to_dates"), 1);
+        assert_eq!(count(&db, "timecard_audits"), 1);
+    }
+}
@@ -0,0 +1,41 @@
+// スキーマのマイグレーション
+// 適用済みのバージョンは PRAGMA user_version で管理する
+use log::{debug, trace};
+use rusqlite::Connection;
+
+// 追加するときは末尾に足すこと (適用済みのものは書き換えない)
+pub(crate) const MIGRATIONS: &[&str] = &[
+    include_str!("../migrations/0001_create_tables.sql"),
+    include_str!("../migrations/0002_withholding.sql"),
+    include_str!("../migrations/0003_line_items.sql"),
+    include_str!("../migrations/0004_year_to_date.sql"),
+    include_str!("../migrations/0005_overtime.sql"),
+    include_str!("../migrations/0006_timecard_audits.sql"),
+    include_str!("../migrations/0007_reversals.sql"),
+    include_str!("../migrations/0008_rate_history.sql"),
+    include_str!("../migrations/0009_final_pays.sql"),
+    include_str!("../migrations/0010_employment.sql"),
+    include_str!("../migrations/0011_employment_status.sql"),
+    include_str!("../migrations/0012_run_types.sql"),
+    include_str!("../migrations/0013_schedules.sql"),
+    include_str!("../migrations/0014_biweekly_epoch.sql"),
+    include_str!("../migrations/0015_drop_overtime_holidays.sql"),
+];
+
+pub fn migrate(conn: &mut Connection) -> Result<(), rusqlite::Error> {
+    trace!("migrate called");
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    debug!(
+        "migrate: current version={}, latest version={}",
+        version,
+        MIGRATIONS.len()
+    );
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        debug!("migrate: applying version={}", i + 1);
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
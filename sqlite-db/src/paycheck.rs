@@ -0,0 +1,322 @@
+// Paycheck とテーブルの行との相互変換
+// 合計額は paychecks に、明細は paycheck_earnings と paycheck_deductions に持つ
+// 年初来の累計は year_to_dates に持ち、Paycheck ごとの累計のスナップショットも paychecks に残す
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension, Params, ToSql};
+
+use dao::DaoError;
+use payroll_domain::{
+    DeductionKind, DeductionLine, EarningKind, EarningLine, EmployeeId, Money, Paycheck, RunType,
+    YearToDate,
+};
+
+use crate::employee::{db_error, unknown};
+
+pub fn earning_kind(kind: EarningKind) -> &'static str {
+    match kind {
+        EarningKind::Salary => "salary",
+        EarningKind::Regular => "regular",
+        EarningKind::Overtime => "overtime",
+        EarningKind::DoubleTime => "double_time",
+        EarningKind::Premium => "premium",
+        EarningKind::Commission => "commission",
+        EarningKind::Clawback => "clawback",
+        EarningKind::RetroPay => "retro_pay",
+        EarningKind::Bonus => "bonus",
+        EarningKind::Adjustment => "adjustment",
+        EarningKind::Unitemized => "unitemized",
+    }
+}
+pub fn parse_earning_kind(kind: &str, emp_id: EmployeeId) -> Result<EarningKind, DaoError> {
+    EarningKind::ALL
+        .into_iter()
+        .find(|k| earning_kind(*k) == kind)
+        .ok_or_else(|| unknown("earning kind", kind, emp_id))
+}
+
+pub fn run_type_kind(run_type: RunType) -> &'static str {
+    match run_type {
+        RunType::Regular => "regular",
+        RunType::Bonus => "bonus",
+        RunType::Correction => "correction",
+        RunType::Final => "final",
+    }
+}
+fn parse_run_type(run_type: &str) -> Option<RunType> {
+    RunType::ALL
+        .into_iter()
+        .find(|r| run_type_kind(*r) == run_type)
+}
+
+// (kind, name) の組にする: name は源泉徴収のルール名で、それ以外は空
+fn deduction_kind(kind: &DeductionKind) -> (&'static str, &str) {
+    match kind {
+        DeductionKind::Dues => ("dues", ""),
+        DeductionKind::ServiceCharge => ("service_charge", ""),
+        DeductionKind::ServiceChargeRefund => ("service_charge_refund", ""),
+        DeductionKind::Withholding(name) => ("withholding", name),
+        DeductionKind::Unitemized => ("unitemized", ""),
+    }
+}
+
+// 明細は別テーブルにあるので、行から読むのは支払い期間だけで明細は fill_details で埋める
+pub fn paycheck_from_row(row: &rusqlite::Row) -> rusqlite::Result<Paycheck> {
+    let period_start: NaiveDate = row.get("period_start")?;
+    let pay_date: NaiveDate = row.get("pay_date")?;
+    let text: String = row.get("run_type")?;
+    let Some(run_type) = parse_run_type(&text) else {
+        let index = row.as_ref().column_index("run_type")?;
+        return Err(rusqlite::Error::InvalidColumnType(
+            index,
+            format!("run_type: {}", text),
+            rusqlite::types::Type::Text,
+        ));
+    };
+    Ok(Paycheck::new(period_start..=pay_date).with_run_type(run_type))
+}
+
+pub fn fill_details(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    pc: &mut Paycheck,
+) -> Result<(), DaoError> {
+    let key = params![
+        u32::from(emp_id),
+        pc.pay_date(),
+        run_type_kind(pc.run_type())
+    ];
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, amount FROM paycheck_earnings
+             WHERE emp_id = ?1 AND pay_date = ?2 AND run_type = ?3 ORDER BY seq",
+        )
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map(key, |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    let earning_lines = rows
+        .into_iter()
+        .map(|(kind, amount)| {
+            let kind = parse_earning_kind(&kind, emp_id)?;
+            Ok(EarningLine::new(kind, Money::from_cents(amount)))
+        })
+        .collect::<Result<Vec<_>, DaoError>>()?;
+
+    let deduction_lines = select_deductions(
+        conn,
+        "SELECT kind, name, amount FROM paycheck_deductions
+         WHERE emp_id = ?1 AND pay_date = ?2 AND run_type = ?3 ORDER BY seq",
+        key,
+        emp_id,
+    )?;
+
+    pc.set_earning_lines(earning_lines);
+    pc.set_deduction_lines(deduction_lines);
+
+    // その Paycheck を含めた累計は、記録していない古い Paycheck では NULL
+    let totals: Option<(i64, i64)> = conn
+        .query_row(
+            "SELECT ytd_gross_pay, ytd_net_pay FROM paychecks
+             WHERE emp_id = ?1 AND pay_date = ?2 AND run_type = ?3",
+            key,
+            |row| {
+                let gross_pay: Option<i64> = row.get(0)?;
+                let net_pay: Option<i64> = row.get(1)?;
+                Ok(gross_pay.zip(net_pay))
+            },
+        )
+        .map_err(db_error)?;
+    if let Some((gross_pay, net_pay)) = totals {
+        let deductions = select_deductions(
+            conn,
+            "SELECT kind, name, amount FROM paycheck_ytd_deductions
+             WHERE emp_id = ?1 AND pay_date = ?2 AND run_type = ?3 ORDER BY seq",
+            key,
+            emp_id,
+        )?;
+        pc.set_year_to_date(YearToDate {
+            year: YearToDate::year_of(pc.pay_date()),
+            gross_pay: Money::from_cents(gross_pay),
+            deductions,
+            net_pay: Money::from_cents(net_pay),
+        });
+    }
+    Ok(())
+}
+
+pub fn insert_details(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    pc: &Paycheck,
+) -> Result<(), DaoError> {
+    let pay_date = pc.pay_date();
+    let run_type = run_type_kind(pc.run_type());
+    for (seq, line) in pc.earning_lines().iter().enumerate() {
+        conn.execute(
+            "INSERT INTO paycheck_earnings (emp_id, pay_date, run_type, seq, kind, amount)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                u32::from(emp_id),
+                pay_date,
+                run_type,
+                seq,
+                earning_kind(line.kind),
+                line.amount.cents()
+            ],
+        )
+        .map_err(db_error)?;
+    }
+    insert_deductions(
+        conn,
+        "INSERT INTO paycheck_deductions (emp_id, pay_date, run_type, seq, kind, name, amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        emp_id,
+        &[&pay_date, &run_type],
+        pc.deduction_lines(),
+    )?;
+    if let Some(ytd) = pc.year_to_date() {
+        conn.execute(
+            "UPDATE paychecks SET ytd_gross_pay = ?4, ytd_net_pay = ?5
+             WHERE emp_id = ?1 AND pay_date = ?2 AND run_type = ?3",
+            params![
+                u32::from(emp_id),
+                pay_date,
+                run_type,
+                ytd.gross_pay.cents(),
+                ytd.net_pay.cents()
+            ],
+        )
+        .map_err(db_error)?;
+        insert_deductions(
+            conn,
+            "INSERT INTO paycheck_ytd_deductions
+                 (emp_id, pay_date, run_type, seq, kind, name, amount)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            emp_id,
+            &[&pay_date, &run_type],
+            &ytd.deductions,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn select_year_to_date(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    year: i32,
+) -> Result<YearToDate, DaoError> {
+    let key = params![u32::from(emp_id), year];
+    let totals = conn
+        .query_row(
+            "SELECT gross_pay, net_pay FROM year_to_dates WHERE emp_id = ?1 AND year = ?2",
+            key,
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(db_error)?;
+    let Some((gross_pay, net_pay)) = totals else {
+        return Ok(YearToDate::new(year));
+    };
+    let deductions = select_deductions(
+        conn,
+        "SELECT kind, name, amount FROM year_to_date_deductions WHERE emp_id = ?1 AND year = ?2
+         ORDER BY seq",
+        key,
+        emp_id,
+    )?;
+    Ok(YearToDate {
+        year,
+        gross_pay: Money::from_cents(gross_pay),
+        deductions,
+        net_pay: Money::from_cents(net_pay),
+    })
+}
+
+pub fn upsert_year_to_date(
+    conn: &Connection,
+    emp_id: EmployeeId,
+    ytd: &YearToDate,
+) -> Result<(), DaoError> {
+    conn.execute(
+        "INSERT INTO year_to_dates (emp_id, year, gross_pay, net_pay) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (emp_id, year) DO UPDATE
+         SET gross_pay = excluded.gross_pay, net_pay = excluded.net_pay",
+        params![
+            u32::from(emp_id),
+            ytd.year,
+            ytd.gross_pay.cents(),
+            ytd.net_pay.cents()
+        ],
+    )
+    .map_err(db_error)?;
+    conn.execute(
+        "DELETE FROM year_to_date_deductions WHERE emp_id = ?1 AND year = ?2",
+        params![u32::from(emp_id), ytd.year],
+    )
+    .map_err(db_error)?;
+    insert_deductions(
+        conn,
+        "INSERT INTO year_to_date_deductions (emp_id, year, seq, kind, name, amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        emp_id,
+        &[&ytd.year],
+        &ytd.deductions,
+    )
+}
+
+// 控除の行を持つテーブルはどれも (emp_id, キー..., seq, kind, name, amount) の形をしている
+// sql は kind, name, amount の順に選ぶこと
+fn select_deductions(
+    conn: &Connection,
+    sql: &str,
+    key: impl Params,
+    emp_id: EmployeeId,
+) -> Result<Vec<DeductionLine>, DaoError> {
+    let mut stmt = conn.prepare(sql).map_err(db_error)?;
+    let rows = stmt
+        .query_map(key, |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get(2)?,
+            ))
+        })
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    rows.into_iter()
+        .map(|(kind, name, amount)| {
+            let kind = match kind.as_str() {
+                "dues" => DeductionKind::Dues,
+                "service_charge" => DeductionKind::ServiceCharge,
+                "service_charge_refund" => DeductionKind::ServiceChargeRefund,
+                "withholding" => DeductionKind::Withholding(name),
+                "unitemized" => DeductionKind::Unitemized,
+                _ => return Err(unknown("deduction kind", &kind, emp_id)),
+            };
+            Ok(DeductionLine::new(kind, Money::from_cents(amount)))
+        })
+        .collect()
+}
+
+fn insert_deductions(
+    conn: &Connection,
+    sql: &str,
+    emp_id: EmployeeId,
+    key: &[&dyn ToSql],
+    lines: &[DeductionLine],
+) -> Result<(), DaoError> {
+    let emp_id = u32::from(emp_id);
+    for (seq, line) in lines.iter().enumerate() {
+        let (kind, name) = deduction_kind(&line.kind);
+        let amount = line.amount.cents();
+        let mut values: Vec<&dyn ToSql> = vec![&emp_id];
+        values.extend_from_slice(key);
+        values.extend_from_slice(&[&seq, &kind, &name, &amount]);
+        conn.execute(sql, values.as_slice()).map_err(db_error)?;
+    }
+    Ok(())
+}
@@ -1,10 +1,13 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, Weekday};
 use log::{debug, trace};
 use parsec_rs::{char, float32, int32, keyword, pred, spaces, string, uint32, Parser};
 use std::collections::HashSet;
 use thiserror::Error;
 
-use payroll_domain::{EmployeeId, MemberId};
+use payroll_domain::{
+    ChargeId, EmployeeFilter, EmployeeId, EmploymentStatus, FilingStatus, MemberId, Money,
+    OvertimePolicy, Proration, ReceiptId, RerunPolicy, RunType, ScheduleInfo, TaxProfile,
+};
 use tx_app::Tx;
 
 #[derive(Debug, Clone, Error)]
@@ -42,13 +45,13 @@ mod test_ignoreable {
 
     #[test]
     fn test_ignoreable() {
-        assert_eq!(ignoreable("\n"), true, "empty line");
-        assert_eq!(ignoreable("# comment\n"), true, "line comment only");
-        assert_eq!(ignoreable("    \n"), true, "whitespace only");
+        assert!(ignoreable("\n"), "empty line");
+        assert!(ignoreable("# comment\n"), "line comment only");
+        assert!(ignoreable("    \n"), "whitespace only");
 
-        assert_eq!(ignoreable(""), false, "the case EOS");
-        assert_eq!(ignoreable("test"), false, "invalid command");
-        assert_eq!(ignoreable(" a\n"), false, "invalid command with whitespace");
+        assert!(!ignoreable(""), "the case EOS");
+        assert!(!ignoreable("test"), "invalid command");
+        assert!(!ignoreable(" a\n"), "invalid command with whitespace");
     }
 }
 
@@ -66,20 +69,37 @@ fn transaction() -> impl Parser<Item = Tx> {
             .or(add_salary_emp())
             .or(add_commissioned_emp())
             .or(del_emp())
+            .or(terminate())
             .or(time_card())
+            .or(amend_time_card())
+            .or(void_time_card())
             .or(sales_receipt())
+            .or(void_sales_receipt())
             .or(service_charge())
+            .or(void_service_charge())
             .or(chg_name())
             .or(chg_address())
+            .or(chg_tax())
+            .or(chg_overtime())
             .or(chg_hourly())
             .or(chg_salaried())
             .or(chg_commissioned())
+            .or(chg_schedule())
             .or(chg_hold())
             .or(chg_direct())
             .or(chg_mail())
             .or(chg_member())
             .or(chg_no_member())
-            .or(payday()),
+            .or(chg_status())
+            .or(payday())
+            .or(preview_payday())
+            .or(bonus())
+            .or(correction())
+            .or(get_paycheck())
+            .or(get_ytd())
+            .or(get_emp())
+            .or(list_emp())
+            .or(get_member()),
     )
 }
 #[cfg(test)]
@@ -125,7 +145,8 @@ mod test_transaction {
                     id: 42.into(),
                     name: "Bob".to_string(),
                     address: "Home".to_string(),
-                    hourly_rate: 1000.0
+                    hourly_rate: Money::from_cents(100000),
+                    hire_date: None
                 },
                 ""
             ))
@@ -142,7 +163,8 @@ mod test_transaction {
                     id: 42.into(),
                     name: "Bob".to_string(),
                     address: "Home".to_string(),
-                    salary: 1000.0
+                    salary: Money::from_cents(100000),
+                    hire_date: None
                 },
                 ""
             ))
@@ -159,8 +181,9 @@ mod test_transaction {
                     id: 42.into(),
                     name: "Bob".to_string(),
                     address: "Home".to_string(),
-                    salary: 1000.0,
-                    commission_rate: 0.1
+                    salary: Money::from_cents(100000),
+                    commission_rate: 0.1,
+                    hire_date: None
                 },
                 ""
             ))
@@ -189,6 +212,37 @@ mod test_transaction {
         );
     }
     #[test]
+    fn test_amend_time_card() {
+        let input = r#"AmendTimeCard 42 2021-01-01 7.5"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::AmendTimeCard {
+                    id: 42.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                    hours: 7.5
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_void_time_card() {
+        let input = r#"VoidTimeCard 42 2021-01-01"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::VoidTimeCard {
+                    id: 42.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
     fn test_sales_receipt() {
         let input = r#"SalesReceipt 42 2021-01-01 1000.0"#;
         let result = transaction().parse(input);
@@ -198,7 +252,38 @@ mod test_transaction {
                 Tx::AddSalesReceipt {
                     id: 42.into(),
                     date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-                    amount: 1000.0
+                    amount: Money::from_cents(100000)
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_refund_sales_receipt() {
+        let input = r#"SalesReceipt 42 2021-01-01 -250"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::AddSalesReceipt {
+                    id: 42.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                    amount: Money::from_cents(-25000)
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_void_sales_receipt() {
+        let input = r#"VoidSalesReceipt 42 3"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::VoidSalesReceipt {
+                    id: 42.into(),
+                    receipt_id: 3.into(),
                 },
                 ""
             ))
@@ -214,7 +299,22 @@ mod test_transaction {
                 Tx::AddServiceCharge {
                     member_id: 42.into(),
                     date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-                    amount: 1000.0
+                    amount: Money::from_cents(100000)
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_void_service_charge() {
+        let input = r#"VoidServiceCharge 42 1"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::VoidServiceCharge {
+                    member_id: 42.into(),
+                    charge_id: 1.into(),
                 },
                 ""
             ))
@@ -251,6 +351,40 @@ mod test_transaction {
         );
     }
     #[test]
+    fn test_chg_tax() {
+        let input = r#"ChgEmp 42 Tax Married 2"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeTax {
+                    id: 42.into(),
+                    tax: TaxProfile::new(FilingStatus::Married, 2)
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_chg_overtime() {
+        let input = r#"ChgEmp 42 Overtime Weekly 40 DoubleTime 12"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeOvertime {
+                    id: 42.into(),
+                    overtime: Some(OvertimePolicy {
+                        weekly_after: Some(40.0),
+                        double_time_after: Some(12.0),
+                        ..OvertimePolicy::none()
+                    })
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
     fn test_chg_hourly() {
         let input = r#"ChgEmp 42 Hourly 1000.0"#;
         let result = transaction().parse(input);
@@ -259,7 +393,8 @@ mod test_transaction {
             Ok((
                 Tx::ChangeEmployeeHourly {
                     id: 42.into(),
-                    hourly_rate: 1000.0
+                    hourly_rate: Money::from_cents(100000),
+                    effective: None,
                 },
                 ""
             ))
@@ -274,7 +409,8 @@ mod test_transaction {
             Ok((
                 Tx::ChangeEmployeeSalaried {
                     id: 42.into(),
-                    salary: 1000.0
+                    salary: Money::from_cents(100000),
+                    effective: None,
                 },
                 ""
             ))
@@ -289,8 +425,24 @@ mod test_transaction {
             Ok((
                 Tx::ChangeEmployeeCommissioned {
                     id: 42.into(),
-                    salary: 1000.0,
-                    commission_rate: 0.1
+                    salary: Money::from_cents(100000),
+                    commission_rate: 0.1,
+                    effective: None,
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_chg_schedule() {
+        let input = r#"ChgEmp 42 Schedule Semimonthly"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSchedule {
+                    id: 42.into(),
+                    schedule: ScheduleInfo::Semimonthly,
                 },
                 ""
             ))
@@ -343,7 +495,7 @@ mod test_transaction {
                 Tx::ChangeEmployeeMember {
                     emp_id: 42.into(),
                     member_id: 7234.into(),
-                    dues: 9.45
+                    dues: Money::from_cents(945)
                 },
                 "",
             ))
@@ -366,7 +518,138 @@ mod test_transaction {
             result,
             Ok((
                 Tx::Payday {
-                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                    policy: RerunPolicy::Reject,
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_payday_replay() {
+        let input = r#"Payday 2021-01-01 Replay"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::Payday {
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                    policy: RerunPolicy::Replay,
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_preview_payday() {
+        let input = r#"PreviewPayday 2024-08-30"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::PreviewPayday {
+                    date: NaiveDate::from_ymd_opt(2024, 8, 30).unwrap(),
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_get_paycheck() {
+        let input = r#"GetPaycheck 42 2021-01-29"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::GetPaycheck {
+                    id: 42.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 29).unwrap(),
+                    run_type: RunType::Regular,
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_bonus() {
+        let input = r#"Bonus 42 2025-03-14 500.00"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::PayOffCycle {
+                    id: 42.into(),
+                    run_type: RunType::Bonus,
+                    date: NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(),
+                    amount: Money::from_cents(50000),
+                    period_start: None,
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_correction() {
+        let input = r#"Correction 42 2025-03-14 -25.50"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::PayOffCycle {
+                    id: 42.into(),
+                    run_type: RunType::Correction,
+                    date: NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(),
+                    amount: Money::from_cents(-2550),
+                    period_start: None,
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_get_ytd() {
+        let input = r#"GetYtd 42 2025"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::GetYearToDate {
+                    id: 42.into(),
+                    year: 2025
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_get_emp() {
+        let input = r#"GetEmp 42"#;
+        let result = transaction().parse(input);
+        assert_eq!(result, Ok((Tx::GetEmployee { id: 42.into() }, "")));
+    }
+    #[test]
+    fn test_list_emp() {
+        let input = r#"ListEmp Hourly"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ListEmployees {
+                    filter: EmployeeFilter::Hourly
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_get_member() {
+        let input = r#"GetMember 7234"#;
+        let result = transaction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::GetUnionMember {
+                    member_id: 7234.into()
                 },
                 ""
             ))
@@ -402,7 +685,8 @@ fn add_hourly_emp() -> impl Parser<Item = Tx> {
     let name = string().with(spaces()).label("<name>".into());
     let address = string().with(spaces()).label("<address>".into());
     let key = char('H').skip(spaces()).label("`H'".into());
-    let hourly_rate = float32().label("<hourly_rate>".into());
+    let hourly_rate = money().with(spaces()).label("<hourly_rate>".into());
+    let hire_date = effective_date();
 
     prefix
         .skip(emp_id)
@@ -410,16 +694,18 @@ fn add_hourly_emp() -> impl Parser<Item = Tx> {
         .join(address)
         .with(key)
         .join(hourly_rate)
-        .map(|(((id, name), address), hourly_rate)| {
+        .join(hire_date)
+        .map(|((((id, name), address), hourly_rate), hire_date)| {
             debug!(
-                "parsed AddHourlyEmployee: id={}, name={}, address={}, hourly_rate={}",
-                id, name, address, hourly_rate
+                "parsed AddHourlyEmployee: id={}, name={}, address={}, hourly_rate={}, hire_date={:?}",
+                id, name, address, hourly_rate, hire_date
             );
             Tx::AddHourlyEmployee {
                 id,
                 name,
                 address,
                 hourly_rate,
+                hire_date,
             }
         })
 }
@@ -439,7 +725,8 @@ mod test_add_hourly_emp {
                     id: 1.into(),
                     name: "Bob".to_string(),
                     address: "Home".to_string(),
-                    hourly_rate: 1000.0
+                    hourly_rate: Money::from_cents(100000),
+                    hire_date: None
                 },
                 ""
             ))
@@ -453,7 +740,8 @@ fn add_salary_emp() -> impl Parser<Item = Tx> {
     let name = string().with(spaces()).label("<name>".into());
     let address = string().with(spaces()).label("<address>".into());
     let key = char('S').skip(spaces()).label("`S'".into());
-    let salary = float32().with(spaces()).label("<monthly_salary>".into());
+    let salary = money().with(spaces()).label("<monthly_salary>".into());
+    let hire_date = effective_date();
 
     prefix
         .skip(emp_id)
@@ -461,16 +749,18 @@ fn add_salary_emp() -> impl Parser<Item = Tx> {
         .join(address)
         .with(key)
         .join(salary)
-        .map(|(((id, name), address), salary)| {
+        .join(hire_date)
+        .map(|((((id, name), address), salary), hire_date)| {
             debug!(
-                "parsed AddSalariedEmployee: id={}, name={}, address={}, salary={}",
-                id, name, address, salary
+                "parsed AddSalariedEmployee: id={}, name={}, address={}, salary={}, hire_date={:?}",
+                id, name, address, salary, hire_date
             );
             Tx::AddSalariedEmployee {
                 id,
                 name,
                 address,
                 salary,
+                hire_date,
             }
         })
 }
@@ -490,11 +780,32 @@ mod test_add_salary_emp {
                     id: 1.into(),
                     name: "Bob".to_string(),
                     address: "Home".to_string(),
-                    salary: 1000.0
+                    salary: Money::from_cents(100000),
+                    hire_date: None
+                },
+                ""
+            ))
+        );
+
+        let input = r#"AddEmp 1 "Bob" "Home" S 2300.0 2025-01-20"#;
+        let result = add_salary_emp().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::AddSalariedEmployee {
+                    id: 1.into(),
+                    name: "Bob".to_string(),
+                    address: "Home".to_string(),
+                    salary: Money::from_cents(230000),
+                    hire_date: NaiveDate::from_ymd_opt(2025, 1, 20)
                 },
                 ""
             ))
         );
+
+        let input = r#"AddEmp 1 "Bob" "Home" S 99999999999999999999.0"#;
+        let result = add_salary_emp().parse(input);
+        assert!(result.is_err());
     }
 }
 
@@ -504,8 +815,9 @@ fn add_commissioned_emp() -> impl Parser<Item = Tx> {
     let name = string().with(spaces()).label("<name>".into());
     let address = string().with(spaces()).label("<address>".into());
     let key = char('C').skip(spaces()).label("`C'".into());
-    let salary = float32().with(spaces()).label("<salary>".into());
-    let commission_rate = float32().label("<commission_rate>".into());
+    let salary = money().with(spaces()).label("<salary>".into());
+    let commission_rate = float32().with(spaces()).label("<commission_rate>".into());
+    let hire_date = effective_date();
 
     prefix
         .skip(emp_id)
@@ -514,14 +826,16 @@ fn add_commissioned_emp() -> impl Parser<Item = Tx> {
         .with(key)
         .join(salary)
         .join(commission_rate)
-        .map(|((((id, name), address), salary), commission_rate)| {
+        .join(hire_date)
+        .map(|(((((id, name), address), salary), commission_rate), hire_date)| {
             debug!(
-		    "parsed AddCommissionedEmployee: id={}, name={}, address={}, salary={}, commission_rate={}",
+		    "parsed AddCommissionedEmployee: id={}, name={}, address={}, salary={}, commission_rate={}, hire_date={:?}",
 		    id,
 		    name,
 		    address,
 		    salary,
-		    commission_rate
+		    commission_rate,
+		    hire_date
 		);
             Tx::AddCommissionedEmployee {
                 id,
@@ -529,6 +843,7 @@ fn add_commissioned_emp() -> impl Parser<Item = Tx> {
                 address,
                 salary,
                 commission_rate,
+                hire_date,
             }
         })
 }
@@ -548,8 +863,9 @@ mod test_add_commissioned_emp {
                     id: 1.into(),
                     name: "Bob".to_string(),
                     address: "Home".to_string(),
-                    salary: 1000.0,
-                    commission_rate: 0.1
+                    salary: Money::from_cents(100000),
+                    commission_rate: 0.1,
+                    hire_date: None
                 },
                 ""
             ))
@@ -560,11 +876,22 @@ mod test_add_commissioned_emp {
 fn del_emp() -> impl Parser<Item = Tx> {
     let prefix = keyword("DelEmp").skip(spaces());
     let emp_id = employee_id();
+    // 日付があれば Terminate と同じく退職日として記録を残し、なければすぐに削除する
+    let effective = effective_date();
 
-    prefix.skip(emp_id).map(|id| {
-        debug!("parsed DeleteEmployee: id={}", id);
-        Tx::DeleteEmployee { id }
-    })
+    prefix
+        .skip(emp_id)
+        .join(effective)
+        .map(|(id, effective)| match effective {
+            Some(date) => {
+                debug!("parsed TerminateEmployee: id={}, date={}", id, date);
+                Tx::TerminateEmployee { id, date }
+            }
+            None => {
+                debug!("parsed DeleteEmployee: id={}", id);
+                Tx::DeleteEmployee { id }
+            }
+        })
 }
 #[cfg(test)]
 mod test_del_emp {
@@ -576,6 +903,55 @@ mod test_del_emp {
         let input = r#"DelEmp 1"#;
         let result = del_emp().parse(input);
         assert_eq!(result, Ok((Tx::DeleteEmployee { id: 1.into() }, "")));
+
+        let input = r#"DelEmp 1 2025-01-21"#;
+        let result = del_emp().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::TerminateEmployee {
+                    id: 1.into(),
+                    date: NaiveDate::from_ymd_opt(2025, 1, 21).unwrap()
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn terminate() -> impl Parser<Item = Tx> {
+    let prefix = keyword("Terminate").skip(spaces());
+    let emp_id = employee_id();
+    let date = date();
+
+    prefix.skip(emp_id).join(date).map(|(id, date)| {
+        debug!("parsed TerminateEmployee: id={}, date={}", id, date);
+        Tx::TerminateEmployee { id, date }
+    })
+}
+#[cfg(test)]
+mod test_terminate {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"Terminate 1 2025-02-14"#;
+        let result = terminate().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::TerminateEmployee {
+                    id: 1.into(),
+                    date: NaiveDate::from_ymd_opt(2025, 2, 14).unwrap(),
+                },
+                ""
+            ))
+        );
+
+        let input = r#"Terminate 1"#;
+        let result = terminate().parse(input);
+        assert!(result.is_err());
     }
 }
 
@@ -591,7 +967,7 @@ fn date() -> impl Parser<Item = NaiveDate> {
 
     date.map(|((y, m), d)| {
         debug!("parsed date: {}-{:02}-{:02}", y, m, d);
-        NaiveDate::from_ymd_opt(y as i32, m as u32, d as u32).expect("date")
+        NaiveDate::from_ymd_opt(y, m, d).expect("date")
     })
 }
 #[cfg(test)]
@@ -610,31 +986,115 @@ mod test_date {
     }
 }
 
-fn time_card() -> impl Parser<Item = Tx> {
-    let prefix = keyword("TimeCard").skip(spaces());
-    let emp_id = employee_id();
-    let date = date();
-    let hours = float32().label("<hour>".into());
-
-    prefix
-        .skip(emp_id)
-        .join(date)
-        .join(hours)
-        .map(|((id, date), hours)| {
-            debug!("parsed TimeCard: id={}, date={}, hours={}", id, date, hours);
-            Tx::AddTimeCard { id, date, hours }
-        })
+fn money() -> impl Parser<Item = Money> {
+    let digit = || pred(|c: char| c.is_ascii_digit());
+    // 整数部は uint32 で読むので、桁あふれする額は社員番号と同じくパースエラーになる
+    let int = uint32();
+    let frac = char('.').skip(digit().many0()).or(digit().many0());
+
+    int.join(frac).map(|(int, frac)| {
+        // 小数第 3 位より下は銀行丸めの向きにしか効かないので、0 でない桁があったかだけを残す
+        let (head, tail) = frac.split_at(frac.len().min(3));
+        let sticky = if tail.iter().any(|c| *c != '0') {
+            "1"
+        } else {
+            ""
+        };
+        let s = format!("{}.{}{}", int, head.iter().collect::<String>(), sticky);
+        debug!("parsed money: {}", s);
+        // 整数部が u32 に収まり、小数部も 4 桁以下なので Money に収まらないことはない
+        s.parse::<Money>().expect("money")
+    })
 }
 #[cfg(test)]
-mod test_time_card {
+mod test_money {
     use super::*;
     use parsec_rs::Parser;
 
     #[test]
     fn test() {
-        let input = r#"TimeCard 1 2021-01-01 8.0"#;
-        let result = time_card().parse(input);
-        assert_eq!(
+        let input = "3215.88";
+        let result = money().parse(input);
+        assert_eq!(result, Ok((Money::from_cents(321588), "")));
+
+        let input = "1000";
+        let result = money().parse(input);
+        assert_eq!(result, Ok((Money::from_cents(100000), "")));
+
+        let input = "9.5 ";
+        let result = money().parse(input);
+        assert_eq!(result, Ok((Money::from_cents(950), " ")));
+
+        // 1 セント未満は銀行丸め
+        let input = "0.125";
+        let result = money().parse(input);
+        assert_eq!(result, Ok((Money::from_cents(12), "")));
+
+        let input = "0.1250000000000000000000000000000000000001";
+        let result = money().parse(input);
+        assert_eq!(result, Ok((Money::from_cents(13), "")));
+
+        let input = "abc";
+        let result = money().parse(input);
+        assert!(result.is_err());
+
+        // 桁あふれする額はパースエラーにする
+        let input = "99999999999999999999";
+        let result = money().parse(input);
+        assert!(result.is_err());
+    }
+}
+
+// 返品の売上は負の額で書く
+fn signed_money() -> impl Parser<Item = Money> {
+    char('-').skip(money()).map(|m| -m).or(money())
+}
+#[cfg(test)]
+mod test_signed_money {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = "-12.5";
+        let result = signed_money().parse(input);
+        assert_eq!(result, Ok((Money::from_cents(-1250), "")));
+
+        let input = "12.5";
+        let result = signed_money().parse(input);
+        assert_eq!(result, Ok((Money::from_cents(1250), "")));
+
+        let input = "-";
+        let result = signed_money().parse(input);
+        assert!(result.is_err());
+    }
+}
+
+fn time_card() -> impl Parser<Item = Tx> {
+    let prefix = keyword("TimeCard").skip(spaces());
+    let emp_id = employee_id();
+    let date = date();
+    let hours = float32().label("<hour>".into());
+
+    prefix
+        .skip(emp_id)
+        .join(date)
+        .join(hours)
+        .map(|((id, date), hours)| {
+            debug!("parsed TimeCard: id={}, date={}, hours={}", id, date, hours);
+            Tx::AddTimeCard { id, date, hours }
+        })
+}
+#[cfg(test)]
+mod test_time_card {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"TimeCard 1 2021-01-01 8.0"#;
+        let result = time_card().parse(input);
+        assert_eq!(
             result,
             Ok((
                 Tx::AddTimeCard {
@@ -648,11 +1108,84 @@ mod test_time_card {
     }
 }
 
+fn amend_time_card() -> impl Parser<Item = Tx> {
+    let prefix = keyword("AmendTimeCard").skip(spaces());
+    let emp_id = employee_id();
+    let date = date();
+    let hours = float32().label("<hour>".into());
+
+    prefix
+        .skip(emp_id)
+        .join(date)
+        .join(hours)
+        .map(|((id, date), hours)| {
+            debug!(
+                "parsed AmendTimeCard: id={}, date={}, hours={}",
+                id, date, hours
+            );
+            Tx::AmendTimeCard { id, date, hours }
+        })
+}
+#[cfg(test)]
+mod test_amend_time_card {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"AmendTimeCard 1 2021-01-01 7.5"#;
+        let result = amend_time_card().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::AmendTimeCard {
+                    id: 1.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                    hours: 7.5
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn void_time_card() -> impl Parser<Item = Tx> {
+    let prefix = keyword("VoidTimeCard").skip(spaces());
+    let emp_id = employee_id();
+    let date = date();
+
+    prefix.skip(emp_id).join(date).map(|(id, date)| {
+        debug!("parsed VoidTimeCard: id={}, date={}", id, date);
+        Tx::VoidTimeCard { id, date }
+    })
+}
+#[cfg(test)]
+mod test_void_time_card {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"VoidTimeCard 1 2021-01-01"#;
+        let result = void_time_card().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::VoidTimeCard {
+                    id: 1.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                },
+                ""
+            ))
+        );
+    }
+}
+
 fn sales_receipt() -> impl Parser<Item = Tx> {
     let prefix = keyword("SalesReceipt").skip(spaces());
     let emp_id = employee_id();
     let date = date();
-    let amount = float32().label("<amount>".into());
+    let amount = signed_money().label("<amount>".into());
 
     prefix
         .skip(emp_id)
@@ -681,7 +1214,45 @@ mod test_sales_receipt {
                 Tx::AddSalesReceipt {
                     id: 1.into(),
                     date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-                    amount: 1000.0
+                    amount: Money::from_cents(100000)
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn void_sales_receipt() -> impl Parser<Item = Tx> {
+    let prefix = keyword("VoidSalesReceipt").skip(spaces());
+    let emp_id = employee_id();
+    let receipt_id = uint32().map(ReceiptId::from).label("<receipt_id>".into());
+
+    prefix
+        .skip(emp_id)
+        .join(receipt_id)
+        .map(|(id, receipt_id)| {
+            debug!(
+                "parsed VoidSalesReceipt: id={}, receipt_id={}",
+                id, receipt_id
+            );
+            Tx::VoidSalesReceipt { id, receipt_id }
+        })
+}
+#[cfg(test)]
+mod test_void_sales_receipt {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"VoidSalesReceipt 1 2"#;
+        let result = void_sales_receipt().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::VoidSalesReceipt {
+                    id: 1.into(),
+                    receipt_id: 2.into(),
                 },
                 ""
             ))
@@ -693,7 +1264,7 @@ fn service_charge() -> impl Parser<Item = Tx> {
     let prefix = keyword("ServiceCharge").skip(spaces());
     let member_id = member_id();
     let date = date();
-    let amount = float32().label("<amount>".into());
+    let amount = money().label("<amount>".into());
 
     prefix
         .skip(member_id)
@@ -726,7 +1297,48 @@ mod test_service_charge {
                 Tx::AddServiceCharge {
                     member_id: 1.into(),
                     date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-                    amount: 1000.0
+                    amount: Money::from_cents(100000)
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn void_service_charge() -> impl Parser<Item = Tx> {
+    let prefix = keyword("VoidServiceCharge").skip(spaces());
+    let member_id = member_id();
+    let charge_id = uint32().map(ChargeId::from).label("<charge_id>".into());
+
+    prefix
+        .skip(member_id)
+        .join(charge_id)
+        .map(|(member_id, charge_id)| {
+            debug!(
+                "parsed VoidServiceCharge: member_id={}, charge_id={}",
+                member_id, charge_id
+            );
+            Tx::VoidServiceCharge {
+                member_id,
+                charge_id,
+            }
+        })
+}
+#[cfg(test)]
+mod test_void_service_charge {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"VoidServiceCharge 7734 1"#;
+        let result = void_service_charge().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::VoidServiceCharge {
+                    member_id: 7734.into(),
+                    charge_id: 1.into(),
                 },
                 ""
             ))
@@ -814,130 +1426,305 @@ mod test_chg_address {
     }
 }
 
-fn chg_hourly() -> impl Parser<Item = Tx> {
+fn chg_tax() -> impl Parser<Item = Tx> {
     let prefix = keyword("ChgEmp").skip(spaces());
     let emp_id = employee_id();
-    let target = keyword("Hourly").skip(spaces()).label("`Hourly'".into());
-    let hourly_rate = float32().label("<hourly_rate>".into());
+    let target = keyword("Tax").skip(spaces()).label("`Tax'".into());
+    let filing_status = filing_status();
+    let allowances = uint32().with(spaces()).label("<allowances>".into());
 
     prefix
         .skip(emp_id)
         .with(target)
-        .join(hourly_rate)
-        .map(|(id, hourly_rate)| {
+        .join(filing_status)
+        .join(allowances)
+        .map(|((id, filing_status), allowances)| {
             debug!(
-                "parsed ChangeEmployeeHourly: id={}, hourly_rate={}",
-                id, hourly_rate
+                "parsed ChangeEmployeeTax: id={}, filing_status={}, allowances={}",
+                id, filing_status, allowances
             );
-            Tx::ChangeEmployeeHourly { id, hourly_rate }
+            Tx::ChangeEmployeeTax {
+                id,
+                tax: TaxProfile::new(filing_status, allowances),
+            }
         })
 }
 #[cfg(test)]
-mod test_chg_hourly {
+mod test_chg_tax {
     use super::*;
     use parsec_rs::Parser;
 
     #[test]
     fn test() {
-        let input = r#"ChgEmp 1 Hourly 13.78"#;
-        let result = chg_hourly().parse(input);
+        let input = r#"ChgEmp 1 Tax HeadOfHousehold 3"#;
+        let result = chg_tax().parse(input);
         assert_eq!(
             result,
             Ok((
-                Tx::ChangeEmployeeHourly {
+                Tx::ChangeEmployeeTax {
                     id: 1.into(),
-                    hourly_rate: 13.78
+                    tax: TaxProfile::new(FilingStatus::HeadOfHousehold, 3)
                 },
                 ""
             ))
         );
+
+        let input = r#"ChgEmp 1 Tax Widowed 0"#;
+        let result = chg_tax().parse(input);
+        assert!(result.is_err());
     }
 }
 
-fn chg_salaried() -> impl Parser<Item = Tx> {
+fn filing_status() -> impl Parser<Item = FilingStatus> {
+    let single = keyword("Single").map(|_| FilingStatus::Single);
+    let married = keyword("Married").map(|_| FilingStatus::Married);
+    let head_of_household = keyword("HeadOfHousehold").map(|_| FilingStatus::HeadOfHousehold);
+
+    single
+        .or(married)
+        .or(head_of_household)
+        .with(spaces())
+        .label("`Single' or `Married' or `HeadOfHousehold'".into())
+}
+
+fn chg_overtime() -> impl Parser<Item = Tx> {
     let prefix = keyword("ChgEmp").skip(spaces());
     let emp_id = employee_id();
-    let target = keyword("Salaried")
+    let target = keyword("Overtime")
         .skip(spaces())
-        .label("`Salaried'".into());
-    let salary = float32().label("<monthly_salary>".into());
+        .label("`Overtime'".into());
+    // Default なら会社全体の規則に戻す
+    let default = keyword("Default").with(spaces()).map(|_| None);
+    let clauses = overtime_clause().many1().map(|clauses| {
+        // 書かれていない規則は適用しない
+        let policy = clauses
+            .into_iter()
+            .fold(OvertimePolicy::none(), |mut policy, clause| {
+                match clause {
+                    OvertimeClause::Daily(h) => policy.daily_after = Some(h),
+                    OvertimeClause::Weekly(h) => policy.weekly_after = Some(h),
+                    OvertimeClause::DoubleTime(h) => policy.double_time_after = Some(h),
+                    OvertimeClause::Weekend(r) => policy.weekend_rate = Some(r),
+                    OvertimeClause::Holiday(r) => policy.holiday_rate = Some(r),
+                    OvertimeClause::WeekStart(w) => policy.week_start = w,
+                    OvertimeClause::Proration(p) => policy.proration = p,
+                }
+                policy
+            });
+        Some(policy)
+    });
 
     prefix
         .skip(emp_id)
         .with(target)
-        .join(salary)
-        .map(|(id, salary)| {
+        .join(default.or(clauses))
+        .map(|(id, overtime)| {
             debug!(
-                "parsed ChangeEmployeeSalaried: id={}, salary={}",
-                id, salary
+                "parsed ChangeEmployeeOvertime: id={}, overtime={:?}",
+                id, overtime
             );
-            Tx::ChangeEmployeeSalaried { id, salary }
+            Tx::ChangeEmployeeOvertime { id, overtime }
         })
 }
 #[cfg(test)]
-mod test_chg_salaried {
+mod test_chg_overtime {
     use super::*;
     use parsec_rs::Parser;
 
     #[test]
     fn test() {
-        let input = r#"ChgEmp 1 Salaried 1023.456"#;
-        let result = chg_salaried().parse(input);
+        let input = r#"ChgEmp 1 Overtime Default"#;
+        let result = chg_overtime().parse(input);
         assert_eq!(
             result,
             Ok((
-                Tx::ChangeEmployeeSalaried {
+                Tx::ChangeEmployeeOvertime {
+                    id: 1.into(),
+                    overtime: None
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Overtime Daily 8 Weekend 1.25 Holiday 2.0 WeekStart Sun"#;
+        let result = chg_overtime().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeOvertime {
                     id: 1.into(),
-                    salary: 1023.456
+                    overtime: Some(OvertimePolicy {
+                        daily_after: Some(8.0),
+                        weekend_rate: Some(1.25),
+                        holiday_rate: Some(2.0),
+                        week_start: Weekday::Sun,
+                        ..OvertimePolicy::none()
+                    })
                 },
                 ""
             ))
         );
+
+        let input = r#"ChgEmp 1 Overtime Proration WorkingDays"#;
+        let result = chg_overtime().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeOvertime {
+                    id: 1.into(),
+                    overtime: Some(OvertimePolicy {
+                        proration: Proration::WorkingDays,
+                        ..OvertimePolicy::none()
+                    })
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Overtime"#;
+        let result = chg_overtime().parse(input);
+        assert!(result.is_err());
+
+        let input = r#"ChgEmp 1 Overtime Holiday"#;
+        let result = chg_overtime().parse(input);
+        assert!(result.is_err());
     }
 }
 
-fn chg_commissioned() -> impl Parser<Item = Tx> {
+#[derive(Debug, Clone, PartialEq)]
+enum OvertimeClause {
+    Daily(f32),
+    Weekly(f32),
+    DoubleTime(f32),
+    Weekend(f32),
+    Holiday(f32),
+    WeekStart(Weekday),
+    Proration(Proration),
+}
+fn overtime_clause() -> impl Parser<Item = OvertimeClause> {
+    let hours = || float32().with(spaces()).label("<hours>".into());
+    let rate = || float32().with(spaces()).label("<rate>".into());
+    let daily = keyword("Daily")
+        .skip(spaces())
+        .skip(hours())
+        .map(OvertimeClause::Daily);
+    let weekly = keyword("Weekly")
+        .skip(spaces())
+        .skip(hours())
+        .map(OvertimeClause::Weekly);
+    let double_time = keyword("DoubleTime")
+        .skip(spaces())
+        .skip(hours())
+        .map(OvertimeClause::DoubleTime);
+    let weekend = keyword("Weekend")
+        .skip(spaces())
+        .skip(rate())
+        .map(OvertimeClause::Weekend);
+    let holiday = keyword("Holiday")
+        .skip(spaces())
+        .skip(rate())
+        .map(OvertimeClause::Holiday);
+    let week_start = keyword("WeekStart")
+        .skip(spaces())
+        .skip(weekday())
+        .map(OvertimeClause::WeekStart);
+    let working_days = keyword("WorkingDays").map(|_| Proration::WorkingDays);
+    let calendar_days = keyword("CalendarDays").map(|_| Proration::CalendarDays);
+    let proration = keyword("Proration")
+        .skip(spaces())
+        .skip(
+            working_days
+                .or(calendar_days)
+                .with(spaces())
+                .label("`WorkingDays' or `CalendarDays'".into()),
+        )
+        .map(OvertimeClause::Proration);
+
+    daily
+        .or(weekly)
+        .or(double_time)
+        .or(weekend)
+        .or(holiday)
+        .or(week_start)
+        .or(proration)
+        .label(
+            "`Daily' or `Weekly' or `DoubleTime' or `Weekend' or `Holiday' or `WeekStart' or `Proration'".into(),
+        )
+}
+
+fn weekday() -> impl Parser<Item = Weekday> {
+    let mon = keyword("Mon").map(|_| Weekday::Mon);
+    let tue = keyword("Tue").map(|_| Weekday::Tue);
+    let wed = keyword("Wed").map(|_| Weekday::Wed);
+    let thu = keyword("Thu").map(|_| Weekday::Thu);
+    let fri = keyword("Fri").map(|_| Weekday::Fri);
+    let sat = keyword("Sat").map(|_| Weekday::Sat);
+    let sun = keyword("Sun").map(|_| Weekday::Sun);
+
+    mon.or(tue)
+        .or(wed)
+        .or(thu)
+        .or(fri)
+        .or(sat)
+        .or(sun)
+        .with(spaces())
+        .label("<weekday>".into())
+}
+
+fn chg_hourly() -> impl Parser<Item = Tx> {
     let prefix = keyword("ChgEmp").skip(spaces());
     let emp_id = employee_id();
-    let target = keyword("Commissioned")
-        .skip(spaces())
-        .label("`Commissioned'".into());
-    let salary = float32().label("<salary>".into()).with(spaces());
-    let commission_rate = float32().label("<commission_rate>".into());
+    let target = keyword("Hourly").skip(spaces()).label("`Hourly'".into());
+    let hourly_rate = money().label("<hourly_rate>".into()).with(spaces());
+    let effective = effective_date();
 
     prefix
         .skip(emp_id)
         .with(target)
-        .join(salary)
-        .join(commission_rate)
-        .map(|((id, salary), commission_rate)| {
+        .join(hourly_rate)
+        .join(effective)
+        .map(|((id, hourly_rate), effective)| {
             debug!(
-                "parsed ChangeEmployeeCommissioned: id={}, salary={}, commission_rate={}",
-                id, salary, commission_rate
+                "parsed ChangeEmployeeHourly: id={}, hourly_rate={}, effective={:?}",
+                id, hourly_rate, effective
             );
-            Tx::ChangeEmployeeCommissioned {
+            Tx::ChangeEmployeeHourly {
                 id,
-                salary,
-                commission_rate,
+                hourly_rate,
+                effective,
             }
         })
 }
 #[cfg(test)]
-mod test_chg_commissioned {
+mod test_chg_hourly {
     use super::*;
     use parsec_rs::Parser;
 
     #[test]
     fn test() {
-        let input = r#"ChgEmp 1 Commissioned 1018.91 .19"#;
-        let result = chg_commissioned().parse(input);
+        let input = r#"ChgEmp 1 Hourly 13.78"#;
+        let result = chg_hourly().parse(input);
         assert_eq!(
             result,
             Ok((
-                Tx::ChangeEmployeeCommissioned {
+                Tx::ChangeEmployeeHourly {
+                    id: 1.into(),
+                    hourly_rate: Money::from_cents(1378),
+                    effective: None,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Hourly 17.50 2025-01-15"#;
+        let result = chg_hourly().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeHourly {
                     id: 1.into(),
-                    salary: 1018.91,
-                    commission_rate: 0.19
+                    hourly_rate: Money::from_cents(1750),
+                    effective: NaiveDate::from_ymd_opt(2025, 1, 15),
                 },
                 ""
             ))
@@ -945,25 +1732,300 @@ mod test_chg_commissioned {
     }
 }
 
-fn chg_hold() -> impl Parser<Item = Tx> {
+fn chg_salaried() -> impl Parser<Item = Tx> {
     let prefix = keyword("ChgEmp").skip(spaces());
     let emp_id = employee_id();
-    let hold = keyword("Hold").label("`Hold'".into());
+    let target = keyword("Salaried")
+        .skip(spaces())
+        .label("`Salaried'".into());
+    let salary = money().label("<monthly_salary>".into()).with(spaces());
+    let effective = effective_date();
 
-    prefix.skip(emp_id).with(hold).map(|id| {
-        debug!("parsed ChangeEmployeeHold: id={}", id);
-        Tx::ChangeEmployeeHold { id }
-    })
+    prefix
+        .skip(emp_id)
+        .with(target)
+        .join(salary)
+        .join(effective)
+        .map(|((id, salary), effective)| {
+            debug!(
+                "parsed ChangeEmployeeSalaried: id={}, salary={}, effective={:?}",
+                id, salary, effective
+            );
+            Tx::ChangeEmployeeSalaried {
+                id,
+                salary,
+                effective,
+            }
+        })
 }
 #[cfg(test)]
-mod test_chg_hold {
+mod test_chg_salaried {
     use super::*;
     use parsec_rs::Parser;
 
     #[test]
     fn test() {
-        let input = r#"ChgEmp 1 Hold"#;
-        let result = chg_hold().parse(input);
+        let input = r#"ChgEmp 1 Salaried 1023.456"#;
+        let result = chg_salaried().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSalaried {
+                    id: 1.into(),
+                    salary: Money::from_cents(102346),
+                    effective: None,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Salaried 3720 2025-01-11"#;
+        let result = chg_salaried().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSalaried {
+                    id: 1.into(),
+                    salary: Money::from_cents(372000),
+                    effective: NaiveDate::from_ymd_opt(2025, 1, 11),
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn chg_commissioned() -> impl Parser<Item = Tx> {
+    let prefix = keyword("ChgEmp").skip(spaces());
+    let emp_id = employee_id();
+    let target = keyword("Commissioned")
+        .skip(spaces())
+        .label("`Commissioned'".into());
+    let salary = money().label("<salary>".into()).with(spaces());
+    let commission_rate = float32().label("<commission_rate>".into()).with(spaces());
+    let effective = effective_date();
+
+    prefix
+        .skip(emp_id)
+        .with(target)
+        .join(salary)
+        .join(commission_rate)
+        .join(effective)
+        .map(|(((id, salary), commission_rate), effective)| {
+            debug!(
+                "parsed ChangeEmployeeCommissioned: id={}, salary={}, commission_rate={}, effective={:?}",
+                id, salary, commission_rate, effective
+            );
+            Tx::ChangeEmployeeCommissioned {
+                id,
+                salary,
+                commission_rate,
+                effective,
+            }
+        })
+}
+#[cfg(test)]
+mod test_chg_commissioned {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"ChgEmp 1 Commissioned 1018.91 .19"#;
+        let result = chg_commissioned().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeCommissioned {
+                    id: 1.into(),
+                    salary: Money::from_cents(101891),
+                    commission_rate: 0.19,
+                    effective: None,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Commissioned 2100 .15 2025-01-18"#;
+        let result = chg_commissioned().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeCommissioned {
+                    id: 1.into(),
+                    salary: Money::from_cents(210000),
+                    commission_rate: 0.15,
+                    effective: NaiveDate::from_ymd_opt(2025, 1, 18),
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn chg_schedule() -> impl Parser<Item = Tx> {
+    let prefix = keyword("ChgEmp").skip(spaces());
+    let emp_id = employee_id();
+    let target = keyword("Schedule")
+        .skip(spaces())
+        .label("`Schedule'".into());
+    let schedule = schedule();
+
+    prefix
+        .skip(emp_id)
+        .with(target)
+        .join(schedule)
+        .map(|(id, schedule)| {
+            debug!(
+                "parsed ChangeEmployeeSchedule: id={}, schedule={:?}",
+                id, schedule
+            );
+            Tx::ChangeEmployeeSchedule { id, schedule }
+        })
+}
+#[cfg(test)]
+mod test_chg_schedule {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"ChgEmp 1 Schedule Monthly"#;
+        let result = chg_schedule().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSchedule {
+                    id: 1.into(),
+                    schedule: ScheduleInfo::Monthly,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Schedule Monthly 25"#;
+        let result = chg_schedule().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSchedule {
+                    id: 1.into(),
+                    schedule: ScheduleInfo::MonthlyOn { day: 25 },
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Schedule Semimonthly"#;
+        let result = chg_schedule().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSchedule {
+                    id: 1.into(),
+                    schedule: ScheduleInfo::Semimonthly,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Schedule Weekly Mon"#;
+        let result = chg_schedule().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSchedule {
+                    id: 1.into(),
+                    schedule: ScheduleInfo::WeeklyOn {
+                        weekday: Weekday::Mon
+                    },
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Schedule Biweekly 2025-01-06"#;
+        let result = chg_schedule().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeSchedule {
+                    id: 1.into(),
+                    schedule: ScheduleInfo::BiweeklyFrom {
+                        anchor: NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()
+                    },
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Schedule Daily"#;
+        let result = chg_schedule().parse(input);
+        assert!(result.is_err());
+    }
+}
+
+// 設定を省略すると給与の種類ごとの既定のスケジュール (月末・金曜日・偶数週の金曜日)
+fn schedule() -> impl Parser<Item = ScheduleInfo> {
+    let day = uint32().with(spaces()).label("<day>".into());
+    let monthly = keyword("Monthly")
+        .skip(spaces())
+        .skip(day.map(Some).or(spaces().map(|_| None)))
+        .map(|day| match day {
+            Some(day) => ScheduleInfo::MonthlyOn { day },
+            None => ScheduleInfo::Monthly,
+        });
+    let semimonthly = keyword("Semimonthly")
+        .skip(spaces())
+        .map(|_| ScheduleInfo::Semimonthly);
+    let weekly = keyword("Weekly")
+        .skip(spaces())
+        .skip(weekday().map(Some).or(spaces().map(|_| None)))
+        .map(|weekday| match weekday {
+            Some(weekday) => ScheduleInfo::WeeklyOn { weekday },
+            None => ScheduleInfo::Weekly,
+        });
+    let biweekly = keyword("Biweekly")
+        .skip(spaces())
+        .skip(date().map(Some).or(spaces().map(|_| None)))
+        .map(|anchor| match anchor {
+            Some(anchor) => ScheduleInfo::BiweeklyFrom { anchor },
+            None => ScheduleInfo::Biweekly,
+        });
+
+    monthly
+        .or(semimonthly)
+        .or(weekly)
+        .or(biweekly)
+        .label("`Monthly' or `Semimonthly' or `Weekly' or `Biweekly'".into())
+}
+
+// レートの効力発生日 (省略時は None)
+fn effective_date() -> impl Parser<Item = Option<NaiveDate>> {
+    let omitted = spaces().map(|_| None);
+
+    date().map(Some).or(omitted)
+}
+
+fn chg_hold() -> impl Parser<Item = Tx> {
+    let prefix = keyword("ChgEmp").skip(spaces());
+    let emp_id = employee_id();
+    let hold = keyword("Hold").label("`Hold'".into());
+
+    prefix.skip(emp_id).with(hold).map(|id| {
+        debug!("parsed ChangeEmployeeHold: id={}", id);
+        Tx::ChangeEmployeeHold { id }
+    })
+}
+#[cfg(test)]
+mod test_chg_hold {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"ChgEmp 1 Hold"#;
+        let result = chg_hold().parse(input);
         assert_eq!(result, Ok((Tx::ChangeEmployeeHold { id: 1.into() }, "")));
     }
 }
@@ -1054,7 +2116,7 @@ fn chg_member() -> impl Parser<Item = Tx> {
     let target = keyword("Member").skip(spaces()).label("`Member'".into());
     let member_id = member_id();
     let key = keyword("Dues").skip(spaces()).label("`Dues'".into());
-    let dues = float32().label("<dues>".into());
+    let dues = money().label("<dues>".into());
 
     prefix
         .skip(emp_id)
@@ -1089,7 +2151,7 @@ mod test_chg_member {
                 Tx::ChangeEmployeeMember {
                     emp_id: 1.into(),
                     member_id: 2.into(),
-                    dues: 100.0
+                    dues: Money::from_cents(10000)
                 },
                 ""
             ))
@@ -1123,13 +2185,85 @@ mod test_chg_no_member {
     }
 }
 
+fn chg_status() -> impl Parser<Item = Tx> {
+    let prefix = keyword("ChgEmp").skip(spaces());
+    let emp_id = employee_id();
+    let target = keyword("Status").skip(spaces()).label("`Status'".into());
+    // 退職は最後の Paycheck を支払う Terminate でしかできない
+    let active = keyword("Active").map(|_| EmploymentStatus::Active);
+    let on_leave = keyword("OnLeave").map(|_| EmploymentStatus::OnLeave);
+    let status = active
+        .or(on_leave)
+        .with(spaces())
+        .label("`Active' or `OnLeave'".into());
+    let effective = date().label("<effective>".into());
+
+    prefix
+        .skip(emp_id)
+        .with(target)
+        .join(status)
+        .join(effective)
+        .map(|((id, status), effective)| {
+            debug!(
+                "parsed ChangeEmployeeStatus: id={}, status={}, effective={}",
+                id, status, effective
+            );
+            Tx::ChangeEmployeeStatus {
+                id,
+                status,
+                effective,
+            }
+        })
+}
+#[cfg(test)]
+mod test_chg_status {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"ChgEmp 1 Status OnLeave 2025-02-03"#;
+        let result = chg_status().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeStatus {
+                    id: 1.into(),
+                    status: EmploymentStatus::OnLeave,
+                    effective: NaiveDate::from_ymd_opt(2025, 2, 3).unwrap(),
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Status Active 2025-03-03"#;
+        let result = chg_status().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ChangeEmployeeStatus {
+                    id: 1.into(),
+                    status: EmploymentStatus::Active,
+                    effective: NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(),
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ChgEmp 1 Status Terminated 2025-03-03"#;
+        let result = chg_status().parse(input);
+        assert!(result.is_err());
+    }
+}
+
 fn payday() -> impl Parser<Item = Tx> {
     let prefix = keyword("Payday").skip(spaces());
     let date = date();
+    let policy = rerun_policy();
 
-    prefix.skip(date).map(|date| {
-        debug!("parsed Payday: date={}", date);
-        Tx::Payday { date }
+    prefix.skip(date).join(policy).map(|(date, policy)| {
+        debug!("parsed Payday: date={},policy={}", date, policy);
+        Tx::Payday { date, policy }
     })
 }
 #[cfg(test)]
@@ -1145,7 +2279,490 @@ mod test_payday {
             result,
             Ok((
                 Tx::Payday {
-                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                    policy: RerunPolicy::Reject,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"Payday 2021-01-01 Skip"#;
+        let result = payday().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::Payday {
+                    date: NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+                    policy: RerunPolicy::Skip,
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn preview_payday() -> impl Parser<Item = Tx> {
+    let prefix = keyword("PreviewPayday").skip(spaces());
+    let date = date();
+
+    prefix.skip(date).map(|date| {
+        debug!("parsed PreviewPayday: date={}", date);
+        Tx::PreviewPayday { date }
+    })
+}
+#[cfg(test)]
+mod test_preview_payday {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"PreviewPayday 2024-08-30"#;
+        let result = preview_payday().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::PreviewPayday {
+                    date: NaiveDate::from_ymd_opt(2024, 8, 30).unwrap(),
+                },
+                ""
+            ))
+        );
+    }
+    #[test]
+    fn test_no_date() {
+        let input = r#"PreviewPayday"#;
+        let result = preview_payday().parse(input);
+        assert!(result.is_err());
+    }
+}
+
+fn rerun_policy() -> impl Parser<Item = RerunPolicy> {
+    let reject = keyword("Reject").map(|_| RerunPolicy::Reject);
+    let skip = keyword("Skip").map(|_| RerunPolicy::Skip);
+    let replay = keyword("Replay").map(|_| RerunPolicy::Replay);
+    // 省略時は Reject
+    let omitted = spaces().map(|_| RerunPolicy::default());
+
+    reject
+        .or(skip)
+        .or(replay)
+        .with(spaces())
+        .label("`Reject' or `Skip' or `Replay'".into())
+        .or(omitted)
+}
+#[cfg(test)]
+mod test_rerun_policy {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"Reject"#;
+        let result = rerun_policy().parse(input);
+        assert_eq!(result, Ok((RerunPolicy::Reject, "")));
+
+        let input = r#"Skip"#;
+        let result = rerun_policy().parse(input);
+        assert_eq!(result, Ok((RerunPolicy::Skip, "")));
+
+        let input = r#"Replay"#;
+        let result = rerun_policy().parse(input);
+        assert_eq!(result, Ok((RerunPolicy::Replay, "")));
+
+        let input = r#""#;
+        let result = rerun_policy().parse(input);
+        assert_eq!(result, Ok((RerunPolicy::Reject, "")));
+    }
+}
+
+fn bonus() -> impl Parser<Item = Tx> {
+    let prefix = keyword("Bonus").skip(spaces());
+    let emp_id = employee_id();
+    let date = date();
+    let amount = money().with(spaces()).label("<amount>".into());
+    let period_start = effective_date();
+
+    prefix
+        .skip(emp_id)
+        .join(date)
+        .join(amount)
+        .join(period_start)
+        .map(|(((id, date), amount), period_start)| {
+            debug!(
+                "parsed Bonus: id={}, date={}, amount={}, period_start={:?}",
+                id, date, amount, period_start
+            );
+            Tx::PayOffCycle {
+                id,
+                run_type: RunType::Bonus,
+                date,
+                amount,
+                period_start,
+            }
+        })
+}
+#[cfg(test)]
+mod test_bonus {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"Bonus 1 2025-03-14 500.00"#;
+        let result = bonus().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::PayOffCycle {
+                    id: 1.into(),
+                    run_type: RunType::Bonus,
+                    date: NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(),
+                    amount: Money::from_cents(50000),
+                    period_start: None,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"Bonus 1 2025-03-31 500.00 2025-01-01"#;
+        let result = bonus().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::PayOffCycle {
+                    id: 1.into(),
+                    run_type: RunType::Bonus,
+                    date: NaiveDate::from_ymd_opt(2025, 3, 31).unwrap(),
+                    amount: Money::from_cents(50000),
+                    period_start: NaiveDate::from_ymd_opt(2025, 1, 1),
+                },
+                ""
+            ))
+        );
+
+        let input = r#"Bonus 1 2025-03-14 -500.00"#;
+        let result = bonus().parse(input);
+        assert!(result.is_err());
+    }
+}
+
+// 払いすぎを回収するときは負の額で書く
+fn correction() -> impl Parser<Item = Tx> {
+    let prefix = keyword("Correction").skip(spaces());
+    let emp_id = employee_id();
+    let date = date();
+    let amount = signed_money().with(spaces()).label("<amount>".into());
+    let period_start = effective_date();
+
+    prefix
+        .skip(emp_id)
+        .join(date)
+        .join(amount)
+        .join(period_start)
+        .map(|(((id, date), amount), period_start)| {
+            debug!(
+                "parsed Correction: id={}, date={}, amount={}, period_start={:?}",
+                id, date, amount, period_start
+            );
+            Tx::PayOffCycle {
+                id,
+                run_type: RunType::Correction,
+                date,
+                amount,
+                period_start,
+            }
+        })
+}
+#[cfg(test)]
+mod test_correction {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"Correction 1 2025-03-14 -25.50 2025-02-01"#;
+        let result = correction().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::PayOffCycle {
+                    id: 1.into(),
+                    run_type: RunType::Correction,
+                    date: NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(),
+                    amount: Money::from_cents(-2550),
+                    period_start: NaiveDate::from_ymd_opt(2025, 2, 1),
+                },
+                ""
+            ))
+        );
+
+        let input = r#"Correction 1 2025-03-14"#;
+        let result = correction().parse(input);
+        assert!(result.is_err());
+    }
+}
+
+fn get_paycheck() -> impl Parser<Item = Tx> {
+    let prefix = keyword("GetPaycheck").skip(spaces());
+    let emp_id = employee_id();
+    let date = date();
+    let run_type = run_type();
+
+    prefix
+        .skip(emp_id)
+        .join(date)
+        .join(run_type)
+        .map(|((id, date), run_type)| {
+            debug!(
+                "parsed GetPaycheck: id={},date={},run_type={}",
+                id, date, run_type
+            );
+            Tx::GetPaycheck { id, date, run_type }
+        })
+}
+#[cfg(test)]
+mod test_get_paycheck {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"GetPaycheck 1 2021-01-29"#;
+        let result = get_paycheck().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::GetPaycheck {
+                    id: 1.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 29).unwrap(),
+                    run_type: RunType::Regular,
+                },
+                ""
+            ))
+        );
+
+        let input = r#"GetPaycheck 1 2021-01-29 Bonus"#;
+        let result = get_paycheck().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::GetPaycheck {
+                    id: 1.into(),
+                    date: NaiveDate::from_ymd_opt(2021, 1, 29).unwrap(),
+                    run_type: RunType::Bonus,
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn run_type() -> impl Parser<Item = RunType> {
+    let regular = keyword("Regular").map(|_| RunType::Regular);
+    let bonus = keyword("Bonus").map(|_| RunType::Bonus);
+    let correction = keyword("Correction").map(|_| RunType::Correction);
+    let final_ = keyword("Final").map(|_| RunType::Final);
+    // 省略時は Regular
+    let omitted = spaces().map(|_| RunType::default());
+
+    regular
+        .or(bonus)
+        .or(correction)
+        .or(final_)
+        .with(spaces())
+        .label("`Regular' or `Bonus' or `Correction' or `Final'".into())
+        .or(omitted)
+}
+
+fn get_ytd() -> impl Parser<Item = Tx> {
+    let prefix = keyword("GetYtd").skip(spaces());
+    let emp_id = employee_id();
+    let year = int32().with(spaces()).label("<year>".into());
+
+    prefix.skip(emp_id).join(year).map(|(id, year)| {
+        debug!("parsed GetYearToDate: id={},year={}", id, year);
+        Tx::GetYearToDate { id, year }
+    })
+}
+#[cfg(test)]
+mod test_get_ytd {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"GetYtd 1 2021"#;
+        let result = get_ytd().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::GetYearToDate {
+                    id: 1.into(),
+                    year: 2021
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn get_emp() -> impl Parser<Item = Tx> {
+    let prefix = keyword("GetEmp").skip(spaces());
+    let emp_id = employee_id();
+
+    prefix.skip(emp_id).map(|id| {
+        debug!("parsed GetEmployee: id={}", id);
+        Tx::GetEmployee { id }
+    })
+}
+#[cfg(test)]
+mod test_get_emp {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"GetEmp 1"#;
+        let result = get_emp().parse(input);
+        assert_eq!(result, Ok((Tx::GetEmployee { id: 1.into() }, "")));
+    }
+}
+
+fn list_emp() -> impl Parser<Item = Tx> {
+    let prefix = keyword("ListEmp").skip(spaces());
+    let filter = employee_filter();
+
+    prefix.skip(filter).map(|filter| {
+        debug!("parsed ListEmployees: filter={}", filter);
+        Tx::ListEmployees { filter }
+    })
+}
+#[cfg(test)]
+mod test_list_emp {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"ListEmp"#;
+        let result = list_emp().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ListEmployees {
+                    filter: EmployeeFilter::All
+                },
+                ""
+            ))
+        );
+
+        let input = r#"ListEmp NoMember"#;
+        let result = list_emp().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::ListEmployees {
+                    filter: EmployeeFilter::NoMember
+                },
+                ""
+            ))
+        );
+    }
+}
+
+fn employee_filter() -> impl Parser<Item = EmployeeFilter> {
+    let salaried = keyword("Salaried").map(|_| EmployeeFilter::Salaried);
+    let hourly = keyword("Hourly").map(|_| EmployeeFilter::Hourly);
+    let commissioned = keyword("Commissioned").map(|_| EmployeeFilter::Commissioned);
+    let monthly = keyword("Monthly").map(|_| EmployeeFilter::Monthly);
+    let weekly = keyword("Weekly").map(|_| EmployeeFilter::Weekly);
+    let biweekly = keyword("Biweekly").map(|_| EmployeeFilter::Biweekly);
+    let semimonthly = keyword("Semimonthly").map(|_| EmployeeFilter::Semimonthly);
+    let hold = keyword("Hold").map(|_| EmployeeFilter::Hold);
+    let direct = keyword("Direct").map(|_| EmployeeFilter::Direct);
+    let mail = keyword("Mail").map(|_| EmployeeFilter::Mail);
+    let member = keyword("Member").map(|_| EmployeeFilter::Member);
+    let no_member = keyword("NoMember").map(|_| EmployeeFilter::NoMember);
+    let active = keyword("Active").map(|_| EmployeeFilter::Active);
+    let on_leave = keyword("OnLeave").map(|_| EmployeeFilter::OnLeave);
+    let terminated = keyword("Terminated").map(|_| EmployeeFilter::Terminated);
+    // 省略時は全員
+    let omitted = spaces().map(|_| EmployeeFilter::default());
+
+    salaried
+        .or(hourly)
+        .or(commissioned)
+        .or(monthly)
+        .or(weekly)
+        .or(biweekly)
+        .or(semimonthly)
+        .or(hold)
+        .or(direct)
+        .or(mail)
+        .or(member)
+        .or(no_member)
+        .or(active)
+        .or(on_leave)
+        .or(terminated)
+        .with(spaces())
+        .label("<filter>".into())
+        .or(omitted)
+}
+#[cfg(test)]
+mod test_employee_filter {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        for (input, expected) in [
+            ("Salaried", EmployeeFilter::Salaried),
+            ("Hourly", EmployeeFilter::Hourly),
+            ("Commissioned", EmployeeFilter::Commissioned),
+            ("Monthly", EmployeeFilter::Monthly),
+            ("Weekly", EmployeeFilter::Weekly),
+            ("Biweekly", EmployeeFilter::Biweekly),
+            ("Semimonthly", EmployeeFilter::Semimonthly),
+            ("Hold", EmployeeFilter::Hold),
+            ("Direct", EmployeeFilter::Direct),
+            ("Mail", EmployeeFilter::Mail),
+            ("Member", EmployeeFilter::Member),
+            ("NoMember", EmployeeFilter::NoMember),
+            ("Active", EmployeeFilter::Active),
+            ("OnLeave", EmployeeFilter::OnLeave),
+            ("Terminated", EmployeeFilter::Terminated),
+            ("", EmployeeFilter::All),
+        ] {
+            let result = employee_filter().parse(input);
+            assert_eq!(result, Ok((expected, "")), "input={:?}", input);
+        }
+    }
+}
+
+fn get_member() -> impl Parser<Item = Tx> {
+    let prefix = keyword("GetMember").skip(spaces());
+    let member_id = member_id();
+
+    prefix.skip(member_id).map(|member_id| {
+        debug!("parsed GetUnionMember: member_id={}", member_id);
+        Tx::GetUnionMember { member_id }
+    })
+}
+#[cfg(test)]
+mod test_get_member {
+    use super::*;
+    use parsec_rs::Parser;
+
+    #[test]
+    fn test() {
+        let input = r#"GetMember 7234"#;
+        let result = get_member().parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Tx::GetUnionMember {
+                    member_id: 7234.into()
                 },
                 ""
             ))
@@ -21,7 +21,9 @@ impl Drop for ThreadPool {
         for worker in &mut self.workers {
             debug!("Shutting down worker {}", worker.id);
             if let Some(thread) = worker.thread.take() {
-                thread.join().expect(&format!("join thread: {}", worker.id));
+                thread
+                    .join()
+                    .unwrap_or_else(|_| panic!("join thread: {}", worker.id));
             }
         }
     }
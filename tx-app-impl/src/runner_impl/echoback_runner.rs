@@ -9,7 +9,11 @@ impl Runner for TxEchoBackRunner {
         match tx.execute() {
             Ok(v) => {
                 // echo back the result of the transaction
-                println!("=> {:?}", v);
+                match &v {
+                    // 試算は表にして見せる
+                    Response::PaydayPreview(preview) => println!("=> {}", preview),
+                    _ => println!("=> {:?}", v),
+                }
                 Ok(v)
             }
             Err(e) => {
@@ -1,13 +1,28 @@
-use anyhow;
-
-use payroll_domain::EmployeeId;
+use payroll_domain::{
+    EmployeeId, EmployeeInfo, MemberId, PayRecord, Paycheck, PaydayPreview, YearToDate,
+};
 
 // トランザクションのインターフェース
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Response {
     Void,
     EmployeeId(EmployeeId),
     Verified(bool),
+    Paycheck(Paycheck),
+    YearToDate(YearToDate),
+    Payday {
+        paid: Vec<EmployeeId>,
+        skipped: Vec<EmployeeId>,
+        replayed: Vec<EmployeeId>,
+        records: Vec<PayRecord>,
+    },
+    PaydayPreview(PaydayPreview),
+    Employee(EmployeeInfo),
+    Employees(Vec<EmployeeInfo>),
+    UnionMember {
+        member_id: MemberId,
+        employee: EmployeeInfo,
+    },
 }
 pub trait Transaction {
     fn execute(&self) -> Result<Response, anyhow::Error>;
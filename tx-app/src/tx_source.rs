@@ -1,7 +1,10 @@
 use chrono::NaiveDate;
 
 use crate::tx::Transaction;
-use payroll_domain::{EmployeeId, MemberId};
+use payroll_domain::{
+    ChargeId, EmployeeFilter, EmployeeId, EmploymentStatus, MemberId, Money, OvertimePolicy,
+    ReceiptId, RerunPolicy, RunType, ScheduleInfo, TaxProfile,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Tx {
@@ -9,38 +12,64 @@ pub enum Tx {
         id: EmployeeId,
         name: String,
         address: String,
-        hourly_rate: f32,
+        hourly_rate: Money,
+        // 入社日 (None なら期間の制限なし)
+        hire_date: Option<NaiveDate>,
     },
     AddSalariedEmployee {
         id: EmployeeId,
         name: String,
         address: String,
-        salary: f32,
+        salary: Money,
+        hire_date: Option<NaiveDate>,
     },
     AddCommissionedEmployee {
         id: EmployeeId,
         name: String,
         address: String,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
+        hire_date: Option<NaiveDate>,
     },
     DeleteEmployee {
         id: EmployeeId,
     },
+    // 退職日までのまだ支払っていない分を最後の Paycheck で支払い、記録は残す
+    TerminateEmployee {
+        id: EmployeeId,
+        date: NaiveDate,
+    },
     AddTimeCard {
         id: EmployeeId,
         date: NaiveDate,
         hours: f32,
     },
+    AmendTimeCard {
+        id: EmployeeId,
+        date: NaiveDate,
+        hours: f32,
+    },
+    VoidTimeCard {
+        id: EmployeeId,
+        date: NaiveDate,
+    },
     AddSalesReceipt {
         id: EmployeeId,
         date: NaiveDate,
-        amount: f32,
+        amount: Money,
+    },
+    VoidSalesReceipt {
+        id: EmployeeId,
+        receipt_id: ReceiptId,
     },
     AddServiceCharge {
         member_id: MemberId,
         date: NaiveDate,
-        amount: f32,
+        amount: Money,
+    },
+    VoidServiceCharge {
+        member_id: MemberId,
+        charge_id: ChargeId,
     },
     ChangeEmployeeName {
         id: EmployeeId,
@@ -50,18 +79,44 @@ pub enum Tx {
         id: EmployeeId,
         new_address: String,
     },
+    ChangeEmployeeTax {
+        id: EmployeeId,
+        tax: TaxProfile,
+    },
+    // 休職と復職 (退職は TerminateEmployee で行う)
+    ChangeEmployeeStatus {
+        id: EmployeeId,
+        status: EmploymentStatus,
+        effective: NaiveDate,
+    },
+    // None なら会社全体の残業の規則に戻す
+    ChangeEmployeeOvertime {
+        id: EmployeeId,
+        overtime: Option<OvertimePolicy>,
+    },
+    // effective はレートの効力発生日 (None なら次の支払い期間から)
+    // 給与の種類が変わるときは使わない
     ChangeEmployeeHourly {
         id: EmployeeId,
-        hourly_rate: f32,
+        hourly_rate: Money,
+        effective: Option<NaiveDate>,
     },
     ChangeEmployeeSalaried {
         id: EmployeeId,
-        salary: f32,
+        salary: Money,
+        effective: Option<NaiveDate>,
     },
     ChangeEmployeeCommissioned {
         id: EmployeeId,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
+        effective: Option<NaiveDate>,
+    },
+    // 給与の種類とは別に支払いスケジュールだけを変える
+    // 新しいスケジュールは最後の支払日の翌日から
+    ChangeEmployeeSchedule {
+        id: EmployeeId,
+        schedule: ScheduleInfo,
     },
     ChangeEmployeeHold {
         id: EmployeeId,
@@ -78,13 +133,44 @@ pub enum Tx {
     ChangeEmployeeMember {
         emp_id: EmployeeId,
         member_id: MemberId,
-        dues: f32,
+        dues: Money,
     },
     ChangeEmployeeNoMember {
         emp_id: EmployeeId,
     },
     Payday {
         date: NaiveDate,
+        policy: RerunPolicy,
+    },
+    // Payday を記録も送り出しもせずに試算して、直近の支払いと比べる
+    PreviewPayday {
+        date: NaiveDate,
+    },
+    // 定期の Payday とは別に、指定した額の賞与 (Bonus) や精算 (Correction) を支払う
+    PayOffCycle {
+        id: EmployeeId,
+        run_type: RunType,
+        date: NaiveDate,
+        amount: Money,
+        period_start: Option<NaiveDate>,
+    },
+    GetPaycheck {
+        id: EmployeeId,
+        date: NaiveDate,
+        run_type: RunType,
+    },
+    GetYearToDate {
+        id: EmployeeId,
+        year: i32,
+    },
+    GetEmployee {
+        id: EmployeeId,
+    },
+    ListEmployees {
+        filter: EmployeeFilter,
+    },
+    GetUnionMember {
+        member_id: MemberId,
     },
 }
 
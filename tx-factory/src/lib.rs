@@ -1,11 +1,20 @@
 use chrono::NaiveDate;
 
-use payroll_domain::{EmployeeId, MemberId};
-use tx_app::Transaction;
+use payroll_domain::{
+    ChargeId, EmployeeFilter, EmployeeId, EmploymentStatus, MemberId, Money, OvertimePolicy,
+    ReceiptId, RerunPolicy, RunType, ScheduleInfo, TaxProfile,
+};
+use tx_app::{Transaction, Tx};
 
 pub trait AddSalariedEmployeeTxFactory {
-    fn mk_tx(&self, id: EmployeeId, name: &str, address: &str, salary: f32)
-        -> Box<dyn Transaction>;
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        name: &str,
+        address: &str,
+        salary: Money,
+        hire_date: Option<NaiveDate>,
+    ) -> Box<dyn Transaction>;
 }
 pub trait AddHourlyEmployeeTxFactory {
     fn mk_tx(
@@ -13,7 +22,8 @@ pub trait AddHourlyEmployeeTxFactory {
         id: EmployeeId,
         name: &str,
         address: &str,
-        hourly_rate: f32,
+        hourly_rate: Money,
+        hire_date: Option<NaiveDate>,
     ) -> Box<dyn Transaction>;
 }
 pub trait AddCommissionedEmployeeTxFactory {
@@ -22,21 +32,37 @@ pub trait AddCommissionedEmployeeTxFactory {
         id: EmployeeId,
         name: &str,
         address: &str,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
+        hire_date: Option<NaiveDate>,
     ) -> Box<dyn Transaction>;
 }
 pub trait DeleteEmployeeTxFactory {
     fn mk_tx(&self, id: EmployeeId) -> Box<dyn Transaction>;
 }
+pub trait TerminateEmployeeTxFactory {
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate) -> Box<dyn Transaction>;
+}
 pub trait AddTimecardTxFactory {
     fn mk_tx(&self, id: EmployeeId, date: NaiveDate, hours: f32) -> Box<dyn Transaction>;
 }
+pub trait AmendTimecardTxFactory {
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, hours: f32) -> Box<dyn Transaction>;
+}
+pub trait VoidTimecardTxFactory {
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate) -> Box<dyn Transaction>;
+}
 pub trait AddSalesReceiptTxFactory {
-    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, amount: f32) -> Box<dyn Transaction>;
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, amount: Money) -> Box<dyn Transaction>;
+}
+pub trait VoidSalesReceiptTxFactory {
+    fn mk_tx(&self, id: EmployeeId, receipt_id: ReceiptId) -> Box<dyn Transaction>;
 }
 pub trait AddServiceChargeTxFactory {
-    fn mk_tx(&self, member_id: MemberId, date: NaiveDate, amount: f32) -> Box<dyn Transaction>;
+    fn mk_tx(&self, member_id: MemberId, date: NaiveDate, amount: Money) -> Box<dyn Transaction>;
+}
+pub trait VoidServiceChargeTxFactory {
+    fn mk_tx(&self, member_id: MemberId, charge_id: ChargeId) -> Box<dyn Transaction>;
 }
 pub trait ChangeEmployeeNameTxFactory {
     fn mk_tx(&self, id: EmployeeId, new_name: &str) -> Box<dyn Transaction>;
@@ -44,14 +70,47 @@ pub trait ChangeEmployeeNameTxFactory {
 pub trait ChangeEmployeeAddressTxFactory {
     fn mk_tx(&self, id: EmployeeId, new_address: &str) -> Box<dyn Transaction>;
 }
+pub trait ChangeEmployeeTaxTxFactory {
+    fn mk_tx(&self, id: EmployeeId, tax: TaxProfile) -> Box<dyn Transaction>;
+}
+pub trait ChangeEmployeeStatusTxFactory {
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        status: EmploymentStatus,
+        effective: NaiveDate,
+    ) -> Box<dyn Transaction>;
+}
+pub trait ChangeEmployeeOvertimeTxFactory {
+    fn mk_tx(&self, id: EmployeeId, overtime: Option<OvertimePolicy>) -> Box<dyn Transaction>;
+}
 pub trait ChangeEmployeeSalariedTxFactory {
-    fn mk_tx(&self, id: EmployeeId, salary: f32) -> Box<dyn Transaction>;
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        salary: Money,
+        effective: Option<NaiveDate>,
+    ) -> Box<dyn Transaction>;
 }
 pub trait ChangeEmployeeHourlyTxFactory {
-    fn mk_tx(&self, id: EmployeeId, hourly_rate: f32) -> Box<dyn Transaction>;
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        hourly_rate: Money,
+        effective: Option<NaiveDate>,
+    ) -> Box<dyn Transaction>;
 }
 pub trait ChangeEmployeeCommissionedTxFactory {
-    fn mk_tx(&self, id: EmployeeId, salary: f32, commission_rate: f32) -> Box<dyn Transaction>;
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        salary: Money,
+        commission_rate: f32,
+        effective: Option<NaiveDate>,
+    ) -> Box<dyn Transaction>;
+}
+pub trait ChangeEmployeeScheduleTxFactory {
+    fn mk_tx(&self, id: EmployeeId, schedule: ScheduleInfo) -> Box<dyn Transaction>;
 }
 pub trait ChangeEmployeeHoldTxFactory {
     fn mk_tx(&self, id: EmployeeId) -> Box<dyn Transaction>;
@@ -63,11 +122,260 @@ pub trait ChangeEmployeeMailTxFactory {
     fn mk_tx(&self, id: EmployeeId, address: &str) -> Box<dyn Transaction>;
 }
 pub trait ChangeEmployeeMemberTxFactory {
-    fn mk_tx(&self, id: EmployeeId, member_id: MemberId, dues: f32) -> Box<dyn Transaction>;
+    fn mk_tx(&self, id: EmployeeId, member_id: MemberId, dues: Money) -> Box<dyn Transaction>;
 }
 pub trait ChangeEmployeeNoMemberTxFactory {
     fn mk_tx(&self, id: EmployeeId) -> Box<dyn Transaction>;
 }
 pub trait PaydayTxFactory {
+    fn mk_tx(&self, date: NaiveDate, policy: RerunPolicy) -> Box<dyn Transaction>;
+}
+pub trait PreviewPaydayTxFactory {
     fn mk_tx(&self, date: NaiveDate) -> Box<dyn Transaction>;
 }
+pub trait PayOffCycleTxFactory {
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        run_type: RunType,
+        date: NaiveDate,
+        amount: Money,
+        period_start: Option<NaiveDate>,
+    ) -> Box<dyn Transaction>;
+}
+pub trait GetPaycheckTxFactory {
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, run_type: RunType) -> Box<dyn Transaction>;
+}
+pub trait GetYearToDateTxFactory {
+    fn mk_tx(&self, id: EmployeeId, year: i32) -> Box<dyn Transaction>;
+}
+pub trait GetEmployeeTxFactory {
+    fn mk_tx(&self, id: EmployeeId) -> Box<dyn Transaction>;
+}
+pub trait ListEmployeesTxFactory {
+    fn mk_tx(&self, filter: EmployeeFilter) -> Box<dyn Transaction>;
+}
+pub trait GetUnionMemberTxFactory {
+    fn mk_tx(&self, member_id: MemberId) -> Box<dyn Transaction>;
+}
+
+// すべてのトランザクションを作れるファクトリ
+pub trait TxFactory:
+    AddSalariedEmployeeTxFactory
+    + AddHourlyEmployeeTxFactory
+    + AddCommissionedEmployeeTxFactory
+    + DeleteEmployeeTxFactory
+    + TerminateEmployeeTxFactory
+    + AddTimecardTxFactory
+    + AmendTimecardTxFactory
+    + VoidTimecardTxFactory
+    + AddSalesReceiptTxFactory
+    + VoidSalesReceiptTxFactory
+    + AddServiceChargeTxFactory
+    + VoidServiceChargeTxFactory
+    + ChangeEmployeeNameTxFactory
+    + ChangeEmployeeAddressTxFactory
+    + ChangeEmployeeTaxTxFactory
+    + ChangeEmployeeStatusTxFactory
+    + ChangeEmployeeOvertimeTxFactory
+    + ChangeEmployeeSalariedTxFactory
+    + ChangeEmployeeHourlyTxFactory
+    + ChangeEmployeeCommissionedTxFactory
+    + ChangeEmployeeScheduleTxFactory
+    + ChangeEmployeeHoldTxFactory
+    + ChangeEmployeeDirectTxFactory
+    + ChangeEmployeeMailTxFactory
+    + ChangeEmployeeMemberTxFactory
+    + ChangeEmployeeNoMemberTxFactory
+    + PaydayTxFactory
+    + PreviewPaydayTxFactory
+    + PayOffCycleTxFactory
+    + GetPaycheckTxFactory
+    + GetYearToDateTxFactory
+    + GetEmployeeTxFactory
+    + ListEmployeesTxFactory
+    + GetUnionMemberTxFactory
+{
+}
+impl<F> TxFactory for F where
+    F: AddSalariedEmployeeTxFactory
+        + AddHourlyEmployeeTxFactory
+        + AddCommissionedEmployeeTxFactory
+        + DeleteEmployeeTxFactory
+        + TerminateEmployeeTxFactory
+        + AddTimecardTxFactory
+        + AmendTimecardTxFactory
+        + VoidTimecardTxFactory
+        + AddSalesReceiptTxFactory
+        + VoidSalesReceiptTxFactory
+        + AddServiceChargeTxFactory
+        + VoidServiceChargeTxFactory
+        + ChangeEmployeeNameTxFactory
+        + ChangeEmployeeAddressTxFactory
+        + ChangeEmployeeTaxTxFactory
+        + ChangeEmployeeStatusTxFactory
+        + ChangeEmployeeOvertimeTxFactory
+        + ChangeEmployeeSalariedTxFactory
+        + ChangeEmployeeHourlyTxFactory
+        + ChangeEmployeeCommissionedTxFactory
+        + ChangeEmployeeScheduleTxFactory
+        + ChangeEmployeeHoldTxFactory
+        + ChangeEmployeeDirectTxFactory
+        + ChangeEmployeeMailTxFactory
+        + ChangeEmployeeMemberTxFactory
+        + ChangeEmployeeNoMemberTxFactory
+        + PaydayTxFactory
+        + PreviewPaydayTxFactory
+        + PayOffCycleTxFactory
+        + GetPaycheckTxFactory
+        + GetYearToDateTxFactory
+        + GetEmployeeTxFactory
+        + ListEmployeesTxFactory
+        + GetUnionMemberTxFactory
+{
+}
+
+// 解析済みの Tx から対応するトランザクションを作る
+pub fn dispatch<F>(tx_factory: &F, tx: Tx) -> Box<dyn Transaction>
+where
+    F: TxFactory,
+{
+    match tx {
+        Tx::AddHourlyEmployee {
+            id,
+            name,
+            address,
+            hourly_rate,
+            hire_date,
+        } => AddHourlyEmployeeTxFactory::mk_tx(
+            tx_factory,
+            id,
+            &name,
+            &address,
+            hourly_rate,
+            hire_date,
+        ),
+        Tx::AddSalariedEmployee {
+            id,
+            name,
+            address,
+            salary,
+            hire_date,
+        } => {
+            AddSalariedEmployeeTxFactory::mk_tx(tx_factory, id, &name, &address, salary, hire_date)
+        }
+        Tx::AddCommissionedEmployee {
+            id,
+            name,
+            address,
+            salary,
+            commission_rate,
+            hire_date,
+        } => AddCommissionedEmployeeTxFactory::mk_tx(
+            tx_factory,
+            id,
+            &name,
+            &address,
+            salary,
+            commission_rate,
+            hire_date,
+        ),
+        Tx::DeleteEmployee { id } => DeleteEmployeeTxFactory::mk_tx(tx_factory, id),
+        Tx::TerminateEmployee { id, date } => {
+            TerminateEmployeeTxFactory::mk_tx(tx_factory, id, date)
+        }
+        Tx::AddTimeCard { id, date, hours } => {
+            AddTimecardTxFactory::mk_tx(tx_factory, id, date, hours)
+        }
+        Tx::AmendTimeCard { id, date, hours } => {
+            AmendTimecardTxFactory::mk_tx(tx_factory, id, date, hours)
+        }
+        Tx::VoidTimeCard { id, date } => VoidTimecardTxFactory::mk_tx(tx_factory, id, date),
+        Tx::AddSalesReceipt { id, date, amount } => {
+            AddSalesReceiptTxFactory::mk_tx(tx_factory, id, date, amount)
+        }
+        Tx::VoidSalesReceipt { id, receipt_id } => {
+            VoidSalesReceiptTxFactory::mk_tx(tx_factory, id, receipt_id)
+        }
+        Tx::AddServiceCharge {
+            member_id,
+            date,
+            amount,
+        } => AddServiceChargeTxFactory::mk_tx(tx_factory, member_id, date, amount),
+        Tx::VoidServiceCharge {
+            member_id,
+            charge_id,
+        } => VoidServiceChargeTxFactory::mk_tx(tx_factory, member_id, charge_id),
+        Tx::ChangeEmployeeName { id, new_name } => {
+            ChangeEmployeeNameTxFactory::mk_tx(tx_factory, id, &new_name)
+        }
+        Tx::ChangeEmployeeAddress { id, new_address } => {
+            ChangeEmployeeAddressTxFactory::mk_tx(tx_factory, id, &new_address)
+        }
+        Tx::ChangeEmployeeTax { id, tax } => ChangeEmployeeTaxTxFactory::mk_tx(tx_factory, id, tax),
+        Tx::ChangeEmployeeStatus {
+            id,
+            status,
+            effective,
+        } => ChangeEmployeeStatusTxFactory::mk_tx(tx_factory, id, status, effective),
+        Tx::ChangeEmployeeOvertime { id, overtime } => {
+            ChangeEmployeeOvertimeTxFactory::mk_tx(tx_factory, id, overtime)
+        }
+        Tx::ChangeEmployeeHourly {
+            id,
+            hourly_rate,
+            effective,
+        } => ChangeEmployeeHourlyTxFactory::mk_tx(tx_factory, id, hourly_rate, effective),
+        Tx::ChangeEmployeeSalaried {
+            id,
+            salary,
+            effective,
+        } => ChangeEmployeeSalariedTxFactory::mk_tx(tx_factory, id, salary, effective),
+        Tx::ChangeEmployeeCommissioned {
+            id,
+            salary,
+            commission_rate,
+            effective,
+        } => ChangeEmployeeCommissionedTxFactory::mk_tx(
+            tx_factory,
+            id,
+            salary,
+            commission_rate,
+            effective,
+        ),
+        Tx::ChangeEmployeeSchedule { id, schedule } => {
+            ChangeEmployeeScheduleTxFactory::mk_tx(tx_factory, id, schedule)
+        }
+        Tx::ChangeEmployeeHold { id } => ChangeEmployeeHoldTxFactory::mk_tx(tx_factory, id),
+        Tx::ChangeEmployeeDirect { id, bank, account } => {
+            ChangeEmployeeDirectTxFactory::mk_tx(tx_factory, id, &bank, &account)
+        }
+        Tx::ChangeEmployeeMail { id, address } => {
+            ChangeEmployeeMailTxFactory::mk_tx(tx_factory, id, &address)
+        }
+        Tx::ChangeEmployeeMember {
+            emp_id,
+            member_id,
+            dues,
+        } => ChangeEmployeeMemberTxFactory::mk_tx(tx_factory, emp_id, member_id, dues),
+        Tx::ChangeEmployeeNoMember { emp_id } => {
+            ChangeEmployeeNoMemberTxFactory::mk_tx(tx_factory, emp_id)
+        }
+        Tx::Payday { date, policy } => PaydayTxFactory::mk_tx(tx_factory, date, policy),
+        Tx::PreviewPayday { date } => PreviewPaydayTxFactory::mk_tx(tx_factory, date),
+        Tx::PayOffCycle {
+            id,
+            run_type,
+            date,
+            amount,
+            period_start,
+        } => PayOffCycleTxFactory::mk_tx(tx_factory, id, run_type, date, amount, period_start),
+        Tx::GetPaycheck { id, date, run_type } => {
+            GetPaycheckTxFactory::mk_tx(tx_factory, id, date, run_type)
+        }
+        Tx::GetYearToDate { id, year } => GetYearToDateTxFactory::mk_tx(tx_factory, id, year),
+        Tx::GetEmployee { id } => GetEmployeeTxFactory::mk_tx(tx_factory, id),
+        Tx::ListEmployees { filter } => ListEmployeesTxFactory::mk_tx(tx_factory, filter),
+        Tx::GetUnionMember { member_id } => GetUnionMemberTxFactory::mk_tx(tx_factory, member_id),
+    }
+}
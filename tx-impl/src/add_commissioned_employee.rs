@@ -1,14 +1,15 @@
-use anyhow;
+use chrono::NaiveDate;
 use log::trace;
 use std::sync::{Arc, Mutex};
 
 use abstract_tx::{AddEmployee, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
 use payroll_domain::{
-    Affiliation, EmployeeId, PaymentClassification, PaymentMethod, PaymentSchedule,
+    validate_commission_rate, Affiliation, EmployeeId, Money, PaymentClassification, PaymentMethod,
+    PaymentSchedule, ScheduleInfo,
 };
 use payroll_factory::{
-    BiweeklyScheduleFactory, CommissionedClassificationFactory, HoldMethodFactory,
+    BiweeklyFromScheduleFactory, CommissionedClassificationFactory, HoldMethodFactory,
     NoAffiliationFactory,
 };
 use tx_app::{Response, Transaction};
@@ -22,8 +23,9 @@ where
     id: EmployeeId,
     name: String,
     address: String,
-    salary: f32,
+    salary: Money,
     commission_rate: f32,
+    hire_date: Option<NaiveDate>,
 
     dao: T,
     payroll_factory: F,
@@ -36,7 +38,7 @@ where
         id: EmployeeId,
         name: &str,
         address: &str,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
         dao: T,
         payroll_factory: F,
@@ -47,10 +49,16 @@ where
             address: address.to_string(),
             salary,
             commission_rate,
+            hire_date: None,
             dao,
             payroll_factory,
         }
     }
+    // 入社日 (None なら制限なし)
+    pub fn with_hire_date(mut self, hire_date: Option<NaiveDate>) -> Self {
+        self.hire_date = hire_date;
+        self
+    }
 }
 
 impl<T, F> HaveEmployeeDao for AddCommissionedEmployeeTx<T, F>
@@ -67,7 +75,7 @@ impl<T, F> AddEmployee for AddCommissionedEmployeeTx<T, F>
 where
     T: EmployeeDao,
     F: CommissionedClassificationFactory
-        + BiweeklyScheduleFactory
+        + BiweeklyFromScheduleFactory
         + HoldMethodFactory
         + NoAffiliationFactory,
 {
@@ -97,7 +105,8 @@ where
             .mk_classification(self.salary, self.commission_rate)
     }
     fn get_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>> {
-        self.payroll_factory.mk_schedule()
+        self.payroll_factory
+            .mk_schedule(ScheduleInfo::DEFAULT_BIWEEKLY_ANCHOR)
     }
     fn get_method(&self) -> Arc<Mutex<dyn PaymentMethod>> {
         self.payroll_factory.mk_method()
@@ -105,18 +114,22 @@ where
     fn get_affiliation(&self) -> Arc<Mutex<dyn Affiliation>> {
         self.payroll_factory.mk_affiliation()
     }
+    fn get_hire_date(&self) -> Option<NaiveDate> {
+        self.hire_date
+    }
 }
 // 共通インターフェースの実装
 impl<T, F> Transaction for AddCommissionedEmployeeTx<T, F>
 where
     T: EmployeeDao,
     F: CommissionedClassificationFactory
-        + BiweeklyScheduleFactory
+        + BiweeklyFromScheduleFactory
         + HoldMethodFactory
         + NoAffiliationFactory,
 {
     fn execute(&self) -> Result<Response, anyhow::Error> {
         trace!("execute called");
+        validate_commission_rate(self.commission_rate).map_err(UsecaseError::InvalidInput)?;
         AddEmployee::execute(self)
             .map(|_| Response::EmployeeId(self.id))
             .map_err(Into::into)
@@ -128,16 +141,17 @@ mod tests {
     use super::*;
     use std::sync::{Arc, Mutex};
 
-    use dao::{DaoError, EmployeeDao};
+    use crate::mock_dao::{impl_employee_dao, FakeDao};
+    use dao::DaoError;
     use payroll_domain::{
-        Affiliation, Employee, EmployeeId, MemberId, NoAffiliation, Paycheck,
-        PaymentClassification, PaymentMethod, PaymentSchedule,
+        Affiliation, Employee, EmployeeId, NoAffiliation, PaymentClassification, PaymentMethod,
+        PaymentSchedule,
     };
     use payroll_factory::{
-        BiweeklyScheduleFactory, CommissionedClassificationFactory, HoldMethodFactory,
+        BiweeklyFromScheduleFactory, CommissionedClassificationFactory, HoldMethodFactory,
         NoAffiliationFactory,
     };
-    use payroll_impl::{BiweeklySchedule, CommissionedClassification, HoldMethod};
+    use payroll_impl::{BiweeklyFromSchedule, CommissionedClassification, HoldMethod};
 
     #[derive(Debug, Clone)]
     struct Tester {
@@ -176,20 +190,19 @@ mod tests {
                     .lock()
                     .unwrap()
                     .as_any()
-                    .downcast_ref::<BiweeklySchedule>()
+                    .downcast_ref::<BiweeklyFromSchedule>()
                     .is_some());
-                // 今の BiweeklySchedule は特にフィールドがないのでこのテストは不要ではある
                 assert_eq!(
                     a.schedule()
                         .lock()
                         .unwrap()
                         .as_any()
-                        .downcast_ref::<BiweeklySchedule>(),
+                        .downcast_ref::<BiweeklyFromSchedule>(),
                     e.schedule()
                         .lock()
                         .unwrap()
                         .as_any()
-                        .downcast_ref::<BiweeklySchedule>()
+                        .downcast_ref::<BiweeklyFromSchedule>()
                 );
                 assert!(a
                     .method()
@@ -234,91 +247,17 @@ mod tests {
             }
         }
     }
-    impl EmployeeDao for Tester {
-        type Ctx<'a> = &'a ();
-
-        fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
-        where
-            F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
-        {
-            f(&())
-        }
-
-        fn add<'a>(
-            &self,
-            emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                self.actual.lock().unwrap().push(emp);
-                Ok(1.into()) // no care
-            })
-        }
-        fn delete<'a>(
-            &self,
-            _id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("delete method should not be called"))
-        }
-
-        fn fetch<'a>(
-            &self,
-            _id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Employee, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("fetch method should not be called"))
-        }
-
-        fn fetch_all<'a>(
-            &self,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Employee)>, Err = DaoError>
-        {
-            tx_rs::with_tx(move |_ctx| unreachable!("fetch_all method should not be called"))
-        }
-
-        fn update<'a>(
-            &self,
-            _emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("update method should not be called"))
-        }
-
-        fn add_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-            _emp_id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("add_union_member method should not be called"))
-        }
-
-        fn delete_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("delete_union_member method should not be called")
-            })
-        }
-
-        fn find_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("find_union_member method should not be called")
-            })
-        }
-
-        fn record_paycheck<'a>(
-            &self,
-            _emp_id: EmployeeId,
-            _paycheck: Paycheck,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("record_paycheck method should not be called"))
+    impl FakeDao for Tester {
+        fn add(&self, emp: Employee) -> Result<EmployeeId, DaoError> {
+            self.actual.lock().unwrap().push(emp);
+            Ok(1.into()) // no care
         }
     }
+    impl_employee_dao!(Tester);
     impl CommissionedClassificationFactory for Tester {
         fn mk_classification(
             &self,
-            salary: f32,
+            salary: Money,
             commission_rate: f32,
         ) -> Arc<Mutex<dyn PaymentClassification>> {
             Arc::new(Mutex::new(CommissionedClassification::new(
@@ -327,9 +266,9 @@ mod tests {
             )))
         }
     }
-    impl BiweeklyScheduleFactory for Tester {
-        fn mk_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>> {
-            Arc::new(Mutex::new(BiweeklySchedule))
+    impl BiweeklyFromScheduleFactory for Tester {
+        fn mk_schedule(&self, anchor: NaiveDate) -> Arc<Mutex<dyn PaymentSchedule>> {
+            Arc::new(Mutex::new(BiweeklyFromSchedule::new(anchor)))
         }
     }
     impl HoldMethodFactory for Tester {
@@ -350,8 +289,11 @@ mod tests {
                 1.into(),
                 "Chris",
                 "Wall St. 123",
-                Arc::new(Mutex::new(CommissionedClassification::new(123.0, 0.15))),
-                Arc::new(Mutex::new(BiweeklySchedule)),
+                Arc::new(Mutex::new(CommissionedClassification::new(
+                    Money::from_cents(12300),
+                    0.15,
+                ))),
+                Arc::new(Mutex::new(BiweeklyFromSchedule::default())),
                 Arc::new(Mutex::new(HoldMethod)),
                 Arc::new(Mutex::new(NoAffiliation)),
             )],
@@ -362,7 +304,7 @@ mod tests {
             1.into(),
             "Chris",
             "Wall St. 123",
-            123.0,
+            Money::from_cents(12300),
             0.15,
             t.clone(),
             t.clone(),
@@ -371,4 +313,31 @@ mod tests {
 
         t.assert();
     }
+
+    #[test]
+    fn test_invalid_commission_rate() {
+        let t = Tester {
+            expect: vec![],
+            actual: Arc::new(Mutex::new(vec![])),
+        };
+
+        for rate in [1.5, f32::NAN] {
+            let tx: Box<dyn tx_app::Transaction> = Box::new(AddCommissionedEmployeeTx::new(
+                1.into(),
+                "Chris",
+                "Wall St. 123",
+                Money::from_cents(12300),
+                rate,
+                t.clone(),
+                t.clone(),
+            ));
+            let err = tx.execute().unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<UsecaseError>(),
+                Some(UsecaseError::InvalidInput(_))
+            ));
+        }
+
+        t.assert();
+    }
 }
@@ -1,11 +1,11 @@
-use anyhow;
+use chrono::NaiveDate;
 use log::trace;
 use std::sync::{Arc, Mutex};
 
 use abstract_tx::{AddEmployee, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
 use payroll_domain::{
-    Affiliation, EmployeeId, PaymentClassification, PaymentMethod, PaymentSchedule,
+    Affiliation, EmployeeId, Money, PaymentClassification, PaymentMethod, PaymentSchedule,
 };
 use payroll_factory::{
     HoldMethodFactory, HourlyClassificationFactory, NoAffiliationFactory, WeeklyScheduleFactory,
@@ -21,7 +21,8 @@ where
     id: EmployeeId,
     name: String,
     address: String,
-    hourly_rate: f32,
+    hourly_rate: Money,
+    hire_date: Option<NaiveDate>,
 
     dao: T,
     payroll_factory: F,
@@ -34,7 +35,7 @@ where
         id: EmployeeId,
         name: &str,
         address: &str,
-        hourly_rate: f32,
+        hourly_rate: Money,
         dao: T,
         payroll_factory: F,
     ) -> Self {
@@ -43,10 +44,16 @@ where
             name: name.to_string(),
             address: address.to_string(),
             hourly_rate,
+            hire_date: None,
             dao,
             payroll_factory,
         }
     }
+    // 入社日 (None なら制限なし)
+    pub fn with_hire_date(mut self, hire_date: Option<NaiveDate>) -> Self {
+        self.hire_date = hire_date;
+        self
+    }
 }
 
 impl<T, F> HaveEmployeeDao for AddHourlyEmployeeTx<T, F>
@@ -100,6 +107,9 @@ where
     fn get_affiliation(&self) -> Arc<Mutex<dyn Affiliation>> {
         self.payroll_factory.mk_affiliation()
     }
+    fn get_hire_date(&self) -> Option<NaiveDate> {
+        self.hire_date
+    }
 }
 // 共通インターフェースの実装
 impl<T, F> Transaction for AddHourlyEmployeeTx<T, F>
@@ -123,10 +133,11 @@ mod tests {
     use super::*;
     use std::sync::{Arc, Mutex};
 
-    use dao::{DaoError, EmployeeDao};
+    use crate::mock_dao::{impl_employee_dao, FakeDao};
+    use dao::DaoError;
     use payroll_domain::{
-        Affiliation, Employee, EmployeeId, MemberId, NoAffiliation, Paycheck,
-        PaymentClassification, PaymentMethod, PaymentSchedule,
+        Affiliation, Employee, EmployeeId, NoAffiliation, PaymentClassification, PaymentMethod,
+        PaymentSchedule,
     };
     use payroll_factory::{
         HoldMethodFactory, HourlyClassificationFactory, NoAffiliationFactory, WeeklyScheduleFactory,
@@ -228,89 +239,15 @@ mod tests {
             }
         }
     }
-    impl EmployeeDao for Tester {
-        type Ctx<'a> = &'a ();
-
-        fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
-        where
-            F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
-        {
-            f(&())
-        }
-
-        fn add<'a>(
-            &self,
-            emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                self.actual.lock().unwrap().push(emp);
-                Ok(1.into()) // no care
-            })
-        }
-        fn delete<'a>(
-            &self,
-            _id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("delete method should not be called"))
-        }
-
-        fn fetch<'a>(
-            &self,
-            _id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Employee, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("fetch method should not be called"))
-        }
-
-        fn fetch_all<'a>(
-            &self,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Employee)>, Err = DaoError>
-        {
-            tx_rs::with_tx(move |_ctx| unreachable!("fetch_all method should not be called"))
-        }
-
-        fn update<'a>(
-            &self,
-            _emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("update method should not be called"))
-        }
-
-        fn add_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-            _emp_id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("add_union_member method should not be called"))
-        }
-
-        fn delete_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("delete_union_member method should not be called")
-            })
-        }
-
-        fn find_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("find_union_member method should not be called")
-            })
-        }
-
-        fn record_paycheck<'a>(
-            &self,
-            _emp_id: EmployeeId,
-            _paycheck: Paycheck,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("record_paycheck method should not be called"))
+    impl FakeDao for Tester {
+        fn add(&self, emp: Employee) -> Result<EmployeeId, DaoError> {
+            self.actual.lock().unwrap().push(emp);
+            Ok(1.into()) // no care
         }
     }
+    impl_employee_dao!(Tester);
     impl HourlyClassificationFactory for Tester {
-        fn mk_classification(&self, hourly_rate: f32) -> Arc<Mutex<dyn PaymentClassification>> {
+        fn mk_classification(&self, hourly_rate: Money) -> Arc<Mutex<dyn PaymentClassification>> {
             Arc::new(Mutex::new(HourlyClassification::new(hourly_rate)))
         }
     }
@@ -337,7 +274,9 @@ mod tests {
                 1.into(),
                 "Ace",
                 "Office",
-                Arc::new(Mutex::new(HourlyClassification::new(123.0))),
+                Arc::new(Mutex::new(HourlyClassification::new(Money::from_cents(
+                    12300,
+                )))),
                 Arc::new(Mutex::new(WeeklySchedule)),
                 Arc::new(Mutex::new(HoldMethod)),
                 Arc::new(Mutex::new(NoAffiliation)),
@@ -349,7 +288,7 @@ mod tests {
             1.into(),
             "Ace",
             "Office",
-            123.0,
+            Money::from_cents(12300),
             t.clone(),
             t.clone(),
         ));
@@ -1,10 +1,9 @@
-use anyhow;
 use chrono::NaiveDate;
 use log::{debug, trace};
 
 use abstract_tx::{ChangeEmployee, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::{Employee, EmployeeId};
+use payroll_domain::{Employee, EmployeeId, Money};
 use payroll_impl::CommissionedClassification;
 use tx_app::{Response, Transaction};
 
@@ -16,7 +15,7 @@ where
 {
     id: EmployeeId,
     date: NaiveDate,
-    amount: f32,
+    amount: Money,
 
     dao: T,
 }
@@ -24,7 +23,7 @@ impl<T> AddSalesReceiptTx<T>
 where
     T: EmployeeDao,
 {
-    pub fn new(id: EmployeeId, date: NaiveDate, amount: f32, dao: T) -> Self {
+    pub fn new(id: EmployeeId, date: NaiveDate, amount: Money, dao: T) -> Self {
         Self {
             id,
             date,
@@ -100,9 +99,10 @@ mod tests {
     use chrono::NaiveDate;
     use std::sync::{Arc, Mutex};
 
-    use dao::{DaoError, EmployeeDao};
-    use payroll_domain::{Employee, EmployeeId, MemberId, NoAffiliation, Paycheck};
-    use payroll_impl::{BiweeklySchedule, CommissionedClassification, HoldMethod};
+    use crate::mock_dao::{impl_employee_dao, FakeDao};
+    use dao::DaoError;
+    use payroll_domain::{Employee, EmployeeId, NoAffiliation};
+    use payroll_impl::{BiweeklyFromSchedule, CommissionedClassification, HoldMethod};
 
     #[derive(Debug, Clone)]
     enum Call {
@@ -128,7 +128,7 @@ mod tests {
                         if let Call::Fetch(a) = a {
                             assert_eq!(a, e);
                         } else {
-                            assert!(false, "unexpected call: {:?}", a);
+                            panic!("unexpected call: {:?}", a);
                         }
                     }
                     Call::Update(e) => {
@@ -153,20 +153,19 @@ mod tests {
                                 .lock()
                                 .unwrap()
                                 .as_any()
-                                .downcast_ref::<BiweeklySchedule>()
+                                .downcast_ref::<BiweeklyFromSchedule>()
                                 .is_some());
-                            // 今の BiweeklySchedule は特にフィールドがないのでこのテストは不要ではある
                             assert_eq!(
                                 a.schedule()
                                     .lock()
                                     .unwrap()
                                     .as_any()
-                                    .downcast_ref::<BiweeklySchedule>(),
+                                    .downcast_ref::<BiweeklyFromSchedule>(),
                                 e.schedule()
                                     .lock()
                                     .unwrap()
                                     .as_any()
-                                    .downcast_ref::<BiweeklySchedule>()
+                                    .downcast_ref::<BiweeklyFromSchedule>()
                             );
                             assert!(a
                                 .method()
@@ -209,102 +208,33 @@ mod tests {
                                     .downcast_ref::<NoAffiliation>()
                             );
                         } else {
-                            assert!(false, "unexpected call: {:?}", a);
+                            panic!("unexpected call: {:?}", a);
                         }
                     }
                 }
             }
         }
     }
-    impl EmployeeDao for Tester {
-        type Ctx<'a> = &'a ();
-
-        fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
-        where
-            F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
-        {
-            f(&())
-        }
-
-        fn add<'a>(
-            &self,
-            _emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("add method should not be called"))
-        }
-        fn delete<'a>(
-            &self,
-            _id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("delete method should not be called"))
-        }
-
-        fn fetch<'a>(
-            &self,
-            id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Employee, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                self.actual.lock().unwrap().push(Call::Fetch(id));
-                self.fetched.lock().unwrap().pop().unwrap()
-            })
-        }
-
-        fn fetch_all<'a>(
-            &self,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Employee)>, Err = DaoError>
-        {
-            tx_rs::with_tx(move |_ctx| unreachable!("fetch_all method should not be called"))
-        }
-
-        fn update<'a>(
-            &self,
-            emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                self.actual.lock().unwrap().push(Call::Update(emp));
-                self.updated.lock().unwrap().pop().unwrap()
-            })
-        }
-
-        fn add_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-            _emp_id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("add_union_member method should not be called"))
-        }
-
-        fn delete_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("delete_union_member method should not be called")
-            })
+    impl FakeDao for Tester {
+        fn fetch(&self, id: EmployeeId) -> Result<Employee, DaoError> {
+            self.actual.lock().unwrap().push(Call::Fetch(id));
+            self.fetched.lock().unwrap().pop().unwrap()
         }
 
-        fn find_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("find_union_member method should not be called")
-            })
-        }
-
-        fn record_paycheck<'a>(
-            &self,
-            _emp_id: EmployeeId,
-            _paycheck: Paycheck,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("record_paycheck method should not be called"))
+        fn update(&self, emp: Employee) -> Result<(), DaoError> {
+            self.actual.lock().unwrap().push(Call::Update(emp));
+            self.updated.lock().unwrap().pop().unwrap()
         }
     }
+    impl_employee_dao!(Tester);
 
     #[test]
     fn test_add_timecard() {
-        let mut cc = CommissionedClassification::new(123.0, 0.01);
-        cc.add_sales_receipt(NaiveDate::from_ymd_opt(2025, 3, 5).unwrap(), 1000.0);
+        let mut cc = CommissionedClassification::new(Money::from_cents(12300), 0.01);
+        cc.add_sales_receipt(
+            NaiveDate::from_ymd_opt(2025, 3, 5).unwrap(),
+            Money::from_cents(100000),
+        );
         let t = Tester {
             expect: vec![
                 Call::Fetch(1.into()),
@@ -313,7 +243,7 @@ mod tests {
                     "Bob",
                     "Home",
                     Arc::new(Mutex::new(cc)),
-                    Arc::new(Mutex::new(BiweeklySchedule)),
+                    Arc::new(Mutex::new(BiweeklyFromSchedule::default())),
                     Arc::new(Mutex::new(HoldMethod)),
                     Arc::new(Mutex::new(NoAffiliation)),
                 )),
@@ -324,8 +254,11 @@ mod tests {
                 1.into(),
                 "Bob",
                 "Home",
-                Arc::new(Mutex::new(CommissionedClassification::new(123.0, 0.01))),
-                Arc::new(Mutex::new(BiweeklySchedule)),
+                Arc::new(Mutex::new(CommissionedClassification::new(
+                    Money::from_cents(12300),
+                    0.01,
+                ))),
+                Arc::new(Mutex::new(BiweeklyFromSchedule::default())),
                 Arc::new(Mutex::new(HoldMethod)),
                 Arc::new(Mutex::new(NoAffiliation)),
             ))])),
@@ -335,7 +268,7 @@ mod tests {
         let tx: Box<dyn tx_app::Transaction> = Box::new(AddSalesReceiptTx::new(
             1.into(),
             NaiveDate::from_ymd_opt(2025, 3, 5).unwrap(),
-            1000.0,
+            Money::from_cents(100000),
             t.clone(),
         ));
         let _ = tx.execute();
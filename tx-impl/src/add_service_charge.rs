@@ -1,11 +1,10 @@
-use anyhow;
 use chrono::NaiveDate;
 use log::{debug, trace};
 use std::sync::{Arc, Mutex};
 
 use abstract_tx::{ChangeAffiliation, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::{Affiliation, MemberId};
+use payroll_domain::{Affiliation, MemberId, Money};
 use payroll_impl::UnionAffiliation;
 use tx_app::{Response, Transaction};
 
@@ -17,7 +16,7 @@ where
 {
     member_id: MemberId,
     date: NaiveDate,
-    amount: f32,
+    amount: Money,
 
     dao: T,
 }
@@ -25,7 +24,7 @@ impl<T> AddServiceChargeTx<T>
 where
     T: EmployeeDao,
 {
-    pub fn new(member_id: MemberId, date: NaiveDate, amount: f32, dao: T) -> Self {
+    pub fn new(member_id: MemberId, date: NaiveDate, amount: Money, dao: T) -> Self {
         Self {
             member_id,
             date,
@@ -88,3 +87,25 @@ where
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use tx_rs::Tx;
+
+    use crate::fixture::service_charge_error;
+
+    #[test]
+    fn test_service_charge_for_dangling_member() {
+        let db = HashDB::new();
+        db.run_tx(|mut ctx| db.add_union_member(7234.into(), 42.into()).run(&mut ctx))
+            .unwrap();
+
+        assert!(matches!(
+            service_charge_error(&db, 7234),
+            DaoError::DanglingUnionMember(member_id, emp_id)
+                if member_id == 7234.into() && emp_id == 42.into()
+        ));
+    }
+}
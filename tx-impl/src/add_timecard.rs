@@ -1,10 +1,9 @@
-use anyhow;
 use chrono::NaiveDate;
 use log::{debug, trace};
 
 use abstract_tx::{ChangeEmployee, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::{Employee, EmployeeId};
+use payroll_domain::{validate_hours, Employee, EmployeeId};
 use payroll_impl::HourlyClassification;
 use tx_app::{Response, Transaction};
 
@@ -65,15 +64,20 @@ where
     }
     fn change(&self, emp: &mut Employee) -> Result<(), DaoError> {
         trace!("change called");
-        emp.classification()
-            .lock()
-            .unwrap()
+        let classification = emp.classification();
+        let mut classification = classification.lock().unwrap();
+        let hc = classification
             .as_any_mut()
             .downcast_mut::<HourlyClassification>()
             .ok_or(DaoError::UnexpectedError(
                 "classification is not HourlyClassification".into(),
-            ))?
-            .add_timecard(self.date, self.hours);
+            ))?;
+        // 同じ日のタイムカードを足し込まないよう、訂正は AmendTimeCard でさせる
+        if hc.timecard(self.date).is_some() {
+            return Err(DaoError::TimeCardAlreadyExists(self.id, self.date));
+        }
+        hc.add_timecard(self.date, self.hours);
+        drop(classification);
         debug!("timecard added: {:?}", emp.classification());
         Ok(())
     }
@@ -85,6 +89,7 @@ where
 {
     fn execute(&self) -> Result<Response, anyhow::Error> {
         trace!("execute called");
+        validate_hours(self.hours).map_err(UsecaseError::InvalidInput)?;
         ChangeEmployee::execute(self)
             .map(|_| Response::Void)
             .map_err(Into::into)
@@ -97,9 +102,13 @@ mod tests {
     use chrono::NaiveDate;
     use std::sync::{Arc, Mutex};
 
-    use dao::{DaoError, EmployeeDao};
-    use payroll_domain::{Employee, EmployeeId, MemberId, NoAffiliation, Paycheck};
-    use payroll_impl::{HoldMethod, HourlyClassification, WeeklySchedule};
+    use crate::fixture::{add_hourly, date, timecard_error, timecards};
+    use crate::mock_dao::{impl_employee_dao, FakeDao};
+    use crate::AddSalariedEmployeeTx;
+    use dao::DaoError;
+    use hs_db::HashDB;
+    use payroll_domain::{Employee, EmployeeId, Money, NoAffiliation};
+    use payroll_impl::{HoldMethod, HourlyClassification, PayrollFactoryImpl, WeeklySchedule};
 
     #[derive(Debug, Clone)]
     enum Call {
@@ -125,7 +134,7 @@ mod tests {
                         if let Call::Fetch(a) = a {
                             assert_eq!(a, e);
                         } else {
-                            assert!(false, "unexpected call: {:?}", a);
+                            panic!("unexpected call: {:?}", a);
                         }
                     }
                     Call::Update(e) => {
@@ -206,101 +215,29 @@ mod tests {
                                     .downcast_ref::<NoAffiliation>()
                             );
                         } else {
-                            assert!(false, "unexpected call: {:?}", a);
+                            panic!("unexpected call: {:?}", a);
                         }
                     }
                 }
             }
         }
     }
-    impl EmployeeDao for Tester {
-        type Ctx<'a> = &'a ();
-
-        fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, DaoError>
-        where
-            F: FnOnce(Self::Ctx<'a>) -> Result<T, DaoError>,
-        {
-            f(&())
-        }
-
-        fn add<'a>(
-            &self,
-            _emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("add method should not be called"))
-        }
-        fn delete<'a>(
-            &self,
-            _id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("delete method should not be called"))
-        }
-
-        fn fetch<'a>(
-            &self,
-            id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Employee, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                self.actual.lock().unwrap().push(Call::Fetch(id));
-                self.fetched.lock().unwrap().pop().unwrap()
-            })
-        }
-
-        fn fetch_all<'a>(
-            &self,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Vec<(EmployeeId, Employee)>, Err = DaoError>
-        {
-            tx_rs::with_tx(move |_ctx| unreachable!("fetch_all method should not be called"))
-        }
-
-        fn update<'a>(
-            &self,
-            emp: Employee,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                self.actual.lock().unwrap().push(Call::Update(emp));
-                self.updated.lock().unwrap().pop().unwrap()
-            })
-        }
-
-        fn add_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-            _emp_id: EmployeeId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("add_union_member method should not be called"))
-        }
-
-        fn delete_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("delete_union_member method should not be called")
-            })
+    impl FakeDao for Tester {
+        fn fetch(&self, id: EmployeeId) -> Result<Employee, DaoError> {
+            self.actual.lock().unwrap().push(Call::Fetch(id));
+            self.fetched.lock().unwrap().pop().unwrap()
         }
 
-        fn find_union_member<'a>(
-            &self,
-            _member_id: MemberId,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = EmployeeId, Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| {
-                unreachable!("find_union_member method should not be called")
-            })
-        }
-
-        fn record_paycheck<'a>(
-            &self,
-            _emp_id: EmployeeId,
-            _paycheck: Paycheck,
-        ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = DaoError> {
-            tx_rs::with_tx(move |_ctx| unreachable!("record_paycheck method should not be called"))
+        fn update(&self, emp: Employee) -> Result<(), DaoError> {
+            self.actual.lock().unwrap().push(Call::Update(emp));
+            self.updated.lock().unwrap().pop().unwrap()
         }
     }
+    impl_employee_dao!(Tester);
 
     #[test]
     fn test_add_timecard() {
-        let mut hc = HourlyClassification::new(12.0);
+        let mut hc = HourlyClassification::new(Money::from_cents(1200));
         hc.add_timecard(NaiveDate::from_ymd_opt(2025, 3, 5).unwrap(), 8.0);
         let t = Tester {
             expect: vec![
@@ -321,7 +258,9 @@ mod tests {
                 1.into(),
                 "Bob",
                 "Home",
-                Arc::new(Mutex::new(HourlyClassification::new(12.0))),
+                Arc::new(Mutex::new(HourlyClassification::new(Money::from_cents(
+                    1200,
+                )))),
                 Arc::new(Mutex::new(WeeklySchedule)),
                 Arc::new(Mutex::new(HoldMethod)),
                 Arc::new(Mutex::new(NoAffiliation)),
@@ -339,4 +278,75 @@ mod tests {
 
         t.assert();
     }
+
+    #[test]
+    fn test_add_timecard_to_salaried_employee() {
+        let db = HashDB::new();
+        AddSalariedEmployeeTx::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Money::from_cents(100000),
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        add_hourly(&db, 2);
+
+        let tx: Box<dyn Transaction> = Box::new(AddTimeCardTx::new(
+            1.into(),
+            date(2025, 1, 31),
+            8.0,
+            db.clone(),
+        ));
+        assert!(tx.execute().is_err());
+        let tx: Box<dyn Transaction> = Box::new(AddTimeCardTx::new(
+            2.into(),
+            date(2025, 1, 31),
+            8.0,
+            db.clone(),
+        ));
+        tx.execute().unwrap();
+        assert_eq!(timecards(&db, 2), vec![(date(2025, 1, 31), 8.0)]);
+    }
+
+    #[test]
+    fn test_duplicate_timecard() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        let tx: Box<dyn Transaction> = Box::new(AddTimeCardTx::new(
+            1.into(),
+            date(2025, 1, 6),
+            8.0,
+            db.clone(),
+        ));
+        tx.execute().unwrap();
+        let tx = AddTimeCardTx::new(1.into(), date(2025, 1, 6), 8.0, db.clone());
+        assert!(matches!(
+            timecard_error(tx),
+            DaoError::TimeCardAlreadyExists(..)
+        ));
+        assert_eq!(timecards(&db, 1), vec![(date(2025, 1, 6), 8.0)]);
+    }
+
+    #[test]
+    fn test_invalid_hours() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        for hours in [f32::NAN, f32::INFINITY, -8.0, 25.0] {
+            let tx: Box<dyn Transaction> = Box::new(AddTimeCardTx::new(
+                1.into(),
+                date(2025, 1, 6),
+                hours,
+                db.clone(),
+            ));
+            let err = tx.execute().unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<UsecaseError>(),
+                Some(UsecaseError::InvalidInput(_))
+            ));
+        }
+        assert_eq!(timecards(&db, 1), vec![]);
+    }
 }
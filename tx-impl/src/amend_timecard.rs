@@ -0,0 +1,212 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+
+use abstract_tx::{ChangeTimeCard, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{validate_hours, Employee, EmployeeId};
+use payroll_impl::HourlyClassification;
+use tx_app::{Response, Transaction};
+
+// ユースケース: AmendTimeCard トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct AmendTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    date: NaiveDate,
+    hours: f32,
+
+    dao: T,
+}
+impl<T> AmendTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, date: NaiveDate, hours: f32, dao: T) -> Self {
+        Self {
+            id,
+            date,
+            hours,
+            dao,
+        }
+    }
+}
+
+impl<T> HaveEmployeeDao for AmendTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> ChangeTimeCard for AmendTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::ChangeEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+    fn get_hours(&self) -> Option<f32> {
+        Some(self.hours)
+    }
+    fn change(&self, emp: &mut Employee) -> Result<f32, DaoError> {
+        trace!("change called");
+        let old_hours = emp
+            .classification()
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<HourlyClassification>()
+            .ok_or(DaoError::UnexpectedError(
+                "classification is not HourlyClassification".into(),
+            ))?
+            .amend_timecard(self.date, self.hours)
+            .ok_or(DaoError::TimeCardNotFound(self.id, self.date))?;
+        debug!("timecard amended: {:?}", emp.classification());
+        Ok(old_hours)
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for AmendTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        validate_hours(self.hours).map_err(UsecaseError::InvalidInput)?;
+        ChangeTimeCard::execute(self)
+            .map(|_| Response::Void)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+    use std::sync::Arc;
+
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{
+        BusinessCalendar, NoWithholding, OvertimePolicy, RerunPolicy, Rollover, ScheduleInfo,
+        TimeCardAudit,
+    };
+    use payroll_impl::{MemorySink, PayrollFactoryImpl};
+    use tx_app::Transaction;
+    use tx_rs::Tx;
+
+    use crate::fixture::{add_hourly, date, payday, timecard_error, timecards};
+    use crate::{AddTimeCardTx, AmendTimeCardTx, ChangeScheduleTx, PaydayTx, VoidTimeCardTx};
+
+    fn timecard_audits(db: &HashDB, emp_id: u32) -> Vec<TimeCardAudit> {
+        db.run_tx(|mut ctx| db.fetch_timecard_audits(emp_id.into()).run(&mut ctx))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_amend_and_void_timecard() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        for (d, hours) in [(6, 8.0), (7, 8.0), (13, 8.0)] {
+            AddTimeCardTx::new(1.into(), date(2025, 1, d), hours, db.clone())
+                .execute()
+                .unwrap();
+        }
+        // 2025-01-10 (金) の Payday で 1/6 と 1/7 は支払い済みになる
+        payday(&db, date(2025, 1, 10));
+
+        AmendTimeCardTx::new(1.into(), date(2025, 1, 7), 9.5, db.clone())
+            .execute()
+            .unwrap();
+        VoidTimeCardTx::new(1.into(), date(2025, 1, 6), db.clone())
+            .execute()
+            .unwrap();
+        // まだ支払っていない期間の変更は記録しない
+        AmendTimeCardTx::new(1.into(), date(2025, 1, 13), 4.0, db.clone())
+            .execute()
+            .unwrap();
+        assert_eq!(
+            timecards(&db, 1),
+            vec![(date(2025, 1, 7), 9.5), (date(2025, 1, 13), 4.0)]
+        );
+        assert_eq!(
+            timecard_audits(&db, 1),
+            vec![
+                TimeCardAudit::new(date(2025, 1, 7), 8.0, Some(9.5), date(2025, 1, 10)),
+                TimeCardAudit::new(date(2025, 1, 6), 8.0, None, date(2025, 1, 10)),
+            ]
+        );
+
+        let tx = VoidTimeCardTx::new(1.into(), date(2025, 1, 6), db.clone());
+        assert!(matches!(timecard_error(tx), DaoError::TimeCardNotFound(..)));
+        let tx = AmendTimeCardTx::new(2.into(), date(2025, 1, 6), 8.0, db.clone());
+        assert!(matches!(timecard_error(tx), DaoError::EmployeeNotFound(_)));
+        assert_eq!(timecard_audits(&db, 1).len(), 2);
+    }
+
+    #[test]
+    fn test_amend_timecard_paid_after_holiday() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        ChangeScheduleTx::new(
+            1.into(),
+            ScheduleInfo::Monthly,
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        AddTimeCardTx::new(1.into(), date(2025, 1, 2), 8.0, db.clone())
+            .execute()
+            .unwrap();
+        // 月末の 1/31 (金) が祝日なので 2/3 (月) に支払い、1/2 から 32 日後になる
+        let calendar = BusinessCalendar {
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: [(2025, vec![date(2025, 1, 31)])].into(),
+            rollover: Rollover::Next,
+        };
+        PaydayTx::new(
+            date(2025, 2, 3),
+            RerunPolicy::Reject,
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .with_calendar(Arc::new(calendar))
+        .execute()
+        .unwrap();
+
+        AmendTimeCardTx::new(1.into(), date(2025, 1, 2), 6.0, db.clone())
+            .execute()
+            .unwrap();
+        assert_eq!(
+            timecard_audits(&db, 1),
+            vec![TimeCardAudit::new(
+                date(2025, 1, 2),
+                8.0,
+                Some(6.0),
+                date(2025, 2, 3)
+            )]
+        );
+    }
+}
@@ -1,4 +1,3 @@
-use anyhow;
 use log::trace;
 
 use abstract_tx::{ChangeEmployee, UsecaseError};
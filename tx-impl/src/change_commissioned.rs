@@ -1,10 +1,15 @@
-use anyhow;
+use chrono::NaiveDate;
 use log::{debug, trace};
+use std::sync::Arc;
 
-use abstract_tx::{ChangeEmployee, UsecaseError};
+use abstract_tx::{ChangeClassification, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::{Employee, EmployeeId};
-use payroll_factory::{BiweeklyScheduleFactory, CommissionedClassificationFactory};
+use payroll_domain::{
+    validate_commission_rate, BusinessCalendar, EarningLine, Employee, EmployeeId, Money,
+    OvertimePolicy, PaymentClassification, ScheduleInfo,
+};
+use payroll_factory::{BiweeklyFromScheduleFactory, CommissionedClassificationFactory};
+use payroll_impl::{CommissionedClassification, FinalPay, RetroPay};
 use tx_app::{Response, Transaction};
 
 // ユースケース: ChangeCommissioned トランザクションの実装 (struct)
@@ -14,11 +19,14 @@ where
     T: EmployeeDao,
 {
     id: EmployeeId,
-    salary: f32,
+    salary: Money,
     commission_rate: f32,
+    effective: Option<NaiveDate>,
 
     dao: T,
     payroll_factory: F,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
 }
 impl<T, F> ChangeCommissionedTx<T, F>
 where
@@ -26,19 +34,29 @@ where
 {
     pub fn new(
         id: EmployeeId,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
+        effective: Option<NaiveDate>,
         dao: T,
         payroll_factory: F,
+        overtime: Arc<OvertimePolicy>,
     ) -> Self {
         Self {
             id,
             salary,
             commission_rate,
+            effective,
             dao,
             payroll_factory,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
         }
     }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
 }
 
 impl<T, F> HaveEmployeeDao for ChangeCommissionedTx<T, F>
@@ -51,10 +69,10 @@ where
         &self.dao
     }
 }
-impl<T, F> ChangeEmployee for ChangeCommissionedTx<T, F>
+impl<T, F> ChangeClassification for ChangeCommissionedTx<T, F>
 where
     T: EmployeeDao,
-    F: CommissionedClassificationFactory + BiweeklyScheduleFactory,
+    F: CommissionedClassificationFactory + BiweeklyFromScheduleFactory,
 {
     fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
     where
@@ -71,14 +89,76 @@ where
     fn get_id(&self) -> EmployeeId {
         self.id
     }
-    fn change(&self, emp: &mut Employee) -> Result<(), DaoError> {
+    fn get_effective(&self) -> Option<NaiveDate> {
+        self.effective
+    }
+    fn get_overtime(&self) -> &OvertimePolicy {
+        &self.overtime
+    }
+    fn get_calendar(&self) -> &BusinessCalendar {
+        self.calendar.as_ref()
+    }
+    fn change_rate(&self, emp: &Employee, effective: Option<NaiveDate>) -> Result<bool, DaoError> {
+        trace!("change_rate called");
+        let classification = emp.classification();
+        let mut classification = classification.lock().unwrap();
+        let Some(c) = classification
+            .as_any_mut()
+            .downcast_mut::<CommissionedClassification>()
+        else {
+            return Ok(false);
+        };
+        c.change_rate(effective, self.salary, self.commission_rate);
+        debug!("rate changed: {:?}", c);
+        Ok(true)
+    }
+    fn add_retro_pay(
+        &self,
+        emp: &Employee,
+        carried_to: NaiveDate,
+        amount: Money,
+    ) -> Result<(), DaoError> {
+        trace!("add_retro_pay called");
+        emp.classification()
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<CommissionedClassification>()
+            .ok_or(DaoError::UnexpectedError(
+                "classification is not CommissionedClassification".into(),
+            ))?
+            .add_retro_pay(RetroPay::new(carried_to, amount));
+        Ok(())
+    }
+    fn change(
+        &self,
+        emp: &mut Employee,
+        effective: Option<NaiveDate>,
+        final_pay: Vec<EarningLine>,
+    ) -> Result<(), DaoError> {
         trace!("change called");
-        emp.set_classification(
+        let classification = self
+            .payroll_factory
+            .mk_classification(self.salary, self.commission_rate);
+        if let Some(effective) = effective {
+            let mut c = classification.lock().unwrap();
+            let c = c
+                .as_any_mut()
+                .downcast_mut::<CommissionedClassification>()
+                .ok_or(DaoError::UnexpectedError(
+                    "classification is not CommissionedClassification".into(),
+                ))?;
+            c.start_on(effective);
+            if !final_pay.is_empty() {
+                c.add_final_pay(FinalPay::new(effective, final_pay));
+            }
+        }
+        emp.set_classification(classification);
+        debug!("classification changed: {:?}", emp.classification());
+        emp.set_schedule(
             self.payroll_factory
-                .mk_classification(self.salary, self.commission_rate),
+                .mk_schedule(ScheduleInfo::DEFAULT_BIWEEKLY_ANCHOR),
         );
-        debug!("classification changed: {:?}", emp.classification());
-        emp.set_schedule(self.payroll_factory.mk_schedule());
         debug!("schedule changed: {:?}", emp.schedule());
         Ok(())
     }
@@ -87,11 +167,12 @@ where
 impl<T, F> Transaction for ChangeCommissionedTx<T, F>
 where
     T: EmployeeDao,
-    F: CommissionedClassificationFactory + BiweeklyScheduleFactory,
+    F: CommissionedClassificationFactory + BiweeklyFromScheduleFactory,
 {
     fn execute(&self) -> Result<Response, anyhow::Error> {
         trace!("execute called");
-        ChangeEmployee::execute(self)
+        validate_commission_rate(self.commission_rate).map_err(UsecaseError::InvalidInput)?;
+        ChangeClassification::execute(self)
             .map(|_| Response::Void)
             .map_err(Into::into)
     }
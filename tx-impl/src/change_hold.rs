@@ -1,4 +1,3 @@
-use anyhow;
 use log::{debug, trace};
 
 use abstract_tx::{ChangeEmployee, UsecaseError};
@@ -1,10 +1,15 @@
-use anyhow;
+use chrono::NaiveDate;
 use log::{debug, trace};
+use std::sync::Arc;
 
-use abstract_tx::{ChangeEmployee, UsecaseError};
+use abstract_tx::{ChangeClassification, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::{Employee, EmployeeId};
+use payroll_domain::{
+    BusinessCalendar, EarningLine, Employee, EmployeeId, Money, OvertimePolicy,
+    PaymentClassification,
+};
 use payroll_factory::{HourlyClassificationFactory, WeeklyScheduleFactory};
+use payroll_impl::{FinalPay, HourlyClassification, RetroPay};
 use tx_app::{Response, Transaction};
 
 // ユースケース: ChangeHourly トランザクションの実装 (struct)
@@ -14,23 +19,41 @@ where
     T: EmployeeDao,
 {
     id: EmployeeId,
-    hourly_rate: f32,
+    hourly_rate: Money,
+    effective: Option<NaiveDate>,
 
     dao: T,
     payroll_factory: F,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
 }
 impl<T, F> ChangeHourlyTx<T, F>
 where
     T: EmployeeDao,
 {
-    pub fn new(id: EmployeeId, hourly_rate: f32, dao: T, payroll_factory: F) -> Self {
+    pub fn new(
+        id: EmployeeId,
+        hourly_rate: Money,
+        effective: Option<NaiveDate>,
+        dao: T,
+        payroll_factory: F,
+        overtime: Arc<OvertimePolicy>,
+    ) -> Self {
         Self {
             id,
             hourly_rate,
+            effective,
             dao,
             payroll_factory,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
         }
     }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
 }
 
 impl<T, F> HaveEmployeeDao for ChangeHourlyTx<T, F>
@@ -43,7 +66,7 @@ where
         &self.dao
     }
 }
-impl<T, F> ChangeEmployee for ChangeHourlyTx<T, F>
+impl<T, F> ChangeClassification for ChangeHourlyTx<T, F>
 where
     T: EmployeeDao,
     F: HourlyClassificationFactory + WeeklyScheduleFactory,
@@ -63,9 +86,69 @@ where
     fn get_id(&self) -> EmployeeId {
         self.id
     }
-    fn change(&self, emp: &mut Employee) -> Result<(), DaoError> {
+    fn get_effective(&self) -> Option<NaiveDate> {
+        self.effective
+    }
+    fn get_overtime(&self) -> &OvertimePolicy {
+        &self.overtime
+    }
+    fn get_calendar(&self) -> &BusinessCalendar {
+        self.calendar.as_ref()
+    }
+    fn change_rate(&self, emp: &Employee, effective: Option<NaiveDate>) -> Result<bool, DaoError> {
+        trace!("change_rate called");
+        let classification = emp.classification();
+        let mut classification = classification.lock().unwrap();
+        let Some(c) = classification
+            .as_any_mut()
+            .downcast_mut::<HourlyClassification>()
+        else {
+            return Ok(false);
+        };
+        c.change_rate(effective, self.hourly_rate);
+        debug!("rate changed: {:?}", c);
+        Ok(true)
+    }
+    fn add_retro_pay(
+        &self,
+        emp: &Employee,
+        carried_to: NaiveDate,
+        amount: Money,
+    ) -> Result<(), DaoError> {
+        trace!("add_retro_pay called");
+        emp.classification()
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<HourlyClassification>()
+            .ok_or(DaoError::UnexpectedError(
+                "classification is not HourlyClassification".into(),
+            ))?
+            .add_retro_pay(RetroPay::new(carried_to, amount));
+        Ok(())
+    }
+    fn change(
+        &self,
+        emp: &mut Employee,
+        effective: Option<NaiveDate>,
+        final_pay: Vec<EarningLine>,
+    ) -> Result<(), DaoError> {
         trace!("change called");
-        emp.set_classification(self.payroll_factory.mk_classification(self.hourly_rate));
+        let classification = self.payroll_factory.mk_classification(self.hourly_rate);
+        if let Some(effective) = effective {
+            let mut c = classification.lock().unwrap();
+            let c = c
+                .as_any_mut()
+                .downcast_mut::<HourlyClassification>()
+                .ok_or(DaoError::UnexpectedError(
+                    "classification is not HourlyClassification".into(),
+                ))?;
+            c.start_on(effective);
+            if !final_pay.is_empty() {
+                c.add_final_pay(FinalPay::new(effective, final_pay));
+            }
+        }
+        emp.set_classification(classification);
         debug!("classification changed: {:?}", emp.classification());
         emp.set_schedule(self.payroll_factory.mk_schedule());
         debug!("schedule changed: {:?}", emp.schedule());
@@ -80,8 +163,103 @@ where
 {
     fn execute(&self) -> Result<Response, anyhow::Error> {
         trace!("execute called");
-        ChangeEmployee::execute(self)
+        ChangeClassification::execute(self)
             .map(|_| Response::Void)
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dao::EmployeeDao;
+    use hs_db::HashDB;
+    use payroll_domain::{
+        EarningKind, EarningLine, Money, NoWithholding, OvertimePolicy, RerunPolicy, RunType,
+    };
+    use payroll_impl::{MemorySink, PayrollFactoryImpl};
+    use tx_app::Transaction;
+    use tx_rs::Tx;
+
+    use crate::fixture::{date, timecards};
+    use crate::{AddHourlyEmployeeTx, AddTimeCardTx, ChangeHourlyTx, PaydayTx};
+
+    #[test]
+    fn test_rate_changes() {
+        let db = HashDB::new();
+        AddHourlyEmployeeTx::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Money::from_cents(1000),
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        let timecard = |d| {
+            AddTimeCardTx::new(1.into(), d, 8.0, db.clone())
+                .execute()
+                .unwrap();
+        };
+        let change_hourly = |cents, effective| {
+            ChangeHourlyTx::new(
+                1.into(),
+                Money::from_cents(cents),
+                effective,
+                db.clone(),
+                PayrollFactoryImpl,
+                Arc::new(OvertimePolicy::default()),
+            )
+            .execute()
+            .unwrap();
+        };
+        let payday = |d| {
+            PaydayTx::new(
+                d,
+                RerunPolicy::Reject,
+                db.clone(),
+                Arc::new(MemorySink::new()),
+                Arc::new(NoWithholding),
+                Arc::new(OvertimePolicy::default()),
+            )
+            .execute()
+            .unwrap();
+            db.run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), d, RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap()
+        };
+
+        timecard(date(2025, 1, 6));
+        timecard(date(2025, 1, 8));
+        payday(date(2025, 1, 10));
+        // 支払い済みの 1/8 にさかのぼって時給を 12.00 にしてもタイムカードは残り、
+        // 1/8 の分の差額 8 * 2.00 を次の Payday で支給する
+        change_hourly(1200, Some(date(2025, 1, 8)));
+        assert_eq!(timecards(&db, 1).len(), 2);
+        timecard(date(2025, 1, 13));
+        let pc = payday(date(2025, 1, 17));
+        assert_eq!(
+            pc.earning_lines(),
+            &[
+                EarningLine::new(EarningKind::Regular, Money::from_cents(9600)),
+                EarningLine::new(EarningKind::RetroPay, Money::from_cents(1600)),
+            ]
+        );
+
+        // 効力発生日を省略すると次の支払い期間からで、差額は出ない
+        change_hourly(1100, None);
+        timecard(date(2025, 1, 20));
+        let pc = payday(date(2025, 1, 24));
+        assert_eq!(
+            pc.earning_lines(),
+            &[EarningLine::new(
+                EarningKind::Regular,
+                Money::from_cents(8800)
+            )]
+        );
+    }
+}
@@ -1,11 +1,10 @@
-use anyhow;
 use log::trace;
 use std::sync::{Arc, Mutex};
 use tx_rs::Tx;
 
 use abstract_tx::{ChangeMember, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::{Affiliation, EmployeeId, MemberId};
+use payroll_domain::{Affiliation, EmployeeId, MemberId, Money};
 use payroll_factory::UnionAffiliationFactory;
 use tx_app::{Response, Transaction};
 
@@ -17,7 +16,7 @@ where
 {
     member_id: MemberId,
     emp_id: EmployeeId,
-    dues: f32,
+    dues: Money,
 
     dao: T,
     payroll_factory: F,
@@ -29,7 +28,7 @@ where
     pub fn new(
         member_id: MemberId,
         emp_id: EmployeeId,
-        dues: f32,
+        dues: Money,
         dao: T,
         payroll_factory: F,
     ) -> Self {
@@ -97,3 +96,118 @@ where
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{Money, NoAffiliation};
+    use payroll_impl::{PayrollFactoryImpl, UnionAffiliation};
+    use tx_app::Transaction;
+    use tx_rs::Tx;
+
+    use crate::fixture::{add_hourly, change_member, member_ids, service_charge_error};
+    use crate::ChangeMemberTx;
+
+    #[test]
+    fn test_change_member_for_unknown_employee() {
+        let db = HashDB::new();
+        // ChgEmp 999 Member 7234 Dues 9.45
+        let tx = ChangeMemberTx::new(
+            7234.into(),
+            999.into(),
+            Money::from_cents(945),
+            db.clone(),
+            PayrollFactoryImpl,
+        );
+        assert!(tx.execute().is_err());
+
+        let result = db.run_tx(|mut ctx| db.find_union_member(7234.into()).run(&mut ctx));
+        assert!(matches!(result, Err(DaoError::MemberNotFound(_))));
+    }
+
+    #[test]
+    fn test_change_member_with_used_member_id() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        add_hourly(&db, 2);
+        let change_member = |emp_id: u32, dues: i64| {
+            ChangeMemberTx::new(
+                7234.into(),
+                emp_id.into(),
+                Money::from_cents(dues),
+                db.clone(),
+                PayrollFactoryImpl,
+            )
+            .execute()
+        };
+        change_member(1, 945).unwrap();
+        assert!(change_member(2, 1000).is_err());
+
+        let result = db.run_tx(|mut ctx| {
+            let member = db.find_union_member(7234.into()).run(&mut ctx)?;
+            let emp = db.fetch(2.into()).run(&mut ctx)?;
+            Ok((member, emp))
+        });
+        let (member, emp) = result.unwrap();
+        assert_eq!(member, 1.into());
+        assert!(emp
+            .affiliation()
+            .lock()
+            .unwrap()
+            .as_any()
+            .is::<NoAffiliation>());
+    }
+
+    #[test]
+    fn test_change_member_keeps_old_membership_on_failure() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        add_hourly(&db, 2);
+        let change_member = |member_id: u32, emp_id: u32| {
+            ChangeMemberTx::new(
+                member_id.into(),
+                emp_id.into(),
+                Money::from_cents(945),
+                db.clone(),
+                PayrollFactoryImpl,
+            )
+            .execute()
+        };
+        change_member(7234, 1).unwrap();
+        change_member(8000, 2).unwrap();
+
+        // 古い登録 8000 を外した後で 7234 の登録に失敗するので、8000 の登録も元に戻る
+        assert!(change_member(7234, 2).is_err());
+
+        let result = db.run_tx(|mut ctx| {
+            let member = db.find_union_member(8000.into()).run(&mut ctx)?;
+            let emp = db.fetch(2.into()).run(&mut ctx)?;
+            Ok((member, emp))
+        });
+        let (member, emp) = result.unwrap();
+        assert_eq!(member, 2.into());
+        let affiliation = emp.affiliation();
+        let affiliation = affiliation.lock().unwrap();
+        let union = affiliation.as_any().downcast_ref::<UnionAffiliation>();
+        assert_eq!(union.map(|u| u.member_id()), Some(8000.into()));
+    }
+
+    #[test]
+    fn test_change_member_releases_old_member_id() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        change_member(&db, 7234, 1);
+        change_member(&db, 8000, 1);
+
+        assert_eq!(member_ids(&db, 1), vec![8000.into()]);
+        assert!(matches!(
+            service_charge_error(&db, 7234),
+            DaoError::MemberNotFound(_)
+        ));
+
+        // 同じ member_id で入り直しても登録は 1 つだけ
+        change_member(&db, 8000, 1);
+        assert_eq!(member_ids(&db, 1), vec![8000.into()]);
+    }
+}
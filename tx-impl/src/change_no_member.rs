@@ -1,13 +1,10 @@
-use anyhow;
-use log::{debug, trace};
+use log::trace;
 use std::sync::{Arc, Mutex};
-use tx_rs::Tx;
 
 use abstract_tx::{ChangeMember, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
 use payroll_domain::{Affiliation, EmployeeId};
 use payroll_factory::NoAffiliationFactory;
-use payroll_impl::UnionAffiliation;
 use tx_app::{Response, Transaction};
 
 // ユースケース: ChangeNoMember トランザクションの実装 (struct)
@@ -69,20 +66,11 @@ where
     }
     fn record_membership<'a>(
         &self,
-        ctx: &mut <Self as HaveEmployeeDao>::Ctx<'a>,
+        _ctx: &mut <Self as HaveEmployeeDao>::Ctx<'a>,
     ) -> Result<(), dao::DaoError> {
         trace!("record_membership called");
-        let emp = self.dao().fetch(self.emp_id).run(ctx)?;
-        let member_id = emp
-            .affiliation()
-            .lock()
-            .unwrap()
-            .as_any()
-            .downcast_ref::<UnionAffiliation>()
-            .ok_or(DaoError::UnexpectedError("didn't union affiliation".into()))?
-            .member_id();
-        debug!("delete union member: {}", member_id);
-        self.dao().delete_union_member(member_id).run(ctx)
+        // 組合員登録は ChangeMember::execute で外されているので、記録するものはない
+        Ok(())
     }
 }
 // 共通インターフェースの実装
@@ -98,3 +86,33 @@ where
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dao::DaoError;
+    use hs_db::HashDB;
+    use payroll_impl::PayrollFactoryImpl;
+    use tx_app::Transaction;
+
+    use crate::fixture::{add_hourly, change_member, member_ids, service_charge_error};
+    use crate::ChangeNoMemberTx;
+
+    #[test]
+    fn test_change_no_member_releases_membership() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        change_member(&db, 7234, 1);
+
+        let tx = ChangeNoMemberTx::new(1.into(), db.clone(), PayrollFactoryImpl);
+        tx.execute().unwrap();
+        assert_eq!(member_ids(&db, 1), vec![]);
+        assert!(matches!(
+            service_charge_error(&db, 7234),
+            DaoError::MemberNotFound(_)
+        ));
+
+        // 組合に入っていない従業員に対しても何も起きない
+        let tx = ChangeNoMemberTx::new(1.into(), db.clone(), PayrollFactoryImpl);
+        tx.execute().unwrap();
+    }
+}
@@ -0,0 +1,78 @@
+use log::trace;
+
+use abstract_tx::{ChangeEmployee, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Employee, EmployeeId, OvertimePolicy};
+use tx_app::{Response, Transaction};
+
+// ユースケース: ChangeEmployeeOvertime トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct ChangeEmployeeOvertimeTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    // None なら会社全体の規則に戻す
+    overtime: Option<OvertimePolicy>,
+
+    dao: T,
+}
+impl<T> ChangeEmployeeOvertimeTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, overtime: Option<OvertimePolicy>, dao: T) -> Self {
+        Self { id, overtime, dao }
+    }
+}
+
+impl<T> HaveEmployeeDao for ChangeEmployeeOvertimeTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> ChangeEmployee for ChangeEmployeeOvertimeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::ChangeEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn change(&self, emp: &mut Employee) -> Result<(), DaoError> {
+        trace!("change called");
+        emp.set_overtime_policy(self.overtime.clone());
+        Ok(())
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for ChangeEmployeeOvertimeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        if let Some(overtime) = &self.overtime {
+            overtime.validate()?;
+        }
+        ChangeEmployee::execute(self)
+            .map(|_| Response::Void)
+            .map_err(Into::into)
+    }
+}
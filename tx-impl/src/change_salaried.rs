@@ -1,10 +1,15 @@
-use anyhow;
+use chrono::NaiveDate;
 use log::{debug, trace};
+use std::sync::Arc;
 
-use abstract_tx::{ChangeEmployee, UsecaseError};
+use abstract_tx::{ChangeClassification, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
-use payroll_domain::{Employee, EmployeeId};
+use payroll_domain::{
+    BusinessCalendar, EarningLine, Employee, EmployeeId, Money, OvertimePolicy,
+    PaymentClassification,
+};
 use payroll_factory::{MonthlyScheduleFactory, SalariedClassificationFactory};
+use payroll_impl::{FinalPay, RetroPay, SalariedClassification};
 use tx_app::{Response, Transaction};
 
 // ユースケース: ChangeSalaried トランザクションの実装 (struct)
@@ -14,23 +19,41 @@ where
     T: EmployeeDao,
 {
     id: EmployeeId,
-    salary: f32,
+    salary: Money,
+    effective: Option<NaiveDate>,
 
     dao: T,
     payroll_factory: F,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
 }
 impl<T, F> ChangeSalariedTx<T, F>
 where
     T: EmployeeDao,
 {
-    pub fn new(id: EmployeeId, salary: f32, dao: T, payroll_factory: F) -> Self {
+    pub fn new(
+        id: EmployeeId,
+        salary: Money,
+        effective: Option<NaiveDate>,
+        dao: T,
+        payroll_factory: F,
+        overtime: Arc<OvertimePolicy>,
+    ) -> Self {
         Self {
             id,
             salary,
+            effective,
             dao,
             payroll_factory,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
         }
     }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
 }
 
 impl<T, F> HaveEmployeeDao for ChangeSalariedTx<T, F>
@@ -43,7 +66,7 @@ where
         &self.dao
     }
 }
-impl<T, F> ChangeEmployee for ChangeSalariedTx<T, F>
+impl<T, F> ChangeClassification for ChangeSalariedTx<T, F>
 where
     T: EmployeeDao,
     F: SalariedClassificationFactory + MonthlyScheduleFactory,
@@ -63,9 +86,69 @@ where
     fn get_id(&self) -> EmployeeId {
         self.id
     }
-    fn change(&self, emp: &mut Employee) -> Result<(), DaoError> {
+    fn get_effective(&self) -> Option<NaiveDate> {
+        self.effective
+    }
+    fn get_overtime(&self) -> &OvertimePolicy {
+        &self.overtime
+    }
+    fn get_calendar(&self) -> &BusinessCalendar {
+        self.calendar.as_ref()
+    }
+    fn change_rate(&self, emp: &Employee, effective: Option<NaiveDate>) -> Result<bool, DaoError> {
+        trace!("change_rate called");
+        let classification = emp.classification();
+        let mut classification = classification.lock().unwrap();
+        let Some(c) = classification
+            .as_any_mut()
+            .downcast_mut::<SalariedClassification>()
+        else {
+            return Ok(false);
+        };
+        c.change_rate(effective, self.salary);
+        debug!("rate changed: {:?}", c);
+        Ok(true)
+    }
+    fn add_retro_pay(
+        &self,
+        emp: &Employee,
+        carried_to: NaiveDate,
+        amount: Money,
+    ) -> Result<(), DaoError> {
+        trace!("add_retro_pay called");
+        emp.classification()
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<SalariedClassification>()
+            .ok_or(DaoError::UnexpectedError(
+                "classification is not SalariedClassification".into(),
+            ))?
+            .add_retro_pay(RetroPay::new(carried_to, amount));
+        Ok(())
+    }
+    fn change(
+        &self,
+        emp: &mut Employee,
+        effective: Option<NaiveDate>,
+        final_pay: Vec<EarningLine>,
+    ) -> Result<(), DaoError> {
         trace!("change called");
-        emp.set_classification(self.payroll_factory.mk_classification(self.salary));
+        let classification = self.payroll_factory.mk_classification(self.salary);
+        if let Some(effective) = effective {
+            let mut c = classification.lock().unwrap();
+            let c = c
+                .as_any_mut()
+                .downcast_mut::<SalariedClassification>()
+                .ok_or(DaoError::UnexpectedError(
+                    "classification is not SalariedClassification".into(),
+                ))?;
+            c.start_on(effective);
+            if !final_pay.is_empty() {
+                c.add_final_pay(FinalPay::new(effective, final_pay));
+            }
+        }
+        emp.set_classification(classification);
         debug!("classification changed: {:?}", emp.classification());
         emp.set_schedule(self.payroll_factory.mk_schedule());
         debug!("schedule changed: {:?}", emp.schedule());
@@ -80,8 +163,113 @@ where
 {
     fn execute(&self) -> Result<Response, anyhow::Error> {
         trace!("execute called");
-        ChangeEmployee::execute(self)
+        ChangeClassification::execute(self)
             .map(|_| Response::Void)
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use abstract_tx::UsecaseError;
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{
+        ClassificationInfo, EarningKind, EarningLine, Money, NoWithholding, OvertimePolicy,
+        RerunPolicy, RunType,
+    };
+    use payroll_impl::{MemorySink, PayrollFactoryImpl};
+    use tx_app::Transaction;
+    use tx_rs::Tx;
+
+    use crate::fixture::date;
+    use crate::{AddHourlyEmployeeTx, AddTimeCardTx, ChangeSalariedTx, PaydayTx};
+
+    #[test]
+    fn test_classification_change() {
+        let db = HashDB::new();
+        AddHourlyEmployeeTx::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Money::from_cents(1000),
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        let timecard = |d| {
+            AddTimeCardTx::new(1.into(), d, 8.0, db.clone())
+                .execute()
+                .unwrap();
+        };
+        let change_salaried = |effective| {
+            ChangeSalariedTx::new(
+                1.into(),
+                Money::from_cents(310000),
+                effective,
+                db.clone(),
+                PayrollFactoryImpl,
+                Arc::new(OvertimePolicy::default()),
+            )
+            .execute()
+        };
+        let payday = |d| {
+            PaydayTx::new(
+                d,
+                RerunPolicy::Reject,
+                db.clone(),
+                Arc::new(MemorySink::new()),
+                Arc::new(NoWithholding),
+                Arc::new(OvertimePolicy::default()),
+            )
+            .execute()
+            .unwrap();
+            db.run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), d, RunType::Regular)
+                    .run(&mut ctx)
+            })
+        };
+
+        timecard(date(2025, 1, 6));
+        payday(date(2025, 1, 10)).unwrap();
+        timecard(date(2025, 1, 13));
+        timecard(date(2025, 1, 14));
+
+        // 支払い済みの期間やタイムカードのある日にはさかのぼれない
+        for effective in [date(2025, 1, 6), date(2025, 1, 14)] {
+            let err = change_salaried(Some(effective)).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<UsecaseError>(),
+                Some(UsecaseError::ChangeEmployeeFailed(
+                    DaoError::InvalidEffectiveDate(..)
+                ))
+            ));
+        }
+
+        // 効力発生日を省略すると最後のタイムカードの翌日 1/15 からになる
+        change_salaried(None).unwrap();
+        let emp = db
+            .run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx))
+            .unwrap();
+        assert_eq!(
+            emp.info().classification,
+            ClassificationInfo::Salaried {
+                salary: Money::from_cents(310000)
+            }
+        );
+        // 月給になったので 1/17 は支払日ではない
+        assert!(payday(date(2025, 1, 17)).is_err());
+        // 1/13, 1/14 のタイムカードの分と、1/15 からの 17 日分の給与 3100 * 17 / 31
+        let pc = payday(date(2025, 1, 31)).unwrap();
+        assert_eq!(
+            pc.earning_lines(),
+            &[
+                EarningLine::new(EarningKind::Salary, Money::from_cents(170000)),
+                EarningLine::new(EarningKind::Regular, Money::from_cents(16000)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use log::trace;
+use std::sync::{Arc, Mutex};
+
+use abstract_tx::{ChangeSchedule, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{BusinessCalendar, EmployeeId, InvalidInput, PaymentSchedule, ScheduleInfo};
+use payroll_factory::{
+    BiweeklyFromScheduleFactory, MonthlyOnScheduleFactory, MonthlyScheduleFactory,
+    SemimonthlyScheduleFactory, WeeklyOnScheduleFactory, WeeklyScheduleFactory,
+};
+use tx_app::{Response, Transaction};
+
+// ユースケース: ChangeSchedule トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct ChangeScheduleTx<T, F>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    schedule: ScheduleInfo,
+
+    dao: T,
+    payroll_factory: F,
+    calendar: Arc<BusinessCalendar>,
+}
+impl<T, F> ChangeScheduleTx<T, F>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, schedule: ScheduleInfo, dao: T, payroll_factory: F) -> Self {
+        Self {
+            id,
+            schedule,
+            dao,
+            payroll_factory,
+            calendar: Arc::new(BusinessCalendar::none()),
+        }
+    }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
+}
+
+impl<T, F> HaveEmployeeDao for ChangeScheduleTx<T, F>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T, F> ChangeSchedule for ChangeScheduleTx<T, F>
+where
+    T: EmployeeDao,
+    F: MonthlyScheduleFactory
+        + WeeklyScheduleFactory
+        + SemimonthlyScheduleFactory
+        + MonthlyOnScheduleFactory
+        + WeeklyOnScheduleFactory
+        + BiweeklyFromScheduleFactory,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::ChangeEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn mk_schedule(&self) -> Arc<Mutex<dyn PaymentSchedule>> {
+        trace!("mk_schedule called");
+        let f = &self.payroll_factory;
+        match self.schedule {
+            ScheduleInfo::Monthly => MonthlyScheduleFactory::mk_schedule(f),
+            ScheduleInfo::Weekly => WeeklyScheduleFactory::mk_schedule(f),
+            ScheduleInfo::Biweekly => {
+                BiweeklyFromScheduleFactory::mk_schedule(f, ScheduleInfo::DEFAULT_BIWEEKLY_ANCHOR)
+            }
+            ScheduleInfo::Semimonthly => SemimonthlyScheduleFactory::mk_schedule(f),
+            ScheduleInfo::MonthlyOn { day } => MonthlyOnScheduleFactory::mk_schedule(f, day),
+            ScheduleInfo::WeeklyOn { weekday } => WeeklyOnScheduleFactory::mk_schedule(f, weekday),
+            ScheduleInfo::BiweeklyFrom { anchor } => {
+                BiweeklyFromScheduleFactory::mk_schedule(f, anchor)
+            }
+        }
+    }
+    fn get_calendar(&self) -> &BusinessCalendar {
+        &self.calendar
+    }
+}
+// 共通インターフェースの実装
+impl<T, F> Transaction for ChangeScheduleTx<T, F>
+where
+    T: EmployeeDao,
+    F: MonthlyScheduleFactory
+        + WeeklyScheduleFactory
+        + SemimonthlyScheduleFactory
+        + MonthlyOnScheduleFactory
+        + WeeklyOnScheduleFactory
+        + BiweeklyFromScheduleFactory,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        if !self.schedule.is_valid() {
+            let msg = format!("invalid schedule: {:?}", self.schedule);
+            return Err(UsecaseError::InvalidInput(InvalidInput::new(msg)).into());
+        }
+        ChangeSchedule::execute(self)
+            .map(|_| Response::Void)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+    use std::sync::Arc;
+
+    use abstract_tx::UsecaseError;
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{
+        BusinessCalendar, Money, NoWithholding, OvertimePolicy, RerunPolicy, Rollover, RunType,
+        ScheduleInfo,
+    };
+    use payroll_impl::{MemorySink, PayrollFactoryImpl};
+    use tx_app::Transaction;
+    use tx_rs::Tx;
+
+    use crate::fixture::{add_hourly, date, payday};
+    use crate::{AddTimeCardTx, ChangeScheduleTx, PaydayTx, TerminateEmployeeTx};
+
+    // 支払い期間を縮める日付の計算は payroll-impl の schedule で確かめる
+    #[test]
+    fn test_schedule_change() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        let timecard = |d| {
+            AddTimeCardTx::new(1.into(), d, 8.0, db.clone())
+                .execute()
+                .unwrap();
+        };
+        let change_schedule = |schedule| {
+            ChangeScheduleTx::new(1.into(), schedule, db.clone(), PayrollFactoryImpl).execute()
+        };
+
+        timecard(date(2025, 1, 6));
+        payday(&db, date(2025, 1, 10));
+        timecard(date(2025, 1, 13));
+
+        // 最後の支払日の翌日からを新しいスケジュールの最初の支払い期間にして、1/6 のタイムカードは二度支払わない
+        change_schedule(ScheduleInfo::Semimonthly).unwrap();
+        payday(&db, date(2025, 1, 15));
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 1, 15), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(pc.get_pay_period(), date(2025, 1, 11)..=date(2025, 1, 15));
+        assert_eq!(pc.gross_pay(), Money::from_cents(1575 * 8));
+
+        // 退職したらもう変えられない
+        TerminateEmployeeTx::new(
+            1.into(),
+            date(2025, 1, 20),
+            date(2025, 1, 20),
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .execute()
+        .unwrap();
+        let err = change_schedule(ScheduleInfo::Weekly).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::ChangeEmployeeFailed(
+                DaoError::EmployeeTerminated(_)
+            ))
+        ));
+
+        // 支払日にできない日は入力の誤り
+        let err = change_schedule(ScheduleInfo::MonthlyOn { day: 32 }).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_schedule_change_before_first_payday() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        AddTimeCardTx::new(1.into(), date(2025, 1, 8), 8.0, db.clone())
+            .execute()
+            .unwrap();
+        // 1/3 (金) が祝日なので、前のスケジュールでは 1/2 に支払い、1/8 を含む期間は 1/3 から
+        let calendar = Arc::new(BusinessCalendar {
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: [(2025, vec![date(2025, 1, 3)])].into(),
+            rollover: Rollover::Previous,
+        });
+        ChangeScheduleTx::new(
+            1.into(),
+            ScheduleInfo::Monthly,
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .with_calendar(calendar.clone())
+        .execute()
+        .unwrap();
+
+        // 一度も支払っていないので、まだ支払っていない前の支払い期間の初日から新しいスケジュールで支払う
+        PaydayTx::new(
+            date(2025, 1, 31),
+            RerunPolicy::Reject,
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .with_calendar(calendar)
+        .execute()
+        .unwrap();
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 1, 31), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(pc.get_pay_period(), date(2025, 1, 3)..=date(2025, 1, 31));
+        assert_eq!(pc.gross_pay(), Money::from_cents(1575 * 8));
+    }
+}
@@ -0,0 +1,91 @@
+use log::trace;
+
+use abstract_tx::{ChangeEmployee, UsecaseError};
+use chrono::NaiveDate;
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Employee, EmployeeId, EmploymentStatus};
+use tx_app::{Response, Transaction};
+
+// ユースケース: ChangeEmployeeStatus トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct ChangeEmployeeStatusTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    status: EmploymentStatus,
+    effective: NaiveDate,
+
+    dao: T,
+}
+impl<T> ChangeEmployeeStatusTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, status: EmploymentStatus, effective: NaiveDate, dao: T) -> Self {
+        Self {
+            id,
+            status,
+            effective,
+            dao,
+        }
+    }
+}
+
+impl<T> HaveEmployeeDao for ChangeEmployeeStatusTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> ChangeEmployee for ChangeEmployeeStatusTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::ChangeEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn change(&self, emp: &mut Employee) -> Result<(), DaoError> {
+        trace!("change called");
+        // 退職は最後の Paycheck を支払う TerminateEmployee でしかできない
+        if self.status == EmploymentStatus::Terminated {
+            return Err(DaoError::UnexpectedError(format!(
+                "can't terminate emp_id={} by changing the status",
+                self.id
+            )));
+        }
+        if emp.is_terminated() {
+            return Err(DaoError::EmployeeTerminated(self.id));
+        }
+        emp.change_status(self.effective, self.status);
+        Ok(())
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for ChangeEmployeeStatusTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        ChangeEmployee::execute(self)
+            .map(|_| Response::Void)
+            .map_err(Into::into)
+    }
+}
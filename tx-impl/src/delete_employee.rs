@@ -1,4 +1,3 @@
-use anyhow;
 use log::trace;
 
 use abstract_tx::{DeleteEmployee, UsecaseError};
@@ -67,3 +66,29 @@ where
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dao::DaoError;
+    use hs_db::HashDB;
+    use tx_app::Transaction;
+
+    use crate::fixture::{add_hourly, change_member, member_ids, service_charge_error};
+    use crate::DeleteEmployeeTx;
+
+    #[test]
+    fn test_delete_employee_releases_membership() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        change_member(&db, 7234, 1);
+
+        DeleteEmployeeTx::new(1.into(), db.clone())
+            .execute()
+            .unwrap();
+        assert_eq!(member_ids(&db, 1), vec![]);
+        assert!(matches!(
+            service_charge_error(&db, 7234),
+            DaoError::MemberNotFound(member_id) if member_id == 7234.into()
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+// HashDB を使うテストで共通の下ごしらえ
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use abstract_tx::UsecaseError;
+use dao::{DaoError, EmployeeDao};
+use hs_db::HashDB;
+use payroll_domain::{MemberId, Money, NoWithholding, OvertimePolicy, RerunPolicy};
+use payroll_impl::{HourlyClassification, MemorySink, PayrollFactoryImpl};
+use tx_app::Transaction;
+use tx_rs::Tx;
+
+use crate::{AddHourlyEmployeeTx, AddServiceChargeTx, ChangeMemberTx, PaydayTx};
+
+pub(crate) fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+// 時給 15.75 の Bob
+pub(crate) fn add_hourly(db: &HashDB, id: u32) {
+    AddHourlyEmployeeTx::new(
+        id.into(),
+        "Bob",
+        "Home",
+        Money::from_cents(1575),
+        db.clone(),
+        PayrollFactoryImpl,
+    )
+    .execute()
+    .unwrap();
+}
+pub(crate) fn timecards(db: &HashDB, id: u32) -> Vec<(NaiveDate, f32)> {
+    let emp = db.run_tx(|mut ctx| db.fetch(id.into()).run(&mut ctx));
+    let classification = emp.unwrap().classification();
+    let classification = classification.lock().unwrap();
+    classification
+        .as_any()
+        .downcast_ref::<HourlyClassification>()
+        .unwrap()
+        .timecards()
+        .collect()
+}
+// 組合費 9.45 で組合に入れる
+pub(crate) fn change_member(db: &HashDB, member_id: u32, emp_id: u32) {
+    ChangeMemberTx::new(
+        member_id.into(),
+        emp_id.into(),
+        Money::from_cents(945),
+        db.clone(),
+        PayrollFactoryImpl,
+    )
+    .execute()
+    .unwrap();
+}
+pub(crate) fn member_ids(db: &HashDB, emp_id: u32) -> Vec<MemberId> {
+    db.run_tx(|mut ctx| db.find_member_ids(emp_id.into()).run(&mut ctx))
+        .unwrap()
+}
+// 源泉徴収なしで pay_date の Payday を実行する
+pub(crate) fn payday(db: &HashDB, pay_date: NaiveDate) {
+    PaydayTx::new(
+        pay_date,
+        RerunPolicy::Reject,
+        db.clone(),
+        Arc::new(MemorySink::new()),
+        Arc::new(NoWithholding),
+        Arc::new(OvertimePolicy::default()),
+    )
+    .execute()
+    .unwrap();
+}
+pub(crate) fn timecard_error(tx: impl Transaction) -> DaoError {
+    let err = tx.execute().unwrap_err();
+    match err.downcast_ref::<UsecaseError>() {
+        Some(UsecaseError::ChangeEmployeeFailed(e)) => e.clone(),
+        _ => panic!("unexpected error: {}", err),
+    }
+}
+pub(crate) fn service_charge_error(db: &HashDB, member_id: u32) -> DaoError {
+    let err = AddServiceChargeTx::new(
+        member_id.into(),
+        date(2025, 1, 31),
+        Money::from_cents(1995),
+        db.clone(),
+    )
+    .execute()
+    .unwrap_err();
+    match err.downcast_ref::<UsecaseError>() {
+        Some(UsecaseError::ChangeAffiliationFailed(e)) => e.clone(),
+        _ => panic!("unexpected error: {}", err),
+    }
+}
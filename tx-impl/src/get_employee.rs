@@ -0,0 +1,120 @@
+use log::trace;
+
+use abstract_tx::{GetEmployee, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::EmployeeId;
+use tx_app::{Response, Transaction};
+
+// ユースケース: GetEmployee トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct GetEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+
+    dao: T,
+}
+impl<T> GetEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, dao: T) -> Self {
+        Self { id, dao }
+    }
+}
+
+impl<T> HaveEmployeeDao for GetEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> GetEmployee for GetEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::FetchEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for GetEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        GetEmployee::execute(self)
+            .map(Response::Employee)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use abstract_tx::UsecaseError;
+    use dao::DaoError;
+    use hs_db::HashDB;
+    use payroll_domain::{AffiliationInfo, ClassificationInfo, MethodInfo, Money, ScheduleInfo};
+    use tx_app::{Response, Transaction};
+
+    use crate::fixture::{add_hourly, change_member};
+    use crate::GetEmployeeTx;
+
+    #[test]
+    fn test_get_employee() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        change_member(&db, 7234, 1);
+
+        let response = GetEmployeeTx::new(1.into(), db.clone()).execute().unwrap();
+        let Response::Employee(emp) = response else {
+            panic!("unexpected response: {:?}", response);
+        };
+        assert_eq!(emp.id, 1.into());
+        assert_eq!(emp.name, "Bob");
+        assert_eq!(emp.address, "Home");
+        assert_eq!(
+            emp.classification,
+            ClassificationInfo::Hourly {
+                hourly_rate: Money::from_cents(1575)
+            }
+        );
+        assert_eq!(emp.schedule, ScheduleInfo::Weekly);
+        assert_eq!(emp.method, MethodInfo::Hold);
+        assert_eq!(
+            emp.affiliation,
+            AffiliationInfo::Union {
+                member_id: 7234.into(),
+                dues: Money::from_cents(945)
+            }
+        );
+
+        let err = GetEmployeeTx::new(2.into(), db.clone())
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::FetchEmployeeFailed(
+                DaoError::EmployeeNotFound(_)
+            ))
+        ));
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::NaiveDate;
+use log::trace;
+
+use abstract_tx::{GetPaycheck, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{EmployeeId, RunType};
+use tx_app::{Response, Transaction};
+
+// ユースケース: GetPaycheck トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct GetPaycheckTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    pay_date: NaiveDate,
+    run_type: RunType,
+
+    dao: T,
+}
+impl<T> GetPaycheckTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, pay_date: NaiveDate, run_type: RunType, dao: T) -> Self {
+        Self {
+            id,
+            pay_date,
+            run_type,
+            dao,
+        }
+    }
+}
+
+impl<T> HaveEmployeeDao for GetPaycheckTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> GetPaycheck for GetPaycheckTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::FetchPaycheckFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn get_pay_date(&self) -> NaiveDate {
+        self.pay_date
+    }
+    fn get_run_type(&self) -> RunType {
+        self.run_type
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for GetPaycheckTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        GetPaycheck::execute(self)
+            .map(Response::Paycheck)
+            .map_err(Into::into)
+    }
+}
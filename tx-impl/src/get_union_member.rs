@@ -0,0 +1,121 @@
+use log::trace;
+
+use abstract_tx::{GetUnionMember, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::MemberId;
+use tx_app::{Response, Transaction};
+
+// ユースケース: GetUnionMember トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct GetUnionMemberTx<T>
+where
+    T: EmployeeDao,
+{
+    member_id: MemberId,
+
+    dao: T,
+}
+impl<T> GetUnionMemberTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(member_id: MemberId, dao: T) -> Self {
+        Self { member_id, dao }
+    }
+}
+
+impl<T> HaveEmployeeDao for GetUnionMemberTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> GetUnionMember for GetUnionMemberTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::FetchUnionMemberFailed)
+    }
+
+    fn get_member_id(&self) -> MemberId {
+        self.member_id
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for GetUnionMemberTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        GetUnionMember::execute(self)
+            .map(|employee| Response::UnionMember {
+                member_id: self.member_id,
+                employee,
+            })
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use abstract_tx::UsecaseError;
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use tx_app::{Response, Transaction};
+    use tx_rs::Tx;
+
+    use crate::fixture::{add_hourly, change_member};
+    use crate::GetUnionMemberTx;
+
+    #[test]
+    fn test_get_union_member() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        change_member(&db, 7234, 1);
+
+        let response = GetUnionMemberTx::new(7234.into(), db.clone())
+            .execute()
+            .unwrap();
+        assert!(matches!(
+            response,
+            Response::UnionMember { member_id, employee }
+                if member_id == 7234.into() && employee.id == 1.into()
+        ));
+
+        let err = GetUnionMemberTx::new(8000.into(), db.clone())
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::FetchUnionMemberFailed(
+                DaoError::MemberNotFound(_)
+            ))
+        ));
+
+        db.run_tx(|mut ctx| db.add_union_member(8000.into(), 42.into()).run(&mut ctx))
+            .unwrap();
+        let err = GetUnionMemberTx::new(8000.into(), db.clone())
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::FetchUnionMemberFailed(
+                DaoError::DanglingUnionMember(..)
+            ))
+        ));
+    }
+}
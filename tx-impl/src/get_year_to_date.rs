@@ -0,0 +1,159 @@
+use log::trace;
+
+use abstract_tx::{GetYearToDate, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::EmployeeId;
+use tx_app::{Response, Transaction};
+
+// ユースケース: GetYearToDate トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct GetYearToDateTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    year: i32,
+
+    dao: T,
+}
+impl<T> GetYearToDateTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, year: i32, dao: T) -> Self {
+        Self { id, year, dao }
+    }
+}
+
+impl<T> HaveEmployeeDao for GetYearToDateTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> GetYearToDate for GetYearToDateTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::FetchYearToDateFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn get_year(&self) -> i32 {
+        self.year
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for GetYearToDateTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        GetYearToDate::execute(self)
+            .map(Response::YearToDate)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use abstract_tx::UsecaseError;
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{Money, RunType, YearToDate};
+    use payroll_impl::PayrollFactoryImpl;
+    use tx_app::{Response, Transaction};
+    use tx_rs::Tx;
+
+    use crate::fixture::{date, payday};
+    use crate::{AddSalariedEmployeeTx, GetYearToDateTx};
+
+    fn year_to_date(db: &HashDB, emp_id: u32, year: i32) -> YearToDate {
+        match GetYearToDateTx::new(emp_id.into(), year, db.clone())
+            .execute()
+            .unwrap()
+        {
+            Response::YearToDate(ytd) => ytd,
+            r => panic!("unexpected response: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_year_to_date() {
+        let db = HashDB::new();
+        AddSalariedEmployeeTx::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Money::from_cents(100000),
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        for pay_date in [date(2025, 11, 30), date(2025, 12, 31), date(2026, 1, 31)] {
+            payday(&db, pay_date);
+        }
+
+        assert_eq!(
+            year_to_date(&db, 1, 2025).gross_pay,
+            Money::from_cents(200000)
+        );
+        // 年が変わると 0 から積み直す
+        assert_eq!(
+            year_to_date(&db, 1, 2026).gross_pay,
+            Money::from_cents(100000)
+        );
+        assert_eq!(year_to_date(&db, 1, 2024), YearToDate::new(2024));
+        // Paycheck にはその時点の累計が残る
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 12, 31), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(
+            pc.year_to_date().unwrap().gross_pay,
+            Money::from_cents(200000)
+        );
+
+        // Paycheck の記録に失敗したら累計も更新されない
+        let result = db.run_tx(|mut ctx| {
+            let mut ytd = db.fetch_year_to_date(1.into(), 2026).run(&mut ctx)?;
+            ytd.gross_pay += Money::from_cents(100000);
+            db.update_year_to_date(1.into(), ytd).run(&mut ctx)?;
+            db.record_paycheck(1.into(), pc.clone()).run(&mut ctx)
+        });
+        assert!(matches!(result, Err(DaoError::PaycheckAlreadyExists(..))));
+        assert_eq!(
+            year_to_date(&db, 1, 2026).gross_pay,
+            Money::from_cents(100000)
+        );
+
+        let err = GetYearToDateTx::new(42.into(), 2025, db.clone())
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::FetchYearToDateFailed(
+                DaoError::EmployeeNotFound(_)
+            ))
+        ));
+    }
+}
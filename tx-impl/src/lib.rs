@@ -4,6 +4,7 @@ mod add_salaried_employee;
 mod add_sales_receipt;
 mod add_service_charge;
 mod add_timecard;
+mod amend_timecard;
 mod change_address;
 mod change_commissioned;
 mod change_direct;
@@ -13,10 +14,29 @@ mod change_mail;
 mod change_member;
 mod change_name;
 mod change_no_member;
+mod change_overtime;
 mod change_salaried;
+mod change_schedule;
+mod change_status;
+mod change_tax;
 mod delete_employee;
+#[cfg(test)]
+mod fixture;
+mod get_employee;
+mod get_paycheck;
+mod get_union_member;
+mod get_year_to_date;
+mod list_employees;
+#[cfg(test)]
+mod mock_dao;
+mod pay_off_cycle;
 mod payday;
+mod preview_payday;
+mod terminate_employee;
 mod tx_factory_impl;
+mod void_sales_receipt;
+mod void_service_charge;
+mod void_timecard;
 
 pub use add_commissioned_employee::*;
 pub use add_hourly_employee::*;
@@ -24,6 +44,7 @@ pub use add_salaried_employee::*;
 pub use add_sales_receipt::*;
 pub use add_service_charge::*;
 pub use add_timecard::*;
+pub use amend_timecard::*;
 pub use change_address::*;
 pub use change_commissioned::*;
 pub use change_direct::*;
@@ -33,7 +54,22 @@ pub use change_mail::*;
 pub use change_member::*;
 pub use change_name::*;
 pub use change_no_member::*;
+pub use change_overtime::*;
 pub use change_salaried::*;
+pub use change_schedule::*;
+pub use change_status::*;
+pub use change_tax::*;
 pub use delete_employee::*;
+pub use get_employee::*;
+pub use get_paycheck::*;
+pub use get_union_member::*;
+pub use get_year_to_date::*;
+pub use list_employees::*;
+pub use pay_off_cycle::*;
 pub use payday::*;
+pub use preview_payday::*;
+pub use terminate_employee::*;
 pub use tx_factory_impl::*;
+pub use void_sales_receipt::*;
+pub use void_service_charge::*;
+pub use void_timecard::*;
@@ -0,0 +1,120 @@
+use log::trace;
+
+use abstract_tx::{ListEmployees, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::EmployeeFilter;
+use tx_app::{Response, Transaction};
+
+// ユースケース: ListEmployees トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct ListEmployeesTx<T>
+where
+    T: EmployeeDao,
+{
+    filter: EmployeeFilter,
+
+    dao: T,
+}
+impl<T> ListEmployeesTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(filter: EmployeeFilter, dao: T) -> Self {
+        Self { filter, dao }
+    }
+}
+
+impl<T> HaveEmployeeDao for ListEmployeesTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> ListEmployees for ListEmployeesTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::FetchEmployeeFailed)
+    }
+
+    fn get_filter(&self) -> EmployeeFilter {
+        self.filter
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for ListEmployeesTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        ListEmployees::execute(self)
+            .map(Response::Employees)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hs_db::HashDB;
+    use payroll_domain::{EmployeeFilter, EmployeeId, Money};
+    use payroll_impl::PayrollFactoryImpl;
+    use tx_app::{Response, Transaction};
+
+    use crate::fixture::{add_hourly, change_member};
+    use crate::{AddSalariedEmployeeTx, ListEmployeesTx};
+
+    fn list_ids(db: &HashDB, filter: EmployeeFilter) -> Vec<EmployeeId> {
+        match ListEmployeesTx::new(filter, db.clone()).execute().unwrap() {
+            Response::Employees(emps) => emps.into_iter().map(|emp| emp.id).collect(),
+            r => panic!("unexpected response: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_list_employees() {
+        let db = HashDB::new();
+        add_hourly(&db, 3);
+        add_hourly(&db, 1);
+        AddSalariedEmployeeTx::new(
+            2.into(),
+            "Alice",
+            "Office",
+            Money::from_cents(100000),
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        change_member(&db, 7234, 3);
+
+        assert_eq!(
+            list_ids(&db, EmployeeFilter::All),
+            vec![1.into(), 2.into(), 3.into()]
+        );
+        assert_eq!(
+            list_ids(&db, EmployeeFilter::Hourly),
+            vec![1.into(), 3.into()]
+        );
+        assert_eq!(list_ids(&db, EmployeeFilter::Monthly), vec![2.into()]);
+        assert_eq!(list_ids(&db, EmployeeFilter::Member), vec![3.into()]);
+        assert_eq!(
+            list_ids(&db, EmployeeFilter::NoMember),
+            vec![1.into(), 2.into()]
+        );
+        assert_eq!(list_ids(&db, EmployeeFilter::Direct), vec![]);
+    }
+}
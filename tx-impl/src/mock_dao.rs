@@ -0,0 +1,280 @@
+// テスト用の EmployeeDao
+// テストで呼ばれるメソッドだけを FakeDao で実装して impl_employee_dao! で EmployeeDao にする
+// 実装しなかったメソッドは呼ばれたら unreachable! で落ちる
+// メソッドは dao::EmployeeDao と同じ順に並べる
+use chrono::NaiveDate;
+use std::ops::RangeInclusive;
+
+use dao::DaoError;
+use payroll_domain::{
+    Employee, EmployeeId, MemberId, Paycheck, RunType, TimeCardAudit, YearToDate,
+};
+
+pub(crate) trait FakeDao {
+    // 従業員
+    fn add(&self, _emp: Employee) -> Result<EmployeeId, DaoError> {
+        unreachable!("add method should not be called")
+    }
+    fn delete(&self, _id: EmployeeId) -> Result<(), DaoError> {
+        unreachable!("delete method should not be called")
+    }
+    fn fetch(&self, _id: EmployeeId) -> Result<Employee, DaoError> {
+        unreachable!("fetch method should not be called")
+    }
+    fn fetch_all(&self) -> Result<Vec<(EmployeeId, Employee)>, DaoError> {
+        unreachable!("fetch_all method should not be called")
+    }
+    fn update(&self, _emp: Employee) -> Result<(), DaoError> {
+        unreachable!("update method should not be called")
+    }
+    // 組合員
+    fn add_union_member(&self, _member_id: MemberId, _emp_id: EmployeeId) -> Result<(), DaoError> {
+        unreachable!("add_union_member method should not be called")
+    }
+    fn delete_union_member(&self, _member_id: MemberId) -> Result<(), DaoError> {
+        unreachable!("delete_union_member method should not be called")
+    }
+    fn find_union_member(&self, _member_id: MemberId) -> Result<EmployeeId, DaoError> {
+        unreachable!("find_union_member method should not be called")
+    }
+    fn find_member_ids(&self, _emp_id: EmployeeId) -> Result<Vec<MemberId>, DaoError> {
+        unreachable!("find_member_ids method should not be called")
+    }
+    // Paycheck
+    fn record_paycheck(&self, _emp_id: EmployeeId, _paycheck: Paycheck) -> Result<(), DaoError> {
+        unreachable!("record_paycheck method should not be called")
+    }
+    fn fetch_paycheck(
+        &self,
+        _emp_id: EmployeeId,
+        _pay_date: NaiveDate,
+        _run_type: RunType,
+    ) -> Result<Paycheck, DaoError> {
+        unreachable!("fetch_paycheck method should not be called")
+    }
+    fn fetch_paychecks(
+        &self,
+        _emp_id: EmployeeId,
+        _pay_dates: RangeInclusive<NaiveDate>,
+    ) -> Result<Vec<Paycheck>, DaoError> {
+        unreachable!("fetch_paychecks method should not be called")
+    }
+    fn fetch_last_pay_date(&self, _emp_id: EmployeeId) -> Result<Option<NaiveDate>, DaoError> {
+        unreachable!("fetch_last_pay_date method should not be called")
+    }
+    fn fetch_paychecks_for_run(
+        &self,
+        _pay_date: NaiveDate,
+    ) -> Result<Vec<(EmployeeId, Paycheck)>, DaoError> {
+        unreachable!("fetch_paychecks_for_run method should not be called")
+    }
+    // 年初来の累計
+    fn fetch_year_to_date(&self, _emp_id: EmployeeId, _year: i32) -> Result<YearToDate, DaoError> {
+        unreachable!("fetch_year_to_date method should not be called")
+    }
+    fn update_year_to_date(&self, _emp_id: EmployeeId, _ytd: YearToDate) -> Result<(), DaoError> {
+        unreachable!("update_year_to_date method should not be called")
+    }
+    // タイムカードの訂正の記録
+    fn record_timecard_audit(
+        &self,
+        _emp_id: EmployeeId,
+        _audit: TimeCardAudit,
+    ) -> Result<(), DaoError> {
+        unreachable!("record_timecard_audit method should not be called")
+    }
+    fn fetch_timecard_audits(&self, _emp_id: EmployeeId) -> Result<Vec<TimeCardAudit>, DaoError> {
+        unreachable!("fetch_timecard_audits method should not be called")
+    }
+}
+
+// FakeDao を実装した型に、トランザクションを持たない EmployeeDao を実装する
+macro_rules! impl_employee_dao {
+    ($t:ty) => {
+        impl dao::EmployeeDao for $t {
+            type Ctx<'a> = &'a ();
+
+            fn run_tx<'a, F, T>(&'a self, f: F) -> Result<T, dao::DaoError>
+            where
+                F: FnOnce(Self::Ctx<'a>) -> Result<T, dao::DaoError>,
+            {
+                f(&())
+            }
+            fn run_tx_rollback<'a, F, T>(&'a self, f: F) -> Result<T, dao::DaoError>
+            where
+                F: FnOnce(Self::Ctx<'a>) -> Result<T, dao::DaoError>,
+            {
+                f(&())
+            }
+
+            fn add<'a>(
+                &self,
+                emp: payroll_domain::Employee,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = payroll_domain::EmployeeId, Err = dao::DaoError>
+            {
+                tx_rs::with_tx(move |_ctx| $crate::mock_dao::FakeDao::add(self, emp))
+            }
+            fn delete<'a>(
+                &self,
+                id: payroll_domain::EmployeeId,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = dao::DaoError> {
+                tx_rs::with_tx(move |_ctx| $crate::mock_dao::FakeDao::delete(self, id))
+            }
+            fn fetch<'a>(
+                &self,
+                id: payroll_domain::EmployeeId,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = payroll_domain::Employee, Err = dao::DaoError>
+            {
+                tx_rs::with_tx(move |_ctx| $crate::mock_dao::FakeDao::fetch(self, id))
+            }
+            fn fetch_all<'a>(
+                &self,
+            ) -> impl tx_rs::Tx<
+                Self::Ctx<'a>,
+                Item = Vec<(payroll_domain::EmployeeId, payroll_domain::Employee)>,
+                Err = dao::DaoError,
+            > {
+                tx_rs::with_tx(move |_ctx| $crate::mock_dao::FakeDao::fetch_all(self))
+            }
+            fn update<'a>(
+                &self,
+                emp: payroll_domain::Employee,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = dao::DaoError> {
+                tx_rs::with_tx(move |_ctx| $crate::mock_dao::FakeDao::update(self, emp))
+            }
+            fn add_union_member<'a>(
+                &self,
+                member_id: payroll_domain::MemberId,
+                emp_id: payroll_domain::EmployeeId,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = dao::DaoError> {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::add_union_member(self, member_id, emp_id)
+                })
+            }
+            fn delete_union_member<'a>(
+                &self,
+                member_id: payroll_domain::MemberId,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = dao::DaoError> {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::delete_union_member(self, member_id)
+                })
+            }
+            fn find_union_member<'a>(
+                &self,
+                member_id: payroll_domain::MemberId,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = payroll_domain::EmployeeId, Err = dao::DaoError>
+            {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::find_union_member(self, member_id)
+                })
+            }
+            fn find_member_ids<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+            ) -> impl tx_rs::Tx<
+                Self::Ctx<'a>,
+                Item = Vec<payroll_domain::MemberId>,
+                Err = dao::DaoError,
+            > {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::find_member_ids(self, emp_id)
+                })
+            }
+            fn record_paycheck<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+                paycheck: payroll_domain::Paycheck,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = dao::DaoError> {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::record_paycheck(self, emp_id, paycheck)
+                })
+            }
+            fn fetch_paycheck<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+                pay_date: chrono::NaiveDate,
+                run_type: payroll_domain::RunType,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = payroll_domain::Paycheck, Err = dao::DaoError>
+            {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::fetch_paycheck(self, emp_id, pay_date, run_type)
+                })
+            }
+            fn fetch_paychecks<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+                pay_dates: std::ops::RangeInclusive<chrono::NaiveDate>,
+            ) -> impl tx_rs::Tx<
+                Self::Ctx<'a>,
+                Item = Vec<payroll_domain::Paycheck>,
+                Err = dao::DaoError,
+            > {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::fetch_paychecks(self, emp_id, pay_dates)
+                })
+            }
+            fn fetch_last_pay_date<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = Option<chrono::NaiveDate>, Err = dao::DaoError>
+            {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::fetch_last_pay_date(self, emp_id)
+                })
+            }
+            fn fetch_paychecks_for_run<'a>(
+                &self,
+                pay_date: chrono::NaiveDate,
+            ) -> impl tx_rs::Tx<
+                Self::Ctx<'a>,
+                Item = Vec<(payroll_domain::EmployeeId, payroll_domain::Paycheck)>,
+                Err = dao::DaoError,
+            > {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::fetch_paychecks_for_run(self, pay_date)
+                })
+            }
+            fn fetch_year_to_date<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+                year: i32,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = payroll_domain::YearToDate, Err = dao::DaoError>
+            {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::fetch_year_to_date(self, emp_id, year)
+                })
+            }
+            fn update_year_to_date<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+                ytd: payroll_domain::YearToDate,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = dao::DaoError> {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::update_year_to_date(self, emp_id, ytd)
+                })
+            }
+            fn record_timecard_audit<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+                audit: payroll_domain::TimeCardAudit,
+            ) -> impl tx_rs::Tx<Self::Ctx<'a>, Item = (), Err = dao::DaoError> {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::record_timecard_audit(self, emp_id, audit)
+                })
+            }
+            fn fetch_timecard_audits<'a>(
+                &self,
+                emp_id: payroll_domain::EmployeeId,
+            ) -> impl tx_rs::Tx<
+                Self::Ctx<'a>,
+                Item = Vec<payroll_domain::TimeCardAudit>,
+                Err = dao::DaoError,
+            > {
+                tx_rs::with_tx(move |_ctx| {
+                    $crate::mock_dao::FakeDao::fetch_timecard_audits(self, emp_id)
+                })
+            }
+        }
+    };
+}
+pub(crate) use impl_employee_dao;
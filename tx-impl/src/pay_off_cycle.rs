@@ -0,0 +1,260 @@
+use chrono::NaiveDate;
+use log::trace;
+use std::sync::Arc;
+
+use abstract_tx::{PayOffCycle, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{DisbursementSink, EmployeeId, Money, PayRecord, RunType, Withholding};
+use tx_app::{Response, Transaction};
+
+// ユースケース: PayOffCycle トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct PayOffCycleTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    run_type: RunType,
+    date: NaiveDate,
+    amount: Money,
+    period_start: Option<NaiveDate>,
+
+    dao: T,
+    sink: Arc<dyn DisbursementSink>,
+    withholding: Arc<dyn Withholding>,
+}
+impl<T> PayOffCycleTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(
+        id: EmployeeId,
+        run_type: RunType,
+        date: NaiveDate,
+        amount: Money,
+        dao: T,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+    ) -> Self {
+        Self {
+            id,
+            run_type,
+            date,
+            amount,
+            period_start: None,
+            dao,
+            sink,
+            withholding,
+        }
+    }
+    pub fn with_period_start(mut self, period_start: Option<NaiveDate>) -> Self {
+        self.period_start = period_start;
+        self
+    }
+}
+
+impl<T> HaveEmployeeDao for PayOffCycleTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> PayOffCycle for PayOffCycleTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::PayOffCycleFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn get_run_type(&self) -> RunType {
+        self.run_type
+    }
+    fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+    fn get_amount(&self) -> Money {
+        self.amount
+    }
+    fn get_period_start(&self) -> Option<NaiveDate> {
+        self.period_start
+    }
+    fn get_withholding(&self) -> &dyn Withholding {
+        self.withholding.as_ref()
+    }
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), UsecaseError> {
+        trace!("disburse called");
+        self.sink
+            .disburse(records)
+            .map_err(UsecaseError::DisbursementFailed)
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for PayOffCycleTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        PayOffCycle::execute(self)
+            .map(Response::Paycheck)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use abstract_tx::UsecaseError;
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{
+        EarningKind, EarningLine, Money, NoWithholding, OvertimePolicy, RerunPolicy, RunType,
+    };
+    use payroll_impl::MemorySink;
+    use tx_app::{Response, Transaction};
+    use tx_rs::Tx;
+
+    use crate::fixture::{add_hourly, change_member, date};
+    use crate::{AddTimeCardTx, PayOffCycleTx, PaydayTx};
+
+    #[test]
+    fn test_off_cycle_runs() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        change_member(&db, 7234, 1);
+        AddTimeCardTx::new(1.into(), date(2025, 1, 6), 8.0, db.clone())
+            .execute()
+            .unwrap();
+        PaydayTx::new(
+            date(2025, 1, 10),
+            RerunPolicy::Reject,
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .execute()
+        .unwrap();
+
+        let sink = Arc::new(MemorySink::new());
+        let pay = |run_type, d, cents, period_start| {
+            PayOffCycleTx::new(
+                1.into(),
+                run_type,
+                d,
+                Money::from_cents(cents),
+                db.clone(),
+                sink.clone(),
+                Arc::new(NoWithholding),
+            )
+            .with_period_start(period_start)
+            .execute()
+        };
+        let pay_error = |run_type, d, cents, period_start| {
+            let err = pay(run_type, d, cents, period_start).unwrap_err();
+            match err.downcast_ref::<UsecaseError>() {
+                Some(UsecaseError::PayOffCycleFailed(e)) => e.clone(),
+                _ => panic!("unexpected error: {}", err),
+            }
+        };
+
+        // 定期の支払日と同じ日の賞与も別の Paycheck になり、組合費は差し引かない
+        let Response::Paycheck(bonus) =
+            pay(RunType::Bonus, date(2025, 1, 10), 50000, None).unwrap()
+        else {
+            panic!("unexpected response");
+        };
+        assert_eq!(bonus.run_type(), RunType::Bonus);
+        assert_eq!(
+            bonus.earning_lines(),
+            &[EarningLine::new(
+                EarningKind::Bonus,
+                Money::from_cents(50000)
+            )]
+        );
+        assert_eq!(bonus.deduction_lines(), &[]);
+        // 累計には定期の支払いと賞与の両方が入る
+        assert_eq!(
+            bonus.year_to_date().unwrap().gross_pay,
+            Money::from_cents(12600 + 50000)
+        );
+        assert_eq!(sink.records().len(), 1);
+        let fetch = |d, run_type| {
+            db.run_tx(|mut ctx| db.fetch_paycheck(1.into(), d, run_type).run(&mut ctx))
+        };
+        assert_eq!(fetch(date(2025, 1, 10), RunType::Bonus).unwrap(), bonus);
+        let regular = fetch(date(2025, 1, 10), RunType::Regular).unwrap();
+        assert_eq!(regular.gross_pay(), Money::from_cents(12600));
+        assert_eq!(regular.deductions(), Money::from_cents(945));
+
+        // 精算は期間を指定でき、支払い済みの期間や Payday の再実行の判定には数えない
+        let Response::Paycheck(correction) = pay(
+            RunType::Correction,
+            date(2025, 1, 14),
+            -2550,
+            Some(date(2025, 1, 6)),
+        )
+        .unwrap() else {
+            panic!("unexpected response");
+        };
+        assert_eq!(
+            correction.get_pay_period(),
+            date(2025, 1, 6)..=date(2025, 1, 14)
+        );
+        assert_eq!(correction.gross_pay(), Money::from_cents(-2550));
+        let last_pay_date = db
+            .run_tx(|mut ctx| db.fetch_last_pay_date(1.into()).run(&mut ctx))
+            .unwrap();
+        assert_eq!(last_pay_date, Some(date(2025, 1, 10)));
+        let for_run = db
+            .run_tx(|mut ctx| db.fetch_paychecks_for_run(date(2025, 1, 10)).run(&mut ctx))
+            .unwrap();
+        assert_eq!(for_run, vec![(1.into(), regular)]);
+
+        // 同じ日の同じ種類の 2 回目は記録できないので、額をまとめて 1 回で支払う
+        assert!(matches!(
+            pay_error(RunType::Bonus, date(2025, 1, 10), 100, None),
+            DaoError::PaycheckAlreadyExists(..)
+        ));
+        assert!(matches!(
+            pay_error(RunType::Correction, date(2025, 1, 14), 100, None),
+            DaoError::PaycheckAlreadyExists(..)
+        ));
+        assert!(matches!(
+            pay_error(
+                RunType::Bonus,
+                date(2025, 1, 17),
+                100,
+                Some(date(2025, 1, 20))
+            ),
+            DaoError::InvalidEffectiveDate(..)
+        ));
+        // 定期の支払いと最後の支払いは Payday と TerminateEmployee で行うので受け付けず、何も記録しない
+        for run_type in [RunType::Regular, RunType::Final] {
+            let err = pay(run_type, date(2025, 1, 17), 100, None).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<UsecaseError>(),
+                Some(UsecaseError::InvalidInput(_))
+            ));
+            assert!(fetch(date(2025, 1, 17), run_type).is_err());
+        }
+        assert_eq!(sink.records().len(), 2);
+    }
+}
@@ -1,9 +1,12 @@
-use anyhow;
 use chrono::NaiveDate;
 use log::trace;
+use std::sync::Arc;
 
 use abstract_tx::{Payday, UsecaseError};
 use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{
+    BusinessCalendar, DisbursementSink, OvertimePolicy, PayRecord, RerunPolicy, Withholding,
+};
 use tx_app::{Response, Transaction};
 
 // ユースケース: Payday トランザクションの実装 (struct)
@@ -13,15 +16,40 @@ where
     T: EmployeeDao,
 {
     pay_date: NaiveDate,
+    policy: RerunPolicy,
 
     dao: T,
+    sink: Arc<dyn DisbursementSink>,
+    withholding: Arc<dyn Withholding>,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
 }
 impl<T> PaydayTx<T>
 where
     T: EmployeeDao,
 {
-    pub fn new(pay_date: NaiveDate, dao: T) -> Self {
-        Self { pay_date, dao }
+    pub fn new(
+        pay_date: NaiveDate,
+        policy: RerunPolicy,
+        dao: T,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+    ) -> Self {
+        Self {
+            pay_date,
+            policy,
+            dao,
+            sink,
+            withholding,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
+        }
+    }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
     }
 }
 
@@ -52,6 +80,24 @@ where
     fn get_pay_date(&self) -> NaiveDate {
         self.pay_date
     }
+    fn get_rerun_policy(&self) -> RerunPolicy {
+        self.policy
+    }
+    fn get_withholding(&self) -> &dyn Withholding {
+        self.withholding.as_ref()
+    }
+    fn get_overtime_policy(&self) -> &OvertimePolicy {
+        self.overtime.as_ref()
+    }
+    fn get_calendar(&self) -> &BusinessCalendar {
+        self.calendar.as_ref()
+    }
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), UsecaseError> {
+        trace!("disburse called");
+        self.sink
+            .disburse(records)
+            .map_err(UsecaseError::DisbursementFailed)
+    }
 }
 // 共通インターフェースの実装
 impl<T> Transaction for PaydayTx<T>
@@ -61,7 +107,378 @@ where
     fn execute(&self) -> Result<Response, anyhow::Error> {
         trace!("execute called");
         Payday::execute(self)
-            .map(|_| Response::Void)
+            .map(|outcome| Response::Payday {
+                paid: outcome.paid,
+                skipped: outcome.skipped,
+                replayed: outcome.replayed,
+                records: outcome.records,
+            })
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::HashMap, sync::Mutex};
+
+    use crate::fixture::{add_hourly, date};
+    use crate::mock_dao::{impl_employee_dao, FakeDao};
+    use crate::AddTimeCardTx;
+    use chrono::Weekday;
+    use hs_db::HashDB;
+    use payroll_domain::{
+        DisbursementError, EarningKind, EarningLine, Employee, EmployeeId, MethodInfo, Money,
+        NoAffiliation, NoWithholding, Paycheck, Rollover, RunType, YearToDate,
+    };
+    use payroll_impl::{HoldMethod, MemorySink, MonthlySchedule, SalariedClassification};
+    use tx_rs::Tx;
+
+    #[derive(Debug, Clone)]
+    struct Tester {
+        employees: Vec<(EmployeeId, Employee)>,
+        paychecks: Arc<Mutex<Vec<(EmployeeId, Paycheck)>>>,
+        year_to_dates: Arc<Mutex<HashMap<(EmployeeId, i32), YearToDate>>>,
+    }
+    impl Tester {
+        fn new(ids: &[u32]) -> Self {
+            let employees = ids
+                .iter()
+                .map(|&id| {
+                    let emp = Employee::new(
+                        id.into(),
+                        "Bob",
+                        "Home",
+                        Arc::new(Mutex::new(SalariedClassification::new(Money::from_cents(
+                            100000,
+                        )))),
+                        Arc::new(Mutex::new(MonthlySchedule)),
+                        Arc::new(Mutex::new(HoldMethod)),
+                        Arc::new(Mutex::new(NoAffiliation)),
+                    );
+                    (id.into(), emp)
+                })
+                .collect();
+            Self {
+                employees,
+                paychecks: Arc::new(Mutex::new(vec![])),
+                year_to_dates: Arc::new(Mutex::new(HashMap::new())),
+            }
+        }
+        fn paid(&self, emp_id: EmployeeId, pay_date: NaiveDate, gross_pay: Money) {
+            let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+            let mut pc = Paycheck::new(start..=pay_date);
+            pc.set_earning_lines(vec![EarningLine::new(EarningKind::Salary, gross_pay)]);
+            self.paychecks.lock().unwrap().push((emp_id, pc));
+        }
+        fn gross_pays(&self) -> Vec<(EmployeeId, Money)> {
+            self.paychecks
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(emp_id, pc)| (*emp_id, pc.gross_pay()))
+                .collect()
+        }
+    }
+    impl FakeDao for Tester {
+        fn fetch_all(&self) -> Result<Vec<(EmployeeId, Employee)>, DaoError> {
+            Ok(self.employees.clone())
+        }
+
+        fn record_paycheck(&self, emp_id: EmployeeId, paycheck: Paycheck) -> Result<(), DaoError> {
+            self.paychecks.lock().unwrap().push((emp_id, paycheck));
+            Ok(())
+        }
+
+        fn fetch_paychecks_for_run(
+            &self,
+            pay_date: NaiveDate,
+        ) -> Result<Vec<(EmployeeId, Paycheck)>, DaoError> {
+            Ok(self
+                .paychecks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(_, pc)| pc.pay_date() == pay_date)
+                .cloned()
+                .collect())
+        }
+
+        fn fetch_year_to_date(
+            &self,
+            emp_id: EmployeeId,
+            year: i32,
+        ) -> Result<YearToDate, DaoError> {
+            Ok(self
+                .year_to_dates
+                .lock()
+                .unwrap()
+                .get(&(emp_id, year))
+                .cloned()
+                .unwrap_or_else(|| YearToDate::new(year)))
+        }
+
+        fn update_year_to_date(&self, emp_id: EmployeeId, ytd: YearToDate) -> Result<(), DaoError> {
+            self.year_to_dates
+                .lock()
+                .unwrap()
+                .insert((emp_id, ytd.year), ytd);
+            Ok(())
+        }
+    }
+    impl_employee_dao!(Tester);
+
+    fn pay_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 31).unwrap()
+    }
+
+    fn mk_tx(t: &Tester, policy: RerunPolicy) -> (PaydayTx<Tester>, MemorySink) {
+        let sink = MemorySink::new();
+        let tx = PaydayTx::new(
+            pay_date(),
+            policy,
+            t.clone(),
+            Arc::new(sink.clone()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        );
+        (tx, sink)
+    }
+
+    fn net_pays(records: &[PayRecord]) -> Vec<(EmployeeId, Money)> {
+        records.iter().map(|r| (r.emp_id, r.net_pay)).collect()
+    }
+
+    #[test]
+    fn test_payday() {
+        let t = Tester::new(&[1, 2]);
+
+        let (tx, sink) = mk_tx(&t, RerunPolicy::Reject);
+        let result = Transaction::execute(&tx).unwrap();
+
+        let Response::Payday {
+            paid,
+            skipped,
+            replayed,
+            records,
+        } = result
+        else {
+            panic!("unexpected response: {:?}", result);
+        };
+        assert_eq!(paid, vec![1.into(), 2.into()]);
+        assert!(skipped.is_empty());
+        assert!(replayed.is_empty());
+        assert_eq!(
+            net_pays(&records),
+            vec![
+                (1.into(), Money::from_cents(100000)),
+                (2.into(), Money::from_cents(100000))
+            ]
+        );
+        assert!(records
+            .iter()
+            .all(|r| r.pay_date == pay_date() && r.method == MethodInfo::Hold));
+        // 返した記録がそのまま送り出されていること
+        assert_eq!(sink.records(), records);
+        assert_eq!(
+            t.gross_pays(),
+            vec![
+                (1.into(), Money::from_cents(100000)),
+                (2.into(), Money::from_cents(100000))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rerun_reject() {
+        let t = Tester::new(&[1, 2]);
+        t.paid(2.into(), pay_date(), Money::from_cents(90000));
+
+        let (tx, sink) = mk_tx(&t, RerunPolicy::Reject);
+        let result = Transaction::execute(&tx);
+
+        assert!(result.is_err());
+        // 未払いの従業員 1 にも支払われていないこと
+        assert_eq!(t.gross_pays(), vec![(2.into(), Money::from_cents(90000))]);
+        assert!(sink.records().is_empty());
+    }
+
+    #[test]
+    fn test_rerun_skip() {
+        let t = Tester::new(&[1, 2]);
+        t.paid(2.into(), pay_date(), Money::from_cents(90000));
+
+        let (tx, sink) = mk_tx(&t, RerunPolicy::Skip);
+        let result = Transaction::execute(&tx).unwrap();
+
+        assert_eq!(
+            result,
+            Response::Payday {
+                paid: vec![1.into()],
+                skipped: vec![2.into()],
+                replayed: vec![],
+                records: sink.records(),
+            }
+        );
+        assert_eq!(
+            net_pays(&sink.records()),
+            vec![(1.into(), Money::from_cents(100000))]
+        );
+        assert_eq!(
+            t.gross_pays(),
+            vec![
+                (2.into(), Money::from_cents(90000)),
+                (1.into(), Money::from_cents(100000))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rerun_replay() {
+        let t = Tester::new(&[1, 2]);
+        t.paid(2.into(), pay_date(), Money::from_cents(90000));
+
+        let (tx, sink) = mk_tx(&t, RerunPolicy::Replay);
+        let result = Transaction::execute(&tx).unwrap();
+
+        assert_eq!(
+            result,
+            Response::Payday {
+                paid: vec![1.into()],
+                skipped: vec![],
+                replayed: vec![2.into()],
+                records: sink.records(),
+            }
+        );
+        // 再送分は記録済みの金額で送り出されること
+        assert_eq!(
+            net_pays(&sink.records()),
+            vec![
+                (1.into(), Money::from_cents(100000)),
+                (2.into(), Money::from_cents(90000))
+            ]
+        );
+        // 再送した Paycheck は再記録されないこと
+        assert_eq!(
+            t.gross_pays(),
+            vec![
+                (2.into(), Money::from_cents(90000)),
+                (1.into(), Money::from_cents(100000))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_year_to_date() {
+        let t = Tester::new(&[1]);
+        let run = |pay_date: NaiveDate| {
+            let tx = PaydayTx::new(
+                pay_date,
+                RerunPolicy::Reject,
+                t.clone(),
+                Arc::new(MemorySink::new()),
+                Arc::new(NoWithholding),
+                Arc::new(OvertimePolicy::default()),
+            );
+            Payday::execute(&tx).unwrap().records
+        };
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        run(date(2025, 11, 30));
+        let records = run(date(2025, 12, 31));
+        let ytd = records[0].year_to_date.as_ref().unwrap();
+        assert_eq!(ytd.year, 2025);
+        assert_eq!(ytd.gross_pay, Money::from_cents(200000));
+
+        // 年が変わると 0 から積み直す
+        let records = run(date(2026, 1, 31));
+        let ytd = records[0].year_to_date.as_ref().unwrap();
+        assert_eq!(ytd.year, 2026);
+        assert_eq!(ytd.gross_pay, Money::from_cents(100000));
+        assert_eq!(ytd.net_pay, Money::from_cents(100000));
+
+        let ytds = t.year_to_dates.lock().unwrap();
+        assert_eq!(ytds[&(1.into(), 2025)].gross_pay, Money::from_cents(200000));
+        assert_eq!(ytds[&(1.into(), 2026)].gross_pay, Money::from_cents(100000));
+    }
+
+    #[derive(Debug)]
+    struct BrokenSink;
+    impl DisbursementSink for BrokenSink {
+        fn disburse(&self, _records: &[PayRecord]) -> Result<(), DisbursementError> {
+            Err(DisbursementError("broken pipe".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_disbursement_failed() {
+        let t = Tester::new(&[1]);
+
+        let tx = PaydayTx::new(
+            pay_date(),
+            RerunPolicy::Reject,
+            t.clone(),
+            Arc::new(BrokenSink),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        );
+        let err = Payday::execute(&tx).unwrap_err();
+
+        assert!(matches!(err, UsecaseError::DisbursementFailed(_)));
+        // 送り出しに失敗しても Paycheck は記録されたままで、Replay で送り直せること
+        assert_eq!(t.gross_pays(), vec![(1.into(), Money::from_cents(100000))]);
+        let (tx, sink) = mk_tx(&t, RerunPolicy::Replay);
+        Transaction::execute(&tx).unwrap();
+        assert_eq!(
+            net_pays(&sink.records()),
+            vec![(1.into(), Money::from_cents(100000))]
+        );
+    }
+
+    // 支払日をずらす日付の計算は payroll-domain と payroll-impl の calendar で確かめる
+    #[test]
+    fn test_holiday_rollover() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        let calendar = Arc::new(BusinessCalendar {
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: [(2025, vec![date(2025, 12, 26)])].into(),
+            rollover: Rollover::Previous,
+        });
+        let timecard = |d, hours| {
+            let tx = AddTimeCardTx::new(1.into(), d, hours, db.clone());
+            Transaction::execute(&tx).unwrap();
+        };
+        let payday = |d| {
+            let tx = PaydayTx::new(
+                d,
+                RerunPolicy::Reject,
+                db.clone(),
+                Arc::new(MemorySink::new()),
+                Arc::new(NoWithholding),
+                Arc::new(OvertimePolicy {
+                    holiday_rate: Some(2.0),
+                    ..OvertimePolicy::default()
+                }),
+            )
+            .with_calendar(calendar.clone());
+            Transaction::execute(&tx).unwrap();
+            db.run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), d, RunType::Regular)
+                    .run(&mut ctx)
+            })
+        };
+
+        timecard(date(2025, 12, 22), 8.0);
+        // 祝日の金曜日には支払わず、前の日に支払う
+        assert!(payday(date(2025, 12, 26)).is_err());
+        let pc = payday(date(2025, 12, 25)).unwrap();
+        assert_eq!(pc.get_pay_period(), date(2025, 12, 20)..=date(2025, 12, 25));
+        assert_eq!(pc.gross_pay(), Money::from_cents(1575 * 8));
+        // 祝日に働いた分は次の支払い期間に入り、暦の祝日なので祝日の倍率で払う
+        timecard(date(2025, 12, 26), 4.0);
+        let pc = payday(date(2026, 1, 2)).unwrap();
+        assert_eq!(pc.get_pay_period(), date(2025, 12, 26)..=date(2026, 1, 2));
+        assert_eq!(pc.gross_pay(), Money::from_cents(1575 * 4 * 2));
+    }
+}
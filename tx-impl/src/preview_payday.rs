@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use log::trace;
+use std::sync::Arc;
+
+use abstract_tx::{PreviewPayday, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{BusinessCalendar, OvertimePolicy, Withholding};
+use tx_app::{Response, Transaction};
+
+// ユースケース: PreviewPayday トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct PreviewPaydayTx<T>
+where
+    T: EmployeeDao,
+{
+    pay_date: NaiveDate,
+
+    dao: T,
+    withholding: Arc<dyn Withholding>,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
+}
+impl<T> PreviewPaydayTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(
+        pay_date: NaiveDate,
+        dao: T,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+    ) -> Self {
+        Self {
+            pay_date,
+            dao,
+            withholding,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
+        }
+    }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
+}
+
+impl<T> HaveEmployeeDao for PreviewPaydayTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> PreviewPayday for PreviewPaydayTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 試算なので成功しても DB のトランザクションはコミットしない
+        self.dao()
+            .run_tx_rollback(f)
+            .map_err(UsecaseError::PreviewPaydayFailed)
+    }
+
+    fn get_pay_date(&self) -> NaiveDate {
+        self.pay_date
+    }
+    fn get_withholding(&self) -> &dyn Withholding {
+        self.withholding.as_ref()
+    }
+    fn get_overtime_policy(&self) -> &OvertimePolicy {
+        self.overtime.as_ref()
+    }
+    fn get_calendar(&self) -> &BusinessCalendar {
+        self.calendar.as_ref()
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for PreviewPaydayTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        PreviewPayday::execute(self)
+            .map(Response::PaydayPreview)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{Money, NoWithholding, OvertimePolicy, RerunPolicy, RunType};
+    use payroll_impl::MemorySink;
+    use tx_app::{Response, Transaction};
+    use tx_rs::Tx;
+
+    use crate::fixture::{add_hourly, date};
+    use crate::{AddTimeCardTx, PaydayTx, PreviewPaydayTx};
+
+    #[test]
+    fn test_preview_payday() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        AddTimeCardTx::new(1.into(), date(2025, 1, 30), 8.0, db.clone())
+            .execute()
+            .unwrap();
+        let sink = MemorySink::new();
+        let preview = |pay_date| {
+            let tx = PreviewPaydayTx::new(
+                pay_date,
+                db.clone(),
+                Arc::new(NoWithholding),
+                Arc::new(OvertimePolicy::default()),
+            );
+            match tx.execute().unwrap() {
+                Response::PaydayPreview(preview) => preview,
+                res => panic!("unexpected response: {:?}", res),
+            }
+        };
+
+        // 試算では Paycheck も累計も記録されない
+        let result = preview(date(2025, 1, 31));
+        assert_eq!(result.previews.len(), 1);
+        assert_eq!(result.previews[0].amounts.net_pay, Money::from_cents(12600));
+        assert_eq!(result.previews[0].last, None);
+        let result = db.run_tx(|mut ctx| {
+            db.fetch_paycheck(1.into(), date(2025, 1, 31), RunType::Regular)
+                .run(&mut ctx)
+        });
+        assert!(matches!(result, Err(DaoError::PaycheckNotFound(..))));
+        let ytd = db.run_tx(|mut ctx| db.fetch_year_to_date(1.into(), 2025).run(&mut ctx));
+        assert_eq!(ytd.unwrap().gross_pay, Money::ZERO);
+
+        // 実行すると試算と同じ額が支払われる
+        PaydayTx::new(
+            date(2025, 1, 31),
+            RerunPolicy::Reject,
+            db.clone(),
+            Arc::new(sink.clone()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .execute()
+        .unwrap();
+        assert_eq!(sink.records()[0].net_pay, Money::from_cents(12600));
+
+        // 支払い済みの支払日は飛ばす
+        let result = preview(date(2025, 1, 31));
+        assert!(result.previews.is_empty());
+        assert_eq!(result.skipped, vec![1.into()]);
+
+        // 次の支払日は直近の支払いと比べる
+        add_hourly(&db, 2);
+        AddTimeCardTx::new(1.into(), date(2025, 2, 6), 6.0, db.clone())
+            .execute()
+            .unwrap();
+        let result = preview(date(2025, 2, 7));
+        assert_eq!(result.previews.len(), 2);
+        let p = &result.previews[0];
+        assert_eq!(p.amounts.net_pay, Money::from_cents(9450));
+        assert_eq!(p.last.as_ref().unwrap().pay_date, date(2025, 1, 31));
+        assert_eq!(p.difference().unwrap().net_pay, Money::from_cents(-3150));
+        assert_eq!(result.previews[1].emp_id, 2.into());
+        assert_eq!(result.previews[1].last, None);
+        assert_eq!(sink.records().len(), 1);
+    }
+}
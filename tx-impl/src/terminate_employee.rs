@@ -0,0 +1,371 @@
+use chrono::NaiveDate;
+use log::trace;
+use std::sync::Arc;
+
+use abstract_tx::{TerminateEmployee, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{
+    BusinessCalendar, DisbursementSink, EmployeeId, OvertimePolicy, PayRecord, Withholding,
+};
+use tx_app::{Response, Transaction};
+
+// ユースケース: TerminateEmployee トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct TerminateEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    date: NaiveDate,
+    today: NaiveDate,
+
+    dao: T,
+    sink: Arc<dyn DisbursementSink>,
+    withholding: Arc<dyn Withholding>,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
+}
+impl<T> TerminateEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    // today は退職日と比べる今日で、呼び出し側が決める
+    pub fn new(
+        id: EmployeeId,
+        date: NaiveDate,
+        today: NaiveDate,
+        dao: T,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+    ) -> Self {
+        Self {
+            id,
+            date,
+            today,
+            dao,
+            sink,
+            withholding,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
+        }
+    }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
+}
+
+impl<T> HaveEmployeeDao for TerminateEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> TerminateEmployee for TerminateEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::TerminateEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+    fn get_today(&self) -> NaiveDate {
+        self.today
+    }
+    fn get_withholding(&self) -> &dyn Withholding {
+        self.withholding.as_ref()
+    }
+    fn get_overtime_policy(&self) -> &OvertimePolicy {
+        self.overtime.as_ref()
+    }
+    fn get_calendar(&self) -> &BusinessCalendar {
+        self.calendar.as_ref()
+    }
+    fn disburse(&self, records: &[PayRecord]) -> Result<(), UsecaseError> {
+        trace!("disburse called");
+        self.sink
+            .disburse(records)
+            .map_err(UsecaseError::DisbursementFailed)
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for TerminateEmployeeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        TerminateEmployee::execute(self)
+            .map(|paid| paid.map_or(Response::Void, Response::Paycheck))
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use abstract_tx::UsecaseError;
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{
+        EarningKind, EarningLine, EmployeeFilter, EmploymentStatus, Money, NoWithholding,
+        OvertimePolicy, RunType,
+    };
+    use payroll_impl::{MemorySink, PayrollFactoryImpl};
+    use tx_app::{Response, Transaction};
+    use tx_rs::Tx;
+
+    use crate::fixture::{add_hourly, date};
+    use crate::{
+        AddHourlyEmployeeTx, AddSalariedEmployeeTx, AddTimeCardTx, ChangeEmployeeStatusTx,
+        ListEmployeesTx, TerminateEmployeeTx,
+    };
+
+    #[test]
+    fn test_hire_and_termination() {
+        let db = HashDB::new();
+        AddSalariedEmployeeTx::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Money::from_cents(230000),
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .with_hire_date(Some(date(2025, 1, 20)))
+        .execute()
+        .unwrap();
+        let payday = |d| {
+            crate::fixture::payday(&db, d);
+            db.run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), d, RunType::Regular)
+                    .run(&mut ctx)
+            })
+        };
+
+        // 入社前の期間は支払わない
+        assert!(payday(date(2024, 12, 31)).is_err());
+        // 1/20 からの 12 日分 2300 * 12 / 31
+        let pc = payday(date(2025, 1, 31)).unwrap();
+        assert_eq!(pc.gross_pay(), Money::from_cents(89032));
+
+        let terminate = |id: u32, d| {
+            TerminateEmployeeTx::new(
+                id.into(),
+                d,
+                date(2025, 2, 14),
+                db.clone(),
+                Arc::new(MemorySink::new()),
+                Arc::new(NoWithholding),
+                Arc::new(OvertimePolicy::default()),
+            )
+            .execute()
+        };
+        let terminate_error = |id, d| {
+            let err = terminate(id, d).unwrap_err();
+            match err.downcast_ref::<UsecaseError>() {
+                Some(UsecaseError::TerminateEmployeeFailed(e)) => e.clone(),
+                _ => panic!("unexpected error: {}", err),
+            }
+        };
+        // 入社前や支払い済みの期間、まだ来ていない日には退職日を置けない
+        for d in [date(2025, 1, 10), date(2025, 1, 30), date(2025, 2, 15)] {
+            assert!(matches!(
+                terminate_error(1, d),
+                DaoError::InvalidEffectiveDate(..)
+            ));
+        }
+
+        // 休職中も支払いは続く
+        ChangeEmployeeStatusTx::new(
+            1.into(),
+            EmploymentStatus::OnLeave,
+            date(2025, 2, 3),
+            db.clone(),
+        )
+        .execute()
+        .unwrap();
+        // 2/14 までの 14 日分 2300 * 14 / 28 を退職日に支払う
+        let Response::Paycheck(pc) = terminate(1, date(2025, 2, 14)).unwrap() else {
+            panic!("no final paycheck");
+        };
+        assert_eq!(pc.get_pay_period(), date(2025, 2, 1)..=date(2025, 2, 14));
+        assert_eq!(pc.gross_pay(), Money::from_cents(115000));
+        assert_eq!(
+            db.run_tx(|mut ctx| db
+                .fetch_paycheck(1.into(), date(2025, 2, 14), RunType::Final)
+                .run(&mut ctx))
+                .unwrap(),
+            pc
+        );
+
+        // 記録は残るが、以降の Payday では支払わない
+        let emp = db
+            .run_tx(|mut ctx| db.fetch(1.into()).run(&mut ctx))
+            .unwrap();
+        let info = emp.info();
+        assert_eq!(info.termination_date, Some(date(2025, 2, 14)));
+        assert_eq!(info.status(), EmploymentStatus::Terminated);
+        assert_eq!(emp.status_on(date(2025, 2, 10)), EmploymentStatus::OnLeave);
+        assert!(payday(date(2025, 2, 28)).is_err());
+        let Response::Employees(infos) =
+            ListEmployeesTx::new(EmployeeFilter::Terminated, db.clone())
+                .execute()
+                .unwrap()
+        else {
+            panic!("unexpected response");
+        };
+        assert_eq!(infos.len(), 1);
+
+        // 退職した従業員はもう一度退職させることも、状況を変えることもできない
+        assert!(matches!(
+            terminate_error(1, date(2025, 3, 14)),
+            DaoError::EmployeeTerminated(_)
+        ));
+        let err = ChangeEmployeeStatusTx::new(
+            1.into(),
+            EmploymentStatus::Active,
+            date(2025, 3, 1),
+            db.clone(),
+        )
+        .execute()
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::ChangeEmployeeFailed(
+                DaoError::EmployeeTerminated(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_final_paycheck_includes_unpaid_timecards() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        AddTimeCardTx::new(1.into(), date(2025, 2, 3), 8.0, db.clone())
+            .execute()
+            .unwrap();
+        AddTimeCardTx::new(1.into(), date(2025, 2, 4), 4.0, db.clone())
+            .execute()
+            .unwrap();
+        // 定期の支払日 (2/7) を待たずに、退職日に 12 時間分を支払う
+        let Response::Paycheck(pc) = TerminateEmployeeTx::new(
+            1.into(),
+            date(2025, 2, 4),
+            date(2025, 2, 4),
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .execute()
+        .unwrap() else {
+            panic!("no final paycheck");
+        };
+        assert_eq!(
+            pc.earning_lines(),
+            &[EarningLine::new(
+                EarningKind::Regular,
+                Money::from_cents(18900)
+            )]
+        );
+        assert_eq!(pc.get_pay_period(), date(2025, 2, 1)..=date(2025, 2, 4));
+    }
+
+    #[test]
+    fn test_final_paycheck_since_hire_date() {
+        let db = HashDB::new();
+        AddHourlyEmployeeTx::new(
+            1.into(),
+            "Bob",
+            "Home",
+            Money::from_cents(1575),
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .with_hire_date(Some(date(2025, 1, 6)))
+        .execute()
+        .unwrap();
+        for d in [date(2025, 1, 6), date(2025, 1, 13), date(2025, 1, 20)] {
+            AddTimeCardTx::new(1.into(), d, 8.0, db.clone())
+                .execute()
+                .unwrap();
+        }
+        // 一度も支払っていないので、2 つ前の支払い期間の入社日からの 24 時間分を支払う
+        let Response::Paycheck(pc) = TerminateEmployeeTx::new(
+            1.into(),
+            date(2025, 1, 22),
+            date(2025, 1, 22),
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .execute()
+        .unwrap() else {
+            panic!("no final paycheck");
+        };
+        assert_eq!(pc.get_pay_period(), date(2025, 1, 6)..=date(2025, 1, 22));
+        assert_eq!(
+            pc.earning_lines(),
+            &[EarningLine::new(
+                EarningKind::Regular,
+                Money::from_cents(1575 * 24)
+            )]
+        );
+    }
+
+    #[test]
+    fn test_final_paycheck_since_first_timecard() {
+        let db = HashDB::new();
+        add_hourly(&db, 1);
+        for d in [date(2025, 1, 6), date(2025, 1, 13)] {
+            AddTimeCardTx::new(1.into(), d, 8.0, db.clone())
+                .execute()
+                .unwrap();
+        }
+        // 入社日がなくても、一度も支払っていないので前の支払い期間の 1/6 のタイムカードから支払う
+        let Response::Paycheck(pc) = TerminateEmployeeTx::new(
+            1.into(),
+            date(2025, 1, 15),
+            date(2025, 1, 15),
+            db.clone(),
+            Arc::new(MemorySink::new()),
+            Arc::new(NoWithholding),
+            Arc::new(OvertimePolicy::default()),
+        )
+        .execute()
+        .unwrap() else {
+            panic!("no final paycheck");
+        };
+        assert_eq!(pc.get_pay_period(), date(2025, 1, 4)..=date(2025, 1, 15));
+        assert_eq!(
+            pc.earning_lines(),
+            &[EarningLine::new(
+                EarningKind::Regular,
+                Money::from_cents(1575 * 16)
+            )]
+        );
+    }
+}
@@ -1,29 +1,44 @@
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate};
 use log::trace;
+use std::sync::Arc;
 
 use crate::{
     AddCommissionedEmployeeTx, AddHourlyEmployeeTx, AddSalariedEmployeeTx, AddSalesReceiptTx,
-    AddServiceChargeTx, AddTimeCardTx, ChangeCommissionedTx, ChangeDirectTx,
-    ChangeEmployeeAddressTx, ChangeEmployeeNameTx, ChangeHoldTx, ChangeHourlyTx, ChangeMailTx,
-    ChangeMemberTx, ChangeNoMemberTx, ChangeSalariedTx, DeleteEmployeeTx, PaydayTx,
+    AddServiceChargeTx, AddTimeCardTx, AmendTimeCardTx, ChangeCommissionedTx, ChangeDirectTx,
+    ChangeEmployeeAddressTx, ChangeEmployeeNameTx, ChangeEmployeeOvertimeTx,
+    ChangeEmployeeStatusTx, ChangeEmployeeTaxTx, ChangeHoldTx, ChangeHourlyTx, ChangeMailTx,
+    ChangeMemberTx, ChangeNoMemberTx, ChangeSalariedTx, ChangeScheduleTx, DeleteEmployeeTx,
+    GetEmployeeTx, GetPaycheckTx, GetUnionMemberTx, GetYearToDateTx, ListEmployeesTx,
+    PayOffCycleTx, PaydayTx, PreviewPaydayTx, TerminateEmployeeTx, VoidSalesReceiptTx,
+    VoidServiceChargeTx, VoidTimeCardTx,
 };
 use dao::EmployeeDao;
-use payroll_domain::{EmployeeId, MemberId};
+use payroll_domain::{
+    BusinessCalendar, ChargeId, DisbursementSink, EmployeeFilter, EmployeeId, EmploymentStatus,
+    MemberId, Money, OvertimePolicy, ReceiptId, RerunPolicy, RunType, ScheduleInfo, TaxProfile,
+    Withholding,
+};
 use payroll_factory::{
-    BiweeklyScheduleFactory, CommissionedClassificationFactory, DirectMethodFactory,
-    HoldMethodFactory, HourlyClassificationFactory, MailMethodFactory, MonthlyScheduleFactory,
-    NoAffiliationFactory, SalariedClassificationFactory, UnionAffiliationFactory,
+    BiweeklyFromScheduleFactory, CommissionedClassificationFactory, DirectMethodFactory,
+    HoldMethodFactory, HourlyClassificationFactory, MailMethodFactory, MonthlyOnScheduleFactory,
+    MonthlyScheduleFactory, NoAffiliationFactory, SalariedClassificationFactory,
+    SemimonthlyScheduleFactory, UnionAffiliationFactory, WeeklyOnScheduleFactory,
     WeeklyScheduleFactory,
 };
 use tx_app::Transaction;
 use tx_factory::{
     AddCommissionedEmployeeTxFactory, AddHourlyEmployeeTxFactory, AddSalariedEmployeeTxFactory,
     AddSalesReceiptTxFactory, AddServiceChargeTxFactory, AddTimecardTxFactory,
-    ChangeEmployeeAddressTxFactory, ChangeEmployeeCommissionedTxFactory,
+    AmendTimecardTxFactory, ChangeEmployeeAddressTxFactory, ChangeEmployeeCommissionedTxFactory,
     ChangeEmployeeDirectTxFactory, ChangeEmployeeHoldTxFactory, ChangeEmployeeHourlyTxFactory,
     ChangeEmployeeMailTxFactory, ChangeEmployeeMemberTxFactory, ChangeEmployeeNameTxFactory,
-    ChangeEmployeeNoMemberTxFactory, ChangeEmployeeSalariedTxFactory, DeleteEmployeeTxFactory,
-    PaydayTxFactory,
+    ChangeEmployeeNoMemberTxFactory, ChangeEmployeeOvertimeTxFactory,
+    ChangeEmployeeSalariedTxFactory, ChangeEmployeeScheduleTxFactory,
+    ChangeEmployeeStatusTxFactory, ChangeEmployeeTaxTxFactory, DeleteEmployeeTxFactory,
+    GetEmployeeTxFactory, GetPaycheckTxFactory, GetUnionMemberTxFactory, GetYearToDateTxFactory,
+    ListEmployeesTxFactory, PayOffCycleTxFactory, PaydayTxFactory, PreviewPaydayTxFactory,
+    TerminateEmployeeTxFactory, VoidSalesReceiptTxFactory, VoidServiceChargeTxFactory,
+    VoidTimecardTxFactory,
 };
 
 pub struct TxFactoryImpl<T, F>
@@ -32,17 +47,36 @@ where
 {
     dao: T,
     payroll_factory: F,
+    sink: Arc<dyn DisbursementSink>,
+    withholding: Arc<dyn Withholding>,
+    overtime: Arc<OvertimePolicy>,
+    calendar: Arc<BusinessCalendar>,
 }
 impl<T, F> TxFactoryImpl<T, F>
 where
     T: EmployeeDao,
 {
-    pub fn new(dao: T, payroll_factory: F) -> Self {
+    pub fn new(
+        dao: T,
+        payroll_factory: F,
+        sink: Arc<dyn DisbursementSink>,
+        withholding: Arc<dyn Withholding>,
+        overtime: Arc<OvertimePolicy>,
+    ) -> Self {
         Self {
             dao,
             payroll_factory,
+            sink,
+            withholding,
+            overtime,
+            calendar: Arc::new(BusinessCalendar::none()),
         }
     }
+    // 休日に当たる支払日を calendar の規則でずらす (省略すればずらさない)
+    pub fn with_calendar(mut self, calendar: Arc<BusinessCalendar>) -> Self {
+        self.calendar = calendar;
+        self
+    }
 }
 impl<T, F> AddSalariedEmployeeTxFactory for TxFactoryImpl<T, F>
 where
@@ -59,17 +93,21 @@ where
         id: EmployeeId,
         name: &str,
         address: &str,
-        salary: f32,
+        salary: Money,
+        hire_date: Option<NaiveDate>,
     ) -> Box<dyn Transaction> {
         trace!("mk_tx called for AddSalariedEmployeeTx");
-        Box::new(AddSalariedEmployeeTx::new(
-            id,
-            name,
-            address,
-            salary,
-            self.dao.clone(),
-            self.payroll_factory.clone(),
-        ))
+        Box::new(
+            AddSalariedEmployeeTx::new(
+                id,
+                name,
+                address,
+                salary,
+                self.dao.clone(),
+                self.payroll_factory.clone(),
+            )
+            .with_hire_date(hire_date),
+        )
     }
 }
 impl<T, F> AddHourlyEmployeeTxFactory for TxFactoryImpl<T, F>
@@ -87,24 +125,28 @@ where
         id: EmployeeId,
         name: &str,
         address: &str,
-        hourly_rate: f32,
+        hourly_rate: Money,
+        hire_date: Option<NaiveDate>,
     ) -> Box<dyn Transaction> {
         trace!("mk_tx called for AddHourlyEmployeeTx");
-        Box::new(AddHourlyEmployeeTx::new(
-            id,
-            name,
-            address,
-            hourly_rate,
-            self.dao.clone(),
-            self.payroll_factory.clone(),
-        ))
+        Box::new(
+            AddHourlyEmployeeTx::new(
+                id,
+                name,
+                address,
+                hourly_rate,
+                self.dao.clone(),
+                self.payroll_factory.clone(),
+            )
+            .with_hire_date(hire_date),
+        )
     }
 }
 impl<T, F> AddCommissionedEmployeeTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
     F: CommissionedClassificationFactory
-        + BiweeklyScheduleFactory
+        + BiweeklyFromScheduleFactory
         + HoldMethodFactory
         + NoAffiliationFactory
         + Clone
@@ -115,19 +157,23 @@ where
         id: EmployeeId,
         name: &str,
         address: &str,
-        salary: f32,
+        salary: Money,
         commission_rate: f32,
+        hire_date: Option<NaiveDate>,
     ) -> Box<dyn Transaction> {
         trace!("mk_tx called for AddCommissionedEmployeeTx");
-        Box::new(AddCommissionedEmployeeTx::new(
-            id,
-            name,
-            address,
-            salary,
-            commission_rate,
-            self.dao.clone(),
-            self.payroll_factory.clone(),
-        ))
+        Box::new(
+            AddCommissionedEmployeeTx::new(
+                id,
+                name,
+                address,
+                salary,
+                commission_rate,
+                self.dao.clone(),
+                self.payroll_factory.clone(),
+            )
+            .with_hire_date(hire_date),
+        )
     }
 }
 impl<T, F> DeleteEmployeeTxFactory for TxFactoryImpl<T, F>
@@ -140,6 +186,27 @@ where
         Box::new(DeleteEmployeeTx::new(id, self.dao.clone()))
     }
 }
+impl<T, F> TerminateEmployeeTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate) -> Box<dyn Transaction> {
+        trace!("mk_tx called for TerminateEmployeeTx");
+        // 最後の Paycheck はすぐに支払うので、このマシンの今日より後の退職日は受け付けない
+        Box::new(
+            TerminateEmployeeTx::new(
+                id,
+                date,
+                Local::now().date_naive(),
+                self.dao.clone(),
+                self.sink.clone(),
+                self.withholding.clone(),
+                self.overtime.clone(),
+            )
+            .with_calendar(self.calendar.clone()),
+        )
+    }
+}
 impl<T, F> AddTimecardTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
@@ -150,22 +217,52 @@ where
         Box::new(AddTimeCardTx::new(id, date, hours, self.dao.clone()))
     }
 }
+impl<T, F> AmendTimecardTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: Clone,
+{
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, hours: f32) -> Box<dyn Transaction> {
+        trace!("mk_tx called for AmendTimeCardTx");
+        Box::new(AmendTimeCardTx::new(id, date, hours, self.dao.clone()))
+    }
+}
+impl<T, F> VoidTimecardTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: Clone,
+{
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate) -> Box<dyn Transaction> {
+        trace!("mk_tx called for VoidTimeCardTx");
+        Box::new(VoidTimeCardTx::new(id, date, self.dao.clone()))
+    }
+}
 impl<T, F> AddSalesReceiptTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
     F: Clone,
 {
-    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, amount: f32) -> Box<dyn Transaction> {
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, amount: Money) -> Box<dyn Transaction> {
         trace!("mk_tx called for AddSalesReceiptTx");
         Box::new(AddSalesReceiptTx::new(id, date, amount, self.dao.clone()))
     }
 }
+impl<T, F> VoidSalesReceiptTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: Clone,
+{
+    fn mk_tx(&self, id: EmployeeId, receipt_id: ReceiptId) -> Box<dyn Transaction> {
+        trace!("mk_tx called for VoidSalesReceiptTx");
+        Box::new(VoidSalesReceiptTx::new(id, receipt_id, self.dao.clone()))
+    }
+}
 impl<T, F> AddServiceChargeTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
     F: Clone,
 {
-    fn mk_tx(&self, member_id: MemberId, date: NaiveDate, amount: f32) -> Box<dyn Transaction> {
+    fn mk_tx(&self, member_id: MemberId, date: NaiveDate, amount: Money) -> Box<dyn Transaction> {
         trace!("mk_tx called for AddServiceChargeTx");
         Box::new(AddServiceChargeTx::new(
             member_id,
@@ -175,6 +272,20 @@ where
         ))
     }
 }
+impl<T, F> VoidServiceChargeTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: Clone,
+{
+    fn mk_tx(&self, member_id: MemberId, charge_id: ChargeId) -> Box<dyn Transaction> {
+        trace!("mk_tx called for VoidServiceChargeTx");
+        Box::new(VoidServiceChargeTx::new(
+            member_id,
+            charge_id,
+            self.dao.clone(),
+        ))
+    }
+}
 impl<T, F> ChangeEmployeeNameTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
@@ -199,50 +310,145 @@ where
         ))
     }
 }
-impl<T, F> ChangeEmployeeSalariedTxFactory for TxFactoryImpl<T, F>
+impl<T, F> ChangeEmployeeTaxTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
-    F: SalariedClassificationFactory + MonthlyScheduleFactory + Clone + 'static,
+    F: Clone,
 {
-    fn mk_tx(&self, id: EmployeeId, salary: f32) -> Box<dyn Transaction> {
-        trace!("mk_tx called for ChangeEmployeeSalariedTx");
-        Box::new(ChangeSalariedTx::new(
+    fn mk_tx(&self, id: EmployeeId, tax: TaxProfile) -> Box<dyn Transaction> {
+        trace!("mk_tx called for ChangeEmployeeTaxTx");
+        Box::new(ChangeEmployeeTaxTx::new(id, tax, self.dao.clone()))
+    }
+}
+impl<T, F> ChangeEmployeeStatusTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: Clone,
+{
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        status: EmploymentStatus,
+        effective: NaiveDate,
+    ) -> Box<dyn Transaction> {
+        trace!("mk_tx called for ChangeEmployeeStatusTx");
+        Box::new(ChangeEmployeeStatusTx::new(
             id,
-            salary,
+            status,
+            effective,
             self.dao.clone(),
-            self.payroll_factory.clone(),
         ))
     }
 }
-impl<T, F> ChangeEmployeeHourlyTxFactory for TxFactoryImpl<T, F>
+impl<T, F> ChangeEmployeeOvertimeTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
-    F: HourlyClassificationFactory + WeeklyScheduleFactory + Clone + 'static,
+    F: Clone,
 {
-    fn mk_tx(&self, id: EmployeeId, hourly_rate: f32) -> Box<dyn Transaction> {
-        trace!("mk_tx called for ChangeEmployeeHourlyTx");
-        Box::new(ChangeHourlyTx::new(
+    fn mk_tx(&self, id: EmployeeId, overtime: Option<OvertimePolicy>) -> Box<dyn Transaction> {
+        trace!("mk_tx called for ChangeEmployeeOvertimeTx");
+        Box::new(ChangeEmployeeOvertimeTx::new(
             id,
-            hourly_rate,
+            overtime,
             self.dao.clone(),
-            self.payroll_factory.clone(),
         ))
     }
 }
+impl<T, F> ChangeEmployeeSalariedTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: SalariedClassificationFactory + MonthlyScheduleFactory + Clone + 'static,
+{
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        salary: Money,
+        effective: Option<NaiveDate>,
+    ) -> Box<dyn Transaction> {
+        trace!("mk_tx called for ChangeEmployeeSalariedTx");
+        Box::new(
+            ChangeSalariedTx::new(
+                id,
+                salary,
+                effective,
+                self.dao.clone(),
+                self.payroll_factory.clone(),
+                self.overtime.clone(),
+            )
+            .with_calendar(self.calendar.clone()),
+        )
+    }
+}
+impl<T, F> ChangeEmployeeHourlyTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: HourlyClassificationFactory + WeeklyScheduleFactory + Clone + 'static,
+{
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        hourly_rate: Money,
+        effective: Option<NaiveDate>,
+    ) -> Box<dyn Transaction> {
+        trace!("mk_tx called for ChangeEmployeeHourlyTx");
+        Box::new(
+            ChangeHourlyTx::new(
+                id,
+                hourly_rate,
+                effective,
+                self.dao.clone(),
+                self.payroll_factory.clone(),
+                self.overtime.clone(),
+            )
+            .with_calendar(self.calendar.clone()),
+        )
+    }
+}
 impl<T, F> ChangeEmployeeCommissionedTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
-    F: CommissionedClassificationFactory + BiweeklyScheduleFactory + Clone + 'static,
+    F: CommissionedClassificationFactory + BiweeklyFromScheduleFactory + Clone + 'static,
 {
-    fn mk_tx(&self, id: EmployeeId, salary: f32, commission_rate: f32) -> Box<dyn Transaction> {
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        salary: Money,
+        commission_rate: f32,
+        effective: Option<NaiveDate>,
+    ) -> Box<dyn Transaction> {
         trace!("mk_tx called for ChangeEmployeeCommissionedTx");
-        Box::new(ChangeCommissionedTx::new(
-            id,
-            salary,
-            commission_rate,
-            self.dao.clone(),
-            self.payroll_factory.clone(),
-        ))
+        Box::new(
+            ChangeCommissionedTx::new(
+                id,
+                salary,
+                commission_rate,
+                effective,
+                self.dao.clone(),
+                self.payroll_factory.clone(),
+                self.overtime.clone(),
+            )
+            .with_calendar(self.calendar.clone()),
+        )
+    }
+}
+impl<T, F> ChangeEmployeeScheduleTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+    F: MonthlyScheduleFactory
+        + WeeklyScheduleFactory
+        + SemimonthlyScheduleFactory
+        + MonthlyOnScheduleFactory
+        + WeeklyOnScheduleFactory
+        + BiweeklyFromScheduleFactory
+        + Clone
+        + 'static,
+{
+    fn mk_tx(&self, id: EmployeeId, schedule: ScheduleInfo) -> Box<dyn Transaction> {
+        trace!("mk_tx called for ChangeEmployeeScheduleTx");
+        Box::new(
+            ChangeScheduleTx::new(id, schedule, self.dao.clone(), self.payroll_factory.clone())
+                .with_calendar(self.calendar.clone()),
+        )
     }
 }
 impl<T, F> ChangeEmployeeHoldTxFactory for TxFactoryImpl<T, F>
@@ -295,7 +501,7 @@ where
     T: EmployeeDao + Clone + 'static,
     F: UnionAffiliationFactory + Clone + 'static,
 {
-    fn mk_tx(&self, emp_id: EmployeeId, member_id: MemberId, dues: f32) -> Box<dyn Transaction> {
+    fn mk_tx(&self, emp_id: EmployeeId, member_id: MemberId, dues: Money) -> Box<dyn Transaction> {
         trace!("mk_change_employee_member_tx called");
         Box::new(ChangeMemberTx::new(
             member_id,
@@ -324,8 +530,107 @@ impl<T, F> PaydayTxFactory for TxFactoryImpl<T, F>
 where
     T: EmployeeDao + Clone + 'static,
 {
-    fn mk_tx(&self, date: NaiveDate) -> Box<dyn Transaction> {
+    fn mk_tx(&self, date: NaiveDate, policy: RerunPolicy) -> Box<dyn Transaction> {
         trace!("mk_payday_tx called");
-        Box::new(PaydayTx::new(date, self.dao.clone()))
+        Box::new(
+            PaydayTx::new(
+                date,
+                policy,
+                self.dao.clone(),
+                self.sink.clone(),
+                self.withholding.clone(),
+                self.overtime.clone(),
+            )
+            .with_calendar(self.calendar.clone()),
+        )
+    }
+}
+impl<T, F> PreviewPaydayTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(&self, date: NaiveDate) -> Box<dyn Transaction> {
+        trace!("mk_tx called for PreviewPaydayTx");
+        Box::new(
+            PreviewPaydayTx::new(
+                date,
+                self.dao.clone(),
+                self.withholding.clone(),
+                self.overtime.clone(),
+            )
+            .with_calendar(self.calendar.clone()),
+        )
+    }
+}
+impl<T, F> PayOffCycleTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(
+        &self,
+        id: EmployeeId,
+        run_type: RunType,
+        date: NaiveDate,
+        amount: Money,
+        period_start: Option<NaiveDate>,
+    ) -> Box<dyn Transaction> {
+        trace!("mk_tx called for PayOffCycleTx");
+        Box::new(
+            PayOffCycleTx::new(
+                id,
+                run_type,
+                date,
+                amount,
+                self.dao.clone(),
+                self.sink.clone(),
+                self.withholding.clone(),
+            )
+            .with_period_start(period_start),
+        )
+    }
+}
+impl<T, F> GetPaycheckTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(&self, id: EmployeeId, date: NaiveDate, run_type: RunType) -> Box<dyn Transaction> {
+        trace!("mk_get_paycheck_tx called");
+        Box::new(GetPaycheckTx::new(id, date, run_type, self.dao.clone()))
+    }
+}
+impl<T, F> GetYearToDateTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(&self, id: EmployeeId, year: i32) -> Box<dyn Transaction> {
+        trace!("mk_get_year_to_date_tx called");
+        Box::new(GetYearToDateTx::new(id, year, self.dao.clone()))
+    }
+}
+impl<T, F> GetEmployeeTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(&self, id: EmployeeId) -> Box<dyn Transaction> {
+        trace!("mk_get_employee_tx called");
+        Box::new(GetEmployeeTx::new(id, self.dao.clone()))
+    }
+}
+impl<T, F> ListEmployeesTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(&self, filter: EmployeeFilter) -> Box<dyn Transaction> {
+        trace!("mk_list_employees_tx called");
+        Box::new(ListEmployeesTx::new(filter, self.dao.clone()))
+    }
+}
+impl<T, F> GetUnionMemberTxFactory for TxFactoryImpl<T, F>
+where
+    T: EmployeeDao + Clone + 'static,
+{
+    fn mk_tx(&self, member_id: MemberId) -> Box<dyn Transaction> {
+        trace!("mk_get_union_member_tx called");
+        Box::new(GetUnionMemberTx::new(member_id, self.dao.clone()))
     }
 }
@@ -0,0 +1,286 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+
+use abstract_tx::{UsecaseError, VoidSalesReceipt};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Employee, EmployeeId, ReceiptId};
+use payroll_impl::CommissionedClassification;
+use tx_app::{Response, Transaction};
+
+// ユースケース: VoidSalesReceipt トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct VoidSalesReceiptTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    receipt_id: ReceiptId,
+
+    dao: T,
+}
+impl<T> VoidSalesReceiptTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, receipt_id: ReceiptId, dao: T) -> Self {
+        Self {
+            id,
+            receipt_id,
+            dao,
+        }
+    }
+}
+
+impl<T> HaveEmployeeDao for VoidSalesReceiptTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> VoidSalesReceipt for VoidSalesReceiptTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::ChangeEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn receipt_date(&self, emp: &Employee) -> Result<NaiveDate, DaoError> {
+        trace!("receipt_date called");
+        emp.classification()
+            .lock()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<CommissionedClassification>()
+            .ok_or(DaoError::UnexpectedError(
+                "classification is not CommissionedClassification".into(),
+            ))?
+            .sales_receipt_date(self.receipt_id)
+            .ok_or(DaoError::SalesReceiptNotFound(self.id, self.receipt_id))
+    }
+    fn void(&self, emp: &mut Employee, last_paid: Option<NaiveDate>) -> Result<(), DaoError> {
+        trace!("void called");
+        let reversal = emp
+            .classification()
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<CommissionedClassification>()
+            .ok_or(DaoError::UnexpectedError(
+                "classification is not CommissionedClassification".into(),
+            ))?
+            .void_sales_receipt(self.receipt_id, last_paid)
+            .ok_or(DaoError::SalesReceiptNotFound(self.id, self.receipt_id))?;
+        debug!("sales receipt voided: {:?}", reversal);
+        Ok(())
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for VoidSalesReceiptTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        VoidSalesReceipt::execute(self)
+            .map(|_| Response::Void)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+    use std::sync::Arc;
+
+    use abstract_tx::UsecaseError;
+    use dao::{DaoError, EmployeeDao};
+    use hs_db::HashDB;
+    use payroll_domain::{
+        BusinessCalendar, DeductionKind, DeductionLine, EarningKind, EarningLine, Money,
+        NoWithholding, OvertimePolicy, RerunPolicy, Rollover, RunType, ScheduleInfo,
+    };
+    use payroll_impl::{MemorySink, PayrollFactoryImpl};
+    use tx_app::Transaction;
+    use tx_rs::Tx;
+
+    use crate::fixture::{change_member, date, payday};
+    use crate::{
+        AddCommissionedEmployeeTx, AddSalesReceiptTx, AddServiceChargeTx, ChangeScheduleTx,
+        PaydayTx, VoidSalesReceiptTx, VoidServiceChargeTx,
+    };
+
+    #[test]
+    fn test_void_sales_receipts_and_service_charges() {
+        let db = HashDB::new();
+        AddCommissionedEmployeeTx::new(
+            1.into(),
+            "Carl",
+            "Office",
+            Money::from_cents(100000),
+            0.1,
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        change_member(&db, 7234, 1);
+        let sales_receipt = |d, cents| {
+            AddSalesReceiptTx::new(1.into(), d, Money::from_cents(cents), db.clone())
+                .execute()
+                .unwrap();
+        };
+
+        sales_receipt(date(2025, 1, 6), 100000);
+        sales_receipt(date(2025, 1, 8), 50000);
+        AddServiceChargeTx::new(
+            7234.into(),
+            date(2025, 1, 9),
+            Money::from_cents(1995),
+            db.clone(),
+        )
+        .execute()
+        .unwrap();
+        // 隔週の 2025-01-10 (金) で 1/6, 1/8 の売上と 1/9 のサービス料は支払い済みになる
+        payday(&db, date(2025, 1, 10));
+
+        VoidSalesReceiptTx::new(1.into(), 1.into(), db.clone())
+            .execute()
+            .unwrap();
+        VoidServiceChargeTx::new(7234.into(), 1.into(), db.clone())
+            .execute()
+            .unwrap();
+        // まだ支払っていない売上の取り消しと返品は次の Payday にそのまま反映される
+        sales_receipt(date(2025, 1, 15), 200000);
+        VoidSalesReceiptTx::new(1.into(), 3.into(), db.clone())
+            .execute()
+            .unwrap();
+        sales_receipt(date(2025, 1, 20), -30000);
+        payday(&db, date(2025, 1, 24));
+
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 1, 24), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert_eq!(
+            pc.earning_lines(),
+            &[
+                EarningLine::new(EarningKind::Salary, Money::from_cents(100000)),
+                EarningLine::new(EarningKind::Commission, Money::from_cents(-3000)),
+                EarningLine::new(EarningKind::Clawback, Money::from_cents(-10000)),
+            ]
+        );
+        assert_eq!(
+            pc.deduction_lines(),
+            &[
+                DeductionLine::new(DeductionKind::Dues, Money::from_cents(1890)),
+                DeductionLine::new(DeductionKind::ServiceChargeRefund, Money::from_cents(-1995)),
+            ]
+        );
+
+        // 取り消し済みや存在しない番号は取り消せない
+        let err = VoidSalesReceiptTx::new(1.into(), 3.into(), db.clone())
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::ChangeEmployeeFailed(
+                DaoError::SalesReceiptNotFound(..)
+            ))
+        ));
+        let err = VoidServiceChargeTx::new(7234.into(), 2.into(), db.clone())
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UsecaseError>(),
+            Some(UsecaseError::ChangeAffiliationFailed(
+                DaoError::ServiceChargeNotFound(..)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_void_sales_receipt_paid_after_holiday() {
+        let db = HashDB::new();
+        AddCommissionedEmployeeTx::new(
+            1.into(),
+            "Carl",
+            "Office",
+            Money::from_cents(100000),
+            0.1,
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        ChangeScheduleTx::new(
+            1.into(),
+            ScheduleInfo::Monthly,
+            db.clone(),
+            PayrollFactoryImpl,
+        )
+        .execute()
+        .unwrap();
+        AddSalesReceiptTx::new(
+            1.into(),
+            date(2025, 1, 2),
+            Money::from_cents(100000),
+            db.clone(),
+        )
+        .execute()
+        .unwrap();
+        // 月末の 1/31 (金) が祝日なので 2/3 (月) に支払い、1/2 から 32 日後になる
+        let calendar = Arc::new(BusinessCalendar {
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: [(2025, vec![date(2025, 1, 31)])].into(),
+            rollover: Rollover::Next,
+        });
+        let payday = |d| {
+            PaydayTx::new(
+                d,
+                RerunPolicy::Reject,
+                db.clone(),
+                Arc::new(MemorySink::new()),
+                Arc::new(NoWithholding),
+                Arc::new(OvertimePolicy::default()),
+            )
+            .with_calendar(calendar.clone())
+            .execute()
+            .unwrap();
+        };
+        payday(date(2025, 2, 3));
+
+        // 支払い済みの売上なので、取り消すと次の支払いで差し戻す
+        VoidSalesReceiptTx::new(1.into(), 1.into(), db.clone())
+            .execute()
+            .unwrap();
+        payday(date(2025, 2, 28));
+        let pc = db
+            .run_tx(|mut ctx| {
+                db.fetch_paycheck(1.into(), date(2025, 2, 28), RunType::Regular)
+                    .run(&mut ctx)
+            })
+            .unwrap();
+        assert!(pc.earning_lines().contains(&EarningLine::new(
+            EarningKind::Clawback,
+            Money::from_cents(-10000)
+        )));
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+use std::sync::{Arc, Mutex};
+
+use abstract_tx::{UsecaseError, VoidServiceCharge};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Affiliation, ChargeId, MemberId};
+use payroll_impl::UnionAffiliation;
+use tx_app::{Response, Transaction};
+
+// ユースケース: VoidServiceCharge トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct VoidServiceChargeTx<T>
+where
+    T: EmployeeDao,
+{
+    member_id: MemberId,
+    charge_id: ChargeId,
+
+    dao: T,
+}
+impl<T> VoidServiceChargeTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(member_id: MemberId, charge_id: ChargeId, dao: T) -> Self {
+        Self {
+            member_id,
+            charge_id,
+            dao,
+        }
+    }
+}
+
+impl<T> HaveEmployeeDao for VoidServiceChargeTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> VoidServiceCharge for VoidServiceChargeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::ChangeAffiliationFailed)
+    }
+
+    fn get_member_id(&self) -> MemberId {
+        self.member_id
+    }
+    fn charge_date(&self, aff: Arc<Mutex<dyn Affiliation>>) -> Result<NaiveDate, DaoError> {
+        trace!("charge_date called");
+        aff.lock()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UnionAffiliation>()
+            .ok_or(DaoError::UnexpectedError("didn't union affiliation".into()))?
+            .service_charge_date(self.charge_id)
+            .ok_or(DaoError::ServiceChargeNotFound(
+                self.member_id,
+                self.charge_id,
+            ))
+    }
+    fn void(
+        &self,
+        aff: Arc<Mutex<dyn Affiliation>>,
+        last_paid: Option<NaiveDate>,
+    ) -> Result<(), DaoError> {
+        trace!("void called");
+        let reversal = aff
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<UnionAffiliation>()
+            .ok_or(DaoError::UnexpectedError("didn't union affiliation".into()))?
+            .void_service_charge(self.charge_id, last_paid)
+            .ok_or(DaoError::ServiceChargeNotFound(
+                self.member_id,
+                self.charge_id,
+            ))?;
+        debug!("service charge voided: {:?}", reversal);
+        Ok(())
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for VoidServiceChargeTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        VoidServiceCharge::execute(self)
+            .map(|_| Response::Void)
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::NaiveDate;
+use log::{debug, trace};
+
+use abstract_tx::{ChangeTimeCard, UsecaseError};
+use dao::{DaoError, EmployeeDao, HaveEmployeeDao};
+use payroll_domain::{Employee, EmployeeId};
+use payroll_impl::HourlyClassification;
+use tx_app::{Response, Transaction};
+
+// ユースケース: VoidTimeCard トランザクションの実装 (struct)
+#[derive(Debug)]
+pub struct VoidTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    id: EmployeeId,
+    date: NaiveDate,
+
+    dao: T,
+}
+impl<T> VoidTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    pub fn new(id: EmployeeId, date: NaiveDate, dao: T) -> Self {
+        Self { id, date, dao }
+    }
+}
+
+impl<T> HaveEmployeeDao for VoidTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    type Ctx<'a> = T::Ctx<'a>;
+
+    fn dao<'a>(&self) -> &impl EmployeeDao<Ctx<'a> = Self::Ctx<'a>> {
+        &self.dao
+    }
+}
+impl<T> ChangeTimeCard for VoidTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    fn run_tx<'a, G, R>(&'a self, f: G) -> Result<R, UsecaseError>
+    where
+        G: FnOnce(Self::Ctx<'a>) -> Result<R, DaoError>,
+    {
+        trace!("run_tx called");
+        // 今は DB しかないのでサービスレベルトランザクションが DB のトランザクションと同一視されている
+        // TODO: ただしここはサービスレベルではなくユースケースレベルであるからサービスレベルに移動したい
+        self.dao()
+            .run_tx(f)
+            .map_err(UsecaseError::ChangeEmployeeFailed)
+    }
+
+    fn get_id(&self) -> EmployeeId {
+        self.id
+    }
+    fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+    fn get_hours(&self) -> Option<f32> {
+        None
+    }
+    fn change(&self, emp: &mut Employee) -> Result<f32, DaoError> {
+        trace!("change called");
+        let old_hours = emp
+            .classification()
+            .lock()
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<HourlyClassification>()
+            .ok_or(DaoError::UnexpectedError(
+                "classification is not HourlyClassification".into(),
+            ))?
+            .void_timecard(self.date)
+            .ok_or(DaoError::TimeCardNotFound(self.id, self.date))?;
+        debug!("timecard voided: {:?}", emp.classification());
+        Ok(old_hours)
+    }
+}
+// 共通インターフェースの実装
+impl<T> Transaction for VoidTimeCardTx<T>
+where
+    T: EmployeeDao,
+{
+    fn execute(&self) -> Result<Response, anyhow::Error> {
+        trace!("execute called");
+        ChangeTimeCard::execute(self)
+            .map(|_| Response::Void)
+            .map_err(Into::into)
+    }
+}